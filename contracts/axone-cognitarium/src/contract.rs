@@ -1,13 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
 };
 use cw2::set_contract_version;
 use cw_utils::nonpayable;
 
 use crate::error::ContractError;
-use crate::msg::{DataFormat, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{DataFormat, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::state::{Store, BLANK_NODE_IDENTIFIER_COUNTER, NAMESPACE_KEY_INCREMENT, STORE};
 
 // version info for migration info
@@ -24,7 +24,15 @@ pub fn instantiate(
     nonpayable(&info)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    STORE.save(deps.storage, &Store::new(info.sender, msg.limits.into()))?;
+    STORE.save(
+        deps.storage,
+        &Store::new(
+            info.sender,
+            msg.limits.into(),
+            msg.strict_typing,
+            msg.versioning,
+        ),
+    )?;
     NAMESPACE_KEY_INCREMENT.save(deps.storage, &0u128)?;
     BLANK_NODE_IDENTIFIER_COUNTER.save(deps.storage, &0u128)?;
 
@@ -34,33 +42,64 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut<'_>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
     match msg {
         ExecuteMsg::InsertData { format, data } => {
-            execute::insert(deps, info, format.unwrap_or_default(), data)
+            execute::insert(deps, env, info, format.unwrap_or_default(), data)
         }
         ExecuteMsg::DeleteData {
             prefixes,
             delete,
             r#where,
-        } => execute::delete(deps, info, prefixes, delete, r#where),
+        } => execute::delete(deps, env, info, prefixes, delete, r#where),
+        ExecuteMsg::Modify {
+            prefixes,
+            delete,
+            insert,
+            r#where,
+        } => execute::modify(deps, env, info, prefixes, delete, insert, r#where),
+        ExecuteMsg::RegisterJsonLdContext { iri, context } => {
+            execute::register_json_ld_context(deps, info, iri, context)
+        }
+        ExecuteMsg::GrantRole {
+            address,
+            role,
+            scope,
+        } => execute::grant_role(deps, info, address, role, scope),
+        ExecuteMsg::RevokeRole {
+            address,
+            role,
+            scope,
+        } => execute::revoke_role(deps, info, address, role, scope),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            execute::transfer_ownership(deps, info, new_owner)
+        }
+        ExecuteMsg::UpdateLimits { limits } => execute::update_limits(deps, info, limits),
+        ExecuteMsg::PruneHistory { retention, limit } => {
+            execute::prune_history(deps, env, info, retention, limit)
+        }
     }
 }
 
 pub mod execute {
     use super::*;
+    use crate::error::StoreError;
+    use crate::msg;
     use crate::msg::{
-        DataFormat, Prefix, SimpleWhereCondition, TripleDeleteTemplate, WhereClause, WhereCondition,
+        DataFormat, Prefix, TripleConstructTemplate, TripleDeleteTemplate, WhereClause,
     };
-    use crate::querier::{PlanBuilder, QueryEngine, ResolvedVariables};
+    use crate::querier::{AtomTemplate, PlanBuilder, QueryEngine, ResolvedVariables};
     use crate::rdf::PrefixMap;
-    use crate::state::{HasCachedNamespaces, Triple};
+    use crate::state::JSON_LD_CONTEXTS;
+    use crate::state::{AccessScope, Batch, Grant, HasCachedNamespaces, Role, Triple, GRANTS};
     use crate::storer::StoreEngine;
+    use axone_rdf::jsonld;
     use axone_rdf::serde::TripleReader;
+    use cosmwasm_std::Uint128;
     use either::{Left, Right};
     use std::io::BufReader;
 
@@ -72,17 +111,56 @@ pub mod execute {
         }
     }
 
+    /// Verifies the sender is granted the role, returning the scopes it's restricted to if any,
+    /// the owner being granted all the roles without restriction.
+    pub fn verify_role(
+        deps: &DepsMut<'_>,
+        info: &MessageInfo,
+        role: Role,
+    ) -> Result<Option<Vec<AccessScope>>, ContractError> {
+        if STORE.load(deps.storage)?.owner == info.sender {
+            return Ok(None);
+        }
+
+        let grants: Vec<Grant> = GRANTS
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|grant| grant.role == role)
+            .collect();
+        if grants.is_empty() {
+            Err(ContractError::Unauthorized)?;
+        }
+        if grants.iter().any(|grant| grant.scope.is_none()) {
+            return Ok(None);
+        }
+        Ok(Some(
+            grants.into_iter().filter_map(|grant| grant.scope).collect(),
+        ))
+    }
+
     pub fn insert(
         deps: DepsMut<'_>,
+        env: Env,
         info: MessageInfo,
         format: DataFormat,
         data: Binary,
     ) -> Result<Response, ContractError> {
-        verify_owner(&deps, &info)?;
+        let scopes = verify_role(&deps, &info, Role::Writer)?;
 
         let buf = BufReader::new(data.as_slice());
         let mut reader = TripleReader::new(&(&format).into(), buf);
-        let mut storer = StoreEngine::new(deps.storage)?;
+        if format == DataFormat::JsonLd {
+            reader = reader.with_contexts(
+                JSON_LD_CONTEXTS
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .collect::<StdResult<_>>()?,
+            );
+        }
+        let mut storer = StoreEngine::new(deps.storage)?
+            .with_insert_scopes(scopes)
+            .with_batch(Some(Batch::new(info.sender, &env.block)))
+            .at_height(env.block.height);
         let count = storer.store_all(&mut reader)?;
 
         Ok(Response::new()
@@ -92,35 +170,31 @@ pub mod execute {
 
     pub fn delete(
         deps: DepsMut<'_>,
+        env: Env,
         info: MessageInfo,
         prefixes: Vec<Prefix>,
         delete: Vec<TripleDeleteTemplate>,
         r#where: WhereClause,
     ) -> Result<Response, ContractError> {
-        verify_owner(&deps, &info)?;
+        let scopes = verify_role(&deps, &info, Role::Deleter)?;
 
-        let delete = if delete.is_empty() {
-            Left(
-                r#where
-                    .iter()
-                    .map(|c| match c {
-                        WhereCondition::Simple(SimpleWhereCondition::TriplePattern(t)) => {
-                            (t.subject.clone(), t.predicate.clone(), t.object.clone())
-                        }
-                    })
-                    .collect(),
-            )
+        let from_where = delete.is_empty();
+        let delete = if from_where {
+            Left(util::as_triple_templates(&r#where)?)
         } else {
             Right(
                 delete
                     .into_iter()
-                    .map(|t| (t.subject, t.predicate, t.object))
+                    .map(|t| (t.subject, t.predicate, t.object, t.graph))
                     .collect(),
             )
         };
 
         let prefix_map = <PrefixMap>::from(prefixes).into_inner();
-        let mut plan_builder = PlanBuilder::new(deps.storage, &prefix_map, None);
+        let store = STORE.load(deps.storage)?;
+        let mut plan_builder = PlanBuilder::new(deps.storage, &prefix_map, None)
+            .with_max_path_depth(store.limits.max_query_path_depth as usize)
+            .with_strict_typing(store.strict_typing);
         let plan = plan_builder.build_plan(&r#where)?;
 
         let query_engine = QueryEngine::new(deps.storage);
@@ -129,45 +203,323 @@ pub mod execute {
             &prefix_map,
             delete,
             plan_builder.cached_namespaces(),
+            store.strict_typing,
         )?;
 
         let triples = if r#where.is_empty() {
             let empty_vars = ResolvedVariables::with_capacity(0);
             delete_templates
                 .into_iter()
-                .filter_map(|tpl| match tpl.resolve(&empty_vars) {
-                    Ok(Some(v)) => Some(Ok(v)),
-                    Ok(None) => None,
-                    Err(e) => Some(Err(e)),
-                })
-                .collect::<StdResult<Vec<Triple>>>()?
+                .filter_map(|tpl| tpl.resolve(&empty_vars))
+                .collect::<Vec<Triple>>()
         } else {
             query_engine
                 .construct_triples(plan, delete_templates)
                 .collect::<StdResult<Vec<Triple>>>()?
         };
+        let triples = if from_where {
+            util::in_matched_graphs(deps.storage, triples)?
+        } else {
+            triples
+        };
 
-        let mut store = StoreEngine::new(deps.storage)?;
+        let mut store = StoreEngine::new(deps.storage)?
+            .with_delete_scopes(scopes)
+            .at_height(env.block.height);
         let count = store.delete_all(&triples)?;
 
         Ok(Response::new()
             .add_attribute("action", "delete")
             .add_attribute("triple_count", count))
     }
+
+    pub fn modify(
+        deps: DepsMut<'_>,
+        env: Env,
+        info: MessageInfo,
+        prefixes: Vec<Prefix>,
+        delete: Vec<TripleDeleteTemplate>,
+        insert: Vec<TripleConstructTemplate>,
+        r#where: WhereClause,
+    ) -> Result<Response, ContractError> {
+        let delete_scopes = if delete.is_empty() {
+            None
+        } else {
+            verify_role(&deps, &info, Role::Deleter)?
+        };
+        let insert_scopes = if insert.is_empty() {
+            None
+        } else {
+            verify_role(&deps, &info, Role::Writer)?
+        };
+
+        let prefix_map = <PrefixMap>::from(prefixes).into_inner();
+        let store = STORE.load(deps.storage)?;
+        let mut plan_builder = PlanBuilder::new(deps.storage, &prefix_map, None)
+            .with_max_path_depth(store.limits.max_query_path_depth as usize)
+            .with_strict_typing(store.strict_typing);
+        let plan = plan_builder.build_plan(&r#where)?;
+
+        let query_engine = QueryEngine::new(deps.storage);
+        let delete_templates = query_engine.make_triple_templates(
+            &plan,
+            &prefix_map,
+            Right(
+                delete
+                    .into_iter()
+                    .map(|t| (t.subject, t.predicate, t.object, t.graph))
+                    .collect(),
+            ),
+            plan_builder.cached_namespaces(),
+            store.strict_typing,
+        )?;
+        let insert_templates = insert
+            .into_iter()
+            .map(|t| {
+                AtomTemplate::try_new(
+                    &plan,
+                    &prefix_map,
+                    (t.subject, t.predicate, t.object, t.graph),
+                )
+            })
+            .collect::<StdResult<Vec<AtomTemplate>>>()?;
+
+        let solutions = if r#where.is_empty() {
+            vec![ResolvedVariables::with_capacity(0)]
+        } else {
+            query_engine
+                .eval_plan(plan)
+                .collect::<StdResult<Vec<ResolvedVariables>>>()?
+        };
+        let delete = solutions
+            .iter()
+            .flat_map(|vars| delete_templates.iter().filter_map(|tpl| tpl.resolve(vars)))
+            .collect::<Vec<Triple>>();
+        let insert = solutions
+            .iter()
+            .map(|vars| {
+                insert_templates
+                    .iter()
+                    .filter_map(|tpl| tpl.resolve_quad(vars))
+                    .collect()
+            })
+            .collect();
+
+        let mut store = StoreEngine::new(deps.storage)?
+            .with_delete_scopes(delete_scopes)
+            .with_insert_scopes(insert_scopes)
+            .with_batch(Some(Batch::new(info.sender, &env.block)))
+            .at_height(env.block.height);
+        let (deleted_count, inserted_count) = store.modify_all(&delete, insert)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "modify")
+            .add_attribute("deleted_count", deleted_count)
+            .add_attribute("inserted_count", inserted_count))
+    }
+
+    pub fn register_json_ld_context(
+        deps: DepsMut<'_>,
+        info: MessageInfo,
+        iri: String,
+        context: Binary,
+    ) -> Result<Response, ContractError> {
+        verify_owner(&deps, &info)?;
+
+        let context = String::from_utf8(context.to_vec())
+            .map_err(|e| StdError::invalid_utf8(e.to_string()))?;
+        jsonld::validate_context(&context)?;
+        JSON_LD_CONTEXTS.save(deps.storage, &iri, &context)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "register_json_ld_context")
+            .add_attribute("iri", iri))
+    }
+
+    pub fn grant_role(
+        deps: DepsMut<'_>,
+        info: MessageInfo,
+        address: String,
+        role: msg::Role,
+        scope: Option<msg::AccessScope>,
+    ) -> Result<Response, ContractError> {
+        verify_owner(&deps, &info)?;
+
+        let addr = deps.api.addr_validate(&address)?;
+        let grant = Grant {
+            role: role.into(),
+            scope: scope.map(Into::into),
+        };
+        GRANTS.update(deps.storage, &addr, |grants| -> StdResult<_> {
+            let mut grants = grants.unwrap_or_default();
+            if !grants.contains(&grant) {
+                grants.push(grant);
+            }
+            Ok(grants)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("address", address))
+    }
+
+    pub fn revoke_role(
+        deps: DepsMut<'_>,
+        info: MessageInfo,
+        address: String,
+        role: msg::Role,
+        scope: Option<msg::AccessScope>,
+    ) -> Result<Response, ContractError> {
+        verify_owner(&deps, &info)?;
+
+        let addr = deps.api.addr_validate(&address)?;
+        let grant = Grant {
+            role: role.into(),
+            scope: scope.map(Into::into),
+        };
+        let grants: Vec<Grant> = GRANTS
+            .may_load(deps.storage, &addr)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|g| *g != grant)
+            .collect();
+        if grants.is_empty() {
+            GRANTS.remove(deps.storage, &addr);
+        } else {
+            GRANTS.save(deps.storage, &addr, &grants)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("address", address))
+    }
+
+    pub fn transfer_ownership(
+        deps: DepsMut<'_>,
+        info: MessageInfo,
+        new_owner: String,
+    ) -> Result<Response, ContractError> {
+        verify_owner(&deps, &info)?;
+
+        let owner = deps.api.addr_validate(&new_owner)?;
+        STORE.update(deps.storage, |mut store| -> StdResult<_> {
+            store.owner = owner;
+            Ok(store)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer_ownership")
+            .add_attribute("new_owner", new_owner))
+    }
+
+    pub fn update_limits(
+        deps: DepsMut<'_>,
+        info: MessageInfo,
+        limits: msg::StoreLimits,
+    ) -> Result<Response, ContractError> {
+        verify_owner(&deps, &info)?;
+
+        let mut store = STORE.load(deps.storage)?;
+        if limits.max_triple_count < store.stat.triple_count {
+            Err(StoreError::LimitBelowUsage(
+                "triple count".to_string(),
+                store.stat.triple_count,
+            ))?;
+        }
+        if limits.max_byte_size < store.stat.byte_size {
+            Err(StoreError::LimitBelowUsage(
+                "byte size".to_string(),
+                store.stat.byte_size,
+            ))?;
+        }
+
+        store.limits = limits.into();
+        STORE.save(deps.storage, &store)?;
+
+        Ok(Response::new().add_attribute("action", "update_limits"))
+    }
+
+    pub fn prune_history(
+        deps: DepsMut<'_>,
+        env: Env,
+        info: MessageInfo,
+        retention: u64,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        verify_owner(&deps, &info)?;
+
+        let limit = match limit {
+            Some(limit) => limit,
+            None => STORE.load(deps.storage)?.limits.max_query_limit,
+        };
+        let (count, has_more) = match env.block.height.checked_sub(retention) {
+            Some(cutoff) => {
+                StoreEngine::new(deps.storage)?.prune_history(cutoff, limit as usize)?
+            }
+            None => (Uint128::zero(), false),
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "prune_history")
+            .add_attribute("triple_count", count)
+            .add_attribute("has_more", has_more.to_string()))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<'_>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Store {} => to_json_binary(&query::store(deps)?),
-        QueryMsg::Select { query } => to_json_binary(&query::select(deps, query)?),
-        QueryMsg::Describe { query, format } => {
-            to_json_binary(&query::describe(deps, query, format.unwrap_or_default())?)
-        }
-        QueryMsg::Construct { query, format } => to_json_binary(&query::construct(
+        QueryMsg::Select {
+            query,
+            format,
+            as_of_height,
+        } => to_json_binary(&query::select(deps, query, format, as_of_height)?),
+        QueryMsg::Describe {
+            query,
+            format,
+            as_of_height,
+        } => to_json_binary(&query::describe(
+            deps,
+            query,
+            format.unwrap_or_default(),
+            as_of_height,
+        )?),
+        QueryMsg::Construct {
+            query,
+            format,
+            as_of_height,
+        } => to_json_binary(&query::construct(
             deps,
             query,
             format.unwrap_or(DataFormat::default()),
+            as_of_height,
+        )?),
+        QueryMsg::Ask {
+            query,
+            as_of_height,
+        } => to_json_binary(&query::ask(deps, query, as_of_height)?),
+        QueryMsg::Explain { query } => to_json_binary(&query::explain(deps, query)?),
+        QueryMsg::TripleProvenance {
+            prefixes,
+            subject,
+            predicate,
+            object,
+            graph,
+        } => to_json_binary(&query::triple_provenance(
+            deps, prefixes, subject, predicate, object, graph,
+        )?),
+        QueryMsg::Sparql {
+            query,
+            format,
+            after,
+            as_of_height,
+        } => to_json_binary(&query::sparql(
+            deps,
+            &query,
+            format.unwrap_or_default(),
+            after,
+            as_of_height,
         )?),
     }
 }
@@ -175,76 +527,120 @@ pub fn query(deps: Deps<'_>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 pub mod query {
     use super::*;
     use crate::msg::{
-        ConstructQuery, ConstructResponse, DescribeQuery, DescribeResponse, Node, SelectQuery,
-        SelectResponse, SimpleWhereCondition, StoreResponse, TripleConstructTemplate,
-        TriplePattern, VarOrNamedNode, VarOrNode, VarOrNodeOrLiteral, WhereCondition,
+        AskQuery, AskResponse, ConstructQuery, ConstructResponse, Cursor, DescribeQuery,
+        DescribeResponse, ExplainResponse, Grant, NamedNodeOrLiteral, Node, Prefix, ResultsFormat,
+        SelectQuery, SelectResponse, SimpleWhereCondition, SparqlResponse, StoreResponse,
+        TripleConstructTemplate, TriplePattern, TripleProvenanceResponse, VarOrNamedNode,
+        VarOrNode, VarOrNodeOrLiteral, WhereCondition, IRI,
+    };
+    use crate::querier::{
+        iri_as_node, literal_as_object, PlanBuilder, QueryEngine, QueryExplainer,
     };
-    use crate::querier::{PlanBuilder, QueryEngine};
     use crate::rdf::PrefixMap;
-    use crate::state::HasCachedNamespaces;
+    use crate::results;
+    use crate::sparql;
+    use crate::state::{
+        HasCachedNamespaces, NamespaceResolver, Object, Subject, BATCHES, GRANTS, TRIPLE_BATCHES,
+    };
     use axone_rdf::normalize::IdentifierIssuer;
 
     pub fn store(deps: Deps<'_>) -> StdResult<StoreResponse> {
-        STORE.load(deps.storage).map(Into::into)
+        let grants = GRANTS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .flat_map(|(address, grants)| {
+                grants.into_iter().map(move |grant| Grant {
+                    address: address.to_string(),
+                    role: grant.role.into(),
+                    scope: grant.scope.map(Into::into),
+                })
+            })
+            .collect();
+
+        Ok(StoreResponse {
+            grants,
+            ..STORE.load(deps.storage)?.into()
+        })
     }
 
-    pub fn select(deps: Deps<'_>, query: SelectQuery) -> StdResult<SelectResponse> {
+    pub fn select(
+        deps: Deps<'_>,
+        query: SelectQuery,
+        format: Option<ResultsFormat>,
+        as_of_height: Option<u64>,
+    ) -> StdResult<SelectResponse> {
         let store = STORE.load(deps.storage)?;
+        util::verify_as_of_height(&store, as_of_height)?;
+        let prefix_map = PrefixMap::from(query.prefixes.clone()).into_inner();
+        let (plan_builder, plan, offset, count) =
+            util::build_select_plan(deps.storage, &store, &prefix_map, &query)?;
 
-        if query.select.len() > store.limits.max_query_variable_count as usize {
-            Err(StdError::generic_err(
-                "Maximum query variable count exceeded",
-            ))?;
-        }
+        let mut response = QueryEngine::new(deps.storage)
+            .at_height(as_of_height)
+            .select(plan, query.select)
+            .and_then(|res| {
+                util::map_select_solutions(
+                    deps,
+                    res,
+                    plan_builder.cached_namespaces(),
+                    offset,
+                    count as usize,
+                )
+            })?;
 
-        let count = query.limit.unwrap_or(store.limits.max_query_limit);
-        if count > store.limits.max_query_limit {
-            Err(StdError::generic_err("Maximum query limit exceeded"))?;
+        if let Some(format) = format {
+            let data = results::write(&format, &response.head, &response.results)?;
+            response.data = Some(Binary::from(data));
+            response.results.bindings.clear();
         }
-
-        let prefix_map = PrefixMap::from(query.prefixes).into_inner();
-        let mut plan_builder =
-            PlanBuilder::new(deps.storage, &prefix_map, None).with_limit(count as usize);
-        let plan = plan_builder.build_plan(&query.r#where)?;
-
-        QueryEngine::new(deps.storage)
-            .select(plan, query.select)
-            .and_then(|res| util::map_select_solutions(deps, res, plan_builder.cached_namespaces()))
+        Ok(response)
     }
 
     pub fn describe(
         deps: Deps<'_>,
         query: DescribeQuery,
         format: DataFormat,
+        as_of_height: Option<u64>,
     ) -> StdResult<DescribeResponse> {
         let (p, o) = ("_2p".to_owned(), "_3o".to_owned());
 
         let (construct, r#where) = match &query.resource {
             VarOrNamedNode::Variable(var) => {
-                let select = TriplePattern {
-                    subject: VarOrNode::Variable(var.clone()),
-                    predicate: VarOrNamedNode::Variable(format!("{var}{p}")),
-                    object: VarOrNodeOrLiteral::Variable(format!("{var}{o}")),
-                };
+                let select = (
+                    VarOrNode::Variable(var.clone()),
+                    VarOrNamedNode::Variable(format!("{var}{p}")),
+                    VarOrNodeOrLiteral::Variable(format!("{var}{o}")),
+                    None,
+                );
 
                 let mut r#where = query.r#where;
                 r#where.push(WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
-                    select.clone(),
+                    TriplePattern {
+                        subject: select.0.clone(),
+                        predicate: select.1.clone().into(),
+                        object: select.2.clone(),
+                    },
                 )));
 
                 (vec![select], r#where)
             }
             VarOrNamedNode::NamedNode(iri) => {
-                let select = TriplePattern {
-                    subject: VarOrNode::Node(Node::NamedNode(iri.clone())),
-                    predicate: VarOrNamedNode::Variable(p),
-                    object: VarOrNodeOrLiteral::Variable(o),
-                };
+                let select = (
+                    VarOrNode::Node(Node::NamedNode(iri.clone())),
+                    VarOrNamedNode::Variable(p),
+                    VarOrNodeOrLiteral::Variable(o),
+                    None,
+                );
 
                 (
                     vec![select.clone()],
                     vec![WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
-                        select,
+                        TriplePattern {
+                            subject: select.0,
+                            predicate: select.1.into(),
+                            object: select.2,
+                        },
                     ))],
                 )
             }
@@ -254,16 +650,16 @@ pub mod query {
             deps.storage,
             &format,
             query.prefixes,
-            construct
-                .into_iter()
-                .map(|t| (t.subject, t.predicate, t.object))
-                .collect(),
+            construct,
             r#where,
+            query.after,
+            as_of_height,
         )?;
 
         Ok(DescribeResponse {
             format,
-            data: Binary::from(out),
+            data: Binary::from(out.0),
+            page_info: out.1,
         })
     }
 
@@ -271,25 +667,26 @@ pub mod query {
         deps: Deps<'_>,
         query: ConstructQuery,
         format: DataFormat,
+        as_of_height: Option<u64>,
     ) -> StdResult<ConstructResponse> {
         let ConstructQuery {
             construct,
             prefixes,
             r#where,
+            after,
         } = query;
 
         let construct = if construct.is_empty() {
-            r#where
-                .iter()
-                .map(|t| match t {
-                    WhereCondition::Simple(SimpleWhereCondition::TriplePattern(t)) => {
-                        TripleConstructTemplate {
-                            subject: t.subject.clone(),
-                            predicate: t.predicate.clone(),
-                            object: t.object.clone(),
-                        }
-                    }
-                })
+            util::as_triple_templates(&r#where)?
+                .into_iter()
+                .map(
+                    |(subject, predicate, object, graph)| TripleConstructTemplate {
+                        subject,
+                        predicate,
+                        object,
+                        graph,
+                    },
+                )
                 .collect()
         } else {
             construct
@@ -312,6 +709,7 @@ pub mod query {
                     ),
                     _ => t.object,
                 },
+                graph: t.graph,
             })
             .collect();
 
@@ -321,106 +719,494 @@ pub mod query {
             prefixes,
             construct
                 .into_iter()
-                .map(|t| (t.subject, t.predicate, t.object))
+                .map(|t| (t.subject, t.predicate, t.object, t.graph))
                 .collect(),
             r#where,
+            after,
+            as_of_height,
         )?;
 
         Ok(ConstructResponse {
             format,
-            data: Binary::from(out),
+            data: Binary::from(out.0),
+            page_info: out.1,
         })
     }
-}
 
-pub mod util {
-    use super::*;
-    use crate::msg::{
-        Head, Prefix, Results, SelectResponse, Value, VarOrNamedNode, VarOrNode,
-        VarOrNodeOrLiteral, WhereClause,
-    };
-    use crate::querier::{PlanBuilder, QueryEngine, SelectResults};
-    use crate::rdf::{Atom, PrefixMap};
-    use crate::state::{HasCachedNamespaces, Namespace, NamespaceResolver};
-    use axone_rdf::normalize::IdentifierIssuer;
-    use axone_rdf::serde::TripleWriter;
-    use cosmwasm_std::Storage;
-    use std::collections::BTreeMap;
+    pub fn explain(deps: Deps<'_>, query: SelectQuery) -> StdResult<ExplainResponse> {
+        let store = STORE.load(deps.storage)?;
+        let prefix_map = PrefixMap::from(query.prefixes.clone()).into_inner();
+        let (plan_builder, plan, ..) =
+            util::build_select_plan(deps.storage, &store, &prefix_map, &query)?;
+
+        let mut ns_resolver: NamespaceResolver = plan_builder.cached_namespaces().into();
+        let plan = QueryExplainer::new(deps.storage, |ns_key| {
+            let res = ns_resolver.resolve_from_key(deps.storage, ns_key);
+            res.and_then(NamespaceResolver::none_as_error_middleware)
+                .map(|ns| ns.value)
+        })?
+        .explain(&plan)?;
+
+        Ok(ExplainResponse { plan })
+    }
 
-    pub fn map_select_solutions(
+    pub fn triple_provenance(
         deps: Deps<'_>,
-        res: SelectResults<'_>,
-        ns_cache: Vec<Namespace>,
-    ) -> StdResult<SelectResponse> {
-        let mut ns_resolver: NamespaceResolver = ns_cache.into();
-        let mut id_issuer = IdentifierIssuer::new("b", 0u128);
+        prefixes: Vec<Prefix>,
+        subject: IRI,
+        predicate: IRI,
+        object: NamedNodeOrLiteral,
+        graph: Option<IRI>,
+    ) -> StdResult<TripleProvenanceResponse> {
+        let store = STORE.load(deps.storage)?;
+        let prefix_map = PrefixMap::from(prefixes).into_inner();
+        let mut ns_resolver = NamespaceResolver::new();
+        let as_node = |ns_resolver: &mut NamespaceResolver, iri| {
+            iri_as_node(ns_resolver, deps.storage, &prefix_map, iri)
+        };
 
-        let mut bindings: Vec<BTreeMap<String, Value>> = vec![];
-        for solution in res.solutions {
-            let vars = solution?;
-            let resolved = vars
-                .into_iter()
-                .map(|(name, var)| -> StdResult<(String, Value)> {
-                    Ok((
-                        name,
-                        var.as_value(
-                            &mut |ns_key| {
-                                let res = ns_resolver.resolve_from_key(deps.storage, ns_key);
-                                res.and_then(NamespaceResolver::none_as_error_middleware)
-                                    .map(|ns| ns.value)
-                            },
-                            &mut id_issuer,
-                        )?,
-                    ))
-                })
-                .collect::<StdResult<BTreeMap<String, Value>>>()?;
-            bindings.push(resolved);
-        }
+        // A namespace not found in the state means the triple can't exist.
+        let not_found = || StdError::not_found("Triple provenance");
+        let resolved = (|| {
+            let subject = Subject::Named(as_node(&mut ns_resolver, subject)?);
+            let predicate = as_node(&mut ns_resolver, predicate)?;
+            let object = match object {
+                NamedNodeOrLiteral::NamedNode(iri) => {
+                    Object::Named(as_node(&mut ns_resolver, iri)?)
+                }
+                NamedNodeOrLiteral::Literal(literal) => literal_as_object(
+                    &mut ns_resolver,
+                    deps.storage,
+                    &prefix_map,
+                    literal,
+                    store.strict_typing,
+                )?,
+            };
+            let graph = graph
+                .map(|iri| as_node(&mut ns_resolver, iri))
+                .transpose()?;
+            Ok((subject, predicate, object, graph))
+        })()
+        .map_err(|err| {
+            if NamespaceResolver::is_ns_not_found_error(&err) {
+                not_found()
+            } else {
+                err
+            }
+        });
+        let (subject, predicate, object, graph) = resolved?;
 
-        Ok(SelectResponse {
-            head: Head { vars: res.head },
-            results: Results { bindings },
-        })
-    }
+        let batch = TRIPLE_BATCHES
+            .may_load(
+                deps.storage,
+                (
+                    object.as_hash().as_bytes(),
+                    predicate.key(),
+                    subject.key_in_graph(graph.as_ref()),
+                ),
+            )?
+            .ok_or_else(not_found)?;
 
-    pub fn construct_atoms(
-        storage: &dyn Storage,
-        format: &DataFormat,
-        prefixes: Vec<Prefix>,
-        construct: Vec<(VarOrNode, VarOrNamedNode, VarOrNodeOrLiteral)>,
-        r#where: WhereClause,
-    ) -> StdResult<Vec<u8>> {
-        let store = STORE.load(storage)?;
+        Ok(BATCHES.load(deps.storage, batch)?.into_response(batch))
+    }
 
+    pub fn ask(
+        deps: Deps<'_>,
+        query: AskQuery,
+        as_of_height: Option<u64>,
+    ) -> StdResult<AskResponse> {
+        let store = STORE.load(deps.storage)?;
+        util::verify_as_of_height(&store, as_of_height)?;
+
+        let prefix_map = PrefixMap::from(query.prefixes).into_inner();
+        let mut plan_builder = PlanBuilder::new(deps.storage, &prefix_map, None)
+            .with_max_path_depth(store.limits.max_query_path_depth as usize)
+            .with_strict_typing(store.strict_typing)
+            .with_limit(1);
+        let plan = plan_builder.build_plan(&query.r#where)?;
+
+        QueryEngine::new(deps.storage)
+            .at_height(as_of_height)
+            .ask(plan)
+            .map(|result| AskResponse { result })
+    }
+
+    pub fn sparql(
+        deps: Deps<'_>,
+        query: &str,
+        format: DataFormat,
+        after: Option<Cursor>,
+        as_of_height: Option<u64>,
+    ) -> StdResult<SparqlResponse> {
+        Ok(match sparql::parse(query)? {
+            sparql::Query::Select(mut query) => {
+                query.after = after.or(query.after);
+                SparqlResponse::Select(select(deps, query, None, as_of_height)?)
+            }
+            sparql::Query::Describe(mut query) => {
+                query.after = after.or(query.after);
+                SparqlResponse::Describe(describe(deps, query, format, as_of_height)?)
+            }
+            sparql::Query::Construct(mut query) => {
+                query.after = after.or(query.after);
+                SparqlResponse::Construct(construct(deps, query, format, as_of_height)?)
+            }
+            sparql::Query::Ask(query) => SparqlResponse::Ask(ask(deps, query, as_of_height)?),
+        })
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut<'_>, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = migrate::verify_version(deps.storage)?;
+
+    if from_version <= migrate::UNINDEXED_VERSION {
+        migrate::backfill_triples(deps.storage)?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+pub mod migrate {
+    use super::*;
+    use crate::state::{triples, StatisticsBatchService, Triple, NAMESPACE_STATS, PREDICATE_STATS};
+    use cosmwasm_std::Storage;
+    use cw2::get_contract_version;
+    use cw_storage_plus::{Bound, Index};
+    use semver::Version;
+
+    /// The last released version storing the triples without their graph and predicate indexes,
+    /// nor the cardinality statistics.
+    pub const UNINDEXED_VERSION: Version = Version::new(5, 0, 0);
+
+    /// The number of triples loaded at once when backfilling them.
+    const BACKFILL_CHUNK_SIZE: usize = 100;
+
+    /// Verifies the stored contract is this one, in a version not greater than the current one,
+    /// which is returned.
+    pub fn verify_version(storage: &dyn Storage) -> Result<Version, ContractError> {
+        let stored = get_contract_version(storage)?;
+        if stored.contract != CONTRACT_NAME {
+            Err(ContractError::MigrationContract(stored.contract))?;
+        }
+
+        let parse = |version: &str| {
+            Version::parse(version).map_err(|e| {
+                StdError::generic_err(format!("Invalid contract version {version}: {e}"))
+            })
+        };
+        let version = parse(&stored.version)?;
+        if version > parse(CONTRACT_VERSION)? {
+            Err(ContractError::MigrationVersion(
+                stored.version,
+                CONTRACT_VERSION.to_string(),
+            ))?;
+        }
+        Ok(version)
+    }
+
+    /// Backfills the graph and subject and the predicate and subject indexes of the stored triples,
+    /// and recomputes the cardinality statistics out of them, in a single pass over the store.
+    ///
+    /// The triples are loaded by chunks to bound the memory used, but the whole store is processed
+    /// at once, the gas consumed growing linearly with its size.
+    pub fn backfill_triples(storage: &mut dyn Storage) -> StdResult<()> {
+        let predicates = PREDICATE_STATS
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for key in predicates {
+            PREDICATE_STATS.remove(storage, &key);
+        }
+        let namespaces = NAMESPACE_STATS
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for key in namespaces {
+            NAMESPACE_STATS.remove(storage, key);
+        }
+
+        let indexes = triples().idx;
+        let mut stats_batch_svc = StatisticsBatchService::new();
+        let mut after = None;
+        loop {
+            let chunk = triples()
+                .range_raw(
+                    storage,
+                    after.map(Bound::ExclusiveRaw),
+                    None,
+                    Order::Ascending,
+                )
+                .take(BACKFILL_CHUNK_SIZE)
+                .collect::<StdResult<Vec<(Vec<u8>, Triple)>>>()?;
+            let Some((last, _)) = chunk.last() else {
+                break;
+            };
+            after = Some(last.clone());
+
+            for (pk, triple) in &chunk {
+                indexes.graph_and_subject.save(storage, pk, triple)?;
+                indexes.predicate_and_subject.save(storage, pk, triple)?;
+                if triple.is_valid_at(None) {
+                    stats_batch_svc.count(triple, 1);
+                }
+            }
+        }
+        stats_batch_svc.flush(storage)
+    }
+}
+
+pub mod util {
+    use super::*;
+    use crate::cursor;
+    use crate::msg::{
+        Cursor, Head, PageInfo, Prefix, PropertyPath, Results, SelectQuery, SelectResponse,
+        SimpleWhereCondition, Value, VarOrNamedNode, VarOrNamedNodeOrPath, WhereClause,
+        WhereCondition,
+    };
+    use crate::querier::{
+        PlanBuilder, QueryEngine, QueryPlan, SelectResults, TripleTemplateWithBlankNode,
+    };
+    use crate::rdf::{Atom, PrefixMap};
+    use crate::state::{triples, HasCachedNamespaces, Namespace, NamespaceResolver, Triple};
+    use axone_rdf::normalize::IdentifierIssuer;
+    use axone_rdf::serde::TripleWriter;
+    use cosmwasm_std::Storage;
+    use std::collections::{BTreeMap, HashMap};
+
+    /// Verifies the store can be queried as of the given block height if any, i.e. is versioned.
+    pub fn verify_as_of_height(store: &Store, as_of_height: Option<u64>) -> StdResult<()> {
+        if as_of_height.is_some() && !store.versioning {
+            Err(StdError::generic_err(
+                "The store isn't versioned, it can't be queried as of a block height",
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Collects the triple patterns of the provided [WhereClause], including the ones of its
+    /// nested clauses, in their order of appearance, along with the name of the graph they're
+    /// matched against if any.
+    ///
+    /// The patterns of a union can't be used as templates, as each solution only satisfies one of
+    /// its groups, nor the patterns of a property path which doesn't designate a single predicate,
+    /// an error is returned in such cases.
+    pub fn as_triple_templates(
+        r#where: &WhereClause,
+    ) -> StdResult<Vec<TripleTemplateWithBlankNode>> {
+        let mut templates = Vec::with_capacity(r#where.len());
+        for cond in r#where {
+            match cond {
+                WhereCondition::Simple(SimpleWhereCondition::TriplePattern(t)) => {
+                    let predicate = match t.predicate.clone() {
+                        VarOrNamedNodeOrPath::Variable(v) => VarOrNamedNode::Variable(v),
+                        VarOrNamedNodeOrPath::NamedNode(iri)
+                        | VarOrNamedNodeOrPath::Path(PropertyPath::NamedNode(iri)) => {
+                            VarOrNamedNode::NamedNode(iri)
+                        }
+                        VarOrNamedNodeOrPath::Path(_) => Err(StdError::generic_err(
+                            "Property path can't be used as template, a template must be provided",
+                        ))?,
+                    };
+                    templates.push((t.subject.clone(), predicate, t.object.clone(), None));
+                }
+                WhereCondition::Optional(clause) => templates.extend(as_triple_templates(clause)?),
+                WhereCondition::Graph { graph, r#where } => templates.extend(
+                    as_triple_templates(r#where)?
+                        .into_iter()
+                        .map(|(s, p, o, g)| (s, p, o, g.or_else(|| Some(graph.clone())))),
+                ),
+                WhereCondition::Filter(_)
+                | WhereCondition::SubQuery(_)
+                | WhereCondition::Minus(_)
+                | WhereCondition::NotExists(_)
+                | WhereCondition::Values { .. }
+                | WhereCondition::Bind { .. } => {}
+                WhereCondition::Union(_) => Err(StdError::generic_err(
+                    "Union condition can't be used as template, a template must be provided",
+                ))?,
+            }
+        }
+        Ok(templates)
+    }
+
+    /// Expands the triples resolved out of the templates of [as_triple_templates] which aren't
+    /// bound to a graph, their patterns matching the triples of all the graphs, to the triples
+    /// stored in each of the graphs.
+    pub fn in_matched_graphs(
+        storage: &dyn Storage,
+        resolved: Vec<Triple>,
+    ) -> StdResult<Vec<Triple>> {
+        let mut expanded = Vec::with_capacity(resolved.len());
+        for triple in resolved {
+            if triple.graph.is_some() {
+                expanded.push(triple);
+                continue;
+            }
+
+            for res in triples()
+                .idx
+                .subject_and_predicate
+                .prefix((triple.subject.key(), triple.predicate.key()))
+                .range(storage, None, None, Order::Ascending)
+            {
+                let (_, stored) = res?;
+                if stored.object == triple.object && stored.is_valid_at(None) {
+                    expanded.push(stored);
+                }
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Builds the plan of the select query, checking it complies with the limits of the store, and
+    /// returns it along with the offset and the number of solutions to return.
+    pub fn build_select_plan<'a>(
+        storage: &'a dyn Storage,
+        store: &Store,
+        prefix_map: &'a HashMap<String, String>,
+        query: &SelectQuery,
+    ) -> StdResult<(PlanBuilder<'a>, QueryPlan, usize, u32)> {
+        if query.select.len() > store.limits.max_query_variable_count as usize {
+            Err(StdError::generic_err(
+                "Maximum query variable count exceeded",
+            ))?;
+        }
+
+        let count = query.limit.unwrap_or(store.limits.max_query_limit);
+        if count > store.limits.max_query_limit {
+            Err(StdError::generic_err("Maximum query limit exceeded"))?;
+        }
+
+        let aggregates = PlanBuilder::select_aggregates(query)?;
+        let offset = query
+            .after
+            .clone()
+            .map(cursor::decode)
+            .transpose()?
+            .unwrap_or(0);
+
+        let mut plan_builder = PlanBuilder::new(storage, prefix_map, None)
+            .with_max_path_depth(store.limits.max_query_path_depth as usize)
+            .with_strict_typing(store.strict_typing)
+            .with_grouping(query.group_by.clone(), aggregates, query.having.clone())
+            .with_order_by(query.order_by.clone())
+            .with_skip(offset)
+            .with_limit(count as usize + 1);
+        if query.distinct {
+            plan_builder = plan_builder.with_distinct(
+                query
+                    .select
+                    .iter()
+                    .map(|item| item.variable().to_string())
+                    .collect(),
+            );
+        }
+        let plan = plan_builder.build_plan(&query.r#where)?;
+
+        Ok((plan_builder, plan, offset, count))
+    }
+
+    pub fn map_select_solutions(
+        deps: Deps<'_>,
+        res: SelectResults<'_>,
+        ns_cache: Vec<Namespace>,
+        offset: usize,
+        limit: usize,
+    ) -> StdResult<SelectResponse> {
+        let mut ns_resolver: NamespaceResolver = ns_cache.into();
+        let mut id_issuer = IdentifierIssuer::new("b", 0u128);
+
+        let mut solutions = res.solutions;
+        let mut bindings: Vec<BTreeMap<String, Value>> = vec![];
+        for solution in solutions.by_ref().take(limit) {
+            let vars = solution?;
+            let resolved = vars
+                .into_iter()
+                .map(|(name, var)| -> StdResult<(String, Value)> {
+                    Ok((
+                        name,
+                        var.as_value(
+                            &mut |ns_key| {
+                                let res = ns_resolver.resolve_from_key(deps.storage, ns_key);
+                                res.and_then(NamespaceResolver::none_as_error_middleware)
+                                    .map(|ns| ns.value)
+                            },
+                            &mut id_issuer,
+                        )?,
+                    ))
+                })
+                .collect::<StdResult<BTreeMap<String, Value>>>()?;
+            bindings.push(resolved);
+        }
+        let has_next_page = solutions.next().transpose()?.is_some();
+
+        Ok(SelectResponse {
+            head: Head { vars: res.head },
+            page_info: PageInfo {
+                has_next_page,
+                cursor: cursor::encode(offset + bindings.len()),
+            },
+            results: Results { bindings },
+            data: None,
+        })
+    }
+
+    pub fn construct_atoms(
+        storage: &dyn Storage,
+        format: &DataFormat,
+        prefixes: Vec<Prefix>,
+        construct: Vec<TripleTemplateWithBlankNode>,
+        r#where: WhereClause,
+        after: Option<Cursor>,
+        as_of_height: Option<u64>,
+    ) -> StdResult<(Vec<u8>, PageInfo)> {
+        let store = STORE.load(storage)?;
+        verify_as_of_height(&store, as_of_height)?;
+        let offset = after.map(cursor::decode).transpose()?.unwrap_or(0);
+        let limit = store.limits.max_query_limit as usize;
+
+        let context = prefixes
+            .iter()
+            .map(|p| (p.prefix.clone(), p.namespace.clone()))
+            .collect();
         let prefix_map = <PrefixMap>::from(prefixes).into_inner();
         let mut plan_builder = PlanBuilder::new(storage, &prefix_map, None)
-            .with_limit(store.limits.max_query_limit as usize);
+            .with_max_path_depth(store.limits.max_query_path_depth as usize)
+            .with_strict_typing(store.strict_typing)
+            .with_skip(offset)
+            .with_limit(limit + 1);
         let plan = plan_builder.build_plan(&r#where)?;
 
-        let atoms = QueryEngine::new(storage)
-            .construct_atoms(
-                plan,
-                &prefix_map,
-                construct,
-                plan_builder.cached_namespaces(),
-            )?
-            .collect::<StdResult<Vec<Atom>>>()?;
+        let engine = QueryEngine::new(storage).at_height(as_of_height);
+        let res = engine.construct_atoms(
+            plan,
+            &prefix_map,
+            construct,
+            plan_builder.cached_namespaces(),
+            limit,
+        )?;
+        let page_info = PageInfo {
+            has_next_page: res.has_next,
+            cursor: cursor::encode(offset + res.solution_count),
+        };
+        let atoms = res.atoms.collect::<StdResult<Vec<Atom>>>()?;
 
         let out: Vec<u8> = Vec::default();
-        let mut writer = TripleWriter::new(&format.into(), out);
+        let mut writer = TripleWriter::new(&format.into(), out).with_prefixes(context);
 
         for atom in &atoms {
-            let triple = atom.into();
+            let quad = atom.into();
 
-            writer.write(&triple).map_err(|e| {
-                StdError::serialize_err(
-                    "triple",
-                    format!("Error writing triple {}: {}", &triple, e),
-                )
+            writer.write_quad(&quad).map_err(|e| {
+                StdError::serialize_err("triple", format!("Error writing triple {}: {}", &quad, e))
             })?;
         }
         writer
             .finish()
+            .map(|out| (out, page_info))
             .map_err(|e| StdError::serialize_err("triple", format!("Error writing triple: {e}")))
     }
 }
@@ -428,23 +1214,29 @@ pub mod util {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::StoreError;
+    use crate::cursor;
+    use crate::error::{RDFParseError, StoreError};
     use crate::msg::ExecuteMsg::{DeleteData, InsertData};
     use crate::msg::Node::{BlankNode, NamedNode};
     use crate::msg::SimpleWhereCondition::TriplePattern;
     use crate::msg::IRI::{Full, Prefixed};
     use crate::msg::{
-        ConstructQuery, ConstructResponse, DescribeQuery, DescribeResponse, Head, Literal, Prefix,
-        Results, SelectItem, SelectQuery, SelectResponse, StoreLimitsInput,
-        StoreLimitsInputBuilder, StoreResponse, Value, VarOrNamedNode, VarOrNamedNodeOrLiteral,
-        VarOrNode, VarOrNodeOrLiteral, WhereCondition,
+        AccessPath, AccessScope, AskQuery, AskResponse, ConstructQuery, ConstructResponse,
+        DescribeQuery, DescribeResponse, ExplainResponse, Head, Literal, NamedNodeOrLiteral,
+        OrderCondition, PageInfo, PlanNode, PlanOperation, PlanTerm, Prefix, PropertyPath, Results,
+        ResultsFormat, Role, SelectItem, SelectQuery, SelectResponse, SparqlResponse,
+        StoreLimitsInput, StoreLimitsInputBuilder, StoreResponse, TripleConstructTemplate,
+        TripleDeleteTemplate, Value, VarOrNamedNode, VarOrNamedNodeOrLiteral, VarOrNamedNodeOrPath,
+        VarOrNode, VarOrNodeOrLiteral, WhereClause, WhereCondition,
     };
+    use crate::rdf::{XSD_BOOLEAN, XSD_DATE_TIME, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER};
     use crate::state::{
-        namespaces, triples, Namespace, Node, Object, StoreLimits, StoreStat, Subject, Triple,
+        namespaces, triples, Namespace, NamespaceStat, Node, Object, StoreLimits, StoreStat,
+        Subject, Triple, BATCHES, GRANTS, NAMESPACE_STATS, PREDICATE_STATS,
     };
     use crate::{msg, state};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_json, Addr, Attribute, Order, Uint128};
+    use cosmwasm_std::{coins, from_json, Addr, Attribute, Order, StdError, Timestamp, Uint128};
     use cw_utils::PaymentError;
     use cw_utils::PaymentError::NonPayable;
     use std::collections::BTreeMap;
@@ -466,7 +1258,10 @@ mod tests {
                 max_query_variable_count: 5,
                 max_insert_data_byte_size: Uint128::from(6u128),
                 max_insert_data_triple_count: Uint128::from(7u128),
+                max_query_path_depth: 8,
             },
+            strict_typing: true,
+            versioning: false,
         };
 
         let info = mock_info("owner", &[]);
@@ -485,6 +1280,7 @@ mod tests {
                 max_query_variable_count: 5,
                 max_insert_data_byte_size: Uint128::from(6u128),
                 max_insert_data_triple_count: Uint128::from(7u128),
+                max_query_path_depth: 8,
             }
         );
         assert_eq!(
@@ -495,6 +1291,7 @@ mod tests {
                 byte_size: Uint128::zero(),
             }
         );
+        assert!(store.strict_typing);
 
         assert_eq!(NAMESPACE_KEY_INCREMENT.load(&deps.storage).unwrap(), 0u128);
         assert_eq!(
@@ -559,10 +1356,6 @@ mod tests {
                 format: Some(DataFormat::NTriples),
                 data: read_test_data("sample.nt"),
             },
-            InsertData {
-                format: Some(DataFormat::NQuads),
-                data: read_test_data("sample.nq"),
-            },
             InsertData {
                 format: None,
                 data: read_test_data("sample.ttl"),
@@ -598,6 +1391,20 @@ mod tests {
                     .count(),
                 40
             );
+            assert_eq!(
+                PREDICATE_STATS
+                    .range(&deps.storage, None, None, Order::Ascending)
+                    .map(|res| res.unwrap().1)
+                    .sum::<u128>(),
+                40
+            );
+            assert_eq!(
+                NAMESPACE_STATS.load(&deps.storage, 0u128).unwrap(),
+                NamespaceStat {
+                    subject_count: 3,
+                    object_count: 2,
+                }
+            );
             assert_eq!(
                 STORE.load(&deps.storage).unwrap().stat,
                 StoreStat {
@@ -658,11 +1465,92 @@ mod tests {
                         namespace: 0u128,
                         value: "97ff7e16-c08d-47be-8475-211016c82e33".to_string(),
                     }),
+                    graph: None,
+                    validity: vec![],
                 }
             )
         }
     }
 
+    #[test]
+    fn proper_insert_quads() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::NQuads),
+                data: read_test_data("sample.nq"),
+            },
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "insert"),
+                Attribute::new("triple_count", "40"),
+            ]
+        );
+
+        // The graph name counts in the byte size, along with its namespace.
+        assert_eq!(
+            STORE.load(&deps.storage).unwrap().stat,
+            StoreStat {
+                triple_count: 40u128.into(),
+                namespace_count: 18u128.into(),
+                byte_size: 8750u128.into(),
+            },
+        );
+
+        let graph = Node {
+            namespace: 3u128,
+            value: "cognigraph".to_string(),
+        };
+        let subject = Subject::Named(Node {
+            namespace: 0u128,
+            value: "97ff7e16-c08d-47be-8475-211016c82e33".to_string(),
+        });
+        let triple = triples()
+            .load(
+                &deps.storage,
+                (
+                    Object::Named(Node {
+                        namespace: 2u128,
+                        value: "NamedIndividual".to_string(),
+                    })
+                    .as_hash()
+                    .as_bytes(),
+                    Node {
+                        namespace: 1u128,
+                        value: "type".to_string(),
+                    }
+                    .key(),
+                    subject.key_in_graph(Some(&graph)),
+                ),
+            )
+            .unwrap();
+        assert_eq!(triple.graph, Some(graph.clone()));
+        assert_eq!(
+            triples()
+                .idx
+                .graph_and_subject
+                .sub_prefix(graph.key())
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            40
+        );
+    }
+
     #[test]
     fn proper_insert_blank_nodes() {
         let mut deps = mock_dependencies();
@@ -875,7 +1763,11 @@ mod tests {
                 deps.as_mut(),
                 mock_env(),
                 info.clone(),
-                InstantiateMsg { limits: case.0 },
+                InstantiateMsg {
+                    limits: case.0,
+                    strict_typing: false,
+                    versioning: false,
+                },
             )
             .unwrap();
 
@@ -891,59 +1783,328 @@ mod tests {
     }
 
     #[test]
-    fn proper_delete() {
-        let id = "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473";
+    fn insert_typed_literals() {
+        let data = r#"@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+<https://example.org/a> <https://example.org/value> "010"^^xsd:integer .
+<https://example.org/b> <https://example.org/value> "9.50"^^xsd:decimal .
+<https://example.org/c> <https://example.org/value> "1.5e1"^^xsd:double .
+"#;
+        let invalid = format!(
+            "{}<https://example.org/d> <https://example.org/value> \"ten\"^^xsd:integer .\n",
+            data
+        );
         let cases = vec![
             (
-                DeleteData {
-                    prefixes: vec![],
-                    delete: vec![msg::TripleDeleteTemplate {
-                        subject: VarOrNamedNode::NamedNode(Full(
-                            "https://ontology.axone.space/dataverse/dataspace/metadata/unknown"
-                                .to_string(),
-                        )),
-                        predicate: VarOrNamedNode::NamedNode(Full(
-                            "https://ontology.axone.space/core/hasTopic".to_string(),
-                        )),
-                        object: VarOrNamedNodeOrLiteral::NamedNode(Full(
-                            "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
-                        )),
-                    }],
-                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                        subject: VarOrNode::Node(NamedNode(Full(
-                            "https://ontology.axone.space/dataverse/dataspace/metadata/unknown"
-                                .to_string(),
-                        ))),
-                        predicate: VarOrNamedNode::NamedNode(Full(
-                            "https://ontology.axone.space/core/hasTopic".to_string(),
-                        )),
-                        object: VarOrNodeOrLiteral::Node(NamedNode(Full(
-                            "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
-                        ))),
-                    }))],
-                },
-                0,
-                0,
-                Uint128::from(7190u128),
+                false,
+                invalid.as_str(),
+                Ok(vec![
+                    ("https://example.org/a", "010", XSD_INTEGER),
+                    ("https://example.org/c", "1.5e1", XSD_DOUBLE),
+                ]),
             ),
             (
-                DeleteData {
-                    prefixes: vec![],
-                    delete: vec![msg::TripleDeleteTemplate {
-                        subject: VarOrNamedNode::NamedNode(Full(id.to_string())),
-                        predicate: VarOrNamedNode::NamedNode(Full(
-                            "https://ontology.axone.space/core/hasTopic".to_string(),
-                        )),
-                        object: VarOrNamedNodeOrLiteral::NamedNode(Full(
-                            "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
-                        )),
-                    }],
-                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                        subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                        predicate: VarOrNamedNode::NamedNode(Full(
-                            "https://ontology.axone.space/core/hasTopic".to_string(),
-                        )),
-                        object: VarOrNodeOrLiteral::Node(NamedNode(Full(
+                true,
+                data,
+                Ok(vec![
+                    ("https://example.org/a", "10", XSD_INTEGER),
+                    ("https://example.org/c", "1.5E1", XSD_DOUBLE),
+                ]),
+            ),
+            (
+                true,
+                invalid.as_str(),
+                Err(ContractError::Std(StdError::generic_err(
+                    "Invalid lexical form 'ten' for datatype <http://www.w3.org/2001/XMLSchema#integer>",
+                ))),
+            ),
+        ];
+
+        for (strict_typing, data, expected) in cases {
+            let mut deps = mock_dependencies();
+
+            let info = mock_info("owner", &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    limits: StoreLimitsInput::default(),
+                    strict_typing,
+                    versioning: false,
+                },
+            )
+            .unwrap();
+
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                InsertData {
+                    format: Some(DataFormat::Turtle),
+                    data: Binary::from(data.as_bytes()),
+                },
+            );
+
+            let expected = match expected {
+                Ok(expected) => expected,
+                Err(err) => {
+                    assert_eq!(res.err(), Some(err));
+                    continue;
+                }
+            };
+            assert!(res.is_ok());
+
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![],
+                        select: vec![
+                            SelectItem::Variable("s".to_string()),
+                            SelectItem::Variable("v".to_string()),
+                        ],
+                        r#where: vec![
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                    "https://example.org/value".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("v".to_string()),
+                            })),
+                            WhereCondition::Filter(msg::Expression::Greater(
+                                Box::new(msg::Expression::Variable("v".to_string())),
+                                Box::new(msg::Expression::Literal(Literal::TypedValue {
+                                    value: "9.5".to_string(),
+                                    datatype: Full(XSD_DECIMAL.to_string()),
+                                })),
+                            )),
+                        ],
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                        group_by: vec![],
+                        having: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            assert!(res.is_ok());
+            assert_eq!(
+                from_json::<SelectResponse>(&res.unwrap())
+                    .unwrap()
+                    .results
+                    .bindings,
+                expected
+                    .into_iter()
+                    .map(|(s, v, datatype)| BTreeMap::from([
+                        (
+                            "s".to_string(),
+                            Value::URI {
+                                value: Full(s.to_string())
+                            }
+                        ),
+                        (
+                            "v".to_string(),
+                            Value::Literal {
+                                value: v.to_string(),
+                                lang: None,
+                                datatype: Some(Full(datatype.to_string())),
+                            }
+                        ),
+                    ]))
+                    .collect::<Vec<_>>()
+            );
+
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![],
+                        select: vec![SelectItem::Variable("s".to_string())],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                "https://example.org/value".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Literal(Literal::TypedValue {
+                                value: if strict_typing { "+9.500" } else { "9.50" }.to_string(),
+                                datatype: Full(XSD_DECIMAL.to_string()),
+                            }),
+                        }))],
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                        group_by: vec![],
+                        having: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            assert!(res.is_ok());
+            assert_eq!(
+                from_json::<SelectResponse>(&res.unwrap())
+                    .unwrap()
+                    .results
+                    .bindings,
+                vec![BTreeMap::from([(
+                    "s".to_string(),
+                    Value::URI {
+                        value: Full("https://example.org/b".to_string())
+                    }
+                )])]
+            );
+        }
+    }
+
+    #[test]
+    fn delete_typed_literals() {
+        let data = r#"@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+<https://example.org/a> <https://example.org/value> "010"^^xsd:integer .
+<https://example.org/b> <https://example.org/value> "2022-01-01T00:00:00+00:00"^^xsd:dateTime .
+"#;
+        let literal = |value: &str, datatype: &str| {
+            VarOrNamedNodeOrLiteral::Literal(Literal::TypedValue {
+                value: value.to_string(),
+                datatype: Full(datatype.to_string()),
+            })
+        };
+
+        // Without strict typing, the literals are stored as is, the way they've always been.
+        for strict_typing in [false, true] {
+            let mut deps = mock_dependencies();
+
+            let info = mock_info("owner", &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    limits: StoreLimitsInput::default(),
+                    strict_typing,
+                    versioning: false,
+                },
+            )
+            .unwrap();
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InsertData {
+                    format: Some(DataFormat::Turtle),
+                    data: Binary::from(data.as_bytes()),
+                },
+            )
+            .unwrap();
+
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                DeleteData {
+                    prefixes: vec![],
+                    delete: vec![
+                        msg::TripleDeleteTemplate {
+                            subject: VarOrNamedNode::NamedNode(Full(
+                                "https://example.org/a".to_string(),
+                            )),
+                            predicate: VarOrNamedNode::NamedNode(Full(
+                                "https://example.org/value".to_string(),
+                            )),
+                            object: literal("010", XSD_INTEGER),
+                            graph: None,
+                        },
+                        msg::TripleDeleteTemplate {
+                            subject: VarOrNamedNode::NamedNode(Full(
+                                "https://example.org/b".to_string(),
+                            )),
+                            predicate: VarOrNamedNode::NamedNode(Full(
+                                "https://example.org/value".to_string(),
+                            )),
+                            object: literal("2022-01-01T00:00:00+00:00", XSD_DATE_TIME),
+                            graph: None,
+                        },
+                    ],
+                    r#where: vec![],
+                },
+            );
+
+            assert!(res.is_ok());
+            assert_eq!(
+                res.unwrap().attributes,
+                vec![
+                    Attribute::new("action", "delete"),
+                    Attribute::new("triple_count", "2")
+                ]
+            );
+            assert_eq!(
+                STORE.load(&deps.storage).unwrap().stat.triple_count,
+                Uint128::zero()
+            );
+        }
+    }
+
+    #[test]
+    fn proper_delete() {
+        let id = "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473";
+        let cases = vec![
+            (
+                DeleteData {
+                    prefixes: vec![],
+                    delete: vec![msg::TripleDeleteTemplate {
+                        subject: VarOrNamedNode::NamedNode(Full(
+                            "https://ontology.axone.space/dataverse/dataspace/metadata/unknown"
+                                .to_string(),
+                        )),
+                        predicate: VarOrNamedNode::NamedNode(Full(
+                            "https://ontology.axone.space/core/hasTopic".to_string(),
+                        )),
+                        object: VarOrNamedNodeOrLiteral::NamedNode(Full(
+                            "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
+                        )),
+                        graph: None,
+                    }],
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Node(NamedNode(Full(
+                            "https://ontology.axone.space/dataverse/dataspace/metadata/unknown"
+                                .to_string(),
+                        ))),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                            "https://ontology.axone.space/core/hasTopic".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Node(NamedNode(Full(
+                            "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
+                        ))),
+                    }))],
+                },
+                0,
+                0,
+                Uint128::from(7190u128),
+            ),
+            (
+                DeleteData {
+                    prefixes: vec![],
+                    delete: vec![msg::TripleDeleteTemplate {
+                        subject: VarOrNamedNode::NamedNode(Full(id.to_string())),
+                        predicate: VarOrNamedNode::NamedNode(Full(
+                            "https://ontology.axone.space/core/hasTopic".to_string(),
+                        )),
+                        object: VarOrNamedNodeOrLiteral::NamedNode(Full(
+                            "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
+                        )),
+                        graph: None,
+                    }],
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                            "https://ontology.axone.space/core/hasTopic".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Node(NamedNode(Full(
                             "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
                         ))),
                     }))],
@@ -970,10 +2131,13 @@ mod tests {
                         object: VarOrNamedNodeOrLiteral::NamedNode(Prefixed(
                             "thesaurus:Test".to_string(),
                         )),
+                        graph: None,
                     }],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                        predicate: VarOrNamedNode::NamedNode(Prefixed("core:hasTopic".to_string())),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                            "core:hasTopic".to_string(),
+                        )),
                         object: VarOrNodeOrLiteral::Node(NamedNode(Prefixed(
                             "thesaurus:Test".to_string(),
                         ))),
@@ -999,10 +2163,13 @@ mod tests {
                         subject: VarOrNamedNode::NamedNode(Full(id.to_string())),
                         predicate: VarOrNamedNode::NamedNode(Prefixed("core:hasTopic".to_string())),
                         object: VarOrNamedNodeOrLiteral::Variable("o".to_string()),
+                        graph: None,
                     }],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                        predicate: VarOrNamedNode::NamedNode(Prefixed("core:hasTopic".to_string())),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                            "core:hasTopic".to_string(),
+                        )),
                         object: VarOrNodeOrLiteral::Variable("o".to_string()),
                     }))],
                 },
@@ -1017,10 +2184,11 @@ mod tests {
                         subject: VarOrNamedNode::NamedNode(Full(id.to_string())),
                         predicate: VarOrNamedNode::Variable("p".to_string()),
                         object: VarOrNamedNodeOrLiteral::Variable("o".to_string()),
+                        graph: None,
                     }],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                        predicate: VarOrNamedNode::Variable("p".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                         object: VarOrNodeOrLiteral::Variable("o".to_string()),
                     }))],
                 },
@@ -1034,7 +2202,7 @@ mod tests {
                     delete: vec![],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                        predicate: VarOrNamedNode::Variable("p".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                         object: VarOrNodeOrLiteral::Variable("o".to_string()),
                     }))],
                 },
@@ -1048,7 +2216,7 @@ mod tests {
                     delete: vec![],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Variable("s".to_string()),
-                        predicate: VarOrNamedNode::Variable("p".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                         object: VarOrNodeOrLiteral::Variable("0".to_string()),
                     }))],
                 },
@@ -1074,6 +2242,7 @@ mod tests {
                         object: VarOrNamedNodeOrLiteral::NamedNode(Prefixed(
                             "thesaurus:Test".to_string(),
                         )),
+                        graph: None,
                     }],
                     r#where: vec![],
                 },
@@ -1081,6 +2250,65 @@ mod tests {
                 0,
                 Uint128::from(7005u128),
             ),
+            (
+                DeleteData {
+                    prefixes: vec![
+                        Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        },
+                        Prefix {
+                            prefix: "thesaurus".to_string(),
+                            namespace: "https://ontology.axone.space/thesaurus/topic/".to_string(),
+                        },
+                    ],
+                    delete: vec![msg::TripleDeleteTemplate {
+                        subject: VarOrNamedNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNode::NamedNode(Prefixed("core:hasTag".to_string())),
+                        object: VarOrNamedNodeOrLiteral::Variable("o".to_string()),
+                        graph: None,
+                    }],
+                    r#where: vec![WhereCondition::Union(vec![
+                        vec![
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTag".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                            })),
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTopic".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Node(NamedNode(Prefixed(
+                                    "thesaurus:Test".to_string(),
+                                ))),
+                            })),
+                        ],
+                        vec![
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTag".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                            })),
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasLicense".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("l".to_string()),
+                            })),
+                        ],
+                    ])],
+                },
+                3,
+                0,
+                Uint128::from(6777u128),
+            ),
         ];
 
         for case in cases {
@@ -1131,6 +2359,13 @@ mod tests {
                     .count(),
                 40 - case.1
             );
+            assert_eq!(
+                PREDICATE_STATS
+                    .range(&deps.storage, None, None, Order::Ascending)
+                    .map(|res| res.unwrap().1)
+                    .sum::<u128>(),
+                40u128 - u128::try_from(case.1).unwrap()
+            );
             assert_eq!(
                 namespaces()
                     .range_raw(&deps.storage, None, None, Order::Ascending)
@@ -1158,10 +2393,11 @@ mod tests {
                         object: VarOrNamedNodeOrLiteral::NamedNode(Full(
                             "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
                         )),
+                        graph: None,
                     }],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Node(NamedNode(Prefixed("foo:bar".to_string()))),
-                        predicate: VarOrNamedNode::NamedNode(Full(
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Full(
                             "https://ontology.axone.space/core/hasTopic".to_string(),
                         )),
                         object: VarOrNodeOrLiteral::Node(NamedNode(Full(
@@ -1180,17 +2416,59 @@ mod tests {
                         )),
                         predicate: VarOrNamedNode::Variable("z".to_string()),
                         object: VarOrNamedNodeOrLiteral::Variable("o".to_string()),
+                        graph: None,
                     }],
                     r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
                         subject: VarOrNode::Node(NamedNode(Full(
                             "https://ontology.axone.space/thesaurus/topic/Test".to_string(),
                         ))),
-                        predicate: VarOrNamedNode::Variable("p".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                         object: VarOrNodeOrLiteral::Variable("o".to_string()),
                     }))],
                 },
                 expected: StdError::generic_err("Selected variable not found in query").into(),
             },
+            TC {
+                command: DeleteData {
+                    prefixes: vec![],
+                    delete: vec![],
+                    r#where: vec![WhereCondition::Union(vec![
+                        vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                    ])],
+                },
+                expected: StdError::generic_err(
+                    "Union condition can't be used as template, a template must be provided",
+                )
+                .into(),
+            },
+            TC {
+                command: DeleteData {
+                    prefixes: vec![],
+                    delete: vec![],
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Path(PropertyPath::OneOrMore(Box::new(
+                            PropertyPath::NamedNode(Full(
+                                "https://ontology.axone.space/core/describes".to_string(),
+                            )),
+                        ))),
+                        object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                    }))],
+                },
+                expected: StdError::generic_err(
+                    "Property path can't be used as template, a template must be provided",
+                )
+                .into(),
+            },
         ];
 
         for case in cases {
@@ -1224,30 +2502,435 @@ mod tests {
     }
 
     #[test]
-    fn proper_store() {
-        let mut deps = mock_dependencies();
-        STORE
-            .save(
-                deps.as_mut().storage,
-                &Store {
-                    owner: Addr::unchecked("owner"),
-                    limits: StoreLimits {
-                        max_triple_count: 1u128.into(),
-                        max_byte_size: 2u128.into(),
-                        max_triple_byte_size: 3u128.into(),
+    fn proper_modify() {
+        let ex = |v: &str| Full(format!("https://example.org/{v}"));
+        let pattern = |s: &str, p: &str, o: &str| {
+            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::NamedNode(ex(p)),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let template = |s: VarOrNode, p: &str, o: VarOrNodeOrLiteral| TripleConstructTemplate {
+            subject: s,
+            predicate: VarOrNamedNode::NamedNode(ex(p)),
+            object: o,
+            graph: None,
+        };
+        let setup = |limits: StoreLimitsInput| {
+            let mut deps = mock_dependencies();
+            let info = mock_info("owner", &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    limits,
+                    strict_typing: false,
+                    versioning: false,
+                },
+            )
+            .unwrap();
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                InsertData {
+                    format: Some(DataFormat::NTriples),
+                    data: Binary::from(
+                        "<https://example.org/a> <https://example.org/name> \"A\" .
+<https://example.org/b> <https://example.org/name> \"B\" .
+_:x <https://example.org/name> \"X\" .
+"
+                        .as_bytes(),
+                    ),
+                },
+            )
+            .unwrap();
+            deps
+        };
+
+        let mut deps = setup(StoreLimitsInput::default());
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Modify {
+                prefixes: vec![],
+                delete: vec![TripleDeleteTemplate {
+                    subject: VarOrNamedNode::Variable("s".to_string()),
+                    predicate: VarOrNamedNode::NamedNode(ex("name")),
+                    object: VarOrNamedNodeOrLiteral::Variable("n".to_string()),
+                    graph: None,
+                }],
+                insert: vec![
+                    template(
+                        VarOrNode::Variable("s".to_string()),
+                        "label",
+                        VarOrNodeOrLiteral::Variable("n".to_string()),
+                    ),
+                    template(
+                        VarOrNode::Variable("s".to_string()),
+                        "tag",
+                        VarOrNodeOrLiteral::Node(BlankNode("t".to_string())),
+                    ),
+                    template(
+                        VarOrNode::Node(BlankNode("t".to_string())),
+                        "value",
+                        VarOrNodeOrLiteral::Literal(Literal::Simple("t".to_string())),
+                    ),
+                ],
+                r#where: vec![pattern("s", "name", "n")],
+            },
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "modify"),
+                Attribute::new("deleted_count", "3"),
+                Attribute::new("inserted_count", "9"),
+            ]
+        );
+        assert_eq!(
+            STORE.load(&deps.storage).unwrap().stat.triple_count,
+            Uint128::new(9)
+        );
+        // A new blank node is issued for each solution.
+        assert_eq!(
+            BLANK_NODE_IDENTIFIER_COUNTER.load(&deps.storage).unwrap(),
+            4u128
+        );
+        // The blank node bound by the WHERE clause keeps its identity.
+        assert!(triples()
+            .range(&deps.storage, None, None, Order::Ascending)
+            .map(|res| res.unwrap().1)
+            .any(|t| t.subject == Subject::Blank(0u128) && t.predicate.value == "label"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Select {
+                query: SelectQuery {
+                    prefixes: vec![],
+                    select: vec![SelectItem::Variable("l".to_string())],
+                    r#where: vec![
+                        pattern("s", "label", "l"),
+                        pattern("s", "tag", "t"),
+                        pattern("t", "value", "v"),
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![OrderCondition::Asc("l".to_string())],
+                    limit: None,
+                    after: None,
+                },
+                format: None,
+                as_of_height: None,
+            },
+        );
+        assert_eq!(
+            from_json::<SelectResponse>(&res.unwrap())
+                .unwrap()
+                .results
+                .bindings,
+            ["A", "B", "X"]
+                .iter()
+                .map(|l| BTreeMap::from([(
+                    "l".to_string(),
+                    Value::Literal {
+                        value: l.to_string(),
+                        lang: None,
+                        datatype: None,
+                    }
+                )]))
+                .collect::<Vec<_>>()
+        );
+
+        // The limits apply to the net result of the operation.
+        let modify = |insert_count: usize| ExecuteMsg::Modify {
+            prefixes: vec![],
+            delete: vec![TripleDeleteTemplate {
+                subject: VarOrNamedNode::NamedNode(ex("a")),
+                predicate: VarOrNamedNode::NamedNode(ex("name")),
+                object: VarOrNamedNodeOrLiteral::Variable("n".to_string()),
+                graph: None,
+            }],
+            insert: ["label", "title"][..insert_count]
+                .iter()
+                .map(|p| {
+                    template(
+                        VarOrNode::Node(NamedNode(ex("a"))),
+                        p,
+                        VarOrNodeOrLiteral::Variable("n".to_string()),
+                    )
+                })
+                .collect(),
+            r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Node(NamedNode(ex("a"))),
+                predicate: VarOrNamedNodeOrPath::NamedNode(ex("name")),
+                object: VarOrNodeOrLiteral::Variable("n".to_string()),
+            }))],
+        };
+        let limits = StoreLimitsInputBuilder::default()
+            .max_triple_count(3u128)
+            .build()
+            .unwrap();
+
+        let mut deps = setup(limits.clone());
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            modify(1),
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "modify"),
+                Attribute::new("deleted_count", "1"),
+                Attribute::new("inserted_count", "1"),
+            ]
+        );
+
+        let mut deps = setup(limits);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            modify(2),
+        );
+        assert_eq!(
+            res.err(),
+            Some(ContractError::from(StoreError::TripleCount(3u128.into())))
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not-owner", &[]),
+            modify(1),
+        );
+        assert_eq!(res.err(), Some(ContractError::Unauthorized));
+    }
+
+    #[test]
+    fn proper_access_control() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        let insert = |data: &str| InsertData {
+            format: Some(DataFormat::NQuads),
+            data: Binary::from(data.as_bytes()),
+        };
+        let delete = |s: &str| DeleteData {
+            prefixes: vec![],
+            delete: vec![],
+            r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Node(NamedNode(Full(s.to_string()))),
+                predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+            }))],
+        };
+        let grant = |address: &str, role, scope| ExecuteMsg::GrantRole {
+            address: address.to_string(),
+            role,
+            scope,
+        };
+        let in_graph =
+            "<https://example.org/a> <https://example.org/p> \"1\" <https://example.org/g> .";
+        let in_ns = "<https://example.org/a> <https://example.org/p> \"2\" .";
+        let out_of_scopes = "<https://other.org/a> <https://example.org/p> \"3\" .";
+
+        let cases = vec![
+            // Only the owner manages the roles.
+            (
+                "writer",
+                grant("writer", Role::Writer, None),
+                Err(ContractError::Unauthorized),
+            ),
+            ("writer", insert(in_ns), Err(ContractError::Unauthorized)),
+            (
+                "owner",
+                grant(
+                    "writer",
+                    Role::Writer,
+                    Some(AccessScope::Graph("https://example.org/g".to_string())),
+                ),
+                Ok(vec![
+                    Attribute::new("action", "grant_role"),
+                    Attribute::new("address", "writer"),
+                ]),
+            ),
+            (
+                "owner",
+                grant(
+                    "writer",
+                    Role::Writer,
+                    Some(AccessScope::Namespace("https://example.org/".to_string())),
+                ),
+                Ok(vec![
+                    Attribute::new("action", "grant_role"),
+                    Attribute::new("address", "writer"),
+                ]),
+            ),
+            (
+                "writer",
+                insert(in_graph),
+                Ok(vec![
+                    Attribute::new("action", "insert"),
+                    Attribute::new("triple_count", "1"),
+                ]),
+            ),
+            (
+                "writer",
+                insert(in_ns),
+                Ok(vec![
+                    Attribute::new("action", "insert"),
+                    Attribute::new("triple_count", "1"),
+                ]),
+            ),
+            (
+                "writer",
+                insert(out_of_scopes),
+                Err(ContractError::Unauthorized),
+            ),
+            (
+                "owner",
+                insert(out_of_scopes),
+                Ok(vec![
+                    Attribute::new("action", "insert"),
+                    Attribute::new("triple_count", "1"),
+                ]),
+            ),
+            // The writer role doesn't allow to delete.
+            (
+                "writer",
+                delete("https://example.org/a"),
+                Err(ContractError::Unauthorized),
+            ),
+            (
+                "owner",
+                grant("deleter", Role::Deleter, None),
+                Ok(vec![
+                    Attribute::new("action", "grant_role"),
+                    Attribute::new("address", "deleter"),
+                ]),
+            ),
+            (
+                "deleter",
+                delete("https://other.org/a"),
+                Ok(vec![
+                    Attribute::new("action", "delete"),
+                    Attribute::new("triple_count", "1"),
+                ]),
+            ),
+            ("deleter", insert(in_ns), Err(ContractError::Unauthorized)),
+            (
+                "owner",
+                ExecuteMsg::RevokeRole {
+                    address: "writer".to_string(),
+                    role: Role::Writer,
+                    scope: Some(AccessScope::Namespace("https://example.org/".to_string())),
+                },
+                Ok(vec![
+                    Attribute::new("action", "revoke_role"),
+                    Attribute::new("address", "writer"),
+                ]),
+            ),
+            ("writer", insert(in_ns), Err(ContractError::Unauthorized)),
+            (
+                "owner",
+                ExecuteMsg::TransferOwnership {
+                    new_owner: "new-owner".to_string(),
+                },
+                Ok(vec![
+                    Attribute::new("action", "transfer_ownership"),
+                    Attribute::new("new_owner", "new-owner"),
+                ]),
+            ),
+            ("owner", insert(in_ns), Err(ContractError::Unauthorized)),
+            (
+                "new-owner",
+                insert(out_of_scopes),
+                Ok(vec![
+                    Attribute::new("action", "insert"),
+                    Attribute::new("triple_count", "1"),
+                ]),
+            ),
+        ];
+
+        for (sender, msg, expected) in cases {
+            let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg);
+            assert_eq!(res.map(|res| res.attributes), expected);
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Store {});
+        let store = from_json::<StoreResponse>(&res.unwrap()).unwrap();
+        assert_eq!(store.owner, "new-owner".to_string());
+        assert_eq!(
+            store.grants,
+            vec![
+                msg::Grant {
+                    address: "deleter".to_string(),
+                    role: Role::Deleter,
+                    scope: None,
+                },
+                msg::Grant {
+                    address: "writer".to_string(),
+                    role: Role::Writer,
+                    scope: Some(AccessScope::Graph("https://example.org/g".to_string())),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn proper_store() {
+        let mut deps = mock_dependencies();
+        STORE
+            .save(
+                deps.as_mut().storage,
+                &Store {
+                    owner: Addr::unchecked("owner"),
+                    limits: StoreLimits {
+                        max_triple_count: 1u128.into(),
+                        max_byte_size: 2u128.into(),
+                        max_triple_byte_size: 3u128.into(),
                         max_query_limit: 4u32,
                         max_query_variable_count: 5u32,
                         max_insert_data_byte_size: 6u128.into(),
                         max_insert_data_triple_count: 7u128.into(),
+                        max_query_path_depth: 8u32,
                     },
                     stat: StoreStat {
                         triple_count: 1u128.into(),
                         namespace_count: 2u128.into(),
                         byte_size: 3u128.into(),
                     },
+                    strict_typing: true,
+                    versioning: false,
                 },
             )
             .unwrap();
+        GRANTS
+            .save(
+                deps.as_mut().storage,
+                &Addr::unchecked("writer"),
+                &vec![state::Grant {
+                    role: state::Role::Writer,
+                    scope: Some(state::AccessScope::Graph(
+                        "https://example.org/g".to_string(),
+                    )),
+                }],
+            )
+            .unwrap();
 
         let res = query(deps.as_ref(), mock_env(), QueryMsg::Store {});
         assert!(res.is_ok());
@@ -1263,12 +2946,20 @@ mod tests {
                     max_query_variable_count: 5u32,
                     max_insert_data_byte_size: 6u128.into(),
                     max_insert_data_triple_count: 7u128.into(),
+                    max_query_path_depth: 8u32,
                 },
                 stat: msg::StoreStat {
                     triple_count: 1u128.into(),
                     namespace_count: 2u128.into(),
                     byte_size: 3u128.into(),
                 },
+                strict_typing: true,
+                versioning: false,
+                grants: vec![msg::Grant {
+                    address: "writer".to_string(),
+                    role: Role::Writer,
+                    scope: Some(AccessScope::Graph("https://example.org/g".to_string())),
+                }],
             }
         );
     }
@@ -1301,13 +2992,18 @@ mod tests {
                     r#where: vec![WhereCondition::Simple(TriplePattern(
                         msg::TriplePattern {
                             subject: VarOrNode::Variable("a".to_string()),
-                            predicate: VarOrNamedNode::NamedNode(Full(
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Full(
                                 "https://ontology.axone.space/core/hasDescription".to_string(),
                             )),
                             object: VarOrNodeOrLiteral::Variable("b".to_string()),
                         },
                     ))],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
                     head: Head {
@@ -1335,13 +3031,13 @@ mod tests {
                                 (
                                     "a".to_string(),
                                     Value::URI {
-                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e".to_string())
+                                        value: Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())
                                     }
                                 ),
                                 (
                                     "b".to_string(),
                                     Value::Literal {
-                                        value: "Un Dataset de test.".to_string(),
+                                        value: "Un Data Space de test.".to_string(),
                                         lang: Some("fr".to_string()),
                                         datatype: None,
                                     }
@@ -1357,8 +3053,8 @@ mod tests {
                                 (
                                     "b".to_string(),
                                     Value::Literal {
-                                        value: "A test Dataset.".to_string(),
-                                        lang: Some("en".to_string()),
+                                        value: "Un Dataset de test.".to_string(),
+                                        lang: Some("fr".to_string()),
                                         datatype: None,
                                     }
                                 )
@@ -1367,20 +3063,22 @@ mod tests {
                                 (
                                     "a".to_string(),
                                     Value::URI {
-                                        value: Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e".to_string())
                                     }
                                 ),
                                 (
                                     "b".to_string(),
                                     Value::Literal {
-                                        value: "Un Data Space de test.".to_string(),
-                                        lang: Some("fr".to_string()),
+                                        value: "A test Dataset.".to_string(),
+                                        lang: Some("en".to_string()),
                                         datatype: None,
                                     }
                                 )
                             ]),
                         ],
                     },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111115".to_string() },
+                    data: None,
                 },
             ),
             (
@@ -1392,13 +3090,18 @@ mod tests {
                     r#where: vec![WhereCondition::Simple(TriplePattern(
                         msg::TriplePattern {
                             subject: VarOrNode::Variable("a".to_string()),
-                            predicate: VarOrNamedNode::NamedNode(Prefixed(
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
                                 "core:hasDescription".to_string(),
                             )),
                             object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString { value: "A test Dataset.".to_string(), language: "en".to_string() }),
                         },
                     ))],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
                     head: Head {
@@ -1416,6 +3119,8 @@ mod tests {
                             ])
                         ],
                     },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
                 },
             ),
             (
@@ -1427,11 +3132,16 @@ mod tests {
                     r#where: vec![WhereCondition::Simple(TriplePattern(
                         msg::TriplePattern {
                             subject: VarOrNode::Node(NamedNode(Full("https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e".to_string()))),
-                            predicate: VarOrNamedNode::Variable("a".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("a".to_string()),
                             object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString { value: "A test Dataset.".to_string(), language: "en".to_string() }),
                         },
                     ))],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
                     head: Head {
@@ -1449,227 +3159,372 @@ mod tests {
                             ])
                         ],
                     },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
                 },
             ),
-        ];
-
-        let mut deps = mock_dependencies();
-
-        let info = mock_info("owner", &[]);
-        instantiate(
-            deps.as_mut(),
-            mock_env(),
-            info.clone(),
-            InstantiateMsg::default(),
-        )
-        .unwrap();
-
-        execute(
-            deps.as_mut(),
-            mock_env(),
-            info,
-            InsertData {
-                format: Some(DataFormat::RDFXml),
-                data: read_test_data("sample.rdf.xml"),
-            },
-        )
-        .unwrap();
-
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), QueryMsg::Select { query: q });
-            assert!(res.is_ok());
-
-            let result = from_json::<SelectResponse>(&res.unwrap()).unwrap();
-            assert_eq!(result, expected);
-        }
-    }
-
-    #[test]
-    fn proper_select_blank_nodes() {
-        let cases = vec![
             (
                 SelectQuery {
                     prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
-                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    select: vec![
+                        SelectItem::Variable("a".to_string()),
+                        SelectItem::Variable("l".to_string()),
+                    ],
                     r#where: vec![
                         WhereCondition::Simple(TriplePattern(
                             msg::TriplePattern {
                                 subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasTemporalCoverage".to_string(),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasPublisher".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Node(BlankNode("a".to_string())),
+                                object: VarOrNodeOrLiteral::Literal(Literal::Simple("AXONE".to_string())),
                             },
                         )),
-                        WhereCondition::Simple(TriplePattern(
+                        WhereCondition::Optional(vec![WhereCondition::Simple(TriplePattern(
                             msg::TriplePattern {
-                                subject: VarOrNode::Node(BlankNode("a".to_string())),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasStartDate".to_string(),
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasLicense".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("l".to_string()),
                             },
-                        ))],
+                        ))]),
+                    ],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
-                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    head: Head {
+                        vars: vec!["a".to_string(), "l".to_string()],
+                    },
                     results: Results {
                         bindings: vec![
                             BTreeMap::from([
                                 (
                                     "a".to_string(),
                                     Value::URI {
-                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                        value: Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())
                                     }
                                 ),
+                            ]),
+                            BTreeMap::from([
                                 (
-                                    "b".to_string(),
-                                    Value::Literal {
-                                        value: "2022-01-01T00:00:00+00:00".to_string(),
-                                        lang: None,
-                                        datatype: Some(Full("http://www.w3.org/2001/XMLSchema#dateTime".to_string())),
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e".to_string())
                                     }
-                                )
-                            ])
-                        ],
-                    },
-                },
-            ),
+                                ),
+                                (
+                                    "l".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/thesaurus/license/LO-FR-1_0".to_string())
+                                    }
+                                ),
+                            ]),
+                        ],
+                    },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111113".to_string() },
+                    data: None,
+                },
+            ),
             (
                 SelectQuery {
                     prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
-                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    select: vec![
+                        SelectItem::Variable("a".to_string()),
+                    ],
+                    r#where: vec![
+                        WhereCondition::Union(vec![
+                            vec![WhereCondition::Simple(TriplePattern(
+                                msg::TriplePattern {
+                                    subject: VarOrNode::Variable("a".to_string()),
+                                    predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                        "core:hasLicense".to_string(),
+                                    )),
+                                    object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                                },
+                            ))],
+                            vec![WhereCondition::Simple(TriplePattern(
+                                msg::TriplePattern {
+                                    subject: VarOrNode::Variable("a".to_string()),
+                                    predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                        "core:hasRegistrar".to_string(),
+                                    )),
+                                    object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                                },
+                            ))],
+                        ]),
+                    ],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
+                },
+                SelectResponse {
+                    head: Head {
+                        vars: vec!["a".to_string()],
+                    },
+                    results: Results {
+                        bindings: vec![
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e".to_string())
+                                    }
+                                ),
+                            ]),
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33".to_string())
+                                    }
+                                ),
+                            ]),
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde".to_string())
+                                    }
+                                ),
+                            ]),
+                        ],
+                    },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111114".to_string() },
+                    data: None,
+                },
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: q,
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            assert!(res.is_ok());
+
+            let result = from_json::<SelectResponse>(&res.unwrap()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn proper_select_filter() {
+        let cases = vec![
+            (
+                SelectQuery {
+                    prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                    select: vec![
+                        SelectItem::Variable("a".to_string()),
+                        SelectItem::Variable("b".to_string()),
+                    ],
                     r#where: vec![
+                        WhereCondition::Filter(msg::Expression::And(vec![
+                            msg::Expression::Equal(
+                                Box::new(msg::Expression::Lang(Box::new(msg::Expression::Variable("b".to_string())))),
+                                Box::new(msg::Expression::Literal(Literal::Simple("en".to_string()))),
+                            ),
+                            msg::Expression::Contains(
+                                Box::new(msg::Expression::Variable("b".to_string())),
+                                Box::new(msg::Expression::Literal(Literal::Simple("Dataset".to_string()))),
+                            ),
+                        ])),
                         WhereCondition::Simple(TriplePattern(
                             msg::TriplePattern {
                                 subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasTemporalCoverage".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Variable("blank".to_string()),
-                            },
-                        )),
-                        WhereCondition::Simple(TriplePattern(
-                            msg::TriplePattern {
-                                subject: VarOrNode::Variable("blank".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasStartDate".to_string(),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasDescription".to_string(),
                                 )),
                                 object: VarOrNodeOrLiteral::Variable("b".to_string()),
                             },
-                        ))],
+                        )),
+                    ],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
-                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    head: Head {
+                        vars: vec!["a".to_string(), "b".to_string()],
+                    },
                     results: Results {
                         bindings: vec![
                             BTreeMap::from([
                                 (
                                     "a".to_string(),
                                     Value::URI {
-                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e".to_string())
                                     }
                                 ),
                                 (
                                     "b".to_string(),
                                     Value::Literal {
-                                        value: "2022-01-01T00:00:00+00:00".to_string(),
-                                        lang: None,
-                                        datatype: Some(Full("http://www.w3.org/2001/XMLSchema#dateTime".to_string())),
+                                        value: "A test Dataset.".to_string(),
+                                        lang: Some("en".to_string()),
+                                        datatype: None,
                                     }
-                                )
-                            ])
+                                ),
+                            ]),
                         ],
                     },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
                 },
             ),
             (
                 SelectQuery {
                     prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
-                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    select: vec![
+                        SelectItem::Variable("a".to_string()),
+                    ],
                     r#where: vec![
                         WhereCondition::Simple(TriplePattern(
                             msg::TriplePattern {
                                 subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasTemporalCoverage".to_string(),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasPublisher".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Node(BlankNode("blank1".to_string())),
+                                object: VarOrNodeOrLiteral::Literal(Literal::Simple("AXONE".to_string())),
                             },
                         )),
-                        WhereCondition::Simple(TriplePattern(
+                        WhereCondition::Optional(vec![WhereCondition::Simple(TriplePattern(
                             msg::TriplePattern {
-                                subject: VarOrNode::Node(BlankNode("blank2".to_string())),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasInformation".to_string(),
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasLicense".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("l".to_string()),
                             },
-                        ))],
+                        ))]),
+                        WhereCondition::Filter(msg::Expression::Not(Box::new(
+                            msg::Expression::Bound("l".to_string()),
+                        ))),
+                    ],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
-                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    head: Head {
+                        vars: vec!["a".to_string()],
+                    },
                     results: Results {
                         bindings: vec![
                             BTreeMap::from([
                                 (
                                     "a".to_string(),
                                     Value::URI {
-                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                        value: Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())
                                     }
                                 ),
-                                (
-                                    "b".to_string(),
-                                    Value::Literal {
-                                        value: "this is a dataset".to_string(),
-                                        lang: None,
-                                        datatype: None,
-                                    }
-                                )
-                            ])
+                            ]),
                         ],
                     },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
                 },
             ),
             (
                 SelectQuery {
-                    prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
-                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    prefixes: vec![],
+                    select: vec![
+                        SelectItem::Variable("o".to_string()),
+                    ],
                     r#where: vec![
                         WhereCondition::Simple(TriplePattern(
                             msg::TriplePattern {
-                                subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasTemporalCoverage".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
                             },
-                        ))],
+                        )),
+                        WhereCondition::Filter(msg::Expression::And(vec![
+                            msg::Expression::IsLiteral(Box::new(msg::Expression::Variable("o".to_string()))),
+                            msg::Expression::Regex(
+                                Box::new(msg::Expression::Variable("o".to_string())),
+                                "^un data".to_string(),
+                                Some("i".to_string()),
+                            ),
+                        ])),
+                    ],
+                    distinct: false,
+                    order_by: vec![],
                     limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
                 },
                 SelectResponse {
-                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    head: Head {
+                        vars: vec!["o".to_string()],
+                    },
                     results: Results {
                         bindings: vec![
                             BTreeMap::from([
                                 (
-                                    "a".to_string(),
-                                    Value::URI {
-                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                    "o".to_string(),
+                                    Value::Literal {
+                                        value: "Un Dataset de test.".to_string(),
+                                        lang: Some("fr".to_string()),
+                                        datatype: None,
                                     }
                                 ),
+                            ]),
+                            BTreeMap::from([
                                 (
-                                    "b".to_string(),
-                                    Value::BlankNode {
-                                        value: "b0".to_string(),
+                                    "o".to_string(),
+                                    Value::Literal {
+                                        value: "Un Data Space de test.".to_string(),
+                                        lang: Some("fr".to_string()),
+                                        datatype: None,
                                     }
-                                )
-                            ])
+                                ),
+                            ]),
                         ],
                     },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111113".to_string() },
+                    data: None,
                 },
             ),
         ];
@@ -1690,14 +3545,22 @@ mod tests {
             mock_env(),
             info,
             InsertData {
-                format: Some(DataFormat::Turtle),
-                data: read_test_data("blank-nodes.ttl"),
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
             },
         )
         .unwrap();
 
         for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), QueryMsg::Select { query: q });
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: q,
+                    format: None,
+                    as_of_height: None,
+                },
+            );
             assert!(res.is_ok());
 
             let result = from_json::<SelectResponse>(&res.unwrap()).unwrap();
@@ -1706,71 +3569,62 @@ mod tests {
     }
 
     #[test]
-    fn invalid_select() {
+    fn proper_select_order_distinct() {
+        let dataset = "https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e";
+        let dataspace = "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473";
+        let tag = |v: &str| Value::Literal {
+            value: v.to_string(),
+            lang: None,
+            datatype: None,
+        };
+        let uri = |v: &str| Value::URI {
+            value: Full(v.to_string()),
+        };
         let cases = vec![
             (
-                SelectQuery {
-                    prefixes: vec![],
-                    select: vec![
-                        SelectItem::Variable("a".to_string()),
-                        SelectItem::Variable("b".to_string()),
-                    ],
-                    r#where: vec![],
-                    limit: None,
-                },
-                Err(StdError::generic_err(
-                    "Maximum query variable count exceeded",
-                )),
+                "t",
+                "core:hasTag",
+                false,
+                vec![OrderCondition::Desc("t".to_string())],
+                None,
+                Ok(vec![tag("test"), tag("Test"), tag("AXONE")]),
             ),
             (
-                SelectQuery {
-                    prefixes: vec![],
-                    select: vec![],
-                    r#where: vec![],
-                    limit: Some(8000),
-                },
-                Err(StdError::generic_err("Maximum query limit exceeded")),
+                "t",
+                "core:hasTag",
+                false,
+                vec![OrderCondition::Asc("t".to_string())],
+                Some(1),
+                Ok(vec![tag("AXONE")]),
             ),
             (
-                SelectQuery {
-                    prefixes: vec![Prefix {
-                        prefix: "core".to_string(),
-                        namespace: "https://ontology.axone.space/core/".to_string(),
-                    }],
-                    select: vec![SelectItem::Variable("a".to_string())],
-                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                        subject: VarOrNode::Variable("a".to_string()),
-                        predicate: VarOrNamedNode::NamedNode(Prefixed(
-                            "invalid:hasDescription".to_string(),
-                        )),
-                        object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString {
-                            value: "A test Dataset.".to_string(),
-                            language: "en".to_string(),
-                        }),
-                    }))],
-                    limit: None,
-                },
-                Err(StdError::generic_err("Prefix not found: invalid")),
+                "a",
+                "core:hasTitle",
+                true,
+                vec![OrderCondition::Asc("a".to_string())],
+                None,
+                Ok(vec![uri(dataset), uri(dataspace)]),
             ),
             (
-                SelectQuery {
-                    prefixes: vec![],
-                    select: vec![SelectItem::Variable("u".to_string())],
-                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                        subject: VarOrNode::Variable("a".to_string()),
-                        predicate: VarOrNamedNode::NamedNode(Full(
-                            "https://ontology.axone.space/core/hasDescription".to_string(),
-                        )),
-                        object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString {
-                            value: "A test Dataset.".to_string(),
-                            language: "en".to_string(),
-                        }),
-                    }))],
-                    limit: None,
-                },
-                Err(StdError::generic_err(
-                    "Selected variable not found in query",
-                )),
+                "a",
+                "core:hasTitle",
+                false,
+                vec![OrderCondition::Desc("t".to_string())],
+                None,
+                Ok(vec![
+                    uri(dataset),
+                    uri(dataspace),
+                    uri(dataset),
+                    uri(dataspace),
+                ]),
+            ),
+            (
+                "a",
+                "core:hasTitle",
+                false,
+                vec![OrderCondition::Desc("unknown".to_string())],
+                None,
+                Err(StdError::generic_err("Order variable not found in query")),
             ),
         ];
 
@@ -1781,12 +3635,7 @@ mod tests {
             deps.as_mut(),
             mock_env(),
             info.clone(),
-            InstantiateMsg {
-                limits: StoreLimitsInput {
-                    max_query_variable_count: 1,
-                    ..Default::default()
-                },
-            },
+            InstantiateMsg::default(),
         )
         .unwrap();
 
@@ -1801,128 +3650,165 @@ mod tests {
         )
         .unwrap();
 
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), QueryMsg::Select { query: q });
-            assert_eq!(res, expected);
+        for (var, predicate, distinct, order_by, limit, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        select: vec![SelectItem::Variable(var.to_string())],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("a".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                predicate.to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                        }))],
+                        distinct,
+                        order_by,
+                        limit,
+                        after: None,
+                        group_by: vec![],
+                        having: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+
+            assert_eq!(
+                res.map(|res| from_json::<SelectResponse>(&res).unwrap()),
+                expected.map(|values| SelectResponse {
+                    head: Head {
+                        vars: vec![var.to_string()],
+                    },
+                    page_info: PageInfo {
+                        has_next_page: limit.is_some(),
+                        cursor: cursor::encode(values.len()),
+                    },
+                    results: Results {
+                        bindings: values
+                            .into_iter()
+                            .map(|v| BTreeMap::from([(var.to_string(), v)]))
+                            .collect(),
+                    },
+                    data: None,
+                })
+            );
         }
     }
 
     #[test]
-    fn formats_describe() {
+    fn proper_select_aggregates() {
+        let dataset = "https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e";
+        let dataspace = "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473";
+        let var = |name: &str| msg::Expression::Variable(name.to_string());
+        let aggregate = |aggregate, variable: &str| SelectItem::Aggregate {
+            aggregate,
+            variable: variable.to_string(),
+        };
+        let integer = |value: &str| Value::Literal {
+            value: value.to_string(),
+            lang: None,
+            datatype: Some(Full(XSD_INTEGER.to_string())),
+        };
+        let tag = |value: &str| Value::Literal {
+            value: value.to_string(),
+            lang: None,
+            datatype: None,
+        };
+        let uri = |value: &str| Value::URI {
+            value: Full(value.to_string()),
+        };
         let cases = vec![
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![],
-                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
-                        r#where: vec![],
-                    },
-                    format: Some(DataFormat::Turtle),
-                },
-                DescribeResponse {
-                    format: DataFormat::Turtle,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;
-\t<https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" ;
-\t<https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr , \"Test Data Space\"@en ;
-\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;
-\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> ;
-\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;
-\t<https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en , \"Un Data Space de test.\"@fr .
-\
-                ".to_string().as_bytes().to_vec()),
-                }
+                vec![
+                    SelectItem::Variable("a".to_string()),
+                    aggregate(
+                        msg::Aggregate::Count {
+                            expression: Some(var("t")),
+                            distinct: false,
+                        },
+                        "n",
+                    ),
+                    aggregate(msg::Aggregate::Min(var("t")), "first"),
+                ],
+                vec!["a".to_string()],
+                None,
+                Ok(vec![
+                    vec![
+                        ("a", uri(dataspace)),
+                        ("n", integer("2")),
+                        ("first", tag("AXONE")),
+                    ],
+                    vec![
+                        ("a", uri(dataset)),
+                        ("n", integer("1")),
+                        ("first", tag("test")),
+                    ],
+                ]),
             ),
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![],
-                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
-                        r#where: vec![],
-                    },
-                    format: Some(DataFormat::RDFXml),
-                },
-                DescribeResponse {
-                    format: DataFormat::RDFXml,
-                    data: Binary::from(
-                        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
-<rdf:Description rdf:about=\"https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473\">\
-<type xmlns=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" rdf:resource=\"https://ontology.axone.space/metadata/dataspace/GeneralMetadata\"/>\
-<type xmlns=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" rdf:resource=\"http://www.w3.org/2002/07/owl#NamedIndividual\"/>\
-<hasTag xmlns=\"https://ontology.axone.space/core/\">Test</hasTag><hasTag xmlns=\"https://ontology.axone.space/core/\">AXONE</hasTag>\
-<hasTitle xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"fr\">Data Space de test</hasTitle>\
-<hasTitle xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"en\">Test Data Space</hasTitle>\
-<hasTopic xmlns=\"https://ontology.axone.space/core/\" rdf:resource=\"https://ontology.axone.space/thesaurus/topic/Test\"/>\
-<describes xmlns=\"https://ontology.axone.space/core/\" rdf:resource=\"https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33\"/>\
-<hasPublisher xmlns=\"https://ontology.axone.space/core/\">AXONE</hasPublisher>\
-<hasDescription xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"en\">A test Data Space.</hasDescription>\
-<hasDescription xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"fr\">Un Data Space de test.</hasDescription></rdf:Description>\
-</rdf:RDF>\
-\
-                ".to_string().as_bytes().to_vec()),
-                }
+                vec![
+                    SelectItem::Variable("a".to_string()),
+                    aggregate(
+                        msg::Aggregate::Count {
+                            expression: None,
+                            distinct: false,
+                        },
+                        "n",
+                    ),
+                ],
+                vec!["a".to_string()],
+                Some(msg::Expression::Greater(
+                    Box::new(var("n")),
+                    Box::new(msg::Expression::Literal(Literal::TypedValue {
+                        value: "1".to_string(),
+                        datatype: Full(XSD_INTEGER.to_string()),
+                    })),
+                )),
+                Ok(vec![vec![("a", uri(dataspace)), ("n", integer("2"))]]),
             ),
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![],
-                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
-                        r#where: vec![WhereCondition::Simple(TriplePattern(
-                            msg::TriplePattern {
-                                subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Full(
-                                    "https://ontology.axone.space/core/hasDescription".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
-                            },
-                        ))],
-                    },
-                    format: Some(DataFormat::NTriples),
-                },
-                DescribeResponse {
-                    format: DataFormat::NTriples,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"Test\" .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"AXONE\" .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Test Data Space\"@en .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasPublisher> \"AXONE\" .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"Un Data Space de test.\"@fr .
-\
-                ".to_string().as_bytes().to_vec()),
-                }
+                vec![
+                    aggregate(
+                        msg::Aggregate::Count {
+                            expression: None,
+                            distinct: false,
+                        },
+                        "n",
+                    ),
+                    aggregate(
+                        msg::Aggregate::Count {
+                            expression: Some(var("a")),
+                            distinct: true,
+                        },
+                        "resources",
+                    ),
+                ],
+                vec![],
+                None,
+                Ok(vec![vec![("n", integer("3")), ("resources", integer("2"))]]),
             ),
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![],
-                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
-                        r#where: vec![],
-                    },
-                    format: Some(DataFormat::NQuads),
-                },
-                DescribeResponse {
-                    format: DataFormat::NQuads,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"Test\" .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"AXONE\" .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Test Data Space\"@en .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasPublisher> \"AXONE\" .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en .
-<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"Un Data Space de test.\"@fr .
-\
-                ".to_string().as_bytes().to_vec()),
-                }
+                vec![SelectItem::Variable("t".to_string())],
+                vec!["a".to_string()],
+                None,
+                Err(StdError::generic_err(
+                    "Selected variable must be grouped when grouping",
+                )),
+            ),
+            (
+                vec![aggregate(msg::Aggregate::Sample(var("t")), "t")],
+                vec![],
+                None,
+                Err(StdError::generic_err(
+                    "Aggregate variable already used in query",
+                )),
             ),
         ];
 
@@ -1948,51 +3834,87 @@ mod tests {
         )
         .unwrap();
 
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), q);
-
-            assert!(res.is_ok());
-
-            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+        for (select, group_by, having, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        select,
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("a".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                "core:hasTag".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                        }))],
+                        group_by,
+                        having,
+                        distinct: false,
+                        order_by: vec![OrderCondition::Desc("n".to_string())],
+                        limit: None,
+                        after: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            );
 
-            assert_eq!(result.format, expected.format);
             assert_eq!(
-                String::from_utf8_lossy(&result.data),
-                String::from_utf8_lossy(&expected.data)
+                res.map(|res| from_json::<SelectResponse>(&res).unwrap().results),
+                expected.map(|rows| Results {
+                    bindings: rows
+                        .into_iter()
+                        .map(|row| {
+                            row.into_iter()
+                                .map(|(name, value)| (name.to_string(), value))
+                                .collect()
+                        })
+                        .collect(),
+                })
             );
         }
     }
 
     #[test]
-    fn prefixes_describe() {
+    fn proper_select_property_path() {
+        let core = |name: &str| PropertyPath::NamedNode(Prefixed(format!("core:{name}")));
+        let uri = |value: &str| Value::URI {
+            value: Full(value.to_string()),
+        };
         let cases = vec![
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![
-                            Prefix {
-                                prefix: "metadata".to_string(),
-                                namespace: "https://ontology.axone.space/dataverse/dataspace/metadata/".to_string(),
-                            },
-                        ],
-                        resource: VarOrNamedNode::NamedNode(Prefixed("metadata:dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
-                        r#where: vec![],
-                    },
-                    format: Some(DataFormat::Turtle),
-                },
-                DescribeResponse {
-                    format: DataFormat::Turtle,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;
-\t<https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" ;
-\t<https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr , \"Test Data Space\"@en ;
-\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;
-\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> ;
-\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;
-\t<https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en , \"Un Data Space de test.\"@fr .
-\
-                ".to_string().as_bytes().to_vec()),
-                }
+                VarOrNode::Variable("s".to_string()),
+                PropertyPath::Sequence(vec![core("describes"), core("belongsTo")]),
+                vec![
+                    "https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                    "https://ontology.axone.space/dataverse/dataset/metadata/15592fd4-e368-46d3-b113-5d0ef8d4d10f",
+                ],
+                vec![
+                    "https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33",
+                    "https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33",
+                ],
+            ),
+            (
+                VarOrNode::Node(NamedNode(Full(
+                    "https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33"
+                        .to_string(),
+                ))),
+                PropertyPath::OneOrMore(Box::new(PropertyPath::Alternative(vec![
+                    PropertyPath::Inverse(Box::new(core("belongsTo"))),
+                    PropertyPath::Inverse(Box::new(core("describes"))),
+                ]))),
+                vec![],
+                vec![
+                    "https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde",
+                    "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473",
+                    "https://ontology.axone.space/dataverse/dataset/metadata/15592fd4-e368-46d3-b113-5d0ef8d4d10f",
+                    "https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                ],
             ),
         ];
 
@@ -2018,46 +3940,180 @@ mod tests {
         )
         .unwrap();
 
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), q);
-
-            assert!(res.is_ok());
-
-            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+        for (subject, path, subjects, objects) in cases {
+            let mut select = vec![SelectItem::Variable("o".to_string())];
+            if !subjects.is_empty() {
+                select.insert(0, SelectItem::Variable("s".to_string()));
+            }
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        select,
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject,
+                            predicate: VarOrNamedNodeOrPath::Path(path),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            );
 
-            assert_eq!(result.format, expected.format);
+            let bindings: Vec<BTreeMap<String, Value>> = objects
+                .iter()
+                .enumerate()
+                .map(|(i, object)| {
+                    let mut row = BTreeMap::from([("o".to_string(), uri(object))]);
+                    if let Some(subject) = subjects.get(i) {
+                        row.insert("s".to_string(), uri(subject));
+                    }
+                    row
+                })
+                .collect();
             assert_eq!(
-                String::from_utf8_lossy(&result.data),
-                String::from_utf8_lossy(&expected.data)
+                from_json::<SelectResponse>(&res.unwrap()).unwrap().results,
+                Results { bindings }
             );
         }
     }
 
     #[test]
-    fn variable_describe() {
+    fn proper_sparql() {
+        let prefixes = vec![Prefix {
+            prefix: "core".to_string(),
+            namespace: "https://ontology.axone.space/core/".to_string(),
+        }];
+        let dataset =
+            "https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde";
         let cases = vec![
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
-                        resource: VarOrNamedNode::Variable("a".to_string()),
-                        r#where: vec![WhereCondition::Simple(TriplePattern(
-                            msg::TriplePattern {
-                                subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasDescription".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString { value: "A test Dataset.".to_string(), language: "en".to_string() }),
-                            },
-                        ))],
+                "PREFIX core: <https://ontology.axone.space/core/>
+                SELECT ?s ?o WHERE { ?s core:describes/core:belongsTo ?o } LIMIT 1",
+                None,
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: prefixes.clone(),
+                        select: vec![
+                            SelectItem::Variable("s".to_string()),
+                            SelectItem::Variable("o".to_string()),
+                        ],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Path(PropertyPath::Sequence(vec![
+                                PropertyPath::NamedNode(Prefixed("core:describes".to_string())),
+                                PropertyPath::NamedNode(Prefixed("core:belongsTo".to_string())),
+                            ])),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: Some(1),
+                        after: None,
                     },
-                    format: Some(DataFormat::Turtle),
+                    format: None,
+                    as_of_height: None,
+                },
+            ),
+            (
+                "SELECT * WHERE { ?s ?p ?o } LIMIT 2 OFFSET 5",
+                Some(cursor::encode(3)),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![],
+                        select: vec![
+                            SelectItem::Variable("s".to_string()),
+                            SelectItem::Variable("p".to_string()),
+                            SelectItem::Variable("o".to_string()),
+                        ],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: Some(2),
+                        after: Some(cursor::encode(3)),
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            ),
+            (
+                "DESCRIBE <https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde>",
+                None,
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![],
+                        resource: VarOrNamedNode::NamedNode(Full(dataset.to_string())),
+                        r#where: vec![],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NTriples),
+                    as_of_height: None,
+                },
+            ),
+            (
+                "PREFIX core: <https://ontology.axone.space/core/>
+                CONSTRUCT { ?s core:hasTag ?t } WHERE { ?s core:hasTag ?t }",
+                None,
+                QueryMsg::Construct {
+                    query: ConstructQuery {
+                        prefixes: prefixes.clone(),
+                        construct: vec![msg::TripleConstructTemplate {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNode::NamedNode(Prefixed(
+                                "core:hasTag".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                            graph: None,
+                        }],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                "core:hasTag".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                        }))],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NTriples),
+                    as_of_height: None,
+                },
+            ),
+            (
+                "ASK { ?s <https://ontology.axone.space/core/hasTag> ?t }",
+                None,
+                QueryMsg::Ask {
+                    query: AskQuery {
+                        prefixes: vec![],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                "https://ontology.axone.space/core/hasTag".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                        }))],
+                    },
+                    as_of_height: None,
                 },
-                DescribeResponse {
-                    format: DataFormat::Turtle,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataset/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;\n\t<https://ontology.axone.space/core/hasTag> \"test\" ;\n\t<https://ontology.axone.space/core/hasTitle> \"test Dataset\"@en , \"Dataset de test\"@fr ;\n\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;\n\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde> ;\n\t<https://ontology.axone.space/core/hasFormat> <https://ontology.axone.space/thesaurus/media-type/application_vndms-excel> ;\n\t<https://ontology.axone.space/core/hasCreator> \"Me\" ;\n\t<https://ontology.axone.space/core/hasLicense> <https://ontology.axone.space/thesaurus/license/LO-FR-1_0> ;\n\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasDescription> \"Un Dataset de test.\"@fr , \"A test Dataset.\"@en .\n".to_string().as_bytes().to_vec()),
-                }
             ),
         ];
 
@@ -2083,46 +4139,82 @@ mod tests {
         )
         .unwrap();
 
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), q);
-
-            assert!(res.is_ok());
-
-            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+        for (sparql, after, msg) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Sparql {
+                    query: sparql.to_string(),
+                    format: Some(DataFormat::NTriples),
+                    after,
+                    as_of_height: None,
+                },
+            )
+            .unwrap();
 
-            assert_eq!(result.format, expected.format);
+            let expected = match &msg {
+                QueryMsg::Select { .. } => SparqlResponse::Select(
+                    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap(),
+                ),
+                QueryMsg::Describe { .. } => SparqlResponse::Describe(
+                    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap(),
+                ),
+                QueryMsg::Construct { .. } => SparqlResponse::Construct(
+                    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap(),
+                ),
+                _ => SparqlResponse::Ask(
+                    from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap(),
+                ),
+            };
             assert_eq!(
-                String::from_utf8_lossy(&result.data),
-                String::from_utf8_lossy(&expected.data)
+                from_json::<SparqlResponse>(&res).unwrap(),
+                expected,
+                "{sparql}"
             );
         }
+
+        assert_eq!(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Sparql {
+                    query: "SELECT ?s WHERE {\n  ?s core:hasTag ?t\n}".to_string(),
+                    format: None,
+                    after: None,
+                    as_of_height: None,
+                },
+            ),
+            Err(StdError::parse_err(
+                "SPARQL query",
+                "undeclared prefix 'core' at line 2, column 6"
+            ))
+        );
     }
 
     #[test]
-    fn variable_mutiple_resources_describe() {
+    fn proper_ask() {
+        let pattern = |subject: &str, predicate: &str| {
+            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Node(NamedNode(Full(subject.to_string()))),
+                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(predicate.to_string())),
+                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+            }))
+        };
+        let dataset =
+            "https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde";
         let cases = vec![
+            (vec![pattern(dataset, "core:belongsTo")], Ok(true)),
+            (vec![pattern(dataset, "core:hasTag")], Ok(false)),
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
-                        resource: VarOrNamedNode::Variable("a".to_string()),
-                        r#where: vec![WhereCondition::Simple(TriplePattern(
-                            msg::TriplePattern {
-                                subject: VarOrNode::Variable("a".to_string()),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasPublisher".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Literal(Literal::Simple("AXONE".to_string())),
-                            },
-                        ))],
-                    },
-                    format: Some(DataFormat::Turtle),
-                },
-                DescribeResponse {
-                    format: DataFormat::Turtle,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;\n\t<https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr , \"Test Data Space\"@en ;\n\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;\n\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> ;\n\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en , \"Un Data Space de test.\"@fr .\n<https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataset/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;\n\t<https://ontology.axone.space/core/hasTag> \"test\" ;\n\t<https://ontology.axone.space/core/hasTitle> \"test Dataset\"@en , \"Dataset de test\"@fr ;\n\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;\n\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde> ;\n\t<https://ontology.axone.space/core/hasFormat> <https://ontology.axone.space/thesaurus/media-type/application_vndms-excel> ;\n\t<https://ontology.axone.space/core/hasCreator> \"Me\" ;\n\t<https://ontology.axone.space/core/hasLicense> <https://ontology.axone.space/thesaurus/license/LO-FR-1_0> ;\n\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasDescription> \"Un Dataset de test.\"@fr , \"A test Dataset.\"@en .\n".to_string().as_bytes().to_vec()),
-                }
+                vec![pattern(
+                    "https://ontology.axone.space/unknown/foo",
+                    "core:hasTitle",
+                )],
+                Ok(false),
+            ),
+            (
+                vec![pattern(dataset, "foo:hasTitle")],
+                Err(StdError::generic_err("Prefix not found: foo")),
             ),
         ];
 
@@ -2148,50 +4240,50 @@ mod tests {
         )
         .unwrap();
 
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), q);
-
-            assert!(res.is_ok());
-
-            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
-
-            assert_eq!(result.format, expected.format);
+        for (r#where, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Ask {
+                    query: AskQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        r#where,
+                    },
+                    as_of_height: None,
+                },
+            );
             assert_eq!(
-                String::from_utf8_lossy(&result.data),
-                String::from_utf8_lossy(&expected.data)
+                res.and_then(|res| from_json::<AskResponse>(&res)),
+                expected.map(|result| AskResponse { result })
             );
         }
     }
 
     #[test]
-    fn blanknode_describe() {
+    fn proper_select_results_format() {
         let cases = vec![
             (
-                QueryMsg::Describe {
-                    query: DescribeQuery {
-                        prefixes: vec![
-                            Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() },
-                            Prefix { prefix: "metadata-dataset".to_string(), namespace: "https://ontology.axone.space/dataverse/dataset/metadata/".to_string() },
-                        ],
-                        resource: VarOrNamedNode::Variable("x".to_string()),
-                        r#where: vec![WhereCondition::Simple(TriplePattern(
-                            msg::TriplePattern {
-                                subject: VarOrNode::Node(NamedNode(Prefixed("metadata-dataset:80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string()))),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasTemporalCoverage".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Variable("x".to_string()),
-                            },
-                        )),
-                        ],
-                    },
-                    format: Some(DataFormat::Turtle),
-                },
-                DescribeResponse {
-                    format: DataFormat::Turtle,
-                    data: Binary::from(
-                        "<b0> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> , <https://ontology.axone.space/core/Period> ;\n\t<https://ontology.axone.space/core/hasStartDate> \"2022-01-01T00:00:00+00:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n".to_string().as_bytes().to_vec()),
-                }
+                ResultsFormat::Json,
+                "{\"head\":{\"vars\":[\"o\",\"p\"]},\"results\":{\"bindings\":[\
+                {\"o\":{\"type\":\"uri\",\"value\":\"https://ontology.axone.space/core/Dataset\"},\
+                \"p\":{\"type\":\"uri\",\"value\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#type\"}},\
+                {\"o\":{\"type\":\"uri\",\"value\":\"http://www.w3.org/2002/07/owl#NamedIndividual\"},\
+                \"p\":{\"type\":\"uri\",\"value\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#type\"}}]}}",
+            ),
+            (
+                ResultsFormat::Csv,
+                "o,p\r\n\
+                https://ontology.axone.space/core/Dataset,http://www.w3.org/1999/02/22-rdf-syntax-ns#type\r\n\
+                http://www.w3.org/2002/07/owl#NamedIndividual,http://www.w3.org/1999/02/22-rdf-syntax-ns#type\r\n",
+            ),
+            (
+                ResultsFormat::Tsv,
+                "?o\t?p\n\
+                <https://ontology.axone.space/core/Dataset>\t<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>\n\
+                <http://www.w3.org/2002/07/owl#NamedIndividual>\t<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>\n",
             ),
         ];
 
@@ -2211,193 +4303,2878 @@ mod tests {
             mock_env(),
             info,
             InsertData {
-                format: Some(DataFormat::Turtle),
-                data: read_test_data("blank-nodes.ttl"),
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
             },
         )
         .unwrap();
 
-        for (q, expected) in cases {
-            let res = query(deps.as_ref(), mock_env(), q);
-
-            assert!(res.is_ok());
-
-            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
-
-            assert_eq!(result.format, expected.format);
+        for (format, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        select: vec![
+                            SelectItem::Variable("p".to_string()),
+                            SelectItem::Variable("o".to_string()),
+                        ],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Node(NamedNode(Full(
+                                "https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde"
+                                    .to_string(),
+                            ))),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: Some(2),
+                        after: None,
+                    },
+                    format: Some(format),
+                    as_of_height: None,
+                },
+            );
+            let response = from_json::<SelectResponse>(&res.unwrap()).unwrap();
+            assert_eq!(response.results, Results { bindings: vec![] });
+            assert!(response.page_info.has_next_page);
             assert_eq!(
-                String::from_utf8_lossy(&result.data),
-                String::from_utf8_lossy(&expected.data)
+                String::from_utf8(response.data.unwrap().to_vec()).unwrap(),
+                expected
             );
         }
     }
 
     #[test]
-    fn proper_construct() {
-        let id = "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473";
-        let cases = vec![
-            (
-                InsertData {
-                    format: Some(DataFormat::RDFXml),
-                    data: read_test_data("sample.rdf.xml"),
-                },
-                QueryMsg::Construct {
-                    query: ConstructQuery {
-                        prefixes: vec![],
-                        construct: vec![],
-                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                            subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                            predicate: VarOrNamedNode::NamedNode(Full(
-                                "https://ontology.axone.space/core/hasTag".to_string(),
+    fn proper_explain() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Explain {
+                query: SelectQuery {
+                    prefixes: vec![Prefix {
+                        prefix: "core".to_string(),
+                        namespace: "https://ontology.axone.space/core/".to_string(),
+                    }],
+                    select: vec![SelectItem::Variable("o".to_string())],
+                    r#where: vec![
+                        WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                "core:hasTag".to_string(),
                             )),
+                            object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                        })),
+                        WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                             object: VarOrNodeOrLiteral::Variable("o".to_string()),
-                        }))],
-                    },
-                    format: None,
-                },
-                ConstructResponse {
-                    format: DataFormat::Turtle,
-                    data: Binary::from(
-                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" .\n".to_string().as_bytes().to_vec()),
+                        })),
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: Some(5),
+                    after: None,
                 },
-            ),
-            (
-                InsertData {
-                    format: Some(DataFormat::RDFXml),
-                    data: read_test_data("sample.rdf.xml"),
+            },
+        );
+        assert_eq!(
+            from_json::<ExplainResponse>(&res.unwrap()).unwrap(),
+            ExplainResponse {
+                plan: PlanNode {
+                    operation: PlanOperation::Limit {
+                        first: Uint128::new(6),
+                    },
+                    access_path: None,
+                    cardinality: Uint128::new(6),
+                    children: vec![PlanNode {
+                        operation: PlanOperation::Skip {
+                            first: Uint128::zero(),
+                        },
+                        access_path: None,
+                        cardinality: Uint128::new(12),
+                        children: vec![PlanNode {
+                            operation: PlanOperation::ForLoopJoin,
+                            access_path: None,
+                            cardinality: Uint128::new(12),
+                            children: vec![
+                                PlanNode {
+                                    operation: PlanOperation::TriplePattern {
+                                        subject: PlanTerm::Variable("s".to_string()),
+                                        predicate: PlanTerm::Value(Value::URI {
+                                            value: Full(
+                                                "https://ontology.axone.space/core/hasTag"
+                                                    .to_string(),
+                                            ),
+                                        }),
+                                        object: PlanTerm::Variable("t".to_string()),
+                                        graph: None,
+                                    },
+                                    access_path: Some(AccessPath::Predicate),
+                                    cardinality: Uint128::new(3),
+                                    children: vec![],
+                                },
+                                PlanNode {
+                                    operation: PlanOperation::TriplePattern {
+                                        subject: PlanTerm::Variable("s".to_string()),
+                                        predicate: PlanTerm::Variable("p".to_string()),
+                                        object: PlanTerm::Variable("o".to_string()),
+                                        graph: None,
+                                    },
+                                    access_path: Some(AccessPath::Subject),
+                                    cardinality: Uint128::new(4),
+                                    children: vec![],
+                                },
+                            ],
+                        }],
+                    }],
                 },
-                QueryMsg::Construct {
-                    query: ConstructQuery {
-                        prefixes: vec![
-                            Prefix { prefix: "my-ns".to_string(), namespace: "https://my-ns.org/".to_string() },
-                            Prefix { prefix: "metadata-dataset".to_string(), namespace: "https://ontology.axone.space/dataverse/dataset/metadata/".to_string() },
-                        ],
-                        construct: vec![
-                            msg::TripleConstructTemplate {
-                                subject: VarOrNode::Node(NamedNode(Prefixed("my-ns:instance-1".to_string()))),
-                                predicate: VarOrNamedNode::NamedNode(Full(
-                                    "https://my-ns/predicate/tag".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
-                            }
-                        ],
-                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                            subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
-                            predicate: VarOrNamedNode::NamedNode(Full(
-                                "https://ontology.axone.space/core/hasTag".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn proper_select_values_bind() {
+        let metadata =
+            |id: &str| format!("https://ontology.axone.space/dataverse/dataset/metadata/{id}");
+        let uri = |value: String| Value::URI { value: Full(value) };
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Select {
+                query: SelectQuery {
+                    prefixes: vec![Prefix {
+                        prefix: "core".to_string(),
+                        namespace: "https://ontology.axone.space/core/".to_string(),
+                    }],
+                    select: vec![
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Variable("o".to_string()),
+                        SelectItem::Variable("iri".to_string()),
+                    ],
+                    r#where: vec![
+                        WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                "core:describes".to_string(),
                             )),
                             object: VarOrNodeOrLiteral::Variable("o".to_string()),
-                        }))],
-                    },
-                    format: Some(DataFormat::NTriples),
+                        })),
+                        WhereCondition::Bind {
+                            expression: msg::Expression::IsIri(Box::new(
+                                msg::Expression::Variable("o".to_string()),
+                            )),
+                            variable: "iri".to_string(),
+                        },
+                        WhereCondition::Values {
+                            variables: vec!["s".to_string()],
+                            values: vec![
+                                vec![Some(NamedNodeOrLiteral::NamedNode(Full(metadata(
+                                    "d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                                ))))],
+                                vec![Some(NamedNodeOrLiteral::NamedNode(Full(
+                                    "https://unknown.org/foo".to_string(),
+                                )))],
+                                vec![Some(NamedNodeOrLiteral::NamedNode(Full(metadata(
+                                    "15592fd4-e368-46d3-b113-5d0ef8d4d10f",
+                                ))))],
+                            ],
+                        },
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
                 },
-                ConstructResponse {
-                    format: DataFormat::NTriples,
-                    data: Binary::from(
-                        "<https://my-ns.org/instance-1> <https://my-ns/predicate/tag> \"Test\" .\n<https://my-ns.org/instance-1> <https://my-ns/predicate/tag> \"AXONE\" .\n".to_string().as_bytes().to_vec()),
+                format: None,
+                as_of_height: None,
+            },
+        );
+
+        let bindings: Vec<BTreeMap<String, Value>> = [
+            "d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+            "15592fd4-e368-46d3-b113-5d0ef8d4d10f",
+        ]
+        .iter()
+        .map(|id| {
+            BTreeMap::from([
+                ("s".to_string(), uri(metadata(id))),
+                (
+                    "o".to_string(),
+                    uri("https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde".to_string()),
+                ),
+                (
+                    "iri".to_string(),
+                    Value::Literal {
+                        value: "true".to_string(),
+                        lang: None,
+                        datatype: Some(Full(XSD_BOOLEAN.to_string())),
+                    },
+                ),
+            ])
+        })
+        .collect();
+        assert_eq!(
+            from_json::<SelectResponse>(&res.unwrap()).unwrap().results,
+            Results { bindings }
+        );
+    }
+
+    #[test]
+    fn proper_select_negation() {
+        let pattern = |s: &str, p: &str, o: &str| {
+            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(p.to_string())),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let cases = vec![
+            (
+                vec![
+                    pattern("s", "core:describes", "o"),
+                    WhereCondition::Minus(vec![pattern("s", "core:hasPublisher", "p")]),
+                ],
+                vec!["dataset/metadata/15592fd4-e368-46d3-b113-5d0ef8d4d10f"],
+            ),
+            (
+                vec![
+                    pattern("s", "core:describes", "o"),
+                    WhereCondition::Minus(vec![pattern("x", "core:hasPublisher", "p")]),
+                ],
+                vec![
+                    "dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473",
+                    "dataset/metadata/15592fd4-e368-46d3-b113-5d0ef8d4d10f",
+                    "dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                ],
+            ),
+            (
+                vec![
+                    WhereCondition::NotExists(vec![pattern("s", "core:hasTag", "t")]),
+                    pattern("s", "core:describes", "o"),
+                ],
+                vec!["dataset/metadata/15592fd4-e368-46d3-b113-5d0ef8d4d10f"],
+            ),
+            (
+                vec![
+                    pattern("s", "core:describes", "o"),
+                    WhereCondition::NotExists(vec![pattern("o", "core:hasTag", "t")]),
+                ],
+                vec![
+                    "dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473",
+                    "dataset/metadata/15592fd4-e368-46d3-b113-5d0ef8d4d10f",
+                    "dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                ],
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (r#where, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        select: vec![SelectItem::Variable("s".to_string())],
+                        r#where,
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                    },
+                    format: None,
+                    as_of_height: None,
                 },
+            );
+
+            let bindings: Vec<BTreeMap<String, Value>> = expected
+                .iter()
+                .map(|id| {
+                    BTreeMap::from([(
+                        "s".to_string(),
+                        Value::URI {
+                            value: Full(format!("https://ontology.axone.space/dataverse/{id}")),
+                        },
+                    )])
+                })
+                .collect();
+            assert_eq!(
+                from_json::<SelectResponse>(&res.unwrap()).unwrap().results,
+                Results { bindings }
+            );
+        }
+    }
+
+    #[test]
+    fn proper_select_sub_query() {
+        let pattern = |s: &str, p: &str, o: &str| {
+            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(p.to_string())),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let sub_query =
+            |r#where: WhereClause, order_by: Vec<OrderCondition>, limit: Option<u32>| {
+                WhereCondition::SubQuery(Box::new(SelectQuery {
+                    prefixes: vec![],
+                    select: vec![SelectItem::Variable("s".to_string())],
+                    r#where,
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by,
+                    limit,
+                    after: None,
+                }))
+            };
+        let cases = vec![
+            (
+                vec![
+                    pattern("s", "core:describes", "o"),
+                    sub_query(
+                        vec![pattern("s", "core:describes", "o")],
+                        vec![OrderCondition::Desc("s".to_string())],
+                        Some(2),
+                    ),
+                ],
+                vec![
+                    "dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473",
+                    "dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                ],
             ),
             (
-                InsertData {
-                    format: Some(DataFormat::Turtle),
-                    data: read_test_data("blank-nodes.ttl"),
+                vec![
+                    pattern("s", "core:describes", "o"),
+                    sub_query(vec![pattern("s", "core:hasPublisher", "o")], vec![], None),
+                ],
+                vec![
+                    "dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473",
+                    "dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e",
+                ],
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (r#where, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![Prefix {
+                            prefix: "core".to_string(),
+                            namespace: "https://ontology.axone.space/core/".to_string(),
+                        }],
+                        select: vec![SelectItem::Variable("s".to_string())],
+                        r#where,
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                    },
+                    format: None,
+                    as_of_height: None,
                 },
-                QueryMsg::Construct {
-                    query: ConstructQuery {
-                        prefixes: vec![
-                            Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() },
-                            Prefix { prefix: "metadata-dataset".to_string(), namespace: "https://ontology.axone.space/dataverse/dataset/metadata/".to_string() },
-                        ],
-                        construct: vec![
-                            msg::TripleConstructTemplate {
-                                subject: VarOrNode::Node(BlankNode("my-metadata".to_string())),
-                                predicate: VarOrNamedNode::NamedNode(Full(
-                                    "https://my-ns/predicate/tcov".to_string(),
+            );
+
+            let bindings: Vec<BTreeMap<String, Value>> = expected
+                .iter()
+                .map(|id| {
+                    BTreeMap::from([(
+                        "s".to_string(),
+                        Value::URI {
+                            value: Full(format!("https://ontology.axone.space/dataverse/{id}")),
+                        },
+                    )])
+                })
+                .collect();
+            assert_eq!(
+                from_json::<SelectResponse>(&res.unwrap()).unwrap().results,
+                Results { bindings }
+            );
+        }
+    }
+
+    #[test]
+    fn proper_select_pagination() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        let select = |limit, after| SelectQuery {
+            prefixes: vec![Prefix {
+                prefix: "core".to_string(),
+                namespace: "https://ontology.axone.space/core/".to_string(),
+            }],
+            select: vec![SelectItem::Variable("t".to_string())],
+            r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Variable("a".to_string()),
+                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed("core:hasTag".to_string())),
+                object: VarOrNodeOrLiteral::Variable("t".to_string()),
+            }))],
+            distinct: false,
+            order_by: vec![OrderCondition::Asc("t".to_string())],
+            limit: Some(limit),
+            after,
+            group_by: vec![],
+            having: None,
+        };
+
+        let mut tags = vec![];
+        let mut after = None;
+        let mut pages = 0;
+        loop {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: select(2, after),
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            let res = from_json::<SelectResponse>(&res.unwrap()).unwrap();
+
+            pages += 1;
+            tags.extend(res.results.bindings.into_iter().map(|b| b["t"].clone()));
+            if !res.page_info.has_next_page {
+                assert_eq!(res.page_info.cursor, cursor::encode(tags.len()));
+                break;
+            }
+            after = Some(res.page_info.cursor);
+        }
+
+        let all = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Select {
+                query: select(10, None),
+                format: None,
+                as_of_height: None,
+            },
+        );
+        let all = from_json::<SelectResponse>(&all.unwrap()).unwrap();
+
+        assert_eq!(pages, 2);
+        assert_eq!(
+            tags,
+            all.results
+                .bindings
+                .into_iter()
+                .map(|b| b["t"].clone())
+                .collect::<Vec<_>>()
+        );
+        assert!(!all.page_info.has_next_page);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Select {
+                query: select(2, Some("abc".to_string())),
+                format: None,
+                as_of_height: None,
+            },
+        );
+        assert_eq!(
+            res,
+            Err(StdError::parse_err("Cursor", "invalid cursor length"))
+        );
+    }
+
+    #[test]
+    fn proper_select_blank_nodes() {
+        let cases = vec![
+            (
+                SelectQuery {
+                    prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    r#where: vec![
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTemporalCoverage".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Variable("tcov".to_string()),
+                                object: VarOrNodeOrLiteral::Node(BlankNode("a".to_string())),
                             },
-                            msg::TripleConstructTemplate {
-                                subject: VarOrNode::Node(BlankNode("my-metadata".to_string())),
-                                predicate: VarOrNamedNode::NamedNode(Full(
-                                    "https://my-ns/predicate/info".to_string(),
-                                )),
-                                object: VarOrNodeOrLiteral::Variable("info".to_string()),
+                        )),
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Node(BlankNode("a".to_string())),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasStartDate".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
                             },
-                            msg::TripleConstructTemplate {
-                                subject: VarOrNode::Variable("tcov".to_string()),
-                                predicate: VarOrNamedNode::Variable("tcov_p".to_string()),
-                                object: VarOrNodeOrLiteral::Variable("tcov_o".to_string()),
+                        ))],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
+                },
+                SelectResponse {
+                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    results: Results {
+                        bindings: vec![
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                    }
+                                ),
+                                (
+                                    "b".to_string(),
+                                    Value::Literal {
+                                        value: "2022-01-01T00:00:00+00:00".to_string(),
+                                        lang: None,
+                                        datatype: Some(Full("http://www.w3.org/2001/XMLSchema#dateTime".to_string())),
+                                    }
+                                )
+                            ])
+                        ],
+                    },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
+                },
+            ),
+            (
+                SelectQuery {
+                    prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    r#where: vec![
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTemporalCoverage".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("blank".to_string()),
                             },
-                            msg::TripleConstructTemplate {
-                                subject: VarOrNode::Variable("info".to_string()),
-                                predicate: VarOrNamedNode::Variable("info_p".to_string()),
-                                object: VarOrNodeOrLiteral::Variable("info_o".to_string()),
-                            }
+                        )),
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("blank".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasStartDate".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                            },
+                        ))],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
+                },
+                SelectResponse {
+                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    results: Results {
+                        bindings: vec![
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                    }
+                                ),
+                                (
+                                    "b".to_string(),
+                                    Value::Literal {
+                                        value: "2022-01-01T00:00:00+00:00".to_string(),
+                                        lang: None,
+                                        datatype: Some(Full("http://www.w3.org/2001/XMLSchema#dateTime".to_string())),
+                                    }
+                                )
+                            ])
                         ],
-                        r#where: vec![
-                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                                subject: VarOrNode::Node(NamedNode(Prefixed("metadata-dataset:80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string()))),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
+                    },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
+                },
+            ),
+            (
+                SelectQuery {
+                    prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    r#where: vec![
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
                                     "core:hasTemporalCoverage".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Variable("tcov".to_string()),
-                            })),
-                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                                subject: VarOrNode::Node(NamedNode(Prefixed("metadata-dataset:80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string()))),
-                                predicate: VarOrNamedNode::NamedNode(Prefixed(
-                                    "core:hasInformations".to_string(),
+                                object: VarOrNodeOrLiteral::Node(BlankNode("blank1".to_string())),
+                            },
+                        )),
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Node(BlankNode("blank2".to_string())),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasInformation".to_string(),
                                 )),
-                                object: VarOrNodeOrLiteral::Variable("info".to_string()),
-                            })),
-                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                                subject: VarOrNode::Variable("tcov".to_string()),
-                                predicate: VarOrNamedNode::Variable("tcov_p".to_string()),
-                                object: VarOrNodeOrLiteral::Variable("tcov_o".to_string()),
-                            })),
-                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
-                                subject: VarOrNode::Variable("info".to_string()),
-                                predicate: VarOrNamedNode::Variable("info_p".to_string()),
-                                object: VarOrNodeOrLiteral::Variable("info_o".to_string()),
-                            }))
+                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                            },
+                        ))],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
+                },
+                SelectResponse {
+                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    results: Results {
+                        bindings: vec![
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                    }
+                                ),
+                                (
+                                    "b".to_string(),
+                                    Value::Literal {
+                                        value: "this is a dataset".to_string(),
+                                        lang: None,
+                                        datatype: None,
+                                    }
+                                )
+                            ])
                         ],
                     },
-                    format: Some(DataFormat::NTriples),
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
                 },
-                ConstructResponse {
-                    format: DataFormat::NTriples,
-                    data: Binary::from(
-                        "<a0> <https://my-ns/predicate/tcov> <b0> .\n<a0> <https://my-ns/predicate/info> <b1> .\n<b0> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .\n<b1> <https://ontology.axone.space/core/hasInformation> \"this is a dataset\" .\n<a0> <https://my-ns/predicate/tcov> <b0> .\n<a0> <https://my-ns/predicate/info> <b1> .\n<b0> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/core/Period> .\n<b1> <https://ontology.axone.space/core/hasInformation> \"this is a dataset\" .\n<a0> <https://my-ns/predicate/tcov> <b0> .\n<a0> <https://my-ns/predicate/info> <b1> .\n<b0> <https://ontology.axone.space/core/hasStartDate> \"2022-01-01T00:00:00+00:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n<b1> <https://ontology.axone.space/core/hasInformation> \"this is a dataset\" .\n".to_string().as_bytes().to_vec()),
+            ),
+            (
+                SelectQuery {
+                    prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                    select: vec![SelectItem::Variable("a".to_string()), SelectItem::Variable("b".to_string())],
+                    r#where: vec![
+                        WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTemporalCoverage".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                            },
+                        ))],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+having: None,
+                },
+                SelectResponse {
+                    head: Head { vars: vec!["a".to_string(), "b".to_string()] },
+                    results: Results {
+                        bindings: vec![
+                            BTreeMap::from([
+                                (
+                                    "a".to_string(),
+                                    Value::URI {
+                                        value: Full("https://ontology.axone.space/dataverse/dataset/metadata/80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string())
+                                    }
+                                ),
+                                (
+                                    "b".to_string(),
+                                    Value::BlankNode {
+                                        value: "b0".to_string(),
+                                    }
+                                )
+                            ])
+                        ],
+                    },
+                    page_info: PageInfo { has_next_page: false, cursor: "11111112".to_string() },
+                    data: None,
+                },
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::Turtle),
+                data: read_test_data("blank-nodes.ttl"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: q,
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            assert!(res.is_ok());
+
+            let result = from_json::<SelectResponse>(&res.unwrap()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn invalid_select() {
+        let cases = vec![
+            (
+                SelectQuery {
+                    prefixes: vec![],
+                    select: vec![
+                        SelectItem::Variable("a".to_string()),
+                        SelectItem::Variable("b".to_string()),
+                    ],
+                    r#where: vec![],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+                    having: None,
+                },
+                Err(StdError::generic_err(
+                    "Maximum query variable count exceeded",
+                )),
+            ),
+            (
+                SelectQuery {
+                    prefixes: vec![],
+                    select: vec![],
+                    r#where: vec![],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: Some(8000),
+                    after: None,
+                    group_by: vec![],
+                    having: None,
                 },
+                Err(StdError::generic_err("Maximum query limit exceeded")),
+            ),
+            (
+                SelectQuery {
+                    prefixes: vec![Prefix {
+                        prefix: "core".to_string(),
+                        namespace: "https://ontology.axone.space/core/".to_string(),
+                    }],
+                    select: vec![SelectItem::Variable("a".to_string())],
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Variable("a".to_string()),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                            "invalid:hasDescription".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString {
+                            value: "A test Dataset.".to_string(),
+                            language: "en".to_string(),
+                        }),
+                    }))],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+                    having: None,
+                },
+                Err(StdError::generic_err("Prefix not found: invalid")),
+            ),
+            (
+                SelectQuery {
+                    prefixes: vec![],
+                    select: vec![SelectItem::Variable("u".to_string())],
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Variable("a".to_string()),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                            "https://ontology.axone.space/core/hasDescription".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString {
+                            value: "A test Dataset.".to_string(),
+                            language: "en".to_string(),
+                        }),
+                    }))],
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                    group_by: vec![],
+                    having: None,
+                },
+                Err(StdError::generic_err(
+                    "Selected variable not found in query",
+                )),
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg {
+                limits: StoreLimitsInput {
+                    max_query_variable_count: 1,
+                    ..Default::default()
+                },
+                strict_typing: false,
+                versioning: false,
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Select {
+                    query: q,
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn formats_describe() {
+        let cases = vec![
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![],
+                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
+                        r#where: vec![],
+                        after: None,
+                    },
+                    format: Some(DataFormat::Turtle),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::Turtle,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;
+\t<https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" ;
+\t<https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr , \"Test Data Space\"@en ;
+\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;
+\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> ;
+\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;
+\t<https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en , \"Un Data Space de test.\"@fr .
+\
+                ".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111C".to_string() },
+                }
+            ),
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![],
+                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
+                        r#where: vec![],
+                        after: None,
+                    },
+                    format: Some(DataFormat::RDFXml),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::RDFXml,
+                    data: Binary::from(
+                        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
+<rdf:Description rdf:about=\"https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473\">\
+<type xmlns=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" rdf:resource=\"https://ontology.axone.space/metadata/dataspace/GeneralMetadata\"/>\
+<type xmlns=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" rdf:resource=\"http://www.w3.org/2002/07/owl#NamedIndividual\"/>\
+<hasTag xmlns=\"https://ontology.axone.space/core/\">Test</hasTag><hasTag xmlns=\"https://ontology.axone.space/core/\">AXONE</hasTag>\
+<hasTitle xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"fr\">Data Space de test</hasTitle>\
+<hasTitle xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"en\">Test Data Space</hasTitle>\
+<hasTopic xmlns=\"https://ontology.axone.space/core/\" rdf:resource=\"https://ontology.axone.space/thesaurus/topic/Test\"/>\
+<describes xmlns=\"https://ontology.axone.space/core/\" rdf:resource=\"https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33\"/>\
+<hasPublisher xmlns=\"https://ontology.axone.space/core/\">AXONE</hasPublisher>\
+<hasDescription xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"en\">A test Data Space.</hasDescription>\
+<hasDescription xmlns=\"https://ontology.axone.space/core/\" xml:lang=\"fr\">Un Data Space de test.</hasDescription></rdf:Description>\
+</rdf:RDF>\
+\
+                ".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111C".to_string() },
+                }
+            ),
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![],
+                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
+                        r#where: vec![WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                    "https://ontology.axone.space/core/hasDescription".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("b".to_string()),
+                            },
+                        ))],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NTriples),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::NTriples,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"Test\" .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"AXONE\" .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Test Data Space\"@en .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasPublisher> \"AXONE\" .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"Un Data Space de test.\"@fr .
+\
+                ".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111C".to_string() },
+                }
+            ),
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![],
+                        resource: VarOrNamedNode::NamedNode(Full("https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
+                        r#where: vec![],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NQuads),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::NQuads,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"Test\" .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"AXONE\" .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTitle> \"Test Data Space\"@en .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasPublisher> \"AXONE\" .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en .
+<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasDescription> \"Un Data Space de test.\"@fr .
+\
+                ".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111C".to_string() },
+                }
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(deps.as_ref(), mock_env(), q);
+
+            assert!(res.is_ok());
+
+            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+
+            assert_eq!(result.format, expected.format);
+            assert_eq!(result.page_info, expected.page_info);
+            assert_eq!(
+                String::from_utf8_lossy(&result.data),
+                String::from_utf8_lossy(&expected.data)
+            );
+        }
+    }
+
+    #[test]
+    fn prefixes_describe() {
+        let cases = vec![
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![
+                            Prefix {
+                                prefix: "metadata".to_string(),
+                                namespace: "https://ontology.axone.space/dataverse/dataspace/metadata/".to_string(),
+                            },
+                        ],
+                        resource: VarOrNamedNode::NamedNode(Prefixed("metadata:dcf48417-01c5-4b43-9bc7-49e54c028473".to_string())),
+                        r#where: vec![],
+                        after: None,
+                    },
+                    format: Some(DataFormat::Turtle),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::Turtle,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;
+\t<https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" ;
+\t<https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr , \"Test Data Space\"@en ;
+\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;
+\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> ;
+\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;
+\t<https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en , \"Un Data Space de test.\"@fr .
+\
+                ".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111C".to_string() },
+                }
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(deps.as_ref(), mock_env(), q);
+
+            assert!(res.is_ok());
+
+            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+
+            assert_eq!(result.format, expected.format);
+            assert_eq!(result.page_info, expected.page_info);
+            assert_eq!(
+                String::from_utf8_lossy(&result.data),
+                String::from_utf8_lossy(&expected.data)
+            );
+        }
+    }
+
+    #[test]
+    fn variable_describe() {
+        let cases = vec![
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                        resource: VarOrNamedNode::Variable("a".to_string()),
+                        r#where: vec![WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasDescription".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString { value: "A test Dataset.".to_string(), language: "en".to_string() }),
+                            },
+                        ))],
+                        after: None,
+                    },
+                    format: Some(DataFormat::Turtle),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::Turtle,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataset/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;\n\t<https://ontology.axone.space/core/hasTag> \"test\" ;\n\t<https://ontology.axone.space/core/hasTitle> \"test Dataset\"@en , \"Dataset de test\"@fr ;\n\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;\n\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde> ;\n\t<https://ontology.axone.space/core/hasFormat> <https://ontology.axone.space/thesaurus/media-type/application_vndms-excel> ;\n\t<https://ontology.axone.space/core/hasCreator> \"Me\" ;\n\t<https://ontology.axone.space/core/hasLicense> <https://ontology.axone.space/thesaurus/license/LO-FR-1_0> ;\n\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasDescription> \"Un Dataset de test.\"@fr , \"A test Dataset.\"@en .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111E".to_string() },
+                }
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(deps.as_ref(), mock_env(), q);
+
+            assert!(res.is_ok());
+
+            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+
+            assert_eq!(result.format, expected.format);
+            assert_eq!(result.page_info, expected.page_info);
+            assert_eq!(
+                String::from_utf8_lossy(&result.data),
+                String::from_utf8_lossy(&expected.data)
+            );
+        }
+    }
+
+    #[test]
+    fn variable_mutiple_resources_describe() {
+        let cases = vec![
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() }],
+                        resource: VarOrNamedNode::Variable("a".to_string()),
+                        r#where: vec![WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Variable("a".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasPublisher".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Literal(Literal::Simple("AXONE".to_string())),
+                            },
+                        ))],
+                        after: None,
+                    },
+                    format: Some(DataFormat::Turtle),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::Turtle,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataspace/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;\n\t<https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasTitle> \"Data Space de test\"@fr , \"Test Data Space\"@en ;\n\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;\n\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataspace/97ff7e16-c08d-47be-8475-211016c82e33> ;\n\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasDescription> \"A test Data Space.\"@en , \"Un Data Space de test.\"@fr .\n<https://ontology.axone.space/dataverse/dataset/metadata/d1615703-4ee1-4e2f-997e-15aecf1eea4e> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/metadata/dataset/GeneralMetadata> , <http://www.w3.org/2002/07/owl#NamedIndividual> ;\n\t<https://ontology.axone.space/core/hasTag> \"test\" ;\n\t<https://ontology.axone.space/core/hasTitle> \"test Dataset\"@en , \"Dataset de test\"@fr ;\n\t<https://ontology.axone.space/core/hasTopic> <https://ontology.axone.space/thesaurus/topic/Test> ;\n\t<https://ontology.axone.space/core/describes> <https://ontology.axone.space/dataverse/dataset/0ea1fc7a-dd97-4adc-a10e-169c6597bcde> ;\n\t<https://ontology.axone.space/core/hasFormat> <https://ontology.axone.space/thesaurus/media-type/application_vndms-excel> ;\n\t<https://ontology.axone.space/core/hasCreator> \"Me\" ;\n\t<https://ontology.axone.space/core/hasLicense> <https://ontology.axone.space/thesaurus/license/LO-FR-1_0> ;\n\t<https://ontology.axone.space/core/hasPublisher> \"AXONE\" ;\n\t<https://ontology.axone.space/core/hasDescription> \"Un Dataset de test.\"@fr , \"A test Dataset.\"@en .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "1111111R".to_string() },
+                }
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(deps.as_ref(), mock_env(), q);
+
+            assert!(res.is_ok());
+
+            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+
+            assert_eq!(result.format, expected.format);
+            assert_eq!(result.page_info, expected.page_info);
+            assert_eq!(
+                String::from_utf8_lossy(&result.data),
+                String::from_utf8_lossy(&expected.data)
+            );
+        }
+    }
+
+    #[test]
+    fn blanknode_describe() {
+        let cases = vec![
+            (
+                QueryMsg::Describe {
+                    query: DescribeQuery {
+                        prefixes: vec![
+                            Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() },
+                            Prefix { prefix: "metadata-dataset".to_string(), namespace: "https://ontology.axone.space/dataverse/dataset/metadata/".to_string() },
+                        ],
+                        resource: VarOrNamedNode::Variable("x".to_string()),
+                        r#where: vec![WhereCondition::Simple(TriplePattern(
+                            msg::TriplePattern {
+                                subject: VarOrNode::Node(NamedNode(Prefixed("metadata-dataset:80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string()))),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTemporalCoverage".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("x".to_string()),
+                            },
+                        )),
+                        ],
+                        after: None,
+                    },
+                    format: Some(DataFormat::Turtle),
+                    as_of_height: None,
+                },
+                DescribeResponse {
+                    format: DataFormat::Turtle,
+                    data: Binary::from(
+                        "<b0> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> , <https://ontology.axone.space/core/Period> ;\n\t<https://ontology.axone.space/core/hasStartDate> \"2022-01-01T00:00:00+00:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "11111114".to_string() },
+                }
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::Turtle),
+                data: read_test_data("blank-nodes.ttl"),
+            },
+        )
+        .unwrap();
+
+        for (q, expected) in cases {
+            let res = query(deps.as_ref(), mock_env(), q);
+
+            assert!(res.is_ok());
+
+            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+
+            assert_eq!(result.format, expected.format);
+            assert_eq!(result.page_info, expected.page_info);
+            assert_eq!(
+                String::from_utf8_lossy(&result.data),
+                String::from_utf8_lossy(&expected.data)
+            );
+        }
+    }
+
+    #[test]
+    fn proper_construct() {
+        let id = "https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473";
+        let cases = vec![
+            (
+                InsertData {
+                    format: Some(DataFormat::RDFXml),
+                    data: read_test_data("sample.rdf.xml"),
+                },
+                QueryMsg::Construct {
+                    query: ConstructQuery {
+                        prefixes: vec![],
+                        construct: vec![],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                "https://ontology.axone.space/core/hasTag".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        after: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+                ConstructResponse {
+                    format: DataFormat::Turtle,
+                    data: Binary::from(
+                        "<https://ontology.axone.space/dataverse/dataspace/metadata/dcf48417-01c5-4b43-9bc7-49e54c028473> <https://ontology.axone.space/core/hasTag> \"Test\" , \"AXONE\" .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "11111113".to_string() },
+                },
+            ),
+            (
+                InsertData {
+                    format: Some(DataFormat::RDFXml),
+                    data: read_test_data("sample.rdf.xml"),
+                },
+                QueryMsg::Construct {
+                    query: ConstructQuery {
+                        prefixes: vec![
+                            Prefix { prefix: "my-ns".to_string(), namespace: "https://my-ns.org/".to_string() },
+                            Prefix { prefix: "metadata-dataset".to_string(), namespace: "https://ontology.axone.space/dataverse/dataset/metadata/".to_string() },
+                        ],
+                        construct: vec![
+                            msg::TripleConstructTemplate {
+                                subject: VarOrNode::Node(NamedNode(Prefixed("my-ns:instance-1".to_string()))),
+                                predicate: VarOrNamedNode::NamedNode(Full(
+                                    "https://my-ns/predicate/tag".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                                graph: None,
+                            }
+                        ],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                "https://ontology.axone.space/core/hasTag".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NTriples),
+                    as_of_height: None,
+                },
+                ConstructResponse {
+                    format: DataFormat::NTriples,
+                    data: Binary::from(
+                        "<https://my-ns.org/instance-1> <https://my-ns/predicate/tag> \"Test\" .\n<https://my-ns.org/instance-1> <https://my-ns/predicate/tag> \"AXONE\" .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "11111113".to_string() },
+                },
+            ),
+            (
+                InsertData {
+                    format: Some(DataFormat::Turtle),
+                    data: read_test_data("blank-nodes.ttl"),
+                },
+                QueryMsg::Construct {
+                    query: ConstructQuery {
+                        prefixes: vec![
+                            Prefix { prefix: "core".to_string(), namespace: "https://ontology.axone.space/core/".to_string() },
+                            Prefix { prefix: "metadata-dataset".to_string(), namespace: "https://ontology.axone.space/dataverse/dataset/metadata/".to_string() },
+                        ],
+                        construct: vec![
+                            msg::TripleConstructTemplate {
+                                subject: VarOrNode::Node(BlankNode("my-metadata".to_string())),
+                                predicate: VarOrNamedNode::NamedNode(Full(
+                                    "https://my-ns/predicate/tcov".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("tcov".to_string()),
+                                graph: None,
+                            },
+                            msg::TripleConstructTemplate {
+                                subject: VarOrNode::Node(BlankNode("my-metadata".to_string())),
+                                predicate: VarOrNamedNode::NamedNode(Full(
+                                    "https://my-ns/predicate/info".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("info".to_string()),
+                                graph: None,
+                            },
+                            msg::TripleConstructTemplate {
+                                subject: VarOrNode::Variable("tcov".to_string()),
+                                predicate: VarOrNamedNode::Variable("tcov_p".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("tcov_o".to_string()),
+                                graph: None,
+                            },
+                            msg::TripleConstructTemplate {
+                                subject: VarOrNode::Variable("info".to_string()),
+                                predicate: VarOrNamedNode::Variable("info_p".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("info_o".to_string()),
+                                graph: None,
+                            }
+                        ],
+                        r#where: vec![
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Node(NamedNode(Prefixed("metadata-dataset:80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string()))),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasTemporalCoverage".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("tcov".to_string()),
+                            })),
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Node(NamedNode(Prefixed("metadata-dataset:80b1f84e-86dc-4730-b54f-701ad9b1888a".to_string()))),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Prefixed(
+                                    "core:hasInformations".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("info".to_string()),
+                            })),
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("tcov".to_string()),
+                                predicate: VarOrNamedNodeOrPath::Variable("tcov_p".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("tcov_o".to_string()),
+                            })),
+                            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Variable("info".to_string()),
+                                predicate: VarOrNamedNodeOrPath::Variable("info_p".to_string()),
+                                object: VarOrNodeOrLiteral::Variable("info_o".to_string()),
+                            }))
+                        ],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NTriples),
+                    as_of_height: None,
+                },
+                ConstructResponse {
+                    format: DataFormat::NTriples,
+                    data: Binary::from(
+                        "<a0> <https://my-ns/predicate/tcov> <b0> .\n<a0> <https://my-ns/predicate/info> <b1> .\n<b0> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .\n<b1> <https://ontology.axone.space/core/hasInformation> \"this is a dataset\" .\n<a0> <https://my-ns/predicate/tcov> <b0> .\n<a0> <https://my-ns/predicate/info> <b1> .\n<b0> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://ontology.axone.space/core/Period> .\n<b1> <https://ontology.axone.space/core/hasInformation> \"this is a dataset\" .\n<a0> <https://my-ns/predicate/tcov> <b0> .\n<a0> <https://my-ns/predicate/info> <b1> .\n<b0> <https://ontology.axone.space/core/hasStartDate> \"2022-01-01T00:00:00+00:00\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n<b1> <https://ontology.axone.space/core/hasInformation> \"this is a dataset\" .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "11111114".to_string() },
+                },
+            ),
+            (
+                InsertData {
+                    format: Some(DataFormat::RDFXml),
+                    data: read_test_data("sample.rdf.xml"),
+                },
+                QueryMsg::Construct {
+                    query: ConstructQuery {
+                        prefixes: vec![
+                            Prefix { prefix: "my-ns".to_string(), namespace: "https://my-ns.org/".to_string() },
+                        ],
+                        construct: vec![
+                            msg::TripleConstructTemplate {
+                                subject: VarOrNode::Node(NamedNode(Prefixed("my-ns:instance-1".to_string()))),
+                                predicate: VarOrNamedNode::NamedNode(Full(
+                                    "https://my-ns/predicate/keyword".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                                graph: None,
+                            }
+                        ],
+                        r#where: vec![WhereCondition::Union(vec![
+                            vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                    "https://ontology.axone.space/core/hasTag".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                            }))],
+                            vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                                subject: VarOrNode::Node(NamedNode(Full(id.to_string()))),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                    "https://ontology.axone.space/core/hasTopic".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                            }))],
+                        ])],
+                        after: None,
+                    },
+                    format: Some(DataFormat::NTriples),
+                    as_of_height: None,
+                },
+                ConstructResponse {
+                    format: DataFormat::NTriples,
+                    data: Binary::from(
+                        "<https://my-ns.org/instance-1> <https://my-ns/predicate/keyword> \"Test\" .\n<https://my-ns.org/instance-1> <https://my-ns/predicate/keyword> \"AXONE\" .\n<https://my-ns.org/instance-1> <https://my-ns/predicate/keyword> <https://ontology.axone.space/thesaurus/topic/Test> .\n".to_string().as_bytes().to_vec()),
+                    page_info: PageInfo { has_next_page: false, cursor: "11111114".to_string() },
+                },
+            ),
+        ];
+
+        for (data, q, expected) in cases {
+            let mut deps = mock_dependencies();
+
+            let info = mock_info("owner", &[]);
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    limits: StoreLimitsInput::default(),
+                    strict_typing: false,
+                    versioning: false,
+                },
+            )
+            .unwrap();
+
+            execute(deps.as_mut(), mock_env(), info.clone(), data).unwrap();
+
+            let res = query(deps.as_ref(), mock_env(), q);
+
+            assert!(res.is_ok());
+
+            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+
+            assert_eq!(result.format, expected.format);
+            assert_eq!(result.page_info, expected.page_info);
+            assert_eq!(
+                String::from_utf8_lossy(&result.data),
+                String::from_utf8_lossy(&expected.data)
+            );
+        }
+    }
+
+    #[test]
+    fn proper_named_graphs() {
+        let ex = |value: &str| Full(format!("https://example.org/{value}"));
+        let pattern = |s: &str, o: &str| {
+            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::NamedNode(ex("p")),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let select = |deps: Deps<'_>, select: Vec<&str>, r#where: Vec<WhereCondition>| {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![],
+                        select: select
+                            .iter()
+                            .map(|v| SelectItem::Variable(v.to_string()))
+                            .collect(),
+                        r#where,
+                        group_by: vec![],
+                        having: None,
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                    },
+                    format: None,
+                    as_of_height: None,
+                },
+            );
+            from_json::<SelectResponse>(&res.unwrap())
+                .unwrap()
+                .results
+                .bindings
+                .into_iter()
+                .map(|binding| {
+                    binding
+                        .into_values()
+                        .map(|value| match value {
+                            Value::URI { value: Full(iri) } => {
+                                iri.replace("https://example.org/", "")
+                            }
+                            Value::Literal { value, .. } => value,
+                            _ => panic!("unexpected value"),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InsertData {
+                format: Some(DataFormat::NQuads),
+                data: Binary::from(
+                    "<https://example.org/a> <https://example.org/p> \"1\" <https://example.org/g1> .
+<https://example.org/b> <https://example.org/p> \"2\" <https://example.org/g1> .
+<https://example.org/a> <https://example.org/p> \"1\" <https://example.org/g2> .
+<https://example.org/a> <https://example.org/p> \"1\" .
+"
+                    .as_bytes(),
+                ),
+            },
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "insert"),
+                Attribute::new("triple_count", "4"),
+            ]
+        );
+
+        // Out of a graph condition, the triples of all the graphs are matched.
+        assert_eq!(
+            select(deps.as_ref(), vec!["s", "o"], vec![pattern("s", "o")]),
+            vec!["1 a", "1 a", "1 a", "2 b"]
+        );
+        assert_eq!(
+            select(
+                deps.as_ref(),
+                vec!["g", "s", "o"],
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::Variable("g".to_string()),
+                    r#where: vec![pattern("s", "o")],
+                }],
+            ),
+            vec!["g1 1 a", "g2 1 a", "g1 2 b"]
+        );
+        assert_eq!(
+            select(
+                deps.as_ref(),
+                vec!["s"],
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::NamedNode(ex("g2")),
+                    r#where: vec![pattern("s", "o")],
+                }],
+            ),
+            vec!["a"]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Construct {
+                query: ConstructQuery {
+                    prefixes: vec![],
+                    construct: vec![],
+                    r#where: vec![WhereCondition::Graph {
+                        graph: VarOrNamedNode::NamedNode(ex("g1")),
+                        r#where: vec![pattern("s", "o")],
+                    }],
+                    after: None,
+                },
+                format: Some(DataFormat::NQuads),
+                as_of_height: None,
+            },
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&from_json::<ConstructResponse>(&res.unwrap()).unwrap().data),
+            "<https://example.org/a> <https://example.org/p> \"1\" <https://example.org/g1> .
+<https://example.org/b> <https://example.org/p> \"2\" <https://example.org/g1> .
+"
+        );
+
+        // Dropping a graph leaves the triples of the other graphs untouched.
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            DeleteData {
+                prefixes: vec![],
+                delete: vec![],
+                r#where: vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::NamedNode(ex("g1")),
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                    }))],
+                }],
+            },
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "delete"),
+                Attribute::new("triple_count", "2"),
+            ]
+        );
+        assert_eq!(
+            select(
+                deps.as_ref(),
+                vec!["g", "s"],
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::Variable("g".to_string()),
+                    r#where: vec![pattern("s", "o")],
+                }],
+            ),
+            vec!["g2 a"]
+        );
+        assert_eq!(
+            select(deps.as_ref(), vec!["s"], vec![pattern("s", "o")]),
+            vec!["a", "a"]
+        );
+
+        // Out of a graph condition, the matched triples are deleted from all their graphs.
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            DeleteData {
+                prefixes: vec![],
+                delete: vec![],
+                r#where: vec![pattern("s", "o")],
+            },
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "delete"),
+                Attribute::new("triple_count", "2"),
+            ]
+        );
+        assert_eq!(
+            select(deps.as_ref(), vec!["s"], vec![pattern("s", "o")]),
+            Vec::<String>::new()
+        );
+
+        // Under a graph condition, the property paths are followed within each graph.
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::NQuads),
+                data: Binary::from(
+                    "<https://example.org/a> <https://example.org/p> <https://example.org/b> <https://example.org/g1> .
+<https://example.org/b> <https://example.org/p> <https://example.org/c> <https://example.org/g1> .
+<https://example.org/b> <https://example.org/p> <https://example.org/d> <https://example.org/g2> .
+<https://example.org/c> <https://example.org/p> <https://example.org/e> .
+"
+                    .as_bytes(),
+                ),
+            },
+        )
+        .unwrap();
+        let path_pattern = |s: VarOrNode| {
+            WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                subject: s,
+                predicate: VarOrNamedNodeOrPath::Path(msg::PropertyPath::OneOrMore(Box::new(
+                    msg::PropertyPath::NamedNode(ex("p")),
+                ))),
+                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+            }))
+        };
+        assert_eq!(
+            select(
+                deps.as_ref(),
+                vec!["o"],
+                vec![path_pattern(VarOrNode::Node(NamedNode(ex("a"))))],
+            ),
+            vec!["b", "d", "c", "e"]
+        );
+        assert_eq!(
+            select(
+                deps.as_ref(),
+                vec!["g", "o"],
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::Variable("g".to_string()),
+                    r#where: vec![path_pattern(VarOrNode::Node(NamedNode(ex("a"))))],
+                }],
+            ),
+            vec!["g1 b", "g1 c"]
+        );
+        assert_eq!(
+            select(
+                deps.as_ref(),
+                vec!["s", "o"],
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::NamedNode(ex("g2")),
+                    r#where: vec![path_pattern(VarOrNode::Variable("s".to_string()))],
+                }],
+            ),
+            vec!["d b"]
+        );
+    }
+
+    #[test]
+    fn proper_json_ld() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        let document = r#"{
+            "@context": "https://example.org/context",
+            "@id": "ex:alice",
+            "@type": "Person",
+            "name": "Alice",
+            "knows": {"@id": "ex:bob", "name": {"@value": "Bob", "@language": "en"}}
+        }"#;
+        let insert = |deps: DepsMut<'_>| {
+            execute(
+                deps,
+                mock_env(),
+                info.clone(),
+                InsertData {
+                    format: Some(DataFormat::JsonLd),
+                    data: Binary::from(document.as_bytes()),
+                },
+            )
+        };
+
+        assert_eq!(
+            insert(deps.as_mut()).err(),
+            Some(ContractError::ParseRDF(RDFParseError::JsonLd(
+                "Unknown context: https://example.org/context".to_string()
+            )))
+        );
+
+        let register = |iri: &str, context: &str| ExecuteMsg::RegisterJsonLdContext {
+            iri: iri.to_string(),
+            context: Binary::from(context.as_bytes()),
+        };
+        let context = r#"{"@context": {
+            "ex": "https://example.org/",
+            "@vocab": "https://example.org/",
+            "knows": {"@type": "@id"}
+        }}"#;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("not-owner", &[]),
+                register("https://example.org/context", context),
+            )
+            .err(),
+            Some(ContractError::Unauthorized)
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                register("https://example.org/context", "[]"),
+            )
+            .err(),
+            Some(ContractError::ParseRDF(RDFParseError::JsonLd(
+                "Invalid context: expected an object with a @context entry".to_string()
+            )))
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                register("https://example.org/context", context),
+            )
+            .unwrap()
+            .attributes,
+            vec![
+                Attribute::new("action", "register_json_ld_context"),
+                Attribute::new("iri", "https://example.org/context"),
+            ]
+        );
+
+        assert_eq!(
+            insert(deps.as_mut()).unwrap().attributes,
+            vec![
+                Attribute::new("action", "insert"),
+                Attribute::new("triple_count", "4"),
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Construct {
+                query: ConstructQuery {
+                    prefixes: vec![Prefix {
+                        prefix: "ex".to_string(),
+                        namespace: "https://example.org/".to_string(),
+                    }],
+                    construct: vec![],
+                    r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                    }))],
+                    after: None,
+                },
+                format: Some(DataFormat::JsonLd),
+                as_of_height: None,
+            },
+        );
+        assert_eq!(
+            String::from_utf8(
+                from_json::<ConstructResponse>(&res.unwrap())
+                    .unwrap()
+                    .data
+                    .to_vec()
+            )
+            .unwrap(),
+            r#"{"@context":{"ex":"https://example.org/"},"@graph":[{"@id":"ex:alice","@type":"ex:Person","ex:name":"Alice","ex:knows":{"@id":"ex:bob"}},{"@id":"ex:bob","ex:name":{"@value":"Bob","@language":"en"}}]}"#
+        );
+    }
+
+    #[test]
+    fn proper_trig() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::TriG),
+                data: Binary::from(
+                    "@prefix ex: <https://example.org/> .
+ex:a ex:p \"1\" .
+ex:g { ex:a ex:p ex:b . ex:b ex:p \"2\" . }
+"
+                    .as_bytes(),
+                ),
+            },
+        );
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "insert"),
+                Attribute::new("triple_count", "3"),
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Construct {
+                query: ConstructQuery {
+                    prefixes: vec![],
+                    construct: vec![],
+                    r#where: vec![WhereCondition::Graph {
+                        graph: VarOrNamedNode::Variable("g".to_string()),
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                    }],
+                    after: None,
+                },
+                format: Some(DataFormat::TriG),
+                as_of_height: None,
+            },
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&from_json::<ConstructResponse>(&res.unwrap()).unwrap().data),
+            "<https://example.org/g> {
+\t<https://example.org/b> <https://example.org/p> \"2\" .
+\t<https://example.org/a> <https://example.org/p> <https://example.org/b> .
+}
+"
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Describe {
+                query: DescribeQuery {
+                    prefixes: vec![],
+                    resource: VarOrNamedNode::NamedNode(Full("https://example.org/a".to_string())),
+                    r#where: vec![],
+                    after: None,
+                },
+                format: Some(DataFormat::TriG),
+                as_of_height: None,
+            },
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&from_json::<DescribeResponse>(&res.unwrap()).unwrap().data),
+            "<https://example.org/a> <https://example.org/p> \"1\" , <https://example.org/b> .
+"
+        );
+    }
+
+    #[test]
+    fn proper_migrate() {
+        use cw_storage_plus::Index;
+
+        let mut deps = mock_dependencies();
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        // Restore the state of a store populated before the graph and predicate indexes and the
+        // statistics.
+        let stored: Vec<(Vec<u8>, Triple)> = triples()
+            .range_raw(&deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        let indexes = triples().idx;
+        for (pk, triple) in &stored {
+            indexes
+                .graph_and_subject
+                .remove(&mut deps.storage, pk, triple)
+                .unwrap();
+            indexes
+                .predicate_and_subject
+                .remove(&mut deps.storage, pk, triple)
+                .unwrap();
+        }
+        PREDICATE_STATS.clear(&mut deps.storage);
+        NAMESPACE_STATS.clear(&mut deps.storage);
+        NAMESPACE_STATS
+            .save(&mut deps.storage, 42u128, &NamespaceStat::default())
+            .unwrap();
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "4.0.0").unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "migrate"),
+                Attribute::new("from_version", "4.0.0"),
+                Attribute::new("to_version", CONTRACT_VERSION),
+            ]
+        );
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            CONTRACT_VERSION
+        );
+
+        let predicate = Node {
+            namespace: 3u128,
+            value: "hasRegistrar".to_string(),
+        };
+        assert_eq!(
+            triples()
+                .idx
+                .predicate_and_subject
+                .sub_prefix(predicate.key())
+                .range(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            2
+        );
+        assert_eq!(
+            triples()
+                .idx
+                .predicate_and_subject
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            40
+        );
+        assert_eq!(
+            triples()
+                .idx
+                .graph_and_subject
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            40
+        );
+        assert_eq!(
+            PREDICATE_STATS.load(&deps.storage, &predicate.key()),
+            Ok(2u128)
+        );
+        assert_eq!(
+            PREDICATE_STATS
+                .range(&deps.storage, None, None, Order::Ascending)
+                .map(|res| res.unwrap().1)
+                .sum::<u128>(),
+            40
+        );
+        assert_eq!(
+            NAMESPACE_STATS.load(&deps.storage, 0u128),
+            Ok(NamespaceStat {
+                subject_count: 3,
+                object_count: 2,
+            })
+        );
+        assert!(!NAMESPACE_STATS.has(&deps.storage, 42u128));
+    }
+
+    #[test]
+    fn migrate_multiple_chunks() {
+        use cw_storage_plus::Index;
+
+        let mut deps = mock_dependencies();
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+        let data = (0..250)
+            .map(|i| format!("<https://example.org/s{i}> <https://example.org/p> \"{i}\" .\n"))
+            .collect::<Vec<_>>()
+            .concat();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::NTriples),
+                data: Binary::from(data.as_bytes()),
+            },
+        )
+        .unwrap();
+
+        let stored: Vec<(Vec<u8>, Triple)> = triples()
+            .range_raw(&deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        let indexes = triples().idx;
+        for (pk, triple) in &stored {
+            indexes
+                .graph_and_subject
+                .remove(&mut deps.storage, pk, triple)
+                .unwrap();
+            indexes
+                .predicate_and_subject
+                .remove(&mut deps.storage, pk, triple)
+                .unwrap();
+        }
+        PREDICATE_STATS.clear(&mut deps.storage);
+        NAMESPACE_STATS.clear(&mut deps.storage);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "5.0.0").unwrap();
+
+        assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg {}).is_ok());
+
+        assert_eq!(
+            triples()
+                .idx
+                .graph_and_subject
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            250
+        );
+        assert_eq!(
+            triples()
+                .idx
+                .predicate_and_subject
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            250
+        );
+        assert_eq!(
+            PREDICATE_STATS
+                .range(&deps.storage, None, None, Order::Ascending)
+                .map(|res| res.unwrap().1)
+                .sum::<u128>(),
+            250
+        );
+    }
+
+    #[test]
+    fn migrate_from_current_version() {
+        use cw_storage_plus::Index;
+
+        let mut deps = mock_dependencies();
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        // Alter the indexes and the statistics, so any backfill would be noticed.
+        let (pk, triple) = triples()
+            .range_raw(&deps.storage, None, None, Order::Ascending)
+            .next()
+            .unwrap()
+            .unwrap();
+        triples()
+            .idx
+            .predicate_and_subject
+            .remove(&mut deps.storage, &pk, &triple)
+            .unwrap();
+        NAMESPACE_STATS
+            .save(&mut deps.storage, 42u128, &NamespaceStat::default())
+            .unwrap();
+        let predicate_stats = PREDICATE_STATS
+            .range(&deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+        assert_eq!(
+            res.unwrap().attributes,
+            vec![
+                Attribute::new("action", "migrate"),
+                Attribute::new("from_version", CONTRACT_VERSION),
+                Attribute::new("to_version", CONTRACT_VERSION),
+            ]
+        );
+
+        assert_eq!(
+            triples()
+                .idx
+                .predicate_and_subject
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            39
+        );
+        assert!(NAMESPACE_STATS.has(&deps.storage, 42u128));
+        assert_eq!(
+            PREDICATE_STATS
+                .range(&deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap(),
+            predicate_stats
+        );
+    }
+
+    #[test]
+    fn invalid_migrate() {
+        let cases = vec![
+            (
+                "crates.io:axone-law-stone",
+                "4.0.0",
+                ContractError::MigrationContract("crates.io:axone-law-stone".to_string()),
+            ),
+            (
+                CONTRACT_NAME,
+                "99.0.0",
+                ContractError::MigrationVersion("99.0.0".to_string(), CONTRACT_VERSION.to_string()),
+            ),
+            (
+                CONTRACT_NAME,
+                "not-a-version",
+                ContractError::Std(StdError::generic_err(
+                    "Invalid contract version not-a-version: unexpected character 'n' while parsing major version number",
+                )),
             ),
         ];
 
-        for (data, q, expected) in cases {
+        for (contract, version, expected) in cases {
             let mut deps = mock_dependencies();
-
-            let info = mock_info("owner", &[]);
             instantiate(
                 deps.as_mut(),
                 mock_env(),
-                info.clone(),
-                InstantiateMsg {
-                    limits: StoreLimitsInput::default(),
+                mock_info("owner", &[]),
+                InstantiateMsg::default(),
+            )
+            .unwrap();
+            set_contract_version(&mut deps.storage, contract, version).unwrap();
+
+            let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+            assert_eq!(res, Err(expected));
+        }
+    }
+
+    #[test]
+    fn proper_update_limits() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InsertData {
+                format: Some(DataFormat::RDFXml),
+                data: read_test_data("sample.rdf.xml"),
+            },
+        )
+        .unwrap();
+
+        let limits = |max_triple_count: u128, max_byte_size: u128| msg::StoreLimits {
+            max_triple_count: max_triple_count.into(),
+            max_byte_size: max_byte_size.into(),
+            max_query_limit: 10,
+            ..StoreLimits::from(StoreLimitsInput::default()).into()
+        };
+        let cases = vec![
+            (
+                "not-owner",
+                limits(100, 10000),
+                Err(ContractError::Unauthorized),
+            ),
+            (
+                "owner",
+                limits(39, 10000),
+                Err(ContractError::from(StoreError::LimitBelowUsage(
+                    "triple count".to_string(),
+                    40u128.into(),
+                ))),
+            ),
+            (
+                "owner",
+                limits(100, 10),
+                Err(ContractError::from(StoreError::LimitBelowUsage(
+                    "byte size".to_string(),
+                    7190u128.into(),
+                ))),
+            ),
+            (
+                "owner",
+                limits(40, 7190),
+                Ok(vec![Attribute::new("action", "update_limits")]),
+            ),
+        ];
+
+        for (sender, limits, expected) in cases {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::UpdateLimits { limits },
+            );
+            assert_eq!(res.map(|res| res.attributes), expected);
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Store {});
+        let store = from_json::<StoreResponse>(&res.unwrap()).unwrap();
+        assert_eq!(store.limits.max_triple_count, Uint128::new(40));
+        assert_eq!(store.limits.max_byte_size, Uint128::new(7190));
+        assert_eq!(store.limits.max_query_limit, 10);
+    }
+
+    #[test]
+    fn proper_triple_provenance() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::GrantRole {
+                address: "writer".to_string(),
+                role: Role::Writer,
+                scope: None,
+            },
+        )
+        .unwrap();
+
+        let insert = |deps: DepsMut<'_>, sender: &str, height: u64, data: &str| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env.block.time = Timestamp::from_seconds(height * 5);
+            execute(
+                deps,
+                env,
+                mock_info(sender, &[]),
+                InsertData {
+                    format: Some(DataFormat::NQuads),
+                    data: Binary::from(data.as_bytes()),
                 },
             )
             .unwrap();
+        };
+        insert(
+            deps.as_mut(),
+            "owner",
+            10,
+            "<https://example.org/a> <https://example.org/p> \"1\" .\n\
+             <https://example.org/a> <https://example.org/p> <https://example.org/b> <https://example.org/g> .",
+        );
+        insert(
+            deps.as_mut(),
+            "writer",
+            20,
+            "<https://example.org/a> <https://example.org/p> \"1\" .\n\
+             <https://example.org/a> <https://example.org/p> \"2\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+        );
+        // Nothing new is inserted, no batch is recorded.
+        insert(
+            deps.as_mut(),
+            "writer",
+            30,
+            "<https://example.org/a> <https://example.org/p> \"2\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+        );
+        assert_eq!(
+            BATCHES
+                .keys(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            2
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            DeleteData {
+                prefixes: vec![],
+                delete: vec![msg::TripleDeleteTemplate {
+                    subject: VarOrNamedNode::NamedNode(Full("https://example.org/a".to_string())),
+                    predicate: VarOrNamedNode::NamedNode(Full("https://example.org/p".to_string())),
+                    object: VarOrNamedNodeOrLiteral::Literal(msg::Literal::Simple("1".to_string())),
+                    graph: None,
+                }],
+                r#where: vec![],
+            },
+        )
+        .unwrap();
 
-            execute(deps.as_mut(), mock_env(), info.clone(), data).unwrap();
+        let provenance = |subject: &str, object: msg::NamedNodeOrLiteral, graph: Option<&str>| {
+            QueryMsg::TripleProvenance {
+                prefixes: vec![Prefix {
+                    prefix: "ex".to_string(),
+                    namespace: "https://example.org/".to_string(),
+                }],
+                subject: Prefixed(subject.to_string()),
+                predicate: Prefixed("ex:p".to_string()),
+                object,
+                graph: graph.map(|g| Full(g.to_string())),
+            }
+        };
+        let cases = vec![
+            (
+                provenance(
+                    "ex:a",
+                    msg::NamedNodeOrLiteral::NamedNode(Full("https://example.org/b".to_string())),
+                    Some("https://example.org/g"),
+                ),
+                Ok(msg::TripleProvenanceResponse {
+                    batch_id: 1,
+                    sender: "owner".to_string(),
+                    block_height: 10,
+                    block_time: Timestamp::from_seconds(50),
+                }),
+            ),
+            (
+                provenance(
+                    "ex:a",
+                    msg::NamedNodeOrLiteral::Literal(msg::Literal::TypedValue {
+                        value: "2".to_string(),
+                        datatype: Full("http://www.w3.org/2001/XMLSchema#integer".to_string()),
+                    }),
+                    None,
+                ),
+                Ok(msg::TripleProvenanceResponse {
+                    batch_id: 2,
+                    sender: "writer".to_string(),
+                    block_height: 20,
+                    block_time: Timestamp::from_seconds(100),
+                }),
+            ),
+            (
+                // The triple has been deleted.
+                provenance(
+                    "ex:a",
+                    msg::NamedNodeOrLiteral::Literal(msg::Literal::Simple("1".to_string())),
+                    None,
+                ),
+                Err(StdError::not_found("Triple provenance")),
+            ),
+            (
+                // The triple exists in another graph only.
+                provenance(
+                    "ex:a",
+                    msg::NamedNodeOrLiteral::NamedNode(Full("https://example.org/b".to_string())),
+                    None,
+                ),
+                Err(StdError::not_found("Triple provenance")),
+            ),
+            (
+                provenance(
+                    "ex:a",
+                    msg::NamedNodeOrLiteral::NamedNode(Full("https://other.org/b".to_string())),
+                    None,
+                ),
+                Err(StdError::not_found("Triple provenance")),
+            ),
+        ];
 
+        for (q, expected) in cases {
             let res = query(deps.as_ref(), mock_env(), q);
+            assert_eq!(
+                res.and_then(|res| from_json::<msg::TripleProvenanceResponse>(&res)),
+                expected
+            );
+        }
+    }
 
-            assert!(res.is_ok());
+    #[test]
+    fn proper_versioning() {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg {
+                versioning: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
 
-            let result = from_json::<DescribeResponse>(&res.unwrap()).unwrap();
+        let env_at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        let object =
+            |value: &str| format!("<https://example.org/a> <https://example.org/p> \"{value}\" .");
+        let insert = |values: &[&str]| InsertData {
+            format: Some(DataFormat::NQuads),
+            data: Binary::from(
+                values
+                    .iter()
+                    .map(|v| object(v))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .as_bytes(),
+            ),
+        };
+        let delete = |value: &str| DeleteData {
+            prefixes: vec![],
+            delete: vec![msg::TripleDeleteTemplate {
+                subject: VarOrNamedNode::NamedNode(Full("https://example.org/a".to_string())),
+                predicate: VarOrNamedNode::NamedNode(Full("https://example.org/p".to_string())),
+                object: VarOrNamedNodeOrLiteral::Literal(Literal::Simple(value.to_string())),
+                graph: None,
+            }],
+            r#where: vec![],
+        };
+        let history = vec![
+            (10, insert(&["1", "2"])),
+            (20, delete("1")),
+            (30, insert(&["1", "3"])),
+            (40, delete("2")),
+        ];
+        for (height, msg) in history {
+            execute(deps.as_mut(), env_at(height), mock_info("owner", &[]), msg).unwrap();
+        }
 
-            assert_eq!(result.format, expected.format);
+        let select = |deps: Deps<'_>, as_of_height: Option<u64>| {
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::Select {
+                    query: SelectQuery {
+                        prefixes: vec![],
+                        select: vec![SelectItem::Variable("o".to_string())],
+                        r#where: vec![WhereCondition::Simple(TriplePattern(msg::TriplePattern {
+                            subject: VarOrNode::Node(NamedNode(Full(
+                                "https://example.org/a".to_string(),
+                            ))),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(Full(
+                                "https://example.org/p".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }))],
+                        distinct: false,
+                        order_by: vec![],
+                        limit: None,
+                        after: None,
+                        group_by: vec![],
+                        having: None,
+                    },
+                    format: None,
+                    as_of_height,
+                },
+            )
+            .map(|res| {
+                let mut values: Vec<String> = from_json::<SelectResponse>(&res)
+                    .unwrap()
+                    .results
+                    .bindings
+                    .into_iter()
+                    .filter_map(|mut b| match b.remove("o") {
+                        Some(Value::Literal { value, .. }) => Some(value),
+                        _ => None,
+                    })
+                    .collect();
+                values.sort();
+                values
+            })
+        };
+        let cases = vec![
+            (None, vec!["1", "3"]),
+            (Some(5), vec![]),
+            (Some(10), vec!["1", "2"]),
+            (Some(25), vec!["2"]),
+            (Some(35), vec!["1", "2", "3"]),
+            (Some(40), vec!["1", "3"]),
+        ];
+        for (as_of_height, expected) in cases {
             assert_eq!(
-                String::from_utf8_lossy(&result.data),
-                String::from_utf8_lossy(&expected.data)
+                select(deps.as_ref(), as_of_height),
+                Ok(expected.iter().map(|v| v.to_string()).collect())
+            );
+        }
+
+        for (as_of_height, expected) in [(None, false), (Some(35), true)] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Sparql {
+                    query: "ASK { <https://example.org/a> <https://example.org/p> \"2\" }"
+                        .to_string(),
+                    format: None,
+                    after: None,
+                    as_of_height,
+                },
+            );
+            assert_eq!(
+                res.map(|res| from_json::<SparqlResponse>(&res).unwrap()),
+                Ok(SparqlResponse::Ask(AskResponse { result: expected }))
+            );
+        }
+
+        let store = STORE.load(&deps.storage).unwrap();
+        assert_eq!(store.stat.triple_count, Uint128::new(2));
+
+        let prunes = vec![
+            ("not-owner", 50, 5, None, Err(ContractError::Unauthorized)),
+            ("owner", 10, 20, None, Ok(("0", "false"))),
+            // The first deletion of "1" is forgotten, the triple being still valid.
+            ("owner", 50, 25, None, Ok(("0", "false"))),
+            ("owner", 50, 5, Some(0), Ok(("0", "true"))),
+            ("owner", 50, 5, Some(1), Ok(("1", "false"))),
+        ];
+        for (sender, height, retention, limit, expected) in prunes {
+            let res = execute(
+                deps.as_mut(),
+                env_at(height),
+                mock_info(sender, &[]),
+                ExecuteMsg::PruneHistory { retention, limit },
+            );
+            assert_eq!(
+                res.map(|res| res.attributes),
+                expected.map(|(count, has_more)| vec![
+                    Attribute::new("action", "prune_history"),
+                    Attribute::new("triple_count", count),
+                    Attribute::new("has_more", has_more),
+                ])
+            );
+        }
+
+        let cases = vec![
+            (None, vec!["1", "3"]),
+            (Some(15), vec![]),
+            (Some(35), vec!["1", "3"]),
+        ];
+        for (as_of_height, expected) in cases {
+            assert_eq!(
+                select(deps.as_ref(), as_of_height),
+                Ok(expected.iter().map(|v| v.to_string()).collect())
             );
         }
+        assert_eq!(
+            triples()
+                .range_raw(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            2
+        );
+
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            select(deps.as_ref(), Some(10)),
+            Err(StdError::generic_err(
+                "The store isn't versioned, it can't be queried as of a block height"
+            ))
+        );
     }
 }
@@ -0,0 +1,51 @@
+use crate::msg::Cursor;
+use cosmwasm_std::{StdError, StdResult};
+
+/// Encode the offset of the next solution to return as an opaque cursor.
+pub fn encode(offset: usize) -> Cursor {
+    bs58::encode((offset as u64).to_be_bytes()).into_string()
+}
+
+/// Decode the offset of the next solution to return from a cursor.
+pub fn decode<I: AsRef<[u8]>>(cursor: I) -> StdResult<usize> {
+    let raw = bs58::decode(cursor)
+        .into_vec()
+        .map_err(|err| StdError::parse_err("Cursor", err))?;
+
+    <[u8; 8]>::try_from(raw.as_slice())
+        .map_err(|_| StdError::parse_err("Cursor", "invalid cursor length"))
+        .map(u64::from_be_bytes)
+        .and_then(|offset| {
+            usize::try_from(offset).map_err(|err| StdError::parse_err("Cursor", err))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proper_encode_decode() {
+        let cases = vec![0usize, 1usize, 30usize, 1_000_000usize];
+
+        for offset in cases {
+            assert_eq!(decode(encode(offset)), Ok(offset));
+        }
+        assert_eq!(encode(30usize), "1111111X".to_string());
+    }
+
+    #[test]
+    fn invalid_decode() {
+        assert_eq!(
+            decode("?"),
+            Err(StdError::parse_err(
+                "Cursor",
+                "provided string contained invalid character '?' at byte 0"
+            ))
+        );
+        assert_eq!(
+            decode("BzZCCcK"),
+            Err(StdError::parse_err("Cursor", "invalid cursor length"))
+        );
+    }
+}
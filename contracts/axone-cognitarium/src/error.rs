@@ -1,3 +1,4 @@
+use axone_rdf::jsonld::JsonLdError;
 use cosmwasm_std::{StdError, Uint128};
 use cw_utils::PaymentError;
 use rio_turtle::TurtleError;
@@ -18,11 +19,17 @@ pub enum ContractError {
     #[error("{0}")]
     Store(#[from] StoreError),
 
-    #[error("Only the owner can perform this operation.")]
+    #[error("Not authorized to perform this operation.")]
     Unauthorized,
 
     #[error("{0}")]
     Payment(#[from] PaymentError),
+
+    #[error("Cannot migrate from contract {0}.")]
+    MigrationContract(String),
+
+    #[error("Cannot migrate from version {0} to {1}.")]
+    MigrationVersion(String, String),
 }
 
 impl From<RdfXmlError> for ContractError {
@@ -37,6 +44,12 @@ impl From<TurtleError> for ContractError {
     }
 }
 
+impl From<JsonLdError> for ContractError {
+    fn from(value: JsonLdError) -> Self {
+        RDFParseError::from(value).into()
+    }
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum StoreError {
     #[error("Maximum triples number exceeded: {0}")]
@@ -53,6 +66,9 @@ pub enum StoreError {
 
     #[error("Maximum insert triple count exceeded: {0}")]
     InsertDataTripleCount(Uint128),
+
+    #[error("Maximum {0} lower than the current usage: {1}")]
+    LimitBelowUsage(String, Uint128),
 }
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -62,6 +78,9 @@ pub enum RDFParseError {
 
     #[error("Error parsing Turtle RDF: {0}")]
     Turtle(String),
+
+    #[error("Error parsing JSON-LD: {0}")]
+    JsonLd(String),
 }
 
 impl From<RdfXmlError> for RDFParseError {
@@ -70,6 +89,12 @@ impl From<RdfXmlError> for RDFParseError {
     }
 }
 
+impl From<JsonLdError> for RDFParseError {
+    fn from(value: JsonLdError) -> Self {
+        RDFParseError::JsonLd(value.to_string())
+    }
+}
+
 impl From<TurtleError> for RDFParseError {
     fn from(value: TurtleError) -> Self {
         RDFParseError::Xml(value.to_string())
@@ -1,8 +1,11 @@
 pub mod contract;
+mod cursor;
 mod error;
 pub mod msg;
 mod querier;
 mod rdf;
+mod results;
+mod sparql;
 pub mod state;
 mod storer;
 
@@ -1,8 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Uint128};
+use cosmwasm_std::{Binary, Timestamp, Uint128};
 use derive_builder::Builder;
 use std::collections::BTreeMap;
 
+/// A cursor, i.e. an opaque string pointing to a position in a sequence of results.
+///
+/// The position is the number of results already returned, so resuming from a cursor evaluates
+/// the query again up to that point. It isn't stable across writes: triples inserted or deleted
+/// between two pages shift the results, which may then be skipped or returned twice.
+pub type Cursor = String;
+
 /// Instantiate message
 #[cw_serde]
 #[derive(Default)]
@@ -10,8 +17,36 @@ pub struct InstantiateMsg {
     /// Limitations regarding store usage.
     #[serde(default)]
     pub limits: StoreLimitsInput,
+
+    /// Enables the strict typing of literals.
+    ///
+    /// When strict typing is enabled, the lexical form of literals typed with `xsd:integer`,
+    /// `xsd:decimal`, `xsd:double`, `xsd:boolean`, `xsd:date` or `xsd:dateTime` is stored in its
+    /// canonical form, and inserting such a literal with an invalid lexical form fails. Otherwise,
+    /// literals are stored as is, their values being still compared by filters and ordering.
+    #[serde(default)]
+    pub strict_typing: bool,
+
+    /// Enables the versioning of the triples.
+    ///
+    /// When enabled, the deleted triples are kept along with the block heights they've been valid
+    /// in between, allowing to query the store as of a past block height. The history can be
+    /// compacted with [ExecuteMsg::PruneHistory].
+    ///
+    /// The store usage and limits only account for the current triples, the deleted ones kept in
+    /// the history still occupying the storage until being pruned.
+    #[serde(default)]
+    pub versioning: bool,
 }
 
+/// Migrate message
+///
+/// Migrating from a version up to `5.0.0` backfills the indexes and the statistics of all the
+/// stored triples within the migration transaction, its gas consumption growing with the size of
+/// the store.
+#[cw_serde]
+pub struct MigrateMsg {}
+
 /// Execute messages
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -19,8 +54,11 @@ pub enum ExecuteMsg {
     /// Insert the data as RDF triples in the store.
     /// For already existing triples it acts as no-op.
     ///
-    /// Only the smart contract owner (i.e. the address who instantiated it) is authorized to perform
-    /// this action.
+    /// The inserted triples are linked to a batch recording the sender, the block height and time
+    /// of the message, as exposed by the [QueryMsg::TripleProvenance] query.
+    ///
+    /// Only the smart contract owner and the addresses granted the [Role::Writer] role are
+    /// authorized to perform this action, within the scope of their role if restricted.
     InsertData {
         /// The data format in which the triples are serialized.
         /// If not provided, the default format is [Turtle](https://www.w3.org/TR/turtle/) format.
@@ -62,18 +100,154 @@ pub enum ExecuteMsg {
     ///  ]
     /// ```
     ///
-    /// Only the smart contract owner (i.e. the address who instantiated it) is authorized to perform
-    /// this action.
+    /// Only the smart contract owner and the addresses granted the [Role::Deleter] role are
+    /// authorized to perform this action, within the scope of their role if restricted.
     DeleteData {
         /// The prefixes used in the operation.
         prefixes: Vec<Prefix>,
         /// Specifies the specific triple templates to delete.
-        /// If nothing is provided, the patterns from the `where` clause are used for deletion.
+        /// If nothing is provided, the patterns from the `where` clause are used for deletion, which
+        /// requires the clause to contain no [WhereCondition::Union]. The patterns of a
+        /// [WhereCondition::Graph] delete the triples from its graph, so a whole named graph is
+        /// dropped by providing a single `?s ?p ?o` pattern in such a condition, while the ones out
+        /// of such a condition delete the matched triples from all the graphs they belong to.
         delete: Vec<TripleDeleteTemplate>,
         /// Defines the patterns that data (RDF triples) should match in order for it to be
         /// considered for deletion.
         r#where: WhereClause,
     },
+
+    /// # Modify
+    /// Delete and insert data (RDF triples) in a single operation, the triples being forged out of
+    /// the solutions of the provided WHERE clause, which is evaluated once before any change.
+    ///
+    /// The deletion is applied first, so a triple both deleted and inserted is kept. The blank
+    /// nodes of the insert templates designate new blank nodes for each solution. The store
+    /// limits apply to the net result of the operation.
+    ///
+    /// Example, replacing the name of a person:
+    /// ```json
+    /// {
+    ///   "prefixes": [
+    ///     { "prefix": "foaf", "namespace": "http://xmlns.com/foaf/0.1/" }
+    ///   ],
+    ///   "delete": [
+    ///     {
+    ///         "subject": { "variable": "s" },
+    ///         "predicate": { "namedNode": {"prefixed": "foaf:givenName"} },
+    ///         "object": { "variable": "name" }
+    ///     }
+    ///   ],
+    ///   "insert": [
+    ///     {
+    ///         "subject": { "variable": "s" },
+    ///         "predicate": { "namedNode": {"prefixed": "foaf:givenName"} },
+    ///         "object": { "literal": { "simple": "Merlin" } }
+    ///     }
+    ///   ],
+    ///   "where": [
+    ///     { "simple": { "triplePattern": {
+    ///         "subject": { "variable": "s" },
+    ///         "predicate": { "namedNode": {"prefixed": "foaf:givenName"} },
+    ///         "object": { "variable": "name" }
+    ///     } } }
+    ///  ]
+    /// ```
+    ///
+    /// Only the smart contract owner and the addresses granted the [Role::Deleter] role, if
+    /// deleting, and the [Role::Writer] role, if inserting, are authorized to perform this action,
+    /// within the scope of their roles if restricted.
+    ///
+    /// As for [ExecuteMsg::InsertData], the inserted triples are linked to a batch.
+    Modify {
+        /// The prefixes used in the operation.
+        prefixes: Vec<Prefix>,
+        /// The triple templates to delete.
+        delete: Vec<TripleDeleteTemplate>,
+        /// The triple templates to insert, into the named graph of the template if provided.
+        insert: Vec<TripleConstructTemplate>,
+        /// The patterns whose solutions are used to forge the triples to delete and insert.
+        r#where: WhereClause,
+    },
+
+    /// # RegisterJsonLdContext
+    /// Register a [JSON-LD context](https://www.w3.org/TR/json-ld11/#the-context) document under
+    /// the given IRI, replacing the one already registered if any.
+    ///
+    /// As remote contexts can't be fetched, the JSON-LD data inserted in the store can only
+    /// reference the contexts registered this way, besides the ones defined inline.
+    ///
+    /// Only the smart contract owner is authorized to perform this action.
+    RegisterJsonLdContext {
+        /// The IRI by which the context is referenced.
+        iri: String,
+        /// The context document, i.e. a JSON object holding a `@context` entry.
+        context: Binary,
+    },
+
+    /// # GrantRole
+    /// Grant a role to an address, optionally restricted to a scope. Granting a role already
+    /// granted acts as no-op.
+    ///
+    /// Only the smart contract owner is authorized to perform this action.
+    GrantRole {
+        /// The address to grant the role to.
+        address: String,
+        /// The role to grant.
+        role: Role,
+        /// The scope the role is restricted to, the whole store if not provided.
+        scope: Option<AccessScope>,
+    },
+
+    /// # RevokeRole
+    /// Revoke a role from an address, the scope having to match the one it has been granted with.
+    /// Revoking a role not granted acts as no-op.
+    ///
+    /// Only the smart contract owner is authorized to perform this action.
+    RevokeRole {
+        /// The address to revoke the role from.
+        address: String,
+        /// The role to revoke.
+        role: Role,
+        /// The scope the role has been granted with.
+        scope: Option<AccessScope>,
+    },
+
+    /// # TransferOwnership
+    /// Transfer the ownership of the store to another address.
+    ///
+    /// Only the smart contract owner is authorized to perform this action.
+    TransferOwnership {
+        /// The address of the new owner.
+        new_owner: String,
+    },
+
+    /// # UpdateLimits
+    /// Update the limitations regarding the usage of the store.
+    ///
+    /// Only the smart contract owner is authorized to perform this action, the maximum triple count
+    /// and byte size can't be set below the current usage of the store.
+    UpdateLimits {
+        /// The new limitations, replacing the current ones, all of them having to be provided.
+        limits: StoreLimits,
+    },
+
+    /// # PruneHistory
+    /// Compact the history of a versioned store, forgetting the triples deleted before the
+    /// retention window, i.e. at a block height lower than or equal to the current one minus the
+    /// retention. The queries as of a height before the window no longer see those triples.
+    ///
+    /// The deletions are processed from the oldest, at most `limit` of them at once, the
+    /// `has_more` attribute of the response telling whether some are left for a next execution.
+    ///
+    /// Only the smart contract owner is authorized to perform this action.
+    PruneHistory {
+        /// The number of blocks the history is kept over.
+        retention: u64,
+        /// The maximum number of deletions to process.
+        /// If `None`, the maximum query limit defined in the store limitations is used.
+        limit: Option<u32>,
+    },
 }
 
 /// # SelectQuery
@@ -95,6 +269,12 @@ pub enum QueryMsg {
     Select {
         /// The query to execute.
         query: SelectQuery,
+        /// The format in which the results are serialized in the response data.
+        /// If not provided, the results are returned as structured bindings.
+        format: Option<ResultsFormat>,
+        /// The block height to query the store as of, the store having to be versioned.
+        /// If not provided, the current state of the store is queried.
+        as_of_height: Option<u64>,
     },
 
     /// # Describe
@@ -108,6 +288,9 @@ pub enum QueryMsg {
         /// The format in which the triples are serialized.
         /// If not provided, the default format is [Turtle](https://www.w3.org/TR/turtle/) format.
         format: Option<DataFormat>,
+        /// The block height to query the store as of, the store having to be versioned.
+        /// If not provided, the current state of the store is queried.
+        as_of_height: Option<u64>,
     },
 
     /// # Construct
@@ -121,6 +304,80 @@ pub enum QueryMsg {
         /// The format in which the triples are serialized.
         /// If not provided, the default format is [Turtle](https://www.w3.org/TR/turtle/) format.
         format: Option<DataFormat>,
+        /// The block height to query the store as of, the store having to be versioned.
+        /// If not provided, the current state of the store is queried.
+        as_of_height: Option<u64>,
+    },
+
+    /// # Ask
+    ///
+    /// Returns whether the provided query has at least one solution, the evaluation stopping at
+    /// the first one found.
+    #[returns(AskResponse)]
+    Ask {
+        /// The query to execute.
+        query: AskQuery,
+        /// The block height to query the store as of, the store having to be versioned.
+        /// If not provided, the current state of the store is queried.
+        as_of_height: Option<u64>,
+    },
+
+    /// # Sparql
+    ///
+    /// Returns the results of the provided [SPARQL](https://www.w3.org/TR/sparql11-query/) query,
+    /// which can be a SELECT, a CONSTRUCT, a DESCRIBE or an ASK query, the response depending on
+    /// its form.
+    ///
+    /// The query is parsed into its corresponding query message, thus the supported grammar is
+    /// limited to what these messages allow to express (e.g. no BASE declaration, no FROM clause).
+    #[returns(SparqlResponse)]
+    Sparql {
+        /// The SPARQL query to execute.
+        query: String,
+        /// The format in which the triples are serialized, for CONSTRUCT and DESCRIBE queries.
+        /// If not provided, the default format is [Turtle](https://www.w3.org/TR/turtle/) format.
+        format: Option<DataFormat>,
+        /// The point in the sequence of results to start returning results from, as given by the
+        /// [PageInfo] of a previous response, taking precedence over the OFFSET of the query. It
+        /// isn't stable across writes (see [Cursor]).
+        after: Option<Cursor>,
+        /// The block height to query the store as of, the store having to be versioned.
+        /// If not provided, the current state of the store is queried.
+        as_of_height: Option<u64>,
+    },
+
+    /// # Explain
+    ///
+    /// Returns the plan the provided query would be evaluated with, without evaluating it, along
+    /// with the way each triple pattern is matched against the store and the estimated number of
+    /// solutions of each step, the estimations being based on the current state of the store.
+    #[returns(ExplainResponse)]
+    Explain {
+        /// The query to explain.
+        query: SelectQuery,
+    },
+
+    /// # TripleProvenance
+    ///
+    /// Returns the provenance of a triple, i.e. the batch it has been inserted with: who submitted
+    /// it and when.
+    ///
+    /// Only the triples made of named nodes and literals can be traced, the blank nodes not being
+    /// addressable. An error is returned if the triple doesn't exist or has been inserted before the
+    /// tracking of the provenance. For a versioned store, the provenance of the deleted triples
+    /// remains available until their history is pruned.
+    #[returns(TripleProvenanceResponse)]
+    TripleProvenance {
+        /// The prefixes used to expand the prefixed IRIs.
+        prefixes: Vec<Prefix>,
+        /// The subject of the triple.
+        subject: IRI,
+        /// The predicate of the triple.
+        predicate: IRI,
+        /// The object of the triple.
+        object: NamedNodeOrLiteral,
+        /// The name of the graph the triple belongs to, the default graph if not provided.
+        graph: Option<IRI>,
     },
 }
 
@@ -147,6 +404,38 @@ pub enum DataFormat {
     /// Output in [N-Quads](https://www.w3.org/TR/n-quads/) format.
     #[serde(rename = "n_quads")]
     NQuads,
+    /// # TriG
+    /// Output in [TriG](https://www.w3.org/TR/trig/) format.
+    #[serde(rename = "trig")]
+    TriG,
+    /// # JSON-LD
+    /// Output in compacted [JSON-LD](https://www.w3.org/TR/json-ld11/) format, the prefixes of the
+    /// query being used as context.
+    /// Expanded and compacted documents are accepted as input, referencing only inline or
+    /// registered contexts (see [ExecuteMsg::RegisterJsonLdContext]).
+    #[serde(rename = "json_ld")]
+    JsonLd,
+}
+
+/// # ResultsFormat
+/// Represents the format in which the results of a select query are serialized.
+#[cw_serde]
+pub enum ResultsFormat {
+    /// # JSON
+    /// Output in [SPARQL 1.1 Query Results JSON](https://www.w3.org/TR/sparql11-results-json/)
+    /// format, i.e. `application/sparql-results+json`.
+    #[serde(rename = "json")]
+    Json,
+    /// # CSV
+    /// Output in [SPARQL 1.1 Query Results CSV](https://www.w3.org/TR/sparql11-results-csv-tsv/)
+    /// format, which doesn't carry the kind, datatype or language of the values.
+    #[serde(rename = "csv")]
+    Csv,
+    /// # TSV
+    /// Output in [SPARQL 1.1 Query Results TSV](https://www.w3.org/TR/sparql11-results-csv-tsv/)
+    /// format, the values being encoded as in [Turtle](https://www.w3.org/TR/turtle/).
+    #[serde(rename = "tsv")]
+    Tsv,
 }
 
 impl From<&DataFormat> for axone_rdf::serde::DataFormat {
@@ -156,6 +445,8 @@ impl From<&DataFormat> for axone_rdf::serde::DataFormat {
             DataFormat::Turtle => Self::Turtle,
             DataFormat::NTriples => Self::NTriples,
             DataFormat::NQuads => Self::NQuads,
+            DataFormat::TriG => Self::TriG,
+            DataFormat::JsonLd => Self::JsonLd,
         }
     }
 }
@@ -199,6 +490,11 @@ pub struct StoreLimitsInput {
     /// Default to [Uint128::MAX] if not set, which can be considered as no limit.
     #[serde(default = "StoreLimitsInput::default_max_insert_data_triple_count")]
     pub max_insert_data_triple_count: Uint128,
+    /// The maximum depth of the traversal of a property path, i.e. the maximum number of times
+    /// a repeated path (i.e. `zero_or_more` and `one_or_more`) is followed when querying.
+    /// Default to 10 if not set.
+    #[serde(default = "StoreLimitsInput::default_max_query_path_depth")]
+    pub max_query_path_depth: u32,
 }
 
 impl StoreLimitsInput {
@@ -223,6 +519,9 @@ impl StoreLimitsInput {
     const fn default_max_insert_data_triple_count() -> Uint128 {
         Uint128::MAX
     }
+    pub(crate) const fn default_max_query_path_depth() -> u32 {
+        10
+    }
 }
 
 impl Default for StoreLimitsInput {
@@ -235,6 +534,7 @@ impl Default for StoreLimitsInput {
             max_query_variable_count: Self::default_max_query_variable_count(),
             max_insert_data_byte_size: Self::default_max_insert_data_byte_size(),
             max_insert_data_triple_count: Self::default_max_insert_data_triple_count(),
+            max_query_path_depth: Self::default_max_query_path_depth(),
         }
     }
 }
@@ -252,6 +552,53 @@ pub struct StoreResponse {
 
     /// The store current usage.
     pub stat: StoreStat,
+
+    /// Whether the strict typing of literals is enabled.
+    pub strict_typing: bool,
+
+    /// Whether the history of the triples is kept, allowing to query the store as of a past block
+    /// height.
+    pub versioning: bool,
+
+    /// The roles granted to the addresses besides the owner.
+    pub grants: Vec<Grant>,
+}
+
+/// # Grant
+/// Represents a role granted to an address.
+#[cw_serde]
+pub struct Grant {
+    /// The address granted the role.
+    pub address: String,
+    /// The granted role.
+    pub role: Role,
+    /// The scope the role is restricted to, the whole store if not set.
+    pub scope: Option<AccessScope>,
+}
+
+/// # Role
+/// Represents a role allowing an address to change the data of the store.
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    /// # Writer
+    /// Allows to insert triples.
+    Writer,
+    /// # Deleter
+    /// Allows to delete triples.
+    Deleter,
+}
+
+/// # AccessScope
+/// Represents the triples a role is restricted to.
+#[cw_serde]
+pub enum AccessScope {
+    /// # Graph
+    /// The triples of the named graph with the given full IRI.
+    Graph(String),
+    /// # Namespace
+    /// The triples whose subject is a named node whose full IRI starts with the given namespace.
+    Namespace(String),
 }
 
 /// # StoreLimits
@@ -285,6 +632,10 @@ pub struct StoreLimits {
 
     /// The maximum number of triples an insert data query can contain (after parsing).
     pub max_insert_data_triple_count: Uint128,
+
+    /// The maximum depth of the traversal of a property path, i.e. the maximum number of times
+    /// a repeated path is followed when querying.
+    pub max_query_path_depth: u32,
 }
 
 /// # StoreStat
@@ -323,7 +674,13 @@ pub struct SelectResponse {
     /// The head of the response, i.e. the set of variables mentioned in the results.
     pub head: Head,
     /// The results of the select query.
+    /// Empty if the results are serialized in the requested format, see `data`.
     pub results: Results,
+    /// The page information, to get the next results.
+    pub page_info: PageInfo,
+    /// The results serialized in the format requested by the query, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Binary>,
 }
 
 /// # DescribeResponse
@@ -334,6 +691,8 @@ pub struct DescribeResponse {
     pub format: DataFormat,
     /// The data serialized in the specified format.
     pub data: Binary,
+    /// The page information, to get the triples of the next WHERE clause solutions.
+    pub page_info: PageInfo,
 }
 
 /// # ConstructResponse
@@ -344,6 +703,199 @@ pub struct ConstructResponse {
     pub format: DataFormat,
     /// The data serialized in the specified format.
     pub data: Binary,
+    /// The page information, to get the triples of the next WHERE clause solutions.
+    pub page_info: PageInfo,
+}
+
+/// # AskResponse
+/// Represents the response of a [QueryMsg::Ask] query.
+#[cw_serde]
+pub struct AskResponse {
+    /// Whether the query has at least one solution.
+    pub result: bool,
+}
+
+/// # SparqlResponse
+/// Represents the response of a [QueryMsg::Sparql] query, depending on the query form.
+#[cw_serde]
+pub enum SparqlResponse {
+    /// # Select
+    /// The response of a SELECT query.
+    Select(SelectResponse),
+    /// # Describe
+    /// The response of a DESCRIBE query.
+    Describe(DescribeResponse),
+    /// # Construct
+    /// The response of a CONSTRUCT query.
+    Construct(ConstructResponse),
+    /// # Ask
+    /// The response of an ASK query.
+    Ask(AskResponse),
+}
+
+/// # ExplainResponse
+/// Represents the response of a [QueryMsg::Explain] query.
+#[cw_serde]
+pub struct ExplainResponse {
+    /// The root node of the query plan, its solutions being the ones of the query.
+    pub plan: PlanNode,
+}
+
+/// # TripleProvenanceResponse
+/// Represents the response of a [QueryMsg::TripleProvenance] query.
+#[cw_serde]
+pub struct TripleProvenanceResponse {
+    /// The identifier of the batch the triple has been inserted with.
+    pub batch_id: u64,
+    /// The address of the sender of the batch.
+    pub sender: String,
+    /// The height of the block the batch has been submitted in.
+    pub block_height: u64,
+    /// The time of the block the batch has been submitted in.
+    pub block_time: Timestamp,
+}
+
+/// # PlanNode
+/// Represents a step of a query plan, computing its solutions out of the ones of its children.
+#[cw_serde]
+pub struct PlanNode {
+    /// The operation performed by the node.
+    pub operation: PlanOperation,
+    /// The way the triples are read from the store, for the triple patterns only.
+    pub access_path: Option<AccessPath>,
+    /// The estimated number of solutions of a single evaluation of the node, which happens once
+    /// per solution of the left node for the right node of a join.
+    ///
+    /// The estimation is based on the number of triples in the store per predicate and per
+    /// namespace of their subject and object, each bound term of a pattern being assumed to make
+    /// it match a tenth of the triples otherwise.
+    pub cardinality: Uint128,
+    /// The nodes the solutions are computed from.
+    pub children: Vec<PlanNode>,
+}
+
+/// # PlanOperation
+/// Represents the operation performed by a [PlanNode].
+#[cw_serde]
+pub enum PlanOperation {
+    /// # TriplePattern
+    /// Match a triple pattern against the store, in the provided named graph if any.
+    TriplePattern {
+        subject: PlanTerm,
+        predicate: PlanTerm,
+        object: PlanTerm,
+        graph: Option<PlanTerm>,
+    },
+    /// # Path
+    /// Follow a property path between the subject and the object.
+    Path {
+        subject: PlanTerm,
+        object: PlanTerm,
+        max_depth: u32,
+    },
+    /// # Noop
+    /// Results in no solutions, the query being known to have none.
+    Noop,
+    /// # Values
+    /// Results in the provided solutions of the variables.
+    Values { variables: Vec<String> },
+    /// # SubQuery
+    /// Results in the solutions of a sub query, its child.
+    SubQuery,
+    /// # CartesianProductJoin
+    /// Join the solutions of the two children, not having any variable in common.
+    CartesianProductJoin,
+    /// # ForLoopJoin
+    /// Join the solutions of the two children, the right one being evaluated for each solution of
+    /// the left one.
+    ForLoopJoin,
+    /// # Optional
+    /// Left join the solutions of the two children, the right one being evaluated for each
+    /// solution of the left one.
+    Optional,
+    /// # Minus
+    /// Remove the solutions of the left child compatible with a solution of the right one.
+    Minus,
+    /// # NotExists
+    /// Keep the solutions of the left child for which the right one has no solutions.
+    NotExists,
+    /// # Union
+    /// Concatenate the solutions of the two children.
+    Union,
+    /// # Filter
+    /// Keep the solutions of the child satisfying an expression.
+    Filter,
+    /// # Bind
+    /// Bind the value of an expression to the variable.
+    Bind { variable: String },
+    /// # Group
+    /// Group the solutions of the child by the key variables, and compute the aggregates.
+    Group { keys: Vec<String> },
+    /// # OrderBy
+    /// Sort the solutions of the child.
+    OrderBy,
+    /// # Distinct
+    /// Remove the duplicated solutions of the child over the variables.
+    Distinct { variables: Vec<String> },
+    /// # Skip
+    /// Skip the first solutions of the child.
+    Skip { first: Uint128 },
+    /// # Limit
+    /// Limit the solutions of the child to the first ones.
+    Limit { first: Uint128 },
+}
+
+/// # PlanTerm
+/// Represents a term of a pattern in a [PlanNode].
+#[cw_serde]
+pub enum PlanTerm {
+    /// # Variable
+    /// A variable, blank nodes being variables whose name is prefixed by `_:`.
+    Variable(String),
+    /// # Value
+    /// A constant value.
+    Value(Value),
+}
+
+/// # AccessPath
+/// Represents the way the triples matching a pattern are read from the store, depending on the
+/// terms of the pattern which are bound when evaluating it.
+#[cw_serde]
+pub enum AccessPath {
+    /// # Key
+    /// The triple is loaded by its key, all its terms and its named graph being bound.
+    Key,
+    /// # SubjectPredicate
+    /// The triples are ranged over the subject and predicate index.
+    SubjectPredicate,
+    /// # Subject
+    /// The triples of the subject are ranged over the subject and predicate index.
+    Subject,
+    /// # ObjectPredicate
+    /// The triples are ranged over their key, prefixed by the object and the predicate.
+    ObjectPredicate,
+    /// # ObjectPrefix
+    /// The triples are ranged over their key, prefixed by the object.
+    ObjectPrefix,
+    /// # Predicate
+    /// The triples of the predicate are ranged over the predicate and subject index.
+    Predicate,
+    /// # GraphSubject
+    /// The triples of the named graph are ranged over the graph and subject index.
+    GraphSubject,
+    /// # FullScan
+    /// All the triples of the store are ranged over.
+    FullScan,
+}
+
+/// # PageInfo
+/// PageInfo is the page information returned for paginated queries.
+#[cw_serde]
+pub struct PageInfo {
+    /// Tells if there is a next page.
+    pub has_next_page: bool,
+    /// The cursor to the next page.
+    pub cursor: Cursor,
 }
 
 /// # Head
@@ -359,6 +911,8 @@ pub struct Head {
 #[cw_serde]
 pub struct Results {
     /// The bindings of the results.
+    /// A variable left unbound in a solution (e.g. by an optional condition) is not present in its
+    /// bindings.
     pub bindings: Vec<BTreeMap<String, Value>>,
 }
 
@@ -406,11 +960,35 @@ pub struct SelectQuery {
     /// The WHERE clause.
     /// If `None`, there is no WHERE clause, i.e. all triples are returned without filtering.
     pub r#where: WhereClause,
+    /// The variables to group the solutions of the WHERE clause by, each group resulting in a
+    /// single result over which the selected aggregates are computed.
+    /// Note: when grouping, or selecting aggregates, only the grouped variables can be selected
+    /// besides the aggregates. Without variables to group by, all the solutions form a single group.
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    /// The HAVING clause, filtering the groups by evaluating the expression against them, the
+    /// aggregates being referenced through the variables they're bound to.
+    /// If `None`, no group is filtered.
+    pub having: Option<Expression>,
+    /// Whether to eliminate duplicate results, two results being duplicates if they bind the same
+    /// values to the selected items.
+    #[serde(default)]
+    pub distinct: bool,
+    /// The conditions to order the results by, the first one taking precedence over the next ones.
+    /// If empty, the results order is unspecified.
+    /// Note: ordering requires to evaluate all the solutions of the WHERE clause before returning
+    /// the first result.
+    #[serde(default)]
+    pub order_by: Vec<OrderCondition>,
     /// The maximum number of results to return.
     /// If `None`, there is no limit.
     /// Note: the value of the limit cannot exceed the maximum query limit defined in the store
     /// limitations.
     pub limit: Option<u32>,
+    /// The point in the sequence of results to start returning results from, as given by the
+    /// [PageInfo] of a previous response, which isn't stable across writes (see [Cursor]).
+    /// If `None`, the results are returned from the beginning.
+    pub after: Option<Cursor>,
 }
 
 /// # DescribeQuery
@@ -425,6 +1003,10 @@ pub struct DescribeQuery {
     /// The WHERE clause.
     /// This clause is used to specify the resource identifier to describe using variable bindings.
     pub r#where: WhereClause,
+    /// The point in the sequence of WHERE clause solutions to start describing from, as given by
+    /// the [PageInfo] of a previous response, which isn't stable across writes (see [Cursor]).
+    /// If `None`, the solutions are described from the beginning.
+    pub after: Option<Cursor>,
 }
 
 /// # ConstructQuery
@@ -435,11 +1017,27 @@ pub struct ConstructQuery {
     /// The prefixes used in the query.
     pub prefixes: Vec<Prefix>,
     /// The triples to construct.
-    /// If nothing is provided, the patterns from the `where` clause are used for construction.
+    /// If nothing is provided, the patterns from the `where` clause are used for construction,
+    /// which requires the clause to contain no [WhereCondition::Union].
     pub construct: Vec<TripleConstructTemplate>,
     /// The WHERE clause.
     /// This clause is used to specify the triples to construct using variable bindings.
     pub r#where: WhereClause,
+    /// The point in the sequence of WHERE clause solutions to start constructing from, as given by
+    /// the [PageInfo] of a previous response, which isn't stable across writes (see [Cursor]).
+    /// If `None`, the triples are constructed from the beginning.
+    pub after: Option<Cursor>,
+}
+
+/// # AskQuery
+/// Represents an ASK query over the triple store, allowing to know whether a set of conditions
+/// has at least one solution.
+#[cw_serde]
+pub struct AskQuery {
+    /// The prefixes used in the query.
+    pub prefixes: Vec<Prefix>,
+    /// The WHERE clause.
+    pub r#where: WhereClause,
 }
 
 /// # Prefix
@@ -459,6 +1057,99 @@ pub enum SelectItem {
     /// # Variable
     /// Represents a variable.
     Variable(String),
+    /// # Aggregate
+    /// Represents an aggregate computed over each group of solutions, bound to the given variable.
+    Aggregate {
+        /// The aggregate to compute.
+        aggregate: Aggregate,
+        /// The name of the variable to bind the aggregate to, which must not appear in the WHERE
+        /// clause.
+        variable: String,
+    },
+}
+
+impl SelectItem {
+    /// Returns the name of the variable bound by the item.
+    pub fn variable(&self) -> &str {
+        match self {
+            SelectItem::Variable(variable) | SelectItem::Aggregate { variable, .. } => variable,
+        }
+    }
+}
+
+/// # Aggregate
+/// Represents an aggregate function computed over the solutions of a group.
+///
+/// The solutions for which the aggregated expression can't be evaluated are ignored, and an
+/// aggregate which can't be computed leaves its variable unbound.
+#[cw_serde]
+pub enum Aggregate {
+    /// # Count
+    /// Counts the solutions, only the ones for which the expression can be evaluated if any.
+    Count {
+        /// The expression to count the values of, if `None` the solutions themselves are counted.
+        expression: Option<Expression>,
+        /// Whether to count only the distinct values, or solutions.
+        #[serde(default)]
+        distinct: bool,
+    },
+    /// # Sum
+    /// Sums the numeric values of the expression, `0` if there are none.
+    /// It can't be computed if any of the values is not numeric.
+    Sum {
+        /// The expression to sum the values of.
+        expression: Expression,
+        /// Whether to sum only the distinct values.
+        #[serde(default)]
+        distinct: bool,
+    },
+    /// # Avg
+    /// Averages the numeric values of the expression, `0` if there are none.
+    /// It can't be computed if any of the values is not numeric.
+    Avg {
+        /// The expression to average the values of.
+        expression: Expression,
+        /// Whether to average only the distinct values.
+        #[serde(default)]
+        distinct: bool,
+    },
+    /// # Min
+    /// Selects the lowest value of the expression, following the ordering of [OrderCondition].
+    Min(Expression),
+    /// # Max
+    /// Selects the greatest value of the expression, following the ordering of [OrderCondition].
+    Max(Expression),
+    /// # Sample
+    /// Selects an arbitrary value of the expression.
+    Sample(Expression),
+    /// # GroupConcat
+    /// Concatenates the lexical forms of the values of the expression into a string.
+    /// It can't be computed if any of the values is a blank node.
+    GroupConcat {
+        /// The expression to concatenate the values of.
+        expression: Expression,
+        /// Whether to concatenate only the distinct values.
+        #[serde(default)]
+        distinct: bool,
+        /// The separator to insert between the values.
+        /// If `None`, the values are separated by a single space.
+        separator: Option<String>,
+    },
+}
+
+/// # OrderCondition
+/// Represents a condition to order the results of a [SelectQuery] by.
+///
+/// Values are compared following the RDF terms ordering: unbound values come first, then blank
+/// nodes, IRIs and literals, the typed literals of a same kind being compared by value.
+#[cw_serde]
+pub enum OrderCondition {
+    /// # Asc
+    /// Orders by the variable values in ascending order.
+    Asc(String),
+    /// # Desc
+    /// Orders by the variable values in descending order.
+    Desc(String),
 }
 
 /// # WhereClause
@@ -472,6 +1163,170 @@ pub enum WhereCondition {
     /// # Simple
     /// Represents a simple condition.
     Simple(SimpleWhereCondition),
+
+    /// # Optional
+    /// Represents an optional group of conditions, evaluated as a left join against the solutions
+    /// of the preceding conditions: a solution is kept even if the group doesn't match, the
+    /// variables only bound by the group being then left unbound.
+    ///
+    /// It must be preceded by at least one condition in the [WhereClause].
+    Optional(WhereClause),
+
+    /// # Union
+    /// Represents a union of alternative groups of conditions, the solutions being the ones of
+    /// each group, in the order they are declared.
+    ///
+    /// It must contain at least two groups.
+    Union(Vec<WhereClause>),
+
+    /// # Graph
+    /// Represents a group of conditions matched against the triples of a named graph, the graph
+    /// name being either a variable, bound to the names of the graphs the group matches in, or a
+    /// constant one.
+    ///
+    /// Out of such a group the triple patterns match the triples of all the graphs, including the
+    /// default one, while the property paths of the group are followed within each of its graphs.
+    Graph {
+        /// The name of the graph.
+        graph: VarOrNamedNode,
+        /// The conditions to match in the graph.
+        r#where: WhereClause,
+    },
+
+    /// # Minus
+    /// Represents the removal from the solutions of the preceding conditions of the ones
+    /// compatible with a solution of the group, two solutions being compatible if they share at
+    /// least one variable and bind the same values to their shared variables.
+    ///
+    /// It must be preceded by at least one condition in the [WhereClause].
+    Minus(WhereClause),
+
+    /// # NotExists
+    /// Represents a filter on the solutions of the [WhereClause] it belongs to, only the ones for
+    /// which the group has no solution are kept, the group being evaluated with the variables
+    /// bound by the solution.
+    ///
+    /// As a filter, it applies to the whole clause whatever its position in it.
+    NotExists(WhereClause),
+
+    /// # Filter
+    /// Represents a filter on the solutions of the [WhereClause] it belongs to, only the ones for
+    /// which the expression evaluates to `true` are kept.
+    ///
+    /// The filter applies to the whole clause whatever its position in it. A solution for which
+    /// the expression can't be evaluated (e.g. unbound variable, incompatible operands) is
+    /// discarded.
+    Filter(Expression),
+
+    /// # Values
+    /// Represents an inline table of solutions, joined with the solutions of the other conditions
+    /// of the [WhereClause].
+    ///
+    /// Being known before evaluation, the table drives the evaluation of the conditions sharing
+    /// variables with it, whatever its position in the clause.
+    Values {
+        /// The variables bound by the table.
+        variables: Vec<String>,
+        /// The rows of the table, each one providing the values of the variables in their order,
+        /// `None` leaving the variable unbound.
+        values: Vec<Vec<Option<NamedNodeOrLiteral>>>,
+    },
+
+    /// # SubQuery
+    /// Represents a nested SELECT query, evaluated independently of the other conditions of the
+    /// [WhereClause], its solutions being joined with theirs through its selected variables only.
+    ///
+    /// Its prefixes come in addition to the ones of the enclosing query, and its `after` cursor
+    /// is taken as the number of solutions to skip.
+    SubQuery(Box<SelectQuery>),
+
+    /// # Bind
+    /// Represents the assignment of an expression, evaluated against the solutions of the
+    /// preceding conditions, to a new variable.
+    ///
+    /// The variable is left unbound for the solutions the expression can't be evaluated against,
+    /// and must not be used by the preceding conditions.
+    /// Note: the assigned values being computed, the variable can't be used to match triples.
+    Bind {
+        /// The expression to evaluate.
+        expression: Expression,
+        /// The name of the variable to assign the expression value to.
+        variable: String,
+    },
+}
+
+/// # Expression
+/// Represents an expression evaluated against the variables of a solution, resulting in an RDF
+/// term.
+///
+/// Operators and functions resulting in a boolean rely on the
+/// [effective boolean value](https://www.w3.org/TR/sparql11-query/#ebv) of their operands.
+#[cw_serde]
+pub enum Expression {
+    /// # NamedNode
+    /// A named node constant.
+    NamedNode(IRI),
+    /// # Literal
+    /// A literal constant.
+    Literal(Literal),
+    /// # Variable
+    /// A variable, the expression can't be evaluated if not bound.
+    Variable(String),
+    /// # And
+    /// The logical conjunction of expressions, `true` if all of them are, and if empty.
+    And(Vec<Self>),
+    /// # Or
+    /// The logical disjunction of expressions, `true` if any of them is, `false` if empty.
+    Or(Vec<Self>),
+    /// # Not
+    /// The logical negation of an expression.
+    Not(Box<Self>),
+    /// # Equal
+    /// Equality comparison of two terms.
+    Equal(Box<Self>, Box<Self>),
+    /// # NotEqual
+    /// Inequality comparison of two terms.
+    NotEqual(Box<Self>, Box<Self>),
+    /// # Greater
+    /// Ordering comparison, `true` if the first term is greater than the second one.
+    Greater(Box<Self>, Box<Self>),
+    /// # GreaterOrEqual
+    /// Ordering comparison, `true` if the first term is greater than or equal to the second one.
+    GreaterOrEqual(Box<Self>, Box<Self>),
+    /// # Less
+    /// Ordering comparison, `true` if the first term is less than the second one.
+    Less(Box<Self>, Box<Self>),
+    /// # LessOrEqual
+    /// Ordering comparison, `true` if the first term is less than or equal to the second one.
+    LessOrEqual(Box<Self>, Box<Self>),
+    /// # Bound
+    /// `true` if the variable is bound.
+    Bound(String),
+    /// # Regex
+    /// `true` if the string literal matches the regular expression pattern, given with optional
+    /// [flags](https://www.w3.org/TR/xpath-functions/#flags) among `i`, `m`, `s` and `x`.
+    Regex(Box<Self>, String, Option<String>),
+    /// # Contains
+    /// `true` if the first string literal contains the second one.
+    Contains(Box<Self>, Box<Self>),
+    /// # StrStarts
+    /// `true` if the first string literal starts with the second one.
+    StrStarts(Box<Self>, Box<Self>),
+    /// # Lang
+    /// The language tag of a literal, empty if it has none.
+    Lang(Box<Self>),
+    /// # Datatype
+    /// The datatype IRI of a literal.
+    Datatype(Box<Self>),
+    /// # IsIri
+    /// `true` if the term is a named node.
+    IsIri(Box<Self>),
+    /// # IsBlank
+    /// `true` if the term is a blank node.
+    IsBlank(Box<Self>),
+    /// # IsLiteral
+    /// `true` if the term is a literal.
+    IsLiteral(Box<Self>),
 }
 
 /// # SimpleWhereCondition
@@ -494,6 +1349,8 @@ pub struct TripleDeleteTemplate {
     pub predicate: VarOrNamedNode,
     /// The object of the triple pattern.
     pub object: VarOrNamedNodeOrLiteral,
+    /// The name of the graph to delete the triple from, the default graph if not provided.
+    pub graph: Option<VarOrNamedNode>,
 }
 
 /// # TripleConstructTemplate
@@ -506,6 +1363,9 @@ pub struct TripleConstructTemplate {
     pub predicate: VarOrNamedNode,
     /// The object of the triple pattern.
     pub object: VarOrNodeOrLiteral,
+    /// The name of the graph the triple belongs to, only output when serializing to a format
+    /// supporting named graphs, i.e. N-Quads.
+    pub graph: Option<VarOrNamedNode>,
 }
 
 /// # TriplePattern
@@ -514,8 +1374,8 @@ pub struct TripleConstructTemplate {
 pub struct TriplePattern {
     /// The subject of the triple pattern.
     pub subject: VarOrNode,
-    /// The predicate of the triple pattern.
-    pub predicate: VarOrNamedNode,
+    /// The predicate of the triple pattern, which can also be a property path.
+    pub predicate: VarOrNamedNodeOrPath,
     /// The object of the triple pattern.
     pub object: VarOrNodeOrLiteral,
 }
@@ -544,6 +1404,60 @@ pub enum VarOrNamedNode {
     NamedNode(IRI),
 }
 
+/// # VarOrNamedNodeOrPath
+/// Represents either a variable, a named node (IRI) or a property path.
+#[cw_serde]
+pub enum VarOrNamedNodeOrPath {
+    /// # Variable
+    /// A variable.
+    Variable(String),
+    /// # NamedNode
+    /// An RDF [IRI](https://www.w3.org/TR/rdf11-concepts/#dfn-iri).
+    NamedNode(IRI),
+    /// # Path
+    /// A [property path](https://www.w3.org/TR/sparql11-query/#propertypaths).
+    Path(PropertyPath),
+}
+
+impl From<VarOrNamedNode> for VarOrNamedNodeOrPath {
+    fn from(value: VarOrNamedNode) -> Self {
+        match value {
+            VarOrNamedNode::Variable(v) => VarOrNamedNodeOrPath::Variable(v),
+            VarOrNamedNode::NamedNode(iri) => VarOrNamedNodeOrPath::NamedNode(iri),
+        }
+    }
+}
+
+/// # PropertyPath
+/// Represents a [property path](https://www.w3.org/TR/sparql11-query/#propertypaths), i.e. a
+/// possible route through the graph between two nodes.
+///
+/// The traversal of repeated paths is bounded by the `max_query_path_depth` limit of the store.
+#[cw_serde]
+pub enum PropertyPath {
+    /// # NamedNode
+    /// A path of length one, through the given predicate.
+    NamedNode(IRI),
+    /// # Inverse
+    /// The inverse path, from object to subject.
+    Inverse(Box<PropertyPath>),
+    /// # Sequence
+    /// A sequence of paths, each one starting where the previous one ended.
+    Sequence(Vec<PropertyPath>),
+    /// # Alternative
+    /// Any of the given paths.
+    Alternative(Vec<PropertyPath>),
+    /// # ZeroOrMore
+    /// The path followed zero or more times.
+    ZeroOrMore(Box<PropertyPath>),
+    /// # OneOrMore
+    /// The path followed one or more times.
+    OneOrMore(Box<PropertyPath>),
+    /// # ZeroOrOne
+    /// The path followed zero or one time.
+    ZeroOrOne(Box<PropertyPath>),
+}
+
 /// # VarOrNodeOrLiteral
 /// Represents either a variable, a node or a literal.
 #[cw_serde]
@@ -576,6 +1490,19 @@ pub enum VarOrNamedNodeOrLiteral {
     Literal(Literal),
 }
 
+/// # NamedNodeOrLiteral
+/// Represents either a named node or a literal.
+#[cw_serde]
+pub enum NamedNodeOrLiteral {
+    /// # NamedNode
+    /// An RDF [IRI](https://www.w3.org/TR/rdf11-concepts/#dfn-iri).
+    NamedNode(IRI),
+    /// # Literal
+    /// An RDF [literal](https://www.w3.org/TR/rdf11-concepts/#dfn-literal), i.e. a simple literal,
+    /// a language-tagged string or a typed value.
+    Literal(Literal),
+}
+
 /// # Literal
 /// An RDF [literal](https://www.w3.org/TR/rdf11-concepts/#dfn-literal).
 #[cw_serde]
@@ -633,6 +1560,7 @@ mod tests {
         assert_eq!(input.max_triple_byte_size, Uint128::MAX);
         assert_eq!(input.max_insert_data_byte_size, Uint128::MAX);
         assert_eq!(input.max_insert_data_triple_count, Uint128::MAX);
+        assert_eq!(input.max_query_path_depth, 10);
     }
 
     #[test]
@@ -649,5 +1577,6 @@ mod tests {
         assert_eq!(msg.limits.max_triple_byte_size, Uint128::MAX);
         assert_eq!(msg.limits.max_insert_data_byte_size, Uint128::MAX);
         assert_eq!(msg.limits.max_insert_data_triple_count, Uint128::MAX);
+        assert_eq!(msg.limits.max_query_path_depth, 10);
     }
 }
@@ -0,0 +1,348 @@
+use crate::querier::expression::{Expression, Term};
+use crate::querier::variable::ResolvedVariables;
+use crate::rdf::Numeric;
+use cosmwasm_std::StdResult;
+use std::collections::HashSet;
+
+/// Represents an aggregate function, computed over the solutions of a group to produce a [Term].
+///
+/// The solutions for which the aggregated expression can't be evaluated are ignored.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Aggregate {
+    Count {
+        expr: Option<Expression>,
+        distinct: bool,
+    },
+    Sum {
+        expr: Expression,
+        distinct: bool,
+    },
+    Avg {
+        expr: Expression,
+        distinct: bool,
+    },
+    Min(Expression),
+    Max(Expression),
+    Sample(Expression),
+    GroupConcat {
+        expr: Expression,
+        distinct: bool,
+        separator: String,
+    },
+}
+
+impl Aggregate {
+    /// Compute the aggregate over the solutions of a group, resolving namespaces through the
+    /// provided function.
+    ///
+    /// Results in `None` if the aggregate can't be computed, for instance when summing non numeric
+    /// values or selecting a value among none, in which case its variable is left unbound.
+    pub fn compute<F>(
+        &self,
+        solutions: &[ResolvedVariables],
+        ns_fn: &mut F,
+    ) -> StdResult<Option<Term>>
+    where
+        F: FnMut(u128) -> StdResult<String>,
+    {
+        Ok(match self {
+            Aggregate::Count {
+                expr: None,
+                distinct,
+            } => Some(Term::Numeric(Numeric::from(if *distinct {
+                solutions.iter().collect::<HashSet<_>>().len()
+            } else {
+                solutions.len()
+            }))),
+            Aggregate::Count {
+                expr: Some(expr),
+                distinct,
+            } => Some(Term::Numeric(Numeric::from(
+                Self::values(expr, *distinct, solutions, ns_fn)?.len(),
+            ))),
+            Aggregate::Sum { expr, distinct } => {
+                Self::sum(&Self::values(expr, *distinct, solutions, ns_fn)?)?.map(Term::Numeric)
+            }
+            Aggregate::Avg { expr, distinct } => {
+                let values = Self::values(expr, *distinct, solutions, ns_fn)?;
+                Self::sum(&values)?
+                    .map(|sum| match values.len() {
+                        0 => sum,
+                        len => sum.divide(len),
+                    })
+                    .map(Term::Numeric)
+            }
+            Aggregate::Min(expr) => Self::values(expr, false, solutions, ns_fn)?
+                .into_iter()
+                .min_by(Term::total_cmp),
+            Aggregate::Max(expr) => Self::values(expr, false, solutions, ns_fn)?
+                .into_iter()
+                .max_by(Term::total_cmp),
+            Aggregate::Sample(expr) => Self::values(expr, false, solutions, ns_fn)?
+                .into_iter()
+                .next(),
+            Aggregate::GroupConcat {
+                expr,
+                distinct,
+                separator,
+            } => Self::values(expr, *distinct, solutions, ns_fn)?
+                .iter()
+                .map(Term::lexical_form)
+                .collect::<Option<Vec<_>>>()
+                .map(|values| Term::String(values.join(separator))),
+        })
+    }
+
+    /// Evaluate the expression against the solutions, keeping only the first occurrence of each
+    /// value if distinct.
+    fn values<F>(
+        expr: &Expression,
+        distinct: bool,
+        solutions: &[ResolvedVariables],
+        ns_fn: &mut F,
+    ) -> StdResult<Vec<Term>>
+    where
+        F: FnMut(u128) -> StdResult<String>,
+    {
+        let mut seen = HashSet::new();
+        let mut values = Vec::with_capacity(solutions.len());
+        for vars in solutions {
+            if let Some(term) = expr.evaluate(vars, ns_fn)? {
+                if !distinct || seen.insert(term.clone()) {
+                    values.push(term);
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Sum the values, resulting in `None` if any of them is not numeric.
+    fn sum(values: &[Term]) -> StdResult<Option<Numeric>> {
+        let mut sum = Numeric::from(0usize);
+        for value in values {
+            match value {
+                Term::Numeric(n) => sum = sum.add(n)?,
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(sum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::querier::variable::ResolvedVariable;
+    use crate::rdf::{XsdValue, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER};
+    use cosmwasm_std::StdError;
+
+    fn ns(_: u128) -> StdResult<String> {
+        Err(StdError::generic_err("namespace not found"))
+    }
+
+    fn typed(value: &str, datatype: &str) -> Term {
+        match XsdValue::parse(value, datatype) {
+            Some(Ok(xsd)) => xsd.into(),
+            _ => Term::Typed(value.to_string(), datatype.to_string()),
+        }
+    }
+
+    fn solutions(values: Vec<Option<Term>>) -> Vec<ResolvedVariables> {
+        values
+            .into_iter()
+            .map(|value| {
+                let mut vars = ResolvedVariables::with_capacity(1);
+                if let Some(term) = value {
+                    vars.merge_index(0, ResolvedVariable::Computed(term));
+                }
+                vars
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compute() {
+        let numbers = || {
+            solutions(vec![
+                Some(typed("1", XSD_INTEGER)),
+                Some(typed("2.5", XSD_DECIMAL)),
+                None,
+                Some(typed("1", XSD_INTEGER)),
+            ])
+        };
+        let strings = || {
+            solutions(vec![
+                Some(Term::String("b".to_string())),
+                Some(Term::NamedNode("a".to_string())),
+                Some(Term::String("b".to_string())),
+            ])
+        };
+        let expr = || Expression::Variable(0);
+
+        let cases = vec![
+            (
+                Aggregate::Count {
+                    expr: None,
+                    distinct: false,
+                },
+                numbers(),
+                Some(typed("4", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Count {
+                    expr: None,
+                    distinct: true,
+                },
+                numbers(),
+                Some(typed("3", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Count {
+                    expr: Some(expr()),
+                    distinct: false,
+                },
+                numbers(),
+                Some(typed("3", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Count {
+                    expr: Some(expr()),
+                    distinct: true,
+                },
+                numbers(),
+                Some(typed("2", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Count {
+                    expr: None,
+                    distinct: false,
+                },
+                vec![],
+                Some(typed("0", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Sum {
+                    expr: expr(),
+                    distinct: false,
+                },
+                numbers(),
+                Some(typed("4.5", XSD_DECIMAL)),
+            ),
+            (
+                Aggregate::Sum {
+                    expr: expr(),
+                    distinct: true,
+                },
+                numbers(),
+                Some(typed("3.5", XSD_DECIMAL)),
+            ),
+            (
+                Aggregate::Sum {
+                    expr: expr(),
+                    distinct: false,
+                },
+                solutions(vec![
+                    Some(typed("1", XSD_INTEGER)),
+                    Some(typed("1E1", XSD_DOUBLE)),
+                ]),
+                Some(typed("1.1E1", XSD_DOUBLE)),
+            ),
+            (
+                Aggregate::Sum {
+                    expr: expr(),
+                    distinct: false,
+                },
+                vec![],
+                Some(typed("0", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Sum {
+                    expr: expr(),
+                    distinct: false,
+                },
+                strings(),
+                None,
+            ),
+            (
+                Aggregate::Avg {
+                    expr: expr(),
+                    distinct: false,
+                },
+                numbers(),
+                Some(typed("1.5", XSD_DECIMAL)),
+            ),
+            (
+                Aggregate::Avg {
+                    expr: expr(),
+                    distinct: true,
+                },
+                numbers(),
+                Some(typed("1.75", XSD_DECIMAL)),
+            ),
+            (
+                Aggregate::Avg {
+                    expr: expr(),
+                    distinct: false,
+                },
+                vec![],
+                Some(typed("0", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Min(expr()),
+                numbers(),
+                Some(typed("1", XSD_INTEGER)),
+            ),
+            (
+                Aggregate::Max(expr()),
+                numbers(),
+                Some(typed("2.5", XSD_DECIMAL)),
+            ),
+            (
+                Aggregate::Min(expr()),
+                strings(),
+                Some(Term::NamedNode("a".to_string())),
+            ),
+            (Aggregate::Max(expr()), vec![], None),
+            (
+                Aggregate::Sample(expr()),
+                solutions(vec![None, Some(Term::String("b".to_string()))]),
+                Some(Term::String("b".to_string())),
+            ),
+            (
+                Aggregate::GroupConcat {
+                    expr: expr(),
+                    distinct: false,
+                    separator: ", ".to_string(),
+                },
+                strings(),
+                Some(Term::String("b, a, b".to_string())),
+            ),
+            (
+                Aggregate::GroupConcat {
+                    expr: expr(),
+                    distinct: true,
+                    separator: " ".to_string(),
+                },
+                numbers(),
+                Some(Term::String("1 2.5".to_string())),
+            ),
+            (
+                Aggregate::GroupConcat {
+                    expr: expr(),
+                    distinct: false,
+                    separator: " ".to_string(),
+                },
+                solutions(vec![Some(Term::BlankNode("0".to_string()))]),
+                None,
+            ),
+        ];
+
+        for (aggregate, solutions, expected) in cases {
+            assert_eq!(
+                aggregate.compute(&solutions, &mut ns),
+                Ok(expected),
+                "{aggregate:?}"
+            );
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use crate::msg::{
-    Node, SelectItem, VarOrNamedNode, VarOrNamedNodeOrLiteral, VarOrNode, VarOrNodeOrLiteral,
+    AccessPath, Node, SelectItem, VarOrNamedNode, VarOrNamedNodeOrLiteral, VarOrNode,
+    VarOrNodeOrLiteral,
 };
+use crate::querier::aggregate::Aggregate;
+use crate::querier::expression::Expression;
 use crate::querier::mapper::{iri_as_node, literal_as_object};
-use crate::querier::plan::{PatternValue, QueryNode, QueryPlan};
+use crate::querier::plan::{OrderCondition, PatternValue, PropertyPath, QueryNode, QueryPlan};
 use crate::querier::variable::{ResolvedVariable, ResolvedVariables};
 use crate::rdf::Atom;
 use crate::state::{triples, Namespace, NamespaceResolver, Object, Predicate, Subject, Triple};
@@ -10,12 +13,15 @@ use crate::{rdf, state};
 use axone_rdf::normalize::IdentifierIssuer;
 use cosmwasm_std::{Order, StdError, StdResult, Storage};
 use either::{Either, Left, Right};
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::iter;
 use std::rc::Rc;
 
 pub struct QueryEngine<'a> {
     storage: &'a dyn Storage,
+    height: Option<u64>,
 }
 
 pub struct SelectResults<'a> {
@@ -23,9 +29,25 @@ pub struct SelectResults<'a> {
     pub solutions: SolutionsIterator<'a>,
 }
 
+pub struct ConstructResults<'a> {
+    pub atoms: ResolvedAtomIterator<'a>,
+    pub solution_count: usize,
+    pub has_next: bool,
+}
+
 impl<'a> QueryEngine<'a> {
     pub fn new(storage: &'a dyn Storage) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            height: None,
+        }
+    }
+
+    /// Evaluates the queries against the triples of the store as of the given block height, or
+    /// the current ones if not provided.
+    pub fn at_height(mut self, height: Option<u64>) -> Self {
+        self.height = height;
+        self
     }
 
     pub fn select(
@@ -35,12 +57,10 @@ impl<'a> QueryEngine<'a> {
     ) -> StdResult<SelectResults<'_>> {
         let bindings = selection
             .iter()
-            .map(|item| match item {
-                SelectItem::Variable(v) => v,
-            })
+            .map(SelectItem::variable)
             .map(|name| -> StdResult<(String, usize)> {
                 match plan.get_var_index(name) {
-                    Some(index) => Ok((name.clone(), index)),
+                    Some(index) => Ok((name.to_string(), index)),
                     None => Err(StdError::generic_err(
                         "Selected variable not found in query",
                     )),
@@ -54,25 +74,39 @@ impl<'a> QueryEngine<'a> {
         })
     }
 
+    /// Constructs the atoms out of the `first` solutions of the plan at most, the solutions are
+    /// evaluated eagerly in order to tell whether there are more of them after.
     pub fn construct_atoms(
         &'a self,
         plan: QueryPlan,
         prefixes: &HashMap<String, String>,
-        templates: Vec<(VarOrNode, VarOrNamedNode, VarOrNodeOrLiteral)>,
+        templates: Vec<TripleTemplateWithBlankNode>,
         ns_cache: Vec<Namespace>,
-    ) -> StdResult<ResolvedAtomIterator<'_>> {
+        first: usize,
+    ) -> StdResult<ConstructResults<'_>> {
         let templates = templates
             .into_iter()
             .map(|t| AtomTemplate::try_new(&plan, prefixes, t))
             .collect::<StdResult<Vec<AtomTemplate>>>()?;
 
-        Ok(ResolvedAtomIterator::new(
-            self.storage,
-            ns_cache.into(),
-            IdentifierIssuer::new("b", 0u128),
-            self.eval_plan(plan),
-            templates,
-        ))
+        let mut solutions = self
+            .eval_plan(plan)
+            .take(first.saturating_add(1))
+            .collect::<StdResult<Vec<ResolvedVariables>>>()?;
+        let has_next = solutions.len() > first;
+        solutions.truncate(first);
+
+        Ok(ConstructResults {
+            solution_count: solutions.len(),
+            has_next,
+            atoms: ResolvedAtomIterator::new(
+                self.storage,
+                ns_cache.into(),
+                IdentifierIssuer::new("b", 0u128),
+                Box::new(solutions.into_iter().map(Ok)),
+                templates,
+            ),
+        })
     }
 
     pub fn construct_triples(
@@ -89,6 +123,7 @@ impl<'a> QueryEngine<'a> {
         prefixes: &HashMap<String, String>,
         templates: Either<Vec<TripleTemplateWithBlankNode>, Vec<TripleTemplateNoBlankNode>>,
         ns_cache: Vec<Namespace>,
+        strict_typing: bool,
     ) -> StdResult<Vec<TripleTemplate>> {
         let mut ns_resolver: NamespaceResolver = ns_cache.into();
 
@@ -96,7 +131,14 @@ impl<'a> QueryEngine<'a> {
             Left(tpl) => tpl
                 .into_iter()
                 .map(|t| {
-                    TripleTemplate::try_new(self.storage, &mut ns_resolver, plan, prefixes, Left(t))
+                    TripleTemplate::try_new(
+                        self.storage,
+                        &mut ns_resolver,
+                        plan,
+                        prefixes,
+                        Left(t),
+                        strict_typing,
+                    )
                 })
                 .collect::<StdResult<Vec<TripleTemplate>>>(),
             Right(tpl) => tpl
@@ -108,12 +150,21 @@ impl<'a> QueryEngine<'a> {
                         plan,
                         prefixes,
                         Right(t),
+                        strict_typing,
                     )
                 })
                 .collect::<StdResult<Vec<TripleTemplate>>>(),
         }
     }
 
+    /// Tells whether the plan has at least one solution, its evaluation stopping at the first one.
+    pub fn ask(&'a self, plan: QueryPlan) -> StdResult<bool> {
+        self.eval_plan(plan)
+            .next()
+            .transpose()
+            .map(|solution| solution.is_some())
+    }
+
     pub fn eval_plan(&'a self, plan: QueryPlan) -> ResolvedVariablesIterator<'_> {
         return self.eval_node(plan.entrypoint)(ResolvedVariables::with_capacity(
             plan.variables.len(),
@@ -132,13 +183,76 @@ impl<'a> QueryEngine<'a> {
             } => Rc::new(move |vars| {
                 Box::new(TriplePatternIterator::new(
                     self.storage,
+                    self.height,
                     vars,
                     subject.clone(),
                     predicate.clone(),
                     object.clone(),
+                    None,
                 ))
             }),
+            QueryNode::QuadPattern {
+                subject,
+                predicate,
+                object,
+                graph,
+            } => Rc::new(move |vars| {
+                Box::new(TriplePatternIterator::new(
+                    self.storage,
+                    self.height,
+                    vars,
+                    subject.clone(),
+                    predicate.clone(),
+                    object.clone(),
+                    Some(graph.clone()),
+                ))
+            }),
+            QueryNode::Path {
+                subject,
+                path,
+                object,
+                graph,
+                max_depth,
+            } => {
+                let walker = Rc::new(PathWalker::new(self.storage, self.height, max_depth));
+                Rc::new(move |vars| {
+                    match eval_graph_path(&walker, &vars, &subject, &path, &object, &graph) {
+                        Ok(solutions) => Box::new(solutions.into_iter().map(Ok)),
+                        Err(e) => Box::new(iter::once(Err(e))),
+                    }
+                })
+            }
             QueryNode::Noop { .. } => Rc::new(|_| Box::new(iter::empty())),
+            QueryNode::Values { variables, rows } => Rc::new(move |vars| {
+                let solutions: Vec<_> = rows
+                    .iter()
+                    .filter_map(|row| join_bindings(&vars, variables.iter().copied().zip(row)))
+                    .map(Ok)
+                    .collect();
+                Box::new(solutions.into_iter())
+            }),
+            QueryNode::SubQuery { plan, bindings } => {
+                let capacity = plan.variables.len();
+                let inner = self.eval_node(plan.entrypoint);
+                let bindings = Rc::new(bindings);
+                Rc::new(move |vars| {
+                    let bindings = Rc::clone(&bindings);
+                    Box::new(
+                        inner(ResolvedVariables::with_capacity(capacity)).filter_map(move |res| {
+                            match res {
+                                Ok(solution) => join_bindings(
+                                    &vars,
+                                    bindings
+                                        .iter()
+                                        .map(|(inner, outer)| (*outer, solution.get(*inner))),
+                                )
+                                .map(Ok),
+                                Err(e) => Some(Err(e)),
+                            }
+                        }),
+                    )
+                })
+            }
             QueryNode::CartesianProductJoin { left, right } => {
                 let left = self.eval_node(*left);
                 let right = self.eval_node(*right);
@@ -168,6 +282,103 @@ impl<'a> QueryEngine<'a> {
                     Box::new(ForLoopJoinIterator::new(left(vars), right))
                 })
             }
+            QueryNode::Optional { left, right } => {
+                let left = self.eval_node(*left);
+                let right = self.eval_node(*right);
+                Rc::new(move |vars| {
+                    let right = Rc::clone(&right);
+                    Box::new(OptionalIterator::new(left(vars), right))
+                })
+            }
+            QueryNode::Minus { left, right } => {
+                let shared = Rc::new(right.bound_variables());
+                let left = self.eval_node(*left);
+                let right = self.eval_node(*right);
+                Rc::new(move |vars| {
+                    Box::new(AntiJoinIterator::new(
+                        left(vars),
+                        Rc::clone(&right),
+                        Some(Rc::clone(&shared)),
+                    ))
+                })
+            }
+            QueryNode::NotExists { left, right } => {
+                let left = self.eval_node(*left);
+                let right = self.eval_node(*right);
+                Rc::new(move |vars| {
+                    Box::new(AntiJoinIterator::new(left(vars), Rc::clone(&right), None))
+                })
+            }
+            QueryNode::Union { left, right } => {
+                let left = self.eval_node(*left);
+                let right = self.eval_node(*right);
+                Rc::new(move |vars| Box::new(left(vars.clone()).chain(right(vars))))
+            }
+            QueryNode::Filter { expr, inner } => {
+                let inner = self.eval_node(*inner);
+                let expr = Rc::new(expr);
+                Rc::new(move |vars| {
+                    Box::new(FilterIterator::new(
+                        self.storage,
+                        inner(vars),
+                        Rc::clone(&expr),
+                    ))
+                })
+            }
+            QueryNode::Bind {
+                child,
+                expr,
+                variable,
+            } => {
+                let upstream = self.eval_node(*child);
+                let expr = Rc::new(expr);
+                Rc::new(move |vars| {
+                    Box::new(BindIterator::new(
+                        self.storage,
+                        upstream(vars),
+                        Rc::clone(&expr),
+                        variable,
+                    ))
+                })
+            }
+            QueryNode::Group {
+                child,
+                keys,
+                aggregates,
+            } => {
+                let upstream = self.eval_node(*child);
+                let keys = Rc::new(keys);
+                let aggregates = Rc::new(aggregates);
+                Rc::new(move |vars| {
+                    match group_solutions(
+                        self.storage,
+                        upstream(vars.clone()),
+                        &vars,
+                        &keys,
+                        &aggregates,
+                    ) {
+                        Ok(solutions) => Box::new(solutions.into_iter().map(Ok)),
+                        Err(e) => Box::new(iter::once(Err(e))),
+                    }
+                })
+            }
+            QueryNode::OrderBy { child, conditions } => {
+                let upstream = self.eval_node(*child);
+                let conditions = Rc::new(conditions);
+                Rc::new(move |vars| {
+                    match sort_solutions(self.storage, upstream(vars), &conditions) {
+                        Ok(solutions) => Box::new(solutions.into_iter().map(Ok)),
+                        Err(e) => Box::new(iter::once(Err(e))),
+                    }
+                })
+            }
+            QueryNode::Distinct { child, variables } => {
+                let upstream = self.eval_node(*child);
+                let variables = Rc::new(variables);
+                Rc::new(move |vars| {
+                    Box::new(DistinctIterator::new(upstream(vars), Rc::clone(&variables)))
+                })
+            }
             QueryNode::Skip { child, first } => {
                 let upstream = self.eval_node(*child);
                 Rc::new(move |vars| Box::new(upstream(vars).skip(first)))
@@ -223,6 +434,375 @@ impl<'a> Iterator for ForLoopJoinIterator<'a> {
     }
 }
 
+struct OptionalIterator<'a> {
+    left: ResolvedVariablesIterator<'a>,
+    right: Rc<dyn Fn(ResolvedVariables) -> ResolvedVariablesIterator<'a> + 'a>,
+    current: ResolvedVariablesIterator<'a>,
+    pending: Option<ResolvedVariables>,
+}
+
+impl<'a> OptionalIterator<'a> {
+    fn new(
+        left: ResolvedVariablesIterator<'a>,
+        right: Rc<dyn Fn(ResolvedVariables) -> ResolvedVariablesIterator<'a> + 'a>,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            current: Box::new(iter::empty()),
+            pending: None,
+        }
+    }
+}
+
+impl<'a> Iterator for OptionalIterator<'a> {
+    type Item = StdResult<ResolvedVariables>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(v) = self.current.next() {
+                // The left values matched, no need to keep them.
+                self.pending = None;
+                return Some(v);
+            }
+
+            if let Some(v) = self.pending.take() {
+                return Some(Ok(v));
+            }
+
+            match self.left.next() {
+                None => None?,
+                Some(Ok(v)) => {
+                    self.current = (self.right)(v.clone());
+                    self.pending = Some(v);
+                }
+                Some(Err(e)) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Iterates over the left solutions for which the right node has no solutions, the right node being
+/// evaluated for each of them with their values as for a [ForLoopJoinIterator].
+///
+/// If `shared` variables are given, only their values are provided to the right node, and the left
+/// solutions not binding any of them are kept without evaluating it.
+struct AntiJoinIterator<'a> {
+    left: ResolvedVariablesIterator<'a>,
+    right: Rc<dyn Fn(ResolvedVariables) -> ResolvedVariablesIterator<'a> + 'a>,
+    shared: Option<Rc<BTreeSet<usize>>>,
+}
+
+impl<'a> AntiJoinIterator<'a> {
+    fn new(
+        left: ResolvedVariablesIterator<'a>,
+        right: Rc<dyn Fn(ResolvedVariables) -> ResolvedVariablesIterator<'a> + 'a>,
+        shared: Option<Rc<BTreeSet<usize>>>,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            shared,
+        }
+    }
+}
+
+impl<'a> Iterator for AntiJoinIterator<'a> {
+    type Item = StdResult<ResolvedVariables>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let vars = match self.left.next()? {
+                Ok(vars) => vars,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let input = match &self.shared {
+                Some(shared) => {
+                    if !shared.iter().any(|v| vars.get(*v).is_some()) {
+                        return Some(Ok(vars));
+                    }
+                    vars.retain(shared)
+                }
+                None => vars.clone(),
+            };
+
+            match (self.right)(input).next() {
+                None => return Some(Ok(vars)),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Join the values with the given variables, returns None if a value differs from the one already
+/// bound to its variable.
+fn join_bindings<'v>(
+    vars: &ResolvedVariables,
+    bindings: impl Iterator<Item = (usize, &'v Option<ResolvedVariable>)>,
+) -> Option<ResolvedVariables> {
+    let mut solution = vars.clone();
+    for (v, value) in bindings {
+        match (solution.get(v), value) {
+            (Some(bound), Some(value)) if bound.canonical() != value.canonical() => None?,
+            (None, Some(value)) => solution.merge_index(v, value.clone())?,
+            _ => {}
+        }
+    }
+    Some(solution)
+}
+
+struct BindIterator<'a> {
+    storage: &'a dyn Storage,
+    upstream: ResolvedVariablesIterator<'a>,
+    expr: Rc<Expression>,
+    variable: usize,
+    ns_resolver: NamespaceResolver,
+}
+
+impl<'a> BindIterator<'a> {
+    fn new(
+        storage: &'a dyn Storage,
+        upstream: ResolvedVariablesIterator<'a>,
+        expr: Rc<Expression>,
+        variable: usize,
+    ) -> Self {
+        Self {
+            storage,
+            upstream,
+            expr,
+            variable,
+            ns_resolver: NamespaceResolver::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for BindIterator<'a> {
+    type Item = StdResult<ResolvedVariables>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut vars = match self.upstream.next()? {
+                Ok(vars) => vars,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let res = self.expr.evaluate(&vars, &mut |ns_key| {
+                let res = self.ns_resolver.resolve_from_key(self.storage, ns_key);
+                res.and_then(NamespaceResolver::none_as_error_middleware)
+                    .map(|ns| ns.value)
+            });
+            match res {
+                Ok(Some(term)) => {
+                    if vars
+                        .merge_index(self.variable, ResolvedVariable::Computed(term))
+                        .is_some()
+                    {
+                        return Some(Ok(vars));
+                    }
+                }
+                Ok(None) => return Some(Ok(vars)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+struct FilterIterator<'a> {
+    storage: &'a dyn Storage,
+    upstream: ResolvedVariablesIterator<'a>,
+    expr: Rc<Expression>,
+    ns_resolver: NamespaceResolver,
+}
+
+impl<'a> FilterIterator<'a> {
+    fn new(
+        storage: &'a dyn Storage,
+        upstream: ResolvedVariablesIterator<'a>,
+        expr: Rc<Expression>,
+    ) -> Self {
+        Self {
+            storage,
+            upstream,
+            expr,
+            ns_resolver: NamespaceResolver::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for FilterIterator<'a> {
+    type Item = StdResult<ResolvedVariables>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.upstream.next()? {
+                Ok(vars) => {
+                    let res = self.expr.evaluate_bool(&vars, &mut |ns_key| {
+                        let res = self.ns_resolver.resolve_from_key(self.storage, ns_key);
+                        res.and_then(NamespaceResolver::none_as_error_middleware)
+                            .map(|ns| ns.value)
+                    });
+                    match res {
+                        Ok(Some(true)) => return Some(Ok(vars)),
+                        Ok(_) => {}
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Group the solutions by the values of the key variables, in their order of appearance, and
+/// compute the aggregates over each group. The resulting solutions extend the provided context
+/// with the keys and aggregates values.
+fn group_solutions(
+    storage: &dyn Storage,
+    upstream: ResolvedVariablesIterator<'_>,
+    context: &ResolvedVariables,
+    keys: &[usize],
+    aggregates: &[(usize, Aggregate)],
+) -> StdResult<Vec<ResolvedVariables>> {
+    let mut groups: Vec<Vec<ResolvedVariables>> = Vec::new();
+    let mut group_index: HashMap<Vec<Option<ResolvedVariable>>, usize> = HashMap::new();
+    for res in upstream {
+        let vars = res?;
+        let key = keys
+            .iter()
+            .map(|k| vars.get(*k).as_ref().map(ResolvedVariable::canonical))
+            .collect();
+        match group_index.entry(key) {
+            Entry::Occupied(entry) => groups[*entry.get()].push(vars),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push(vec![vars]);
+            }
+        }
+    }
+    if keys.is_empty() && groups.is_empty() {
+        groups.push(Vec::new());
+    }
+
+    let mut ns_resolver = NamespaceResolver::new();
+    let mut ns_fn = |ns_key| {
+        ns_resolver
+            .resolve_from_key(storage, ns_key)
+            .and_then(NamespaceResolver::none_as_error_middleware)
+            .map(|ns| ns.value)
+    };
+
+    groups
+        .into_iter()
+        .map(|solutions| {
+            let mut grouped = context.clone();
+            if let Some(first) = solutions.first() {
+                for key in keys {
+                    if let Some(value) = first.get(*key) {
+                        grouped.merge_index(*key, value.clone());
+                    }
+                }
+            }
+            for (variable, aggregate) in aggregates {
+                if let Some(term) = aggregate.compute(&solutions, &mut ns_fn)? {
+                    grouped.merge_index(*variable, ResolvedVariable::Computed(term));
+                }
+            }
+            Ok(grouped)
+        })
+        .collect()
+}
+
+/// Evaluate all the solutions and sort them according to the conditions, the sort being stable.
+fn sort_solutions(
+    storage: &dyn Storage,
+    upstream: ResolvedVariablesIterator<'_>,
+    conditions: &[OrderCondition],
+) -> StdResult<Vec<ResolvedVariables>> {
+    let mut ns_resolver = NamespaceResolver::new();
+    let mut ns_fn = |ns_key| {
+        ns_resolver
+            .resolve_from_key(storage, ns_key)
+            .and_then(NamespaceResolver::none_as_error_middleware)
+            .map(|ns| ns.value)
+    };
+
+    let mut solutions = upstream
+        .map(|res| {
+            let vars = res?;
+            let keys = conditions
+                .iter()
+                .map(|condition| {
+                    vars.get(condition.variable())
+                        .as_ref()
+                        .map(|var| var.as_term(&mut ns_fn))
+                        .transpose()
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok((keys, vars))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    solutions.sort_by(|(left, _), (right, _)| {
+        conditions
+            .iter()
+            .zip(left.iter().zip(right))
+            .map(|(condition, (l, r))| {
+                let ordering = match (l, r) {
+                    (Some(l), Some(r)) => l.total_cmp(r),
+                    (l, r) => l.is_some().cmp(&r.is_some()),
+                };
+                match condition {
+                    OrderCondition::Asc(_) => ordering,
+                    OrderCondition::Desc(_) => ordering.reverse(),
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    Ok(solutions.into_iter().map(|(_, vars)| vars).collect())
+}
+
+struct DistinctIterator<'a> {
+    upstream: ResolvedVariablesIterator<'a>,
+    variables: Rc<Vec<usize>>,
+    seen: HashSet<Vec<Option<ResolvedVariable>>>,
+}
+
+impl<'a> DistinctIterator<'a> {
+    fn new(upstream: ResolvedVariablesIterator<'a>, variables: Rc<Vec<usize>>) -> Self {
+        Self {
+            upstream,
+            variables,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for DistinctIterator<'a> {
+    type Item = StdResult<ResolvedVariables>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.upstream.next()? {
+                Ok(vars) => {
+                    let key = self
+                        .variables
+                        .iter()
+                        .map(|v| vars.get(*v).as_ref().map(ResolvedVariable::canonical))
+                        .collect();
+                    if self.seen.insert(key) {
+                        return Some(Ok(vars));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
 struct CartesianProductJoinIterator<'a> {
     values: Vec<ResolvedVariables>,
     upstream_iter: ResolvedVariablesIterator<'a>,
@@ -276,57 +856,142 @@ impl<'a> Iterator for CartesianProductJoinIterator<'a> {
 struct TriplePatternIterator<'a> {
     input: ResolvedVariables,
     output_bindings: (Option<usize>, Option<usize>, Option<usize>),
+    graph_binding: Option<usize>,
     triple_iter: Box<dyn Iterator<Item = StdResult<Triple>> + 'a>,
 }
 
+/// Represents the graphs the triples of a pattern are matched against.
+#[derive(Clone, Debug, PartialEq)]
+enum GraphFilter {
+    /// All the graphs, including the default one.
+    All,
+    /// Any of the named graphs.
+    Named,
+    /// A specific named graph.
+    Graph(state::Node),
+}
+
 type TriplePatternFilters = (Option<Subject>, Option<Predicate>, Option<Object>);
 type TriplePatternBlankFilters = (bool, bool);
 type TriplePatternBindings = (Option<usize>, Option<usize>, Option<usize>);
 
+/// Returns the access path used by [TriplePatternIterator::make_state_iter] given which of the
+/// subject, predicate and object are bound, and whether the triples are matched against a
+/// specific named graph.
+pub(super) fn access_path(bound: (bool, bool, bool), in_graph: bool) -> AccessPath {
+    match bound {
+        (true, true, true) if in_graph => AccessPath::Key,
+        (false, false, false) if in_graph => AccessPath::GraphSubject,
+        (true, true, _) => AccessPath::SubjectPredicate,
+        (false, true, true) => AccessPath::ObjectPredicate,
+        (true, false, _) => AccessPath::Subject,
+        (false, false, true) => AccessPath::ObjectPrefix,
+        (false, true, false) => AccessPath::Predicate,
+        (false, false, false) => AccessPath::FullScan,
+    }
+}
+
 impl<'a> TriplePatternIterator<'a> {
+    /// Create the iterator over the solutions of the pattern, matched against the triples of the
+    /// named graphs only if a graph is provided.
     fn new(
         storage: &'a dyn Storage,
+        height: Option<u64>,
         input: ResolvedVariables,
         subject: PatternValue<Subject>,
         predicate: PatternValue<Predicate>,
         object: PatternValue<Object>,
+        graph: Option<PatternValue<state::Node>>,
     ) -> Self {
-        if let Some((filters, blank_filters, output_bindings)) =
-            Self::compute_iter_io(&input, subject, predicate, object)
-        {
+        if let (Some((filters, blank_filters, output_bindings)), Some((graph, graph_binding))) = (
+            Self::compute_iter_io(&input, subject, predicate, object),
+            Self::compute_graph_io(&input, graph),
+        ) {
             return Self {
                 input,
                 output_bindings,
-                triple_iter: Self::make_state_iter(storage, filters, blank_filters),
+                graph_binding,
+                triple_iter: Self::make_state_iter(storage, height, filters, blank_filters, graph),
             };
         }
 
         Self {
             input,
             output_bindings: (None, None, None),
+            graph_binding: None,
             triple_iter: Box::new(iter::empty()),
         }
     }
 
     fn make_state_iter(
         storage: &'a dyn Storage,
+        height: Option<u64>,
         filters: TriplePatternFilters,
         blank_filters: (bool, bool),
+        graph: GraphFilter,
     ) -> Box<dyn Iterator<Item = StdResult<Triple>> + 'a> {
-        let post_filter = move |t: &Triple| {
-            let s = !blank_filters.0 || matches!(t.subject, Subject::Blank(_));
-            let o = !blank_filters.1 || matches!(t.object, Object::Blank(_));
-            o && s
+        let post_filter = {
+            let graph = graph.clone();
+            move |t: &Triple| {
+                if !t.is_valid_at(height) {
+                    return false;
+                }
+                let s = !blank_filters.0 || matches!(t.subject, Subject::Blank(_));
+                let o = !blank_filters.1 || matches!(t.object, Object::Blank(_));
+                let g = match &graph {
+                    GraphFilter::All => true,
+                    GraphFilter::Named => t.graph.is_some(),
+                    GraphFilter::Graph(g) => t.graph.as_ref() == Some(g),
+                };
+                o && s && g
+            }
         };
 
-        match filters {
-            (Some(s), Some(p), Some(o)) => {
-                let res = triples().load(storage, (o.as_hash().as_bytes(), p.key(), s.key()));
-                match res {
-                    Err(StdError::NotFound { .. }) => Box::new(iter::empty()),
-                    _ => Box::new(iter::once(res)),
+        if let GraphFilter::Graph(g) = &graph {
+            match &filters {
+                (Some(s), Some(p), Some(o)) => {
+                    let res = triples().load(
+                        storage,
+                        (o.as_hash().as_bytes(), p.key(), s.key_in_graph(Some(g))),
+                    );
+                    return match res {
+                        Err(StdError::NotFound { .. }) => Box::new(iter::empty()),
+                        Ok(t) if !t.is_valid_at(height) => Box::new(iter::empty()),
+                        _ => Box::new(iter::once(res)),
+                    };
+                }
+                (None, None, None) => {
+                    return Box::new(
+                        triples()
+                            .idx
+                            .graph_and_subject
+                            .sub_prefix(g.key())
+                            .range(storage, None, None, Order::Ascending)
+                            .filter(move |res| match res {
+                                Ok((_, triple)) => post_filter(triple),
+                                Err(_) => true,
+                            })
+                            .map(|res| res.map(|(_, t)| t)),
+                    );
                 }
+                _ => {}
             }
+        }
+
+        match filters {
+            // The triple may belong to several graphs.
+            (Some(s), Some(p), Some(o)) => Box::new(
+                triples()
+                    .idx
+                    .subject_and_predicate
+                    .prefix((s.key(), p.key()))
+                    .range(storage, None, None, Order::Ascending)
+                    .filter(move |res| match res {
+                        Ok((_, triple)) => triple.object == o && post_filter(triple),
+                        Err(_) => true,
+                    })
+                    .map(|res| res.map(|(_, t)| t)),
+            ),
             (Some(s), Some(p), None) => Box::new(
                 triples()
                     .idx
@@ -375,9 +1040,12 @@ impl<'a> TriplePatternIterator<'a> {
             ),
             (None, Some(p), None) => Box::new(
                 triples()
+                    .idx
+                    .predicate_and_subject
+                    .sub_prefix(p.key())
                     .range(storage, None, None, Order::Ascending)
                     .filter(move |res| match res {
-                        Ok((_, triple)) => triple.predicate == p && post_filter(triple),
+                        Ok((_, triple)) => post_filter(triple),
                         Err(_) => true,
                     })
                     .map(|res| res.map(|(_, t)| t)),
@@ -432,6 +1100,24 @@ impl<'a> TriplePatternIterator<'a> {
         ))
     }
 
+    /// Resolve the graphs to match the triples against, and the variable to bind their name to if
+    /// any.
+    fn compute_graph_io(
+        input: &ResolvedVariables,
+        graph: Option<PatternValue<state::Node>>,
+    ) -> Option<(GraphFilter, Option<usize>)> {
+        let Some(graph) = graph else {
+            return Some((GraphFilter::All, None));
+        };
+
+        Some(
+            match Self::resolve_pattern_part(graph, ResolvedVariable::as_predicate, input)? {
+                (Some(g), _, _) => (GraphFilter::Graph(g), None),
+                (None, _, binding) => (GraphFilter::Named, binding),
+            },
+        )
+    }
+
     fn resolve_pattern_part<T, M>(
         pattern_part: PatternValue<T>,
         map_fn: M,
@@ -465,6 +1151,9 @@ impl<'a> TriplePatternIterator<'a> {
         if let Some(v) = self.output_bindings.2 {
             vars.merge_index(v, ResolvedVariable::Object(triple.object))?;
         }
+        if let Some(v) = self.graph_binding {
+            vars.merge_index(v, ResolvedVariable::Predicate(triple.graph?))?;
+        }
 
         Some(vars)
     }
@@ -488,6 +1177,336 @@ impl<'a> Iterator for TriplePatternIterator<'a> {
     }
 }
 
+/// Evaluate the property path within each of the named graphs matching the provided graph if any,
+/// binding their name to the graph variable, or else against the triples of all the graphs.
+fn eval_graph_path(
+    walker: &PathWalker<'_>,
+    context: &ResolvedVariables,
+    subject: &PatternValue<Subject>,
+    path: &PropertyPath,
+    object: &PatternValue<Object>,
+    graph: &Option<PatternValue<state::Node>>,
+) -> StdResult<Vec<ResolvedVariables>> {
+    let Some(graph) = graph else {
+        return eval_path(walker, context, subject, path, object);
+    };
+    let Some((g_filter, _, g_bind)) = TriplePatternIterator::resolve_pattern_part(
+        graph.clone(),
+        ResolvedVariable::as_predicate,
+        context,
+    ) else {
+        return Ok(vec![]);
+    };
+
+    let graphs = match g_filter {
+        Some(g) => vec![g],
+        None => walker.graphs()?,
+    };
+    let mut solutions = Vec::new();
+    for g in graphs {
+        let walker = walker.in_graph(g.clone());
+        for mut vars in eval_path(&walker, context, subject, path, object)? {
+            if let Some(v) = g_bind {
+                if vars
+                    .merge_index(v, ResolvedVariable::Predicate(g.clone()))
+                    .is_none()
+                {
+                    continue;
+                }
+            }
+            solutions.push(vars);
+        }
+    }
+    Ok(solutions)
+}
+
+/// Evaluate the property path between the subject and the object, starting from the bound end if
+/// any, or else from every node of the graph. The resulting solutions extend the provided context
+/// with the ends of the path.
+fn eval_path(
+    walker: &PathWalker<'_>,
+    context: &ResolvedVariables,
+    subject: &PatternValue<Subject>,
+    path: &PropertyPath,
+    object: &PatternValue<Object>,
+) -> StdResult<Vec<ResolvedVariables>> {
+    let Some((s_filter, sb_filter, s_bind)) = TriplePatternIterator::resolve_pattern_part(
+        subject.clone(),
+        ResolvedVariable::as_subject,
+        context,
+    ) else {
+        return Ok(vec![]);
+    };
+    let Some((o_filter, ob_filter, o_bind)) = TriplePatternIterator::resolve_pattern_part(
+        object.clone(),
+        ResolvedVariable::as_object,
+        context,
+    ) else {
+        return Ok(vec![]);
+    };
+
+    let mut ends = Vec::new();
+    match (s_filter, o_filter) {
+        (Some(s), Some(o)) => {
+            let start = subject_as_object(s);
+            if walker.walk(path, &start, false)?.contains(&o) {
+                ends.push((start, o));
+            }
+        }
+        (Some(s), None) => {
+            let start = subject_as_object(s);
+            for end in walker.walk(path, &start, false)? {
+                ends.push((start.clone(), end));
+            }
+        }
+        (None, Some(o)) => {
+            for start in walker.walk(path, &o, true)? {
+                ends.push((start, o.clone()));
+            }
+        }
+        (None, None) => {
+            for start in walker.nodes()? {
+                for end in walker.walk(path, &start, false)? {
+                    ends.push((start.clone(), end));
+                }
+            }
+        }
+    }
+
+    Ok(ends
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let start = object_as_subject(start)?;
+            if (sb_filter && !matches!(start, Subject::Blank(_)))
+                || (ob_filter && !matches!(end, Object::Blank(_)))
+            {
+                return None;
+            }
+
+            let mut vars = context.clone();
+            match (s_bind, o_bind) {
+                (Some(s), Some(o)) if s == o => {
+                    (object_as_subject(end)? == start).then_some(())?;
+                    vars.merge_index(s, ResolvedVariable::Subject(start))?;
+                }
+                _ => {
+                    if let Some(v) = s_bind {
+                        vars.merge_index(v, ResolvedVariable::Subject(start))?;
+                    }
+                    if let Some(v) = o_bind {
+                        vars.merge_index(v, ResolvedVariable::Object(end))?;
+                    }
+                }
+            }
+            Some(vars)
+        })
+        .collect())
+}
+
+fn subject_as_object(subject: Subject) -> Object {
+    match subject {
+        Subject::Named(n) => Object::Named(n),
+        Subject::Blank(n) => Object::Blank(n),
+    }
+}
+
+fn object_as_subject(object: Object) -> Option<Subject> {
+    match object {
+        Object::Named(n) => Some(Subject::Named(n)),
+        Object::Blank(n) => Some(Subject::Blank(n)),
+        Object::Literal(_) => None,
+    }
+}
+
+/// Traverse the graph along property paths, the repeated paths being followed at most `max_depth`
+/// times, through the triples of the named graph if any or else of all the graphs.
+struct PathWalker<'a> {
+    storage: &'a dyn Storage,
+    height: Option<u64>,
+    max_depth: usize,
+    graph: Option<state::Node>,
+}
+
+impl<'a> PathWalker<'a> {
+    fn new(storage: &'a dyn Storage, height: Option<u64>, max_depth: usize) -> Self {
+        Self {
+            storage,
+            height,
+            max_depth,
+            graph: None,
+        }
+    }
+
+    /// Restrict the traversal to the triples of the given named graph.
+    fn in_graph(&self, graph: state::Node) -> Self {
+        Self {
+            storage: self.storage,
+            height: self.height,
+            max_depth: self.max_depth,
+            graph: Some(graph),
+        }
+    }
+
+    /// Tells whether the triple can be traversed, i.e. is valid and belongs to the walked graph.
+    fn follows(&self, triple: &Triple) -> bool {
+        triple.is_valid_at(self.height)
+            && self
+                .graph
+                .as_ref()
+                .map_or(true, |g| triple.graph.as_ref() == Some(g))
+    }
+
+    /// Resolve the nodes reached by following the path from the given node, or the nodes leading
+    /// to it through the path if inverse. Each node is returned once, in order of discovery.
+    fn walk(&self, path: &PropertyPath, node: &Object, inverse: bool) -> StdResult<Vec<Object>> {
+        Ok(match path {
+            PropertyPath::Predicate(None) => vec![],
+            PropertyPath::Predicate(Some(predicate)) => {
+                Self::unique(self.step(predicate, node, inverse)?)
+            }
+            PropertyPath::Inverse(path) => self.walk(path, node, !inverse)?,
+            PropertyPath::Sequence(paths) => {
+                let paths = if inverse {
+                    Left(paths.iter().rev())
+                } else {
+                    Right(paths.iter())
+                };
+                let mut nodes = vec![node.clone()];
+                for path in paths {
+                    let mut next = Vec::new();
+                    for node in &nodes {
+                        next.extend(self.walk(path, node, inverse)?);
+                    }
+                    nodes = Self::unique(next);
+                }
+                nodes
+            }
+            PropertyPath::Alternative(paths) => {
+                let mut nodes = Vec::new();
+                for path in paths {
+                    nodes.extend(self.walk(path, node, inverse)?);
+                }
+                Self::unique(nodes)
+            }
+            PropertyPath::ZeroOrOne(path) => {
+                let mut nodes = vec![node.clone()];
+                nodes.extend(self.walk(path, node, inverse)?);
+                Self::unique(nodes)
+            }
+            PropertyPath::ZeroOrMore(path) => self.closure(path, node, inverse, true)?,
+            PropertyPath::OneOrMore(path) => self.closure(path, node, inverse, false)?,
+        })
+    }
+
+    /// Breadth-first traversal of the repeated path, each node being expanded once.
+    fn closure(
+        &self,
+        path: &PropertyPath,
+        node: &Object,
+        inverse: bool,
+        reflexive: bool,
+    ) -> StdResult<Vec<Object>> {
+        let mut visited = HashSet::new();
+        let mut reached = Vec::new();
+        if reflexive {
+            visited.insert(node.clone());
+            reached.push(node.clone());
+        }
+
+        let mut frontier = vec![node.clone()];
+        let mut depth = 0;
+        while !frontier.is_empty() && depth < self.max_depth {
+            let mut next = Vec::new();
+            for node in &frontier {
+                for reachable in self.walk(path, node, inverse)? {
+                    if visited.insert(reachable.clone()) {
+                        reached.push(reachable.clone());
+                        next.push(reachable);
+                    }
+                }
+            }
+            frontier = next;
+            depth += 1;
+        }
+        Ok(reached)
+    }
+
+    /// Follow the predicate a single time, using the subject and predicate index forward and the
+    /// object and predicate prefix of the triples backward.
+    fn step(&self, predicate: &Predicate, node: &Object, inverse: bool) -> StdResult<Vec<Object>> {
+        if inverse {
+            return triples()
+                .prefix((node.as_hash().as_bytes(), predicate.key()))
+                .range(self.storage, None, None, Order::Ascending)
+                .filter(|res| res.as_ref().map_or(true, |(_, t)| self.follows(t)))
+                .map(|res| res.map(|(_, t)| subject_as_object(t.subject)))
+                .collect();
+        }
+
+        let Some(subject) = object_as_subject(node.clone()) else {
+            return Ok(vec![]);
+        };
+        triples()
+            .idx
+            .subject_and_predicate
+            .prefix((subject.key(), predicate.key()))
+            .range(self.storage, None, None, Order::Ascending)
+            .filter(|res| res.as_ref().map_or(true, |(_, t)| self.follows(t)))
+            .map(|res| res.map(|(_, t)| t.object))
+            .collect()
+    }
+
+    /// Resolve all the nodes of the graph that can be the subject of a triple, in order of
+    /// discovery.
+    fn nodes(&self) -> StdResult<Vec<Object>> {
+        let iter = match &self.graph {
+            Some(g) => triples()
+                .idx
+                .graph_and_subject
+                .sub_prefix(g.key())
+                .range(self.storage, None, None, Order::Ascending),
+            None => triples().range(self.storage, None, None, Order::Ascending),
+        };
+        let mut nodes = Vec::new();
+        for res in iter {
+            let (_, triple) = res?;
+            if !triple.is_valid_at(self.height) {
+                continue;
+            }
+            nodes.push(subject_as_object(triple.subject));
+            if !matches!(triple.object, Object::Literal(_)) {
+                nodes.push(triple.object);
+            }
+        }
+        Ok(Self::unique(nodes))
+    }
+
+    /// Resolve the names of all the named graphs holding a triple, in order of discovery.
+    fn graphs(&self) -> StdResult<Vec<state::Node>> {
+        let mut graphs = Vec::new();
+        for res in triples().range(self.storage, None, None, Order::Ascending) {
+            let (_, triple) = res?;
+            if !triple.is_valid_at(self.height) {
+                continue;
+            }
+            if let Some(g) = triple.graph {
+                if !graphs.contains(&g) {
+                    graphs.push(g);
+                }
+            }
+        }
+        Ok(graphs)
+    }
+
+    fn unique(nodes: Vec<Object>) -> Vec<Object> {
+        let mut seen = HashSet::with_capacity(nodes.len());
+        nodes
+            .into_iter()
+            .filter(|node| seen.insert(node.clone()))
+            .collect()
+    }
+}
+
 pub struct SolutionsIterator<'a> {
     iter: ResolvedVariablesIterator<'a>,
     bindings: BTreeMap<String, usize>,
@@ -509,18 +1528,16 @@ impl<'a> Iterator for SolutionsIterator<'a> {
         };
 
         resolved_variables
-            .and_then(|variables| {
+            .map(|variables| {
                 self.bindings
-                    .clone()
-                    .into_iter()
-                    .map(|(name, index)| (name, variables.get(index)))
-                    .map(|(name, var)| match var {
-                        None => Err(StdError::generic_err(
-                            "Couldn't find variable in result set",
-                        )),
-                        Some(val) => Ok((name, val.clone())),
+                    .iter()
+                    .filter_map(|(name, index)| {
+                        variables
+                            .get(*index)
+                            .as_ref()
+                            .map(|var| (name.clone(), var.clone()))
                     })
-                    .collect::<StdResult<BTreeMap<String, ResolvedVariable>>>()
+                    .collect::<BTreeMap<String, ResolvedVariable>>()
             })
             .into()
     }
@@ -564,16 +1581,12 @@ impl<'a> Iterator for ResolvedTripleIterator<'a> {
                     self.buffer.push_back(Err(err));
                 }
                 Ok(vars) => {
-                    for res in self
+                    for triple in self
                         .templates
                         .iter()
-                        .map(|template| template.resolve(&vars))
+                        .filter_map(|template| template.resolve(&vars))
                     {
-                        match res {
-                            Ok(Some(triple)) => self.buffer.push_back(Ok(triple)),
-                            Err(err) => self.buffer.push_back(Err(err)),
-                            _ => {}
-                        }
+                        self.buffer.push_back(Ok(triple));
                     }
                 }
             }
@@ -585,10 +1598,21 @@ pub struct TripleTemplate {
     subject: Either<Subject, usize>,
     predicate: Either<Predicate, usize>,
     object: Either<Object, usize>,
+    graph: Option<Either<state::Node, usize>>,
 }
 
-pub type TripleTemplateWithBlankNode = (VarOrNode, VarOrNamedNode, VarOrNodeOrLiteral);
-pub type TripleTemplateNoBlankNode = (VarOrNamedNode, VarOrNamedNode, VarOrNamedNodeOrLiteral);
+pub type TripleTemplateWithBlankNode = (
+    VarOrNode,
+    VarOrNamedNode,
+    VarOrNodeOrLiteral,
+    Option<VarOrNamedNode>,
+);
+pub type TripleTemplateNoBlankNode = (
+    VarOrNamedNode,
+    VarOrNamedNode,
+    VarOrNamedNodeOrLiteral,
+    Option<VarOrNamedNode>,
+);
 
 impl TripleTemplate {
     fn try_new(
@@ -597,72 +1621,65 @@ impl TripleTemplate {
         plan: &QueryPlan,
         prefixes: &HashMap<String, String>,
         template: Either<TripleTemplateWithBlankNode, TripleTemplateNoBlankNode>,
+        strict_typing: bool,
     ) -> StdResult<TripleTemplate> {
-        let (s_tpl, p_tpl, o_tpl) = match template {
-            Right((s, p, o)) => (Right(s), p, Right(o)),
-            Left((s, p, o)) => (Left(s), p, Left(o)),
+        let (s_tpl, p_tpl, o_tpl, g_tpl) = match template {
+            Right((s, p, o, g)) => (Right(s), p, Right(o), g),
+            Left((s, p, o, g)) => (Left(s), p, Left(o), g),
         };
 
         Ok(TripleTemplate {
             subject: Self::build_subject_template(storage, ns_resolver, plan, prefixes, s_tpl)?,
             predicate: Self::build_predicate_template(storage, ns_resolver, plan, prefixes, p_tpl)?,
-            object: Self::build_object_template(storage, ns_resolver, plan, prefixes, o_tpl)?,
+            object: Self::build_object_template(
+                storage,
+                ns_resolver,
+                plan,
+                prefixes,
+                o_tpl,
+                strict_typing,
+            )?,
+            graph: g_tpl
+                .map(|g| Self::build_predicate_template(storage, ns_resolver, plan, prefixes, g))
+                .transpose()?,
         })
     }
 
-    pub fn resolve(&self, vars: &ResolvedVariables) -> StdResult<Option<Triple>> {
-        let subject = match Self::resolve_triple_term(
-            &self.subject,
-            ResolvedVariable::as_subject,
-            vars,
-            "subject",
-        )? {
-            Some(s) => s,
-            None => return Ok(None),
-        };
-
-        let predicate = match Self::resolve_triple_term(
-            &self.predicate,
-            ResolvedVariable::as_predicate,
-            vars,
-            "predicate",
-        )? {
-            Some(p) => p,
-            None => return Ok(None),
-        };
-
-        let object = match Self::resolve_triple_term(
-            &self.object,
-            ResolvedVariable::as_object,
-            vars,
-            "object",
-        )? {
-            Some(o) => o,
-            None => return Ok(None),
-        };
-
-        Ok(Some(Triple {
-            subject,
-            predicate,
-            object,
-        }))
+    /// Resolve the template against the provided variables, returning `None` if a variable is
+    /// unbound or can't be used at its position in the triple.
+    pub fn resolve(&self, vars: &ResolvedVariables) -> Option<Triple> {
+        Some(Triple {
+            subject: Self::resolve_triple_term(&self.subject, ResolvedVariable::as_subject, vars)?,
+            predicate: Self::resolve_triple_term(
+                &self.predicate,
+                ResolvedVariable::as_predicate,
+                vars,
+            )?,
+            object: Self::resolve_triple_term(&self.object, ResolvedVariable::as_object, vars)?,
+            graph: match &self.graph {
+                Some(graph) => Some(Self::resolve_triple_term(
+                    graph,
+                    ResolvedVariable::as_predicate,
+                    vars,
+                )?),
+                None => None,
+            },
+            validity: vec![],
+        })
     }
 
     fn resolve_triple_term<T, F>(
         term: &Either<T, usize>,
         from_var: F,
         vars: &ResolvedVariables,
-        term_name: &str,
-    ) -> StdResult<Option<T>>
+    ) -> Option<T>
     where
         T: Clone,
         F: Fn(&ResolvedVariable) -> Option<T>,
     {
         match term {
-            Left(p) => Ok(Some(p.clone())),
-            Right(key) => vars.get(*key).as_ref().map(from_var).ok_or_else(|| {
-                StdError::generic_err(format!("Unbound {:?} variable: {:?}", term_name, key))
-            }),
+            Left(p) => Some(p.clone()),
+            Right(key) => vars.get(*key).as_ref().and_then(from_var),
         }
     }
 
@@ -719,6 +1736,7 @@ impl TripleTemplate {
         plan: &QueryPlan,
         prefixes: &HashMap<String, String>,
         value: Either<VarOrNodeOrLiteral, VarOrNamedNodeOrLiteral>,
+        strict_typing: bool,
     ) -> StdResult<Either<Object, usize>> {
         Ok(match value {
             Left(VarOrNodeOrLiteral::Variable(v)) | Right(VarOrNamedNodeOrLiteral::Variable(v)) => {
@@ -740,7 +1758,13 @@ impl TripleTemplate {
                 iri,
             )?)),
             Left(VarOrNodeOrLiteral::Literal(l)) | Right(VarOrNamedNodeOrLiteral::Literal(l)) => {
-                Left(literal_as_object(ns_resolver, storage, prefixes, l)?)
+                Left(literal_as_object(
+                    ns_resolver,
+                    storage,
+                    prefixes,
+                    l,
+                    strict_typing,
+                )?)
             }
         })
     }
@@ -817,14 +1841,35 @@ pub struct AtomTemplate {
     subject: Either<rdf::Subject, usize>,
     property: Either<rdf::Property, usize>,
     value: Either<rdf::Value, usize>,
+    graph: Option<Either<rdf::Property, usize>>,
+}
+
+/// A quad resolved out of an [AtomTemplate] against a solution, whose terms are either the
+/// constants of the template or the values bound to its variables, the latter keeping their
+/// identity in the store, e.g. for blank nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedQuad {
+    pub subject: Either<rdf::Subject, Subject>,
+    pub predicate: Either<rdf::Property, Predicate>,
+    pub object: Either<rdf::Value, Object>,
+    pub graph: Option<Either<rdf::Property, state::Node>>,
 }
 
 impl AtomTemplate {
     pub fn try_new(
         plan: &QueryPlan,
         prefixes: &HashMap<String, String>,
-        (s_tpl, p_tpl, o_tpl): (VarOrNode, VarOrNamedNode, VarOrNodeOrLiteral),
+        (s_tpl, p_tpl, o_tpl, g_tpl): TripleTemplateWithBlankNode,
     ) -> StdResult<AtomTemplate> {
+        let named_node_term = |tpl: VarOrNamedNode| -> StdResult<Either<rdf::Property, usize>> {
+            Ok(match tpl {
+                VarOrNamedNode::Variable(key) => Right(plan.get_var_index(key.as_str()).ok_or(
+                    StdError::generic_err("Selected variable not found in query"),
+                )?),
+                VarOrNamedNode::NamedNode(iri) => Left((iri, prefixes).try_into()?),
+            })
+        };
+
         Ok(Self {
             subject: match s_tpl {
                 VarOrNode::Variable(key) => Right(plan.get_var_index(key.as_str()).ok_or(
@@ -832,12 +1877,7 @@ impl AtomTemplate {
                 )?),
                 VarOrNode::Node(n) => Left((n, prefixes).try_into()?),
             },
-            property: match p_tpl {
-                VarOrNamedNode::Variable(key) => Right(plan.get_var_index(key.as_str()).ok_or(
-                    StdError::generic_err("Selected variable not found in query"),
-                )?),
-                VarOrNamedNode::NamedNode(iri) => Left((iri, prefixes).try_into()?),
-            },
+            property: named_node_term(p_tpl)?,
             value: match o_tpl {
                 VarOrNodeOrLiteral::Variable(key) => Right(
                     plan.get_var_index(key.as_str())
@@ -848,6 +1888,7 @@ impl AtomTemplate {
                 VarOrNodeOrLiteral::Node(n) => Left((n, prefixes).try_into()?),
                 VarOrNodeOrLiteral::Literal(l) => Left((l, prefixes).try_into()?),
             },
+            graph: g_tpl.map(named_node_term).transpose()?,
         })
     }
 
@@ -879,13 +1920,52 @@ impl AtomTemplate {
             None => return Ok(None),
         };
 
+        let graph = match &self.graph {
+            Some(graph) => match Self::resolve_atom_term(
+                graph,
+                ResolvedVariable::as_predicate,
+                vars,
+                &mut |value| value.as_iri(resolve_ns_fn).map(rdf::Property),
+            )? {
+                Some(g) => Some(g.0),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
         Ok(Some(Atom {
             subject,
             property,
             value,
+            graph,
         }))
     }
 
+    /// Resolve the template against the provided variables without mapping the bound values to
+    /// atoms, returning `None` if a variable is unbound or can't be used at its position.
+    pub fn resolve_quad(&self, vars: &ResolvedVariables) -> Option<ResolvedQuad> {
+        fn resolve<A: Clone, T>(
+            term: &Either<A, usize>,
+            from_var: impl Fn(&ResolvedVariable) -> Option<T>,
+            vars: &ResolvedVariables,
+        ) -> Option<Either<A, T>> {
+            match term {
+                Left(v) => Some(Left(v.clone())),
+                Right(key) => vars.get(*key).as_ref().and_then(from_var).map(Right),
+            }
+        }
+
+        Some(ResolvedQuad {
+            subject: resolve(&self.subject, ResolvedVariable::as_subject, vars)?,
+            predicate: resolve(&self.property, ResolvedVariable::as_predicate, vars)?,
+            object: resolve(&self.value, ResolvedVariable::as_object, vars)?,
+            graph: match &self.graph {
+                Some(graph) => Some(resolve(graph, ResolvedVariable::as_predicate, vars)?),
+                None => None,
+            },
+        })
+    }
+
     fn resolve_atom_subject<F>(
         &self,
         resolve_ns_fn: &mut F,
@@ -907,7 +1987,6 @@ impl AtomTemplate {
                     ),
                 })
             },
-            "subject",
         )
     }
 
@@ -924,7 +2003,6 @@ impl AtomTemplate {
             ResolvedVariable::as_predicate,
             vars,
             &mut |value| value.as_iri(resolve_ns_fn).map(rdf::Property),
-            "predicate",
         )
     }
 
@@ -958,7 +2036,6 @@ impl AtomTemplate {
                     },
                 })
             },
-            "object",
         )
     }
 
@@ -967,7 +2044,6 @@ impl AtomTemplate {
         from_var: F,
         vars: &ResolvedVariables,
         mapping_fn: &mut M,
-        term_name: &str,
     ) -> StdResult<Option<A>>
     where
         A: Clone,
@@ -976,16 +2052,10 @@ impl AtomTemplate {
     {
         match term {
             Left(v) => Ok(Some(v.clone())),
-            Right(key) => {
-                let var = vars.get(*key).as_ref().ok_or_else(|| {
-                    StdError::generic_err(format!("Unbound {:?} variable: {:?}", term_name, key))
-                })?;
-
-                match from_var(var) {
-                    None => Ok(None),
-                    Some(v) => mapping_fn(v).map(Some),
-                }
-            }
+            Right(key) => match vars.get(*key).as_ref().and_then(from_var) {
+                None => Ok(None),
+                Some(v) => mapping_fn(v).map(Some),
+            },
         }
     }
 }
@@ -1032,6 +2102,8 @@ mod test {
                     owner: Addr::unchecked("owner"),
                     limits: StoreLimitsInput::default().into(),
                     stat: StoreStat::default(),
+                    strict_typing: false,
+                    versioning: false,
                 },
             )
             .unwrap();
@@ -1367,6 +2439,66 @@ mod test {
                 },
                 expects: 3,
             },
+            TestCase {
+                plan: QueryPlan {
+                    entrypoint: QueryNode::Optional {
+                        left: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0),
+                            predicate: PatternValue::Constant(state::Node {
+                                namespace: 1,
+                                value: "type".to_string(),
+                            }),
+                            object: PatternValue::Constant(Object::Named(state::Node {
+                                namespace: 2,
+                                value: "NamedIndividual".to_string(),
+                            })),
+                        }),
+                        right: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0),
+                            predicate: PatternValue::Constant(state::Node {
+                                namespace: 3,
+                                value: "hasTag".to_string(),
+                            }),
+                            object: PatternValue::Variable(1),
+                        }),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("v1".to_string()),
+                        PlanVariable::Basic("v2".to_string()),
+                    ],
+                },
+                expects: 6,
+            },
+            TestCase {
+                plan: QueryPlan {
+                    entrypoint: QueryNode::Union {
+                        left: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0),
+                            predicate: PatternValue::Constant(state::Node {
+                                namespace: 1,
+                                value: "type".to_string(),
+                            }),
+                            object: PatternValue::Constant(Object::Named(state::Node {
+                                namespace: 2,
+                                value: "NamedIndividual".to_string(),
+                            })),
+                        }),
+                        right: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0),
+                            predicate: PatternValue::Constant(state::Node {
+                                namespace: 3,
+                                value: "hasTag".to_string(),
+                            }),
+                            object: PatternValue::Variable(1),
+                        }),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("v1".to_string()),
+                        PlanVariable::Basic("v2".to_string()),
+                    ],
+                },
+                expects: 8,
+            },
         ];
 
         let engine = QueryEngine::new(&deps.storage);
@@ -1439,6 +2571,197 @@ mod test {
         }
     }
 
+    #[test]
+    fn optional_iter() {
+        struct TestCase {
+            left: Vec<u128>,
+            right: Vec<(u128, u128)>,
+            expects: Vec<(u128, Option<u128>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                left: vec![],
+                right: vec![(2u128, 0u128)],
+                expects: vec![],
+            },
+            TestCase {
+                left: vec![2u128, 3u128],
+                right: vec![],
+                expects: vec![(2u128, None), (3u128, None)],
+            },
+            TestCase {
+                left: vec![2u128, 3u128],
+                right: vec![(2u128, 0u128), (2u128, 1u128), (3u128, 0u128)],
+                expects: vec![
+                    (2u128, Some(0u128)),
+                    (2u128, Some(1u128)),
+                    (3u128, Some(0u128)),
+                ],
+            },
+            TestCase {
+                left: vec![2u128, 3u128, 4u128],
+                right: vec![(3u128, 1u128)],
+                expects: vec![(2u128, None), (3u128, Some(1u128)), (4u128, None)],
+            },
+        ];
+
+        for case in cases {
+            let right = case.right.clone();
+            let result = OptionalIterator::new(
+                Box::new(case.left.iter().map(|v| {
+                    let mut vars = ResolvedVariables::with_capacity(3);
+                    vars.merge_index(1, ResolvedVariable::Subject(Subject::Blank(*v)));
+                    Ok(vars)
+                })),
+                Rc::new(move |input| {
+                    let matches: Vec<u128> = right
+                        .iter()
+                        .filter(|(l, _)| {
+                            input.get(1) == &Some(ResolvedVariable::Subject(Subject::Blank(*l)))
+                        })
+                        .map(|(_, r)| *r)
+                        .collect();
+                    Box::new(matches.into_iter().map(move |v| {
+                        let mut vars = input.clone();
+                        vars.merge_index(2, ResolvedVariable::Subject(Subject::Blank(v)));
+                        Ok(vars)
+                    }))
+                }),
+            )
+            .collect::<StdResult<Vec<ResolvedVariables>>>();
+            assert!(result.is_ok());
+
+            let expects: Vec<ResolvedVariables> = case
+                .expects
+                .iter()
+                .map(|(v1, v2)| {
+                    let mut vars = ResolvedVariables::with_capacity(3);
+                    vars.merge_index(1, ResolvedVariable::Subject(Subject::Blank(*v1)));
+                    if let Some(v2) = v2 {
+                        vars.merge_index(2, ResolvedVariable::Subject(Subject::Blank(*v2)));
+                    }
+                    vars
+                })
+                .collect();
+
+            assert_eq!(result.unwrap(), expects);
+        }
+    }
+
+    #[test]
+    fn sort_solutions() {
+        let literal = |value: &str, lang: Option<&str>| {
+            Some(ResolvedVariable::Object(Literal(match lang {
+                Some(lang) => state::Literal::I18NString {
+                    value: value.to_string(),
+                    language: lang.to_string(),
+                },
+                None => state::Literal::Simple {
+                    value: value.to_string(),
+                },
+            })))
+        };
+        let solutions = [
+            (literal("b", None), literal("x", None)),
+            (None, literal("x", None)),
+            (
+                Some(ResolvedVariable::Subject(Subject::Blank(0u128))),
+                literal("x", None),
+            ),
+            (literal("a", Some("en")), literal("x", None)),
+            (literal("a", None), literal("y", None)),
+            (literal("a", None), literal("z", None)),
+        ];
+        let cases = vec![
+            (vec![OrderCondition::Asc(0)], vec![1, 2, 4, 5, 3, 0]),
+            (vec![OrderCondition::Desc(0)], vec![0, 3, 4, 5, 2, 1]),
+            (
+                vec![OrderCondition::Asc(0), OrderCondition::Desc(1)],
+                vec![1, 2, 5, 4, 3, 0],
+            ),
+            (vec![OrderCondition::Asc(1)], vec![0, 1, 2, 3, 4, 5]),
+        ];
+
+        let deps = mock_dependencies();
+        for (conditions, expects) in cases {
+            let upstream = solutions.iter().map(|(v0, v1)| {
+                let mut vars = ResolvedVariables::with_capacity(2);
+                for (i, v) in [v0, v1].into_iter().enumerate() {
+                    if let Some(v) = v {
+                        vars.merge_index(i, v.clone());
+                    }
+                }
+                Ok(vars)
+            });
+            let result = super::sort_solutions(&deps.storage, Box::new(upstream), &conditions);
+            assert!(result.is_ok());
+
+            let actual: Vec<(Option<ResolvedVariable>, Option<ResolvedVariable>)> = result
+                .unwrap()
+                .iter()
+                .map(|vars| (vars.get(0).clone(), vars.get(1).clone()))
+                .collect();
+            let expects: Vec<(Option<ResolvedVariable>, Option<ResolvedVariable>)> =
+                expects.iter().map(|i| solutions[*i].clone()).collect();
+            assert_eq!(actual, expects);
+        }
+
+        let result = super::sort_solutions(
+            &deps.storage,
+            Box::new(iter::once(Err(StdError::generic_err("error")))),
+            &[OrderCondition::Asc(0)],
+        );
+        assert_eq!(result, Err(StdError::generic_err("error")));
+    }
+
+    #[test]
+    fn distinct_iter() {
+        let node = |value: &str| Node {
+            namespace: 0u128,
+            value: value.to_string(),
+        };
+        let solutions = vec![
+            (
+                Some(ResolvedVariable::Subject(Subject::Named(node("a")))),
+                0u128,
+            ),
+            (Some(ResolvedVariable::Object(Named(node("a")))), 1u128),
+            (Some(ResolvedVariable::Predicate(node("b"))), 2u128),
+            (None, 3u128),
+            (
+                Some(ResolvedVariable::Subject(Subject::Named(node("b")))),
+                4u128,
+            ),
+            (None, 5u128),
+        ];
+        let cases = vec![
+            (vec![0usize], vec![0u128, 2u128, 3u128]),
+            (vec![1usize], vec![0u128, 1u128, 2u128, 3u128, 4u128, 5u128]),
+            (vec![], vec![0u128]),
+        ];
+
+        for (variables, expects) in cases {
+            let upstream = solutions.clone().into_iter().map(|(v0, v1)| {
+                let mut vars = ResolvedVariables::with_capacity(2);
+                if let Some(v0) = v0 {
+                    vars.merge_index(0, v0);
+                }
+                vars.merge_index(1, ResolvedVariable::Subject(Subject::Blank(v1)));
+                Ok(vars)
+            });
+            let result = DistinctIterator::new(Box::new(upstream), Rc::new(variables))
+                .map(|res| {
+                    res.map(|vars| match vars.get(1) {
+                        Some(ResolvedVariable::Subject(Subject::Blank(v))) => *v,
+                        _ => u128::MAX,
+                    })
+                })
+                .collect::<StdResult<Vec<u128>>>();
+            assert_eq!(result, Ok(expects));
+        }
+    }
+
     #[test]
     fn cartesian_join_iter() {
         struct TestCase {
@@ -1739,10 +3062,164 @@ mod test {
 
         for case in cases {
             assert_eq!(
-                TriplePatternIterator::make_state_iter(&deps.storage, case.filters, (false, false))
-                    .count(),
+                TriplePatternIterator::make_state_iter(
+                    &deps.storage,
+                    None,
+                    case.filters,
+                    (false, false),
+                    GraphFilter::All,
+                )
+                .count(),
                 case.expects
             );
         }
     }
+
+    #[test]
+    fn eval_path() {
+        let mut deps = mock_dependencies();
+        STORE
+            .save(
+                deps.as_mut().storage,
+                &Store {
+                    owner: Addr::unchecked("owner"),
+                    limits: StoreLimitsInput::default().into(),
+                    stat: StoreStat::default(),
+                    strict_typing: false,
+                    versioning: false,
+                },
+            )
+            .unwrap();
+        NAMESPACE_KEY_INCREMENT
+            .save(deps.as_mut().storage, &0u128)
+            .unwrap();
+        BLANK_NODE_IDENTIFIER_COUNTER
+            .save(deps.as_mut().storage, &0u128)
+            .unwrap();
+        let data = r#"<http://example.com/a> <http://example.com/next> <http://example.com/b> .
+<http://example.com/b> <http://example.com/next> <http://example.com/c> .
+<http://example.com/c> <http://example.com/next> <http://example.com/a> .
+<http://example.com/c> <http://example.com/label> "C" .
+<http://example.com/d> <http://example.com/next> <http://example.com/a> .
+"#;
+        let mut reader = TripleReader::new(
+            &axone_rdf::serde::DataFormat::NTriples,
+            BufReader::new(data.as_bytes()),
+        );
+        StoreEngine::new(deps.as_mut().storage)
+            .unwrap()
+            .store_all(&mut reader)
+            .unwrap();
+
+        let node = |value: &str| Node {
+            namespace: 0u128,
+            value: value.to_string(),
+        };
+        let next = || PropertyPath::Predicate(Some(node("next")));
+        let subject = |value: &str| Some(ResolvedVariable::Subject(Subject::Named(node(value))));
+        let object = |value: &str| Some(ResolvedVariable::Object(Named(node(value))));
+
+        let cases = vec![
+            (
+                PatternValue::Constant(Subject::Named(node("a"))),
+                PropertyPath::OneOrMore(Box::new(next())),
+                PatternValue::Variable(1),
+                10usize,
+                vec![
+                    (None, object("b")),
+                    (None, object("c")),
+                    (None, object("a")),
+                ],
+            ),
+            (
+                PatternValue::Constant(Subject::Named(node("a"))),
+                PropertyPath::ZeroOrMore(Box::new(next())),
+                PatternValue::Variable(1),
+                10usize,
+                vec![
+                    (None, object("a")),
+                    (None, object("b")),
+                    (None, object("c")),
+                ],
+            ),
+            (
+                PatternValue::Constant(Subject::Named(node("a"))),
+                PropertyPath::OneOrMore(Box::new(next())),
+                PatternValue::Variable(1),
+                1usize,
+                vec![(None, object("b"))],
+            ),
+            (
+                PatternValue::Variable(0),
+                PropertyPath::Sequence(vec![next(), next()]),
+                PatternValue::Constant(Named(node("a"))),
+                10usize,
+                vec![(subject("b"), None)],
+            ),
+            (
+                PatternValue::Variable(0),
+                PropertyPath::OneOrMore(Box::new(next())),
+                PatternValue::Variable(0),
+                10usize,
+                vec![
+                    (subject("b"), None),
+                    (subject("c"), None),
+                    (subject("a"), None),
+                ],
+            ),
+            (
+                PatternValue::Constant(Subject::Named(node("a"))),
+                PropertyPath::Alternative(vec![
+                    PropertyPath::Sequence(vec![
+                        next(),
+                        next(),
+                        PropertyPath::Predicate(Some(node("label"))),
+                    ]),
+                    PropertyPath::ZeroOrOne(Box::new(PropertyPath::Inverse(Box::new(next())))),
+                ]),
+                PatternValue::Variable(1),
+                10usize,
+                vec![
+                    (
+                        None,
+                        Some(ResolvedVariable::Object(Literal(state::Literal::Simple {
+                            value: "C".to_string(),
+                        }))),
+                    ),
+                    (None, object("a")),
+                    (None, object("c")),
+                    (None, object("d")),
+                ],
+            ),
+            (
+                PatternValue::Constant(Subject::Named(node("a"))),
+                PropertyPath::ZeroOrMore(Box::new(PropertyPath::Predicate(None))),
+                PatternValue::Variable(1),
+                10usize,
+                vec![(None, object("a"))],
+            ),
+        ];
+
+        let engine = QueryEngine::new(&deps.storage);
+        for (subject, path, object, max_depth, expects) in cases {
+            let result = engine
+                .eval_plan(QueryPlan {
+                    entrypoint: QueryNode::Path {
+                        subject,
+                        path,
+                        object,
+                        graph: None,
+                        max_depth,
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("o".to_string()),
+                    ],
+                })
+                .map(|res| res.map(|vars| (vars.get(0).clone(), vars.get(1).clone())))
+                .collect::<StdResult<Vec<_>>>();
+
+            assert_eq!(result, Ok(expects));
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use crate::msg::AccessPath;
+use crate::querier::engine::access_path;
+use crate::querier::plan::{PatternValue, QueryNode};
+use crate::state::{
+    NamespaceStat, Node, Object, Predicate, Subject, NAMESPACE_STATS, PREDICATE_STATS, STORE,
+};
+use cosmwasm_std::{StdResult, Storage};
+use std::collections::{BTreeSet, HashMap};
+
+/// The factor by which each bound term of a pattern, for which no statistics apply, is assumed to
+/// divide the number of triples it matches.
+const SELECTIVITY: u128 = 10;
+
+/// The estimated cost of evaluating a node, compared by the number of triples read from the store
+/// first, then by the number of solutions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cost {
+    /// The number of triples read, being the number of triples in the store for a full scan.
+    pub reads: u128,
+    /// The number of solutions.
+    pub cardinality: u128,
+}
+
+/// Estimates the number of triples matched by the patterns out of the cardinality statistics
+/// maintained by the store, i.e. the number of triples per predicate and per namespace of their
+/// subject and object. The statistics are cached once loaded from the state.
+pub struct CardinalityEstimator<'a> {
+    storage: &'a dyn Storage,
+    triple_count: u128,
+    predicates: HashMap<Vec<u8>, u128>,
+    namespaces: HashMap<u128, NamespaceStat>,
+}
+
+impl<'a> CardinalityEstimator<'a> {
+    pub fn new(storage: &'a dyn Storage) -> StdResult<Self> {
+        Ok(Self {
+            storage,
+            triple_count: STORE
+                .may_load(storage)?
+                .map_or(0, |store| store.stat.triple_count.u128()),
+            predicates: HashMap::new(),
+            namespaces: HashMap::new(),
+        })
+    }
+
+    /// Estimates the number of solutions of a single evaluation of a pattern, given the variables
+    /// bound by the context it's evaluated in, its predicate being unknown for a path.
+    pub fn estimate_pattern(
+        &mut self,
+        subject: &PatternValue<Subject>,
+        predicate: Option<&PatternValue<Predicate>>,
+        object: &PatternValue<Object>,
+        graph: Option<&PatternValue<Node>>,
+        bound: &BTreeSet<usize>,
+    ) -> StdResult<u128> {
+        let mut count = match predicate {
+            Some(PatternValue::Constant(p)) => self.predicate_count(p)?,
+            Some(p) if !is_bound(p, bound) => self.triple_count,
+            _ => self.triple_count.div_ceil(SELECTIVITY),
+        };
+
+        if let PatternValue::Constant(Subject::Named(n)) = subject {
+            count = count.min(self.namespace_stat(n.namespace)?.subject_count);
+        }
+        if is_bound(subject, bound) {
+            count = count.div_ceil(SELECTIVITY);
+        }
+
+        if let PatternValue::Constant(Object::Named(n)) = object {
+            count = count.min(self.namespace_stat(n.namespace)?.object_count);
+        }
+        if is_bound(object, bound) {
+            count = count.div_ceil(SELECTIVITY);
+        }
+
+        if graph.is_some_and(|g| is_bound(g, bound)) {
+            count = count.div_ceil(SELECTIVITY);
+        }
+        Ok(count)
+    }
+
+    /// Estimates the cost of a single evaluation of the pattern or path node, given the variables
+    /// bound by the context it's evaluated in.
+    pub fn estimate_cost(&mut self, node: &QueryNode, bound: &BTreeSet<usize>) -> StdResult<Cost> {
+        match node {
+            QueryNode::TriplePattern {
+                subject,
+                predicate,
+                object,
+            } => self.pattern_cost(subject, predicate, object, None, bound),
+            QueryNode::QuadPattern {
+                subject,
+                predicate,
+                object,
+                graph,
+            } => self.pattern_cost(subject, predicate, object, Some(graph), bound),
+            QueryNode::Path {
+                subject, object, ..
+            } => {
+                let cardinality = self.estimate_pattern(subject, None, object, None, bound)?;
+                Ok(Cost {
+                    reads: cardinality,
+                    cardinality,
+                })
+            }
+            _ => Ok(Cost {
+                reads: self.triple_count,
+                cardinality: self.triple_count,
+            }),
+        }
+    }
+
+    fn pattern_cost(
+        &mut self,
+        subject: &PatternValue<Subject>,
+        predicate: &PatternValue<Predicate>,
+        object: &PatternValue<Object>,
+        graph: Option<&PatternValue<Node>>,
+        bound: &BTreeSet<usize>,
+    ) -> StdResult<Cost> {
+        let cardinality = self.estimate_pattern(subject, Some(predicate), object, graph, bound)?;
+        Ok(Cost {
+            reads: match pattern_access_path(subject, predicate, object, graph, bound) {
+                AccessPath::FullScan => self.triple_count,
+                _ => cardinality,
+            },
+            cardinality,
+        })
+    }
+
+    fn predicate_count(&mut self, predicate: &Predicate) -> StdResult<u128> {
+        let key = predicate.key();
+        if let Some(count) = self.predicates.get(&key) {
+            return Ok(*count);
+        }
+
+        let count = PREDICATE_STATS
+            .may_load(self.storage, &key)?
+            .unwrap_or_default();
+        self.predicates.insert(key, count);
+        Ok(count)
+    }
+
+    fn namespace_stat(&mut self, namespace: u128) -> StdResult<NamespaceStat> {
+        if let Some(stat) = self.namespaces.get(&namespace) {
+            return Ok(stat.clone());
+        }
+
+        let stat = NAMESPACE_STATS
+            .may_load(self.storage, namespace)?
+            .unwrap_or_default();
+        self.namespaces.insert(namespace, stat.clone());
+        Ok(stat)
+    }
+}
+
+/// Returns the access path used to match the pattern against the store, given the variables bound
+/// by the context it's evaluated in.
+pub fn pattern_access_path(
+    subject: &PatternValue<Subject>,
+    predicate: &PatternValue<Predicate>,
+    object: &PatternValue<Object>,
+    graph: Option<&PatternValue<Node>>,
+    bound: &BTreeSet<usize>,
+) -> AccessPath {
+    access_path(
+        (
+            is_bound(subject, bound),
+            is_bound(predicate, bound),
+            is_bound(object, bound),
+        ),
+        graph.is_some_and(|g| is_bound(g, bound)),
+    )
+}
+
+fn is_bound<V>(value: &PatternValue<V>, bound: &BTreeSet<usize>) -> bool {
+    match value {
+        PatternValue::Constant(_) => true,
+        PatternValue::Variable(v) | PatternValue::BlankVariable(v) => bound.contains(v),
+    }
+}
@@ -0,0 +1,368 @@
+use crate::msg::{PlanNode, PlanOperation, PlanTerm};
+use crate::querier::estimator::{pattern_access_path, CardinalityEstimator};
+use crate::querier::plan::{PatternValue, PlanVariable, QueryNode, QueryPlan};
+use crate::querier::variable::ResolvedVariable;
+use crate::state::{Node, Object, Predicate, Subject};
+use axone_rdf::normalize::IdentifierIssuer;
+use cosmwasm_std::{StdResult, Storage, Uint128};
+use std::collections::BTreeSet;
+
+/// Describes a [QueryPlan] as a tree of [PlanNode], telling how each triple pattern would be
+/// matched against the store and estimating the number of solutions of each node, without
+/// evaluating it.
+pub struct QueryExplainer<'a, F> {
+    estimator: CardinalityEstimator<'a>,
+    ns_fn: F,
+    id_issuer: IdentifierIssuer,
+}
+
+impl<'a, F> QueryExplainer<'a, F>
+where
+    F: FnMut(u128) -> StdResult<String>,
+{
+    /// Create an explainer estimating the cardinalities out of the statistics of the store, the
+    /// namespaces of the constants being resolved with the provided function.
+    pub fn new(storage: &'a dyn Storage, ns_fn: F) -> StdResult<Self> {
+        Ok(Self {
+            estimator: CardinalityEstimator::new(storage)?,
+            ns_fn,
+            id_issuer: IdentifierIssuer::new("b", 0u128),
+        })
+    }
+
+    pub fn explain(&mut self, plan: &QueryPlan) -> StdResult<PlanNode> {
+        self.explain_node(plan, &plan.entrypoint, &BTreeSet::new())
+    }
+
+    /// Explain the node, given the variables bound by the context it's evaluated in, e.g. by the
+    /// left node of a join when explaining its right node.
+    fn explain_node(
+        &mut self,
+        plan: &QueryPlan,
+        node: &QueryNode,
+        bound: &BTreeSet<usize>,
+    ) -> StdResult<PlanNode> {
+        Ok(match node {
+            QueryNode::TriplePattern {
+                subject,
+                predicate,
+                object,
+            } => self.explain_pattern(plan, bound, subject, predicate, object, None)?,
+            QueryNode::QuadPattern {
+                subject,
+                predicate,
+                object,
+                graph,
+            } => self.explain_pattern(plan, bound, subject, predicate, object, Some(graph))?,
+            QueryNode::Path {
+                subject,
+                object,
+                max_depth,
+                ..
+            } => PlanNode {
+                operation: PlanOperation::Path {
+                    subject: self.term(plan, subject, ResolvedVariable::Subject)?,
+                    object: self.term(plan, object, ResolvedVariable::Object)?,
+                    max_depth: u32::try_from(*max_depth).unwrap_or(u32::MAX),
+                },
+                access_path: None,
+                cardinality: Uint128::from(
+                    self.estimator
+                        .estimate_pattern(subject, None, object, None, bound)?,
+                ),
+                children: vec![],
+            },
+            QueryNode::Noop { .. } => leaf(PlanOperation::Noop, 0),
+            QueryNode::Values { variables, rows } => leaf(
+                PlanOperation::Values {
+                    variables: variables.iter().map(|v| var_name(plan, *v)).collect(),
+                },
+                rows.len() as u128,
+            ),
+            QueryNode::SubQuery { plan: sub_plan, .. } => {
+                let child = self.explain(sub_plan)?;
+                PlanNode {
+                    operation: PlanOperation::SubQuery,
+                    access_path: None,
+                    cardinality: child.cardinality,
+                    children: vec![child],
+                }
+            }
+            QueryNode::CartesianProductJoin { left, right } => {
+                let left = self.explain_node(plan, left, bound)?;
+                let right = self.explain_node(plan, right, bound)?;
+                join(
+                    PlanOperation::CartesianProductJoin,
+                    left.cardinality
+                        .u128()
+                        .saturating_mul(right.cardinality.u128()),
+                    left,
+                    right,
+                )
+            }
+            QueryNode::ForLoopJoin { left, right }
+            | QueryNode::Optional { left, right }
+            | QueryNode::Minus { left, right }
+            | QueryNode::NotExists { left, right } => {
+                let right_bound = bound.union(&left.bound_variables()).copied().collect();
+                let left = self.explain_node(plan, left, bound)?;
+                let right = self.explain_node(plan, right, &right_bound)?;
+                let (l, r) = (left.cardinality.u128(), right.cardinality.u128());
+                match node {
+                    QueryNode::ForLoopJoin { .. } => {
+                        join(PlanOperation::ForLoopJoin, l.saturating_mul(r), left, right)
+                    }
+                    QueryNode::Optional { .. } => join(
+                        PlanOperation::Optional,
+                        l.saturating_mul(r.max(1)),
+                        left,
+                        right,
+                    ),
+                    QueryNode::Minus { .. } => join(PlanOperation::Minus, l, left, right),
+                    _ => join(PlanOperation::NotExists, l, left, right),
+                }
+            }
+            QueryNode::Union { left, right } => {
+                let left = self.explain_node(plan, left, bound)?;
+                let right = self.explain_node(plan, right, bound)?;
+                join(
+                    PlanOperation::Union,
+                    left.cardinality
+                        .u128()
+                        .saturating_add(right.cardinality.u128()),
+                    left,
+                    right,
+                )
+            }
+            QueryNode::Filter { inner, .. } => {
+                self.explain_child(plan, inner, bound, PlanOperation::Filter, |c| c)?
+            }
+            QueryNode::Bind {
+                child, variable, ..
+            } => self.explain_child(
+                plan,
+                child,
+                bound,
+                PlanOperation::Bind {
+                    variable: var_name(plan, *variable),
+                },
+                |c| c,
+            )?,
+            QueryNode::Group { child, keys, .. } => self.explain_child(
+                plan,
+                child,
+                bound,
+                PlanOperation::Group {
+                    keys: keys.iter().map(|v| var_name(plan, *v)).collect(),
+                },
+                |c| if keys.is_empty() { 1 } else { c },
+            )?,
+            QueryNode::OrderBy { child, .. } => {
+                self.explain_child(plan, child, bound, PlanOperation::OrderBy, |c| c)?
+            }
+            QueryNode::Distinct { child, variables } => self.explain_child(
+                plan,
+                child,
+                bound,
+                PlanOperation::Distinct {
+                    variables: variables.iter().map(|v| var_name(plan, *v)).collect(),
+                },
+                |c| c,
+            )?,
+            QueryNode::Skip { child, first } => self.explain_child(
+                plan,
+                child,
+                bound,
+                PlanOperation::Skip {
+                    first: Uint128::from(*first as u128),
+                },
+                |c| c.saturating_sub(*first as u128),
+            )?,
+            QueryNode::Limit { child, first } => self.explain_child(
+                plan,
+                child,
+                bound,
+                PlanOperation::Limit {
+                    first: Uint128::from(*first as u128),
+                },
+                |c| c.min(*first as u128),
+            )?,
+        })
+    }
+
+    fn explain_child(
+        &mut self,
+        plan: &QueryPlan,
+        child: &QueryNode,
+        bound: &BTreeSet<usize>,
+        operation: PlanOperation,
+        cardinality: impl FnOnce(u128) -> u128,
+    ) -> StdResult<PlanNode> {
+        let child = self.explain_node(plan, child, bound)?;
+        Ok(PlanNode {
+            operation,
+            access_path: None,
+            cardinality: Uint128::from(cardinality(child.cardinality.u128())),
+            children: vec![child],
+        })
+    }
+
+    fn explain_pattern(
+        &mut self,
+        plan: &QueryPlan,
+        bound: &BTreeSet<usize>,
+        subject: &PatternValue<Subject>,
+        predicate: &PatternValue<Predicate>,
+        object: &PatternValue<Object>,
+        graph: Option<&PatternValue<Node>>,
+    ) -> StdResult<PlanNode> {
+        Ok(PlanNode {
+            operation: PlanOperation::TriplePattern {
+                subject: self.term(plan, subject, ResolvedVariable::Subject)?,
+                predicate: self.term(plan, predicate, ResolvedVariable::Predicate)?,
+                object: self.term(plan, object, ResolvedVariable::Object)?,
+                graph: graph
+                    .map(|g| self.term(plan, g, ResolvedVariable::Predicate))
+                    .transpose()?,
+            },
+            access_path: Some(pattern_access_path(
+                subject, predicate, object, graph, bound,
+            )),
+            cardinality: Uint128::from(self.estimator.estimate_pattern(
+                subject,
+                Some(predicate),
+                object,
+                graph,
+                bound,
+            )?),
+            children: vec![],
+        })
+    }
+
+    fn term<V: Clone>(
+        &mut self,
+        plan: &QueryPlan,
+        value: &PatternValue<V>,
+        as_var: impl FnOnce(V) -> ResolvedVariable,
+    ) -> StdResult<PlanTerm> {
+        Ok(match value {
+            PatternValue::Constant(v) => {
+                PlanTerm::Value(as_var(v.clone()).as_value(&mut self.ns_fn, &mut self.id_issuer)?)
+            }
+            PatternValue::Variable(v) | PatternValue::BlankVariable(v) => {
+                PlanTerm::Variable(var_name(plan, *v))
+            }
+        })
+    }
+}
+
+fn var_name(plan: &QueryPlan, index: usize) -> String {
+    match plan.variables.get(index) {
+        Some(PlanVariable::Basic(name)) => name.clone(),
+        Some(PlanVariable::BlankNode(name)) => format!("_:{name}"),
+        None => format!("_{index}"),
+    }
+}
+
+fn leaf(operation: PlanOperation, cardinality: u128) -> PlanNode {
+    PlanNode {
+        operation,
+        access_path: None,
+        cardinality: Uint128::from(cardinality),
+        children: vec![],
+    }
+}
+
+fn join(operation: PlanOperation, cardinality: u128, left: PlanNode, right: PlanNode) -> PlanNode {
+    PlanNode {
+        operation,
+        access_path: None,
+        cardinality: Uint128::from(cardinality),
+        children: vec![left, right],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::{AccessPath, StoreLimitsInput, Value, IRI};
+    use crate::state::{Store, PREDICATE_STATS, STORE};
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::Addr;
+
+    #[test]
+    fn proper_explain() {
+        let mut deps = mock_dependencies();
+        let mut store = Store::new(
+            Addr::unchecked("owner"),
+            StoreLimitsInput::default().into(),
+            false,
+            false,
+        );
+        store.stat.triple_count = Uint128::new(1000);
+        STORE.save(&mut deps.storage, &store).unwrap();
+        let predicate = Node {
+            namespace: 0u128,
+            value: "p".to_string(),
+        };
+        PREDICATE_STATS
+            .save(&mut deps.storage, &predicate.key(), &50u128)
+            .unwrap();
+
+        let pattern = |subject, predicate, graph| QueryNode::QuadPattern {
+            subject: PatternValue::Variable(subject),
+            predicate,
+            object: PatternValue::Variable(3usize),
+            graph: PatternValue::Variable(graph),
+        };
+        let plan = QueryPlan {
+            entrypoint: QueryNode::Optional {
+                left: Box::new(pattern(0usize, PatternValue::Constant(predicate), 1usize)),
+                right: Box::new(pattern(0usize, PatternValue::Variable(2usize), 1usize)),
+            },
+            variables: vec![
+                PlanVariable::Basic("s".to_string()),
+                PlanVariable::Basic("g".to_string()),
+                PlanVariable::Basic("p".to_string()),
+                PlanVariable::BlankNode("o".to_string()),
+            ],
+        };
+        let node = |predicate, graph, access_path, cardinality| PlanNode {
+            operation: PlanOperation::TriplePattern {
+                subject: PlanTerm::Variable("s".to_string()),
+                predicate,
+                object: PlanTerm::Variable("_:o".to_string()),
+                graph: Some(PlanTerm::Variable(graph)),
+            },
+            access_path: Some(access_path),
+            cardinality: Uint128::new(cardinality),
+            children: vec![],
+        };
+
+        assert_eq!(
+            QueryExplainer::new(&deps.storage, |_| Ok("https://example.org/".to_string()))
+                .and_then(|mut explainer| explainer.explain(&plan)),
+            Ok(PlanNode {
+                operation: PlanOperation::Optional,
+                access_path: None,
+                cardinality: Uint128::new(50),
+                children: vec![
+                    node(
+                        PlanTerm::Value(Value::URI {
+                            value: IRI::Full("https://example.org/p".to_string()),
+                        }),
+                        "g".to_string(),
+                        AccessPath::Predicate,
+                        50,
+                    ),
+                    node(
+                        PlanTerm::Variable("p".to_string()),
+                        "g".to_string(),
+                        AccessPath::Subject,
+                        1,
+                    ),
+                ],
+            })
+        );
+    }
+}
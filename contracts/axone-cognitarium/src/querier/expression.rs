@@ -0,0 +1,810 @@
+use crate::querier::variable::ResolvedVariables;
+use crate::rdf::{
+    Date, DateTime, Numeric, Value, XsdValue, RDF_LANG_STRING, XSD_BOOLEAN, XSD_DATE,
+    XSD_DATE_TIME, XSD_STRING,
+};
+use cosmwasm_std::{StdError, StdResult};
+use regex_lite::{Regex, RegexBuilder};
+use std::cmp::Ordering;
+
+/// Represents an expression, evaluated against the variables of a solution to produce a [Term].
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Expression {
+    Constant(Term),
+    Variable(usize),
+    And(Vec<Self>),
+    Or(Vec<Self>),
+    Not(Box<Self>),
+    Equal(Box<Self>, Box<Self>),
+    NotEqual(Box<Self>, Box<Self>),
+    Greater(Box<Self>, Box<Self>),
+    GreaterOrEqual(Box<Self>, Box<Self>),
+    Less(Box<Self>, Box<Self>),
+    LessOrEqual(Box<Self>, Box<Self>),
+    Bound(usize),
+    Regex(Box<Self>, RegexPattern),
+    Contains(Box<Self>, Box<Self>),
+    StrStarts(Box<Self>, Box<Self>),
+    Lang(Box<Self>),
+    Datatype(Box<Self>),
+    IsIri(Box<Self>),
+    IsBlank(Box<Self>),
+    IsLiteral(Box<Self>),
+}
+
+impl Expression {
+    /// Evaluate the expression given the solution variables, resolving namespaces through the
+    /// provided function.
+    ///
+    /// Results in `None` if the expression can't be evaluated, for instance when relying on an
+    /// unbound variable or on operands of incompatible types, in which case the solution is
+    /// expected to be discarded.
+    pub fn evaluate<F>(&self, vars: &ResolvedVariables, ns_fn: &mut F) -> StdResult<Option<Term>>
+    where
+        F: FnMut(u128) -> StdResult<String>,
+    {
+        Ok(match self {
+            Expression::Constant(term) => Some(term.clone()),
+            Expression::Variable(v) => match vars.get(*v) {
+                Some(var) => Some(var.as_term(ns_fn)?),
+                None => None,
+            },
+            Expression::And(exprs) => {
+                let mut res = Some(true);
+                for expr in exprs {
+                    match expr.evaluate_bool(vars, ns_fn)? {
+                        Some(false) => return Ok(Some(Term::Boolean(false))),
+                        Some(true) => {}
+                        None => res = None,
+                    }
+                }
+                res.map(Term::Boolean)
+            }
+            Expression::Or(exprs) => {
+                let mut res = Some(false);
+                for expr in exprs {
+                    match expr.evaluate_bool(vars, ns_fn)? {
+                        Some(true) => return Ok(Some(Term::Boolean(true))),
+                        Some(false) => {}
+                        None => res = None,
+                    }
+                }
+                res.map(Term::Boolean)
+            }
+            Expression::Not(expr) => expr.evaluate_bool(vars, ns_fn)?.map(|b| Term::Boolean(!b)),
+            Expression::Equal(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| Some(l.equals(r)))?
+            }
+            Expression::NotEqual(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| Some(!l.equals(r)))?
+            }
+            Expression::Greater(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| {
+                    l.compare(r).map(Ordering::is_gt)
+                })?
+            }
+            Expression::GreaterOrEqual(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| {
+                    l.compare(r).map(Ordering::is_ge)
+                })?
+            }
+            Expression::Less(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| {
+                    l.compare(r).map(Ordering::is_lt)
+                })?
+            }
+            Expression::LessOrEqual(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| {
+                    l.compare(r).map(Ordering::is_le)
+                })?
+            }
+            Expression::Bound(v) => Some(Term::Boolean(vars.get(*v).is_some())),
+            Expression::Regex(expr, pattern) => expr
+                .evaluate(vars, ns_fn)?
+                .as_ref()
+                .and_then(Term::as_string_literal)
+                .map(|(text, _)| Term::Boolean(pattern.is_match(text))),
+            Expression::Contains(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| {
+                    Term::string_literal_args(l, r).map(|(l, r)| l.contains(r))
+                })?
+            }
+            Expression::StrStarts(left, right) => {
+                Self::evaluate_binary(left, right, vars, ns_fn, |l, r| {
+                    Term::string_literal_args(l, r).map(|(l, r)| l.starts_with(r))
+                })?
+            }
+            Expression::Lang(expr) => expr
+                .evaluate(vars, ns_fn)?
+                .as_ref()
+                .and_then(Term::lang)
+                .map(|lang| Term::String(lang.to_string())),
+            Expression::Datatype(expr) => expr
+                .evaluate(vars, ns_fn)?
+                .as_ref()
+                .and_then(Term::datatype)
+                .map(|datatype| Term::NamedNode(datatype.to_string())),
+            Expression::IsIri(expr) => expr
+                .evaluate(vars, ns_fn)?
+                .map(|t| Term::Boolean(matches!(t, Term::NamedNode(_)))),
+            Expression::IsBlank(expr) => expr
+                .evaluate(vars, ns_fn)?
+                .map(|t| Term::Boolean(matches!(t, Term::BlankNode(_)))),
+            Expression::IsLiteral(expr) => expr
+                .evaluate(vars, ns_fn)?
+                .map(|t| Term::Boolean(t.is_literal())),
+        })
+    }
+
+    /// Evaluate the expression as its effective boolean value.
+    pub fn evaluate_bool<F>(
+        &self,
+        vars: &ResolvedVariables,
+        ns_fn: &mut F,
+    ) -> StdResult<Option<bool>>
+    where
+        F: FnMut(u128) -> StdResult<String>,
+    {
+        Ok(self.evaluate(vars, ns_fn)?.as_ref().and_then(Term::as_bool))
+    }
+
+    fn evaluate_binary<F, O>(
+        left: &Self,
+        right: &Self,
+        vars: &ResolvedVariables,
+        ns_fn: &mut F,
+        op: O,
+    ) -> StdResult<Option<Term>>
+    where
+        F: FnMut(u128) -> StdResult<String>,
+        O: FnOnce(&Term, &Term) -> Option<bool>,
+    {
+        Ok(
+            match (left.evaluate(vars, ns_fn)?, right.evaluate(vars, ns_fn)?) {
+                (Some(l), Some(r)) => op(&l, &r).map(Term::Boolean),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// Represents an RDF term resulting from an expression evaluation, with its IRIs fully resolved.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub enum Term {
+    NamedNode(String),
+    BlankNode(String),
+    String(String),
+    LangString(String, String),
+    Typed(String, String),
+    Boolean(bool),
+    Numeric(Numeric),
+    Date(Date),
+    DateTime(DateTime),
+}
+
+impl Term {
+    /// Returns the [effective boolean value](https://www.w3.org/TR/sparql11-query/#ebv) of the
+    /// term, if any.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Term::Boolean(b) => Some(*b),
+            Term::String(value) | Term::LangString(value, _) => Some(!value.is_empty()),
+            Term::Numeric(n) => Some(!n.is_zero_or_nan()),
+            Term::NamedNode(_)
+            | Term::BlankNode(_)
+            | Term::Typed(..)
+            | Term::Date(_)
+            | Term::DateTime(_) => None,
+        }
+    }
+
+    /// Returns the lexical form and language tag of the term if it's a string literal.
+    pub fn as_string_literal(&self) -> Option<(&str, Option<&str>)> {
+        match self {
+            Term::String(value) => Some((value, None)),
+            Term::LangString(value, lang) => Some((value, Some(lang))),
+            _ => None,
+        }
+    }
+
+    /// Returns the lexical form of the term, as the `STR` function does, blank nodes having none.
+    pub fn lexical_form(&self) -> Option<String> {
+        match self {
+            Term::NamedNode(value)
+            | Term::String(value)
+            | Term::LangString(value, _)
+            | Term::Typed(value, _) => Some(value.clone()),
+            Term::Boolean(b) => Some(b.to_string()),
+            Term::Numeric(n) => Some(n.to_string()),
+            Term::Date(d) => Some(d.to_string()),
+            Term::DateTime(dt) => Some(dt.to_string()),
+            Term::BlankNode(_) => None,
+        }
+    }
+
+    pub fn is_literal(&self) -> bool {
+        !matches!(self, Term::NamedNode(_) | Term::BlankNode(_))
+    }
+
+    pub fn lang(&self) -> Option<&str> {
+        match self {
+            Term::LangString(_, lang) => Some(lang),
+            Term::String(_)
+            | Term::Typed(..)
+            | Term::Boolean(_)
+            | Term::Numeric(_)
+            | Term::Date(_)
+            | Term::DateTime(_) => Some(""),
+            Term::NamedNode(_) | Term::BlankNode(_) => None,
+        }
+    }
+
+    pub fn datatype(&self) -> Option<&str> {
+        match self {
+            Term::String(_) => Some(XSD_STRING),
+            Term::LangString(..) => Some(RDF_LANG_STRING),
+            Term::Typed(_, datatype) => Some(datatype),
+            Term::Boolean(_) => Some(XSD_BOOLEAN),
+            Term::Numeric(n) => Some(n.datatype()),
+            Term::Date(_) => Some(XSD_DATE),
+            Term::DateTime(_) => Some(XSD_DATE_TIME),
+            Term::NamedNode(_) | Term::BlankNode(_) => None,
+        }
+    }
+
+    /// Compare the term with another one, only terms of compatible types can be ordered: numerics
+    /// are compared by value whatever their datatype, and dates chronologically.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Term::String(l), Term::String(r)) => Some(l.cmp(r)),
+            (Term::Boolean(l), Term::Boolean(r)) => Some(l.cmp(r)),
+            (Term::Numeric(l), Term::Numeric(r)) => l.compare(r),
+            (Term::Date(l), Term::Date(r)) => Some(l.compare(r)),
+            (Term::DateTime(l), Term::DateTime(r)) => Some(l.compare(r)),
+            _ => None,
+        }
+    }
+
+    /// Compare the term with another one following the ordering used to sort solutions: blank
+    /// nodes come first, then IRIs and literals. Literals of a same kind are compared by value, ties
+    /// between distinct terms being broken deterministically.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.kind_rank()
+            .cmp(&other.kind_rank())
+            .then_with(|| match (self, other) {
+                (Term::BlankNode(l), Term::BlankNode(r))
+                | (Term::NamedNode(l), Term::NamedNode(r)) => l.cmp(r),
+                (Term::Numeric(l), Term::Numeric(r)) => l.total_cmp(r),
+                (Term::Boolean(l), Term::Boolean(r)) => l.cmp(r),
+                (Term::DateTime(l), Term::DateTime(r)) => l.total_cmp(r),
+                (Term::Date(l), Term::Date(r)) => l.total_cmp(r),
+                (Term::Typed(l, l_type), Term::Typed(r, r_type)) => (l_type, l).cmp(&(r_type, r)),
+                _ => self.as_string_literal().cmp(&other.as_string_literal()),
+            })
+    }
+
+    fn kind_rank(&self) -> u8 {
+        match self {
+            Term::BlankNode(_) => 0,
+            Term::NamedNode(_) => 1,
+            Term::Numeric(_) => 2,
+            Term::Boolean(_) => 3,
+            Term::DateTime(_) => 4,
+            Term::Date(_) => 5,
+            Term::String(_) | Term::LangString(..) => 6,
+            Term::Typed(..) => 7,
+        }
+    }
+
+    /// Tells if the term is equal to another one, by value for numerics and dates, and as RDF terms
+    /// otherwise.
+    pub fn equals(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Term::Numeric(_), Term::Numeric(_))
+            | (Term::Date(_), Term::Date(_))
+            | (Term::DateTime(_), Term::DateTime(_)) => {
+                self.compare(other).is_some_and(Ordering::is_eq)
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Returns the lexical forms of two string literals if they are
+    /// [argument compatible](https://www.w3.org/TR/sparql11-query/#func-arg-compatibility).
+    fn string_literal_args<'a>(left: &'a Self, right: &'a Self) -> Option<(&'a str, &'a str)> {
+        match (left.as_string_literal()?, right.as_string_literal()?) {
+            ((l, l_lang), (r, r_lang)) if r_lang.is_none() || l_lang == r_lang => Some((l, r)),
+            _ => None,
+        }
+    }
+}
+
+impl From<Value> for Term {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::NamedNode(iri) => Term::NamedNode(iri),
+            Value::BlankNode(id) => Term::BlankNode(id),
+            Value::LiteralSimple(value) => Term::String(value),
+            Value::LiteralLang(value, lang) => Term::LangString(value, lang),
+            Value::LiteralDatatype(value, datatype) => match datatype.as_str() {
+                XSD_STRING => Term::String(value),
+                _ => match XsdValue::parse(&value, &datatype) {
+                    Some(Ok(xsd)) => xsd.into(),
+                    _ => Term::Typed(value, datatype),
+                },
+            },
+        }
+    }
+}
+
+impl From<XsdValue> for Term {
+    fn from(value: XsdValue) -> Self {
+        match value {
+            XsdValue::Boolean(b) => Term::Boolean(b),
+            XsdValue::Numeric(n) => Term::Numeric(n),
+            XsdValue::Date(d) => Term::Date(d),
+            XsdValue::DateTime(dt) => Term::DateTime(dt),
+        }
+    }
+}
+
+/// A compiled regular expression, compared through its pattern and flags.
+#[derive(Debug, Clone)]
+pub struct RegexPattern {
+    pattern: String,
+    flags: String,
+    regex: Regex,
+}
+
+impl RegexPattern {
+    pub fn try_new(pattern: String, flags: Option<String>) -> StdResult<Self> {
+        let flags = flags.unwrap_or_default();
+        let mut builder = RegexBuilder::new(&pattern);
+        for flag in flags.chars() {
+            match flag {
+                'i' => builder.case_insensitive(true),
+                'm' => builder.multi_line(true),
+                's' => builder.dot_matches_new_line(true),
+                'x' => builder.ignore_whitespace(true),
+                _ => Err(StdError::generic_err(format!(
+                    "Unsupported regex flag: {flag}"
+                )))?,
+            };
+        }
+
+        let regex = builder
+            .build()
+            .map_err(|e| StdError::generic_err(format!("Invalid regex pattern: {e}")))?;
+        Ok(Self {
+            pattern,
+            flags,
+            regex,
+        })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for RegexPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.flags == other.flags
+    }
+}
+
+impl Eq for RegexPattern {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::querier::variable::ResolvedVariable;
+    use crate::rdf::{XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER};
+    use crate::state::{Literal, Node, Object, Subject};
+
+    fn ns(i: u128) -> StdResult<String> {
+        match i {
+            0 => Ok("foo".to_string()),
+            1 => Ok("http://www.w3.org/2001/XMLSchema#".to_string()),
+            _ => Err(StdError::generic_err("namespace not found")),
+        }
+    }
+
+    fn vars() -> ResolvedVariables {
+        let mut vars = ResolvedVariables::with_capacity(6);
+        vars.merge_index(
+            0,
+            ResolvedVariable::Object(Object::Literal(Literal::I18NString {
+                value: "Un Dataset de test".to_string(),
+                language: "fr".to_string(),
+            })),
+        );
+        vars.merge_index(
+            1,
+            ResolvedVariable::Subject(Subject::Named(Node {
+                namespace: 0,
+                value: "bar".to_string(),
+            })),
+        );
+        vars.merge_index(2, ResolvedVariable::Object(Object::Blank(0u128)));
+        vars.merge_index(
+            4,
+            ResolvedVariable::Object(Object::Literal(Literal::Simple {
+                value: "AXONE".to_string(),
+            })),
+        );
+        vars.merge_index(
+            5,
+            ResolvedVariable::Object(Object::Literal(Literal::Typed {
+                value: "true".to_string(),
+                datatype: Node {
+                    namespace: 1,
+                    value: "boolean".to_string(),
+                },
+            })),
+        );
+        vars
+    }
+
+    fn var(i: usize) -> Box<Expression> {
+        Box::new(Expression::Variable(i))
+    }
+
+    fn string(s: &str) -> Box<Expression> {
+        Box::new(Expression::Constant(Term::String(s.to_string())))
+    }
+
+    fn typed(value: &str, datatype: &str) -> Box<Expression> {
+        Box::new(Expression::Constant(Term::from(Value::LiteralDatatype(
+            value.to_string(),
+            datatype.to_string(),
+        ))))
+    }
+
+    fn regex(pattern: &str, flags: Option<&str>) -> RegexPattern {
+        RegexPattern::try_new(pattern.to_string(), flags.map(ToString::to_string)).unwrap()
+    }
+
+    #[test]
+    fn evaluate() {
+        let cases = vec![
+            (
+                Expression::Constant(Term::String("foo".to_string())),
+                Some(Term::String("foo".to_string())),
+            ),
+            (
+                Expression::Variable(1),
+                Some(Term::NamedNode("foobar".to_string())),
+            ),
+            (Expression::Variable(3), None),
+            (Expression::Variable(5), Some(Term::Boolean(true))),
+            (Expression::And(vec![]), Some(Term::Boolean(true))),
+            (
+                Expression::And(vec![*var(5), *var(4)]),
+                Some(Term::Boolean(true)),
+            ),
+            (Expression::And(vec![*var(5), *var(3)]), None),
+            (
+                Expression::And(vec![*var(3), Expression::Not(var(5))]),
+                Some(Term::Boolean(false)),
+            ),
+            (Expression::Or(vec![]), Some(Term::Boolean(false))),
+            (
+                Expression::Or(vec![*var(3), *var(5)]),
+                Some(Term::Boolean(true)),
+            ),
+            (Expression::Or(vec![*var(3), *string("")]), None),
+            (
+                Expression::Or(vec![*string(""), Expression::Not(var(4))]),
+                Some(Term::Boolean(false)),
+            ),
+            (Expression::Not(var(1)), None),
+            (
+                Expression::Equal(var(4), string("AXONE")),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Equal(
+                    var(1),
+                    Box::new(Expression::Constant(Term::NamedNode("foobar".to_string()))),
+                ),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Equal(var(0), string("Un Dataset de test")),
+                Some(Term::Boolean(false)),
+            ),
+            (Expression::Equal(var(3), var(3)), None),
+            (
+                Expression::NotEqual(var(4), string("AXONE")),
+                Some(Term::Boolean(false)),
+            ),
+            (
+                Expression::Greater(var(4), string("AAA")),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::GreaterOrEqual(var(4), string("AXONE")),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Less(var(4), string("AXONE")),
+                Some(Term::Boolean(false)),
+            ),
+            (
+                Expression::LessOrEqual(var(4), string("B")),
+                Some(Term::Boolean(true)),
+            ),
+            (Expression::Less(var(0), string("B")), None),
+            (Expression::Less(var(1), var(1)), None),
+            (Expression::Bound(1), Some(Term::Boolean(true))),
+            (Expression::Bound(3), Some(Term::Boolean(false))),
+            (
+                Expression::Regex(var(0), regex("^un dataset", Some("i"))),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Regex(var(0), regex("^un dataset", None)),
+                Some(Term::Boolean(false)),
+            ),
+            (Expression::Regex(var(1), regex("foo", None)), None),
+            (
+                Expression::Contains(var(0), string("Dataset")),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Contains(
+                    var(0),
+                    Box::new(Expression::Constant(Term::LangString(
+                        "Dataset".to_string(),
+                        "fr".to_string(),
+                    ))),
+                ),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Contains(
+                    var(4),
+                    Box::new(Expression::Constant(Term::LangString(
+                        "AX".to_string(),
+                        "fr".to_string(),
+                    ))),
+                ),
+                None,
+            ),
+            (
+                Expression::StrStarts(var(4), string("AX")),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::StrStarts(var(4), string("ONE")),
+                Some(Term::Boolean(false)),
+            ),
+            (
+                Expression::Lang(var(0)),
+                Some(Term::String("fr".to_string())),
+            ),
+            (Expression::Lang(var(4)), Some(Term::String("".to_string()))),
+            (Expression::Lang(var(1)), None),
+            (
+                Expression::Datatype(var(0)),
+                Some(Term::NamedNode(RDF_LANG_STRING.to_string())),
+            ),
+            (
+                Expression::Datatype(var(4)),
+                Some(Term::NamedNode(XSD_STRING.to_string())),
+            ),
+            (
+                Expression::Datatype(var(5)),
+                Some(Term::NamedNode(XSD_BOOLEAN.to_string())),
+            ),
+            (Expression::Datatype(var(2)), None),
+            (Expression::IsIri(var(1)), Some(Term::Boolean(true))),
+            (Expression::IsIri(var(2)), Some(Term::Boolean(false))),
+            (Expression::IsBlank(var(2)), Some(Term::Boolean(true))),
+            (Expression::IsBlank(var(0)), Some(Term::Boolean(false))),
+            (Expression::IsLiteral(var(0)), Some(Term::Boolean(true))),
+            (Expression::IsLiteral(var(1)), Some(Term::Boolean(false))),
+            (Expression::IsLiteral(var(3)), None),
+            (
+                Expression::Equal(typed("010", XSD_INTEGER), typed("1.0e1", XSD_DOUBLE)),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::NotEqual(typed("1", XSD_INTEGER), typed("1.00", XSD_DECIMAL)),
+                Some(Term::Boolean(false)),
+            ),
+            (
+                Expression::NotEqual(typed("NaN", XSD_DOUBLE), typed("NaN", XSD_DOUBLE)),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Less(typed("9", XSD_INTEGER), typed("10", XSD_INTEGER)),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Greater(typed("-INF", XSD_DOUBLE), typed("-1.5", XSD_DECIMAL)),
+                Some(Term::Boolean(false)),
+            ),
+            (Expression::Less(typed("1", XSD_INTEGER), string("2")), None),
+            (
+                Expression::GreaterOrEqual(
+                    typed("2024-01-01T12:00:00+02:00", XSD_DATE_TIME),
+                    typed("2024-01-01T10:00:00Z", XSD_DATE_TIME),
+                ),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Equal(
+                    typed("2024-01-01T12:00:00+02:00", XSD_DATE_TIME),
+                    typed("2024-01-01T10:00:00Z", XSD_DATE_TIME),
+                ),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::LessOrEqual(
+                    typed("2024-01-02", XSD_DATE),
+                    typed("2024-01-01", XSD_DATE),
+                ),
+                Some(Term::Boolean(false)),
+            ),
+            (
+                Expression::Less(
+                    typed("2024-01-01", XSD_DATE),
+                    typed("2024-01-02T00:00:00Z", XSD_DATE_TIME),
+                ),
+                None,
+            ),
+            (
+                Expression::Not(typed("0.0", XSD_DECIMAL)),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Not(typed("NaN", XSD_DOUBLE)),
+                Some(Term::Boolean(true)),
+            ),
+            (
+                Expression::Not(typed("-3", XSD_INTEGER)),
+                Some(Term::Boolean(false)),
+            ),
+            (Expression::Not(typed("2024-01-01", XSD_DATE)), None),
+            (
+                Expression::Datatype(typed("1", XSD_DECIMAL)),
+                Some(Term::NamedNode(XSD_DECIMAL.to_string())),
+            ),
+        ];
+
+        let vars = vars();
+        for (expr, expected) in cases {
+            assert_eq!(expr.evaluate(&vars, &mut ns), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn evaluate_error() {
+        let mut vars = ResolvedVariables::with_capacity(1);
+        vars.merge_index(
+            0,
+            ResolvedVariable::Subject(Subject::Named(Node {
+                namespace: 2,
+                value: "bar".to_string(),
+            })),
+        );
+
+        assert_eq!(
+            Expression::IsIri(var(0)).evaluate(&vars, &mut ns),
+            Err(StdError::generic_err("namespace not found"))
+        );
+    }
+
+    #[test]
+    fn terms_from_values() {
+        let cases = vec![
+            (
+                Value::NamedNode("foo".to_string()),
+                Term::NamedNode("foo".to_string()),
+            ),
+            (
+                Value::BlankNode("0".to_string()),
+                Term::BlankNode("0".to_string()),
+            ),
+            (
+                Value::LiteralSimple("foo".to_string()),
+                Term::String("foo".to_string()),
+            ),
+            (
+                Value::LiteralLang("foo".to_string(), "en".to_string()),
+                Term::LangString("foo".to_string(), "en".to_string()),
+            ),
+            (
+                Value::LiteralDatatype("foo".to_string(), XSD_STRING.to_string()),
+                Term::String("foo".to_string()),
+            ),
+            (
+                Value::LiteralDatatype("1".to_string(), XSD_BOOLEAN.to_string()),
+                Term::Boolean(true),
+            ),
+            (
+                Value::LiteralDatatype("false".to_string(), XSD_BOOLEAN.to_string()),
+                Term::Boolean(false),
+            ),
+            (
+                Value::LiteralDatatype("foo".to_string(), XSD_BOOLEAN.to_string()),
+                Term::Typed("foo".to_string(), XSD_BOOLEAN.to_string()),
+            ),
+            (
+                Value::LiteralDatatype("1.5".to_string(), XSD_INTEGER.to_string()),
+                Term::Typed("1.5".to_string(), XSD_INTEGER.to_string()),
+            ),
+            (
+                Value::LiteralDatatype("foo".to_string(), "bar".to_string()),
+                Term::Typed("foo".to_string(), "bar".to_string()),
+            ),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(Term::from(value), expected);
+        }
+    }
+
+    #[test]
+    fn terms_total_cmp() {
+        let typed = |value: &str, datatype: &str| {
+            Term::from(Value::LiteralDatatype(
+                value.to_string(),
+                datatype.to_string(),
+            ))
+        };
+        let ordered = vec![
+            Term::BlankNode("0".to_string()),
+            Term::BlankNode("1".to_string()),
+            Term::NamedNode("http://a".to_string()),
+            Term::NamedNode("http://b".to_string()),
+            typed("NaN", XSD_DOUBLE),
+            typed("-INF", XSD_DOUBLE),
+            typed("-1", XSD_INTEGER),
+            typed("1", XSD_DECIMAL),
+            typed("1", XSD_INTEGER),
+            typed("1.5", XSD_DECIMAL),
+            typed("9", XSD_INTEGER),
+            typed("1E1", XSD_DOUBLE),
+            typed("10", XSD_INTEGER),
+            typed("INF", XSD_DOUBLE),
+            Term::Boolean(false),
+            Term::Boolean(true),
+            typed("2024-01-01T10:00:00Z", XSD_DATE_TIME),
+            typed("2024-01-01T12:00:00+02:00", XSD_DATE_TIME),
+            typed("2024-01-01T11:00:00", XSD_DATE_TIME),
+            typed("2024-01-01", XSD_DATE),
+            typed("2024-01-02", XSD_DATE),
+            Term::String("a".to_string()),
+            Term::LangString("a".to_string(), "en".to_string()),
+            Term::LangString("a".to_string(), "fr".to_string()),
+            Term::String("b".to_string()),
+            typed("b", "http://a"),
+            typed("a", "http://b"),
+        ];
+
+        for (i, l) in ordered.iter().enumerate() {
+            for (j, r) in ordered.iter().enumerate() {
+                assert_eq!(l.total_cmp(r), i.cmp(&j), "{l:?} <=> {r:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn regex_pattern() {
+        assert!(regex("^foo.bar$", Some("s")).is_match("foo\nbar"));
+        assert!(!regex("^foo.bar$", None).is_match("foo\nbar"));
+        assert!(regex("^bar", Some("m")).is_match("foo\nbar"));
+        assert!(regex("f o o", Some("x")).is_match("foo"));
+        assert_eq!(regex("foo", Some("i")), regex("foo", Some("i")));
+        assert_ne!(regex("foo", Some("i")), regex("foo", None));
+
+        assert_eq!(
+            RegexPattern::try_new("foo".to_string(), Some("q".to_string())),
+            Err(StdError::generic_err("Unsupported regex flag: q"))
+        );
+        assert!(RegexPattern::try_new("(foo".to_string(), None).is_err());
+    }
+}
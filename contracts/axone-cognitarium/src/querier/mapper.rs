@@ -1,25 +1,39 @@
 use crate::msg::{Literal, IRI};
+use crate::rdf::XsdValue;
 use crate::state;
 use crate::state::{NamespaceResolver, Object};
 use axone_rdf::uri::{expand_uri, explode_iri};
 use cosmwasm_std::{StdResult, Storage};
 use std::collections::HashMap;
 
+/// Map the literal to its state representation, the lexical form of the typed ones being
+/// canonicalized when strict typing is enabled, as it is the case for the stored literals.
 pub fn literal_as_object(
     ns_resolver: &mut NamespaceResolver,
     storage: &dyn Storage,
     prefixes: &HashMap<String, String>,
     literal: Literal,
+    strict_typing: bool,
 ) -> StdResult<Object> {
     Ok(Object::Literal(match literal {
         Literal::Simple(value) => state::Literal::Simple { value },
         Literal::LanguageTaggedString { value, language } => {
             state::Literal::I18NString { value, language }
         }
-        Literal::TypedValue { value, datatype } => state::Literal::Typed {
-            value,
-            datatype: iri_as_node(ns_resolver, storage, prefixes, datatype)?,
-        },
+        Literal::TypedValue { value, datatype } => {
+            let datatype = match datatype {
+                IRI::Prefixed(prefixed) => expand_uri(&prefixed, prefixes)?,
+                IRI::Full(full) => full,
+            };
+            let value = match XsdValue::parse(&value, &datatype) {
+                Some(Ok(xsd)) if strict_typing => xsd.to_string(),
+                _ => value,
+            };
+            state::Literal::Typed {
+                value,
+                datatype: iri_as_node(ns_resolver, storage, prefixes, IRI::Full(datatype))?,
+            }
+        }
     }))
 }
 
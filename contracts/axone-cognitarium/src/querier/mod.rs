@@ -1,9 +1,16 @@
+mod aggregate;
 mod engine;
+mod estimator;
+mod explain;
+mod expression;
 mod mapper;
 mod plan;
 mod plan_builder;
 mod variable;
 
 pub use engine::*;
+pub use explain::QueryExplainer;
+pub use mapper::{iri_as_node, literal_as_object};
+pub use plan::QueryPlan;
 pub use plan_builder::*;
 pub use variable::ResolvedVariables;
@@ -1,4 +1,7 @@
-use crate::state::{Object, Predicate, Subject};
+use crate::querier::aggregate::Aggregate;
+use crate::querier::expression::Expression;
+use crate::querier::variable::ResolvedVariable;
+use crate::state::{Node, Object, Predicate, Subject};
 use std::collections::BTreeSet;
 
 /// Represents a querying plan.
@@ -48,11 +51,53 @@ pub enum QueryNode {
         object: PatternValue<Object>,
     },
 
+    /// Match the triple pattern against the triples of the named graphs only, the graph name being
+    /// either a variable or a constant value as the triple elements.
+    QuadPattern {
+        subject: PatternValue<Subject>,
+        predicate: PatternValue<Predicate>,
+        object: PatternValue<Object>,
+        graph: PatternValue<Node>,
+    },
+
+    /// Match the property path between the subject and the object against the state, which can be
+    /// either a variable or a constant value, as for a triple pattern. If a graph is provided, the
+    /// path is followed within each of the matching named graphs, as for a quad pattern.
+    ///
+    /// The path is followed by traversing the graph from the bound end, each node being visited
+    /// once per traversal so cycles are safe, and repeated paths are followed at most `max_depth`
+    /// times.
+    Path {
+        subject: PatternValue<Subject>,
+        path: PropertyPath,
+        object: PatternValue<Object>,
+        graph: Option<PatternValue<Node>>,
+        max_depth: usize,
+    },
+
     /// Results in no solutions, this special node is used when we know before plan execution that a node
     /// will end up with no possible solutions. For example, using a triple pattern filtering with a constant
     /// named node containing a non-existing namespace.
     Noop { bound_variables: Vec<usize> },
 
+    /// Results in the given solutions, the rows providing the values of the variables in their
+    /// order, `None` leaving the variable unbound.
+    ///
+    /// Solutions incompatible with the variables provided by the evaluation context are discarded.
+    Values {
+        variables: Vec<usize>,
+        rows: Vec<Vec<Option<ResolvedVariable>>>,
+    },
+
+    /// Results in the solutions of the plan of a sub query, evaluated independently of the
+    /// evaluation context, the bindings mapping its variables to the ones of the enclosing plan.
+    ///
+    /// Solutions incompatible with the variables provided by the evaluation context are discarded.
+    SubQuery {
+        plan: Box<QueryPlan>,
+        bindings: Vec<(usize, usize)>,
+    },
+
     /// Join two nodes by applying the cartesian product of the nodes variables.
     ///
     /// This should be used when the nodes don't have variables in common, and can be seen as a
@@ -66,6 +111,69 @@ pub enum QueryNode {
     /// left node to use them as right node values.
     ForLoopJoin { left: Box<Self>, right: Box<Self> },
 
+    /// Join two nodes by using the variables values from the left node as replacement in the right
+    /// node, keeping the left values when the right node has no solutions.
+    ///
+    /// This results to a left join, the variables only bound by the right node being left unbound
+    /// when it doesn't match.
+    Optional { left: Box<Self>, right: Box<Self> },
+
+    /// Remove the solutions of the left node compatible with a solution of the right node, the
+    /// right node being evaluated with the variables values from the left node it shares.
+    ///
+    /// This results to an anti join, the left solutions not sharing any bound variable with the
+    /// right node being kept.
+    Minus { left: Box<Self>, right: Box<Self> },
+
+    /// Keep the solutions of the left node for which the right node has no solutions, the right
+    /// node being evaluated with the variables values from the left node.
+    NotExists { left: Box<Self>, right: Box<Self> },
+
+    /// Concatenate the solutions of the two nodes, the left node solutions coming first.
+    ///
+    /// This results to a union, the variables only bound by one of the nodes being left unbound
+    /// in the solutions of the other.
+    Union { left: Box<Self>, right: Box<Self> },
+
+    /// Filter the solutions of the inner node by evaluating the expression against them, only the
+    /// ones evaluating to `true` are kept.
+    Filter { expr: Expression, inner: Box<Self> },
+
+    /// Bind the value of the expression evaluated against each solution of the child node to the
+    /// variable, which is left unbound if the expression can't be evaluated.
+    Bind {
+        child: Box<Self>,
+        expr: Expression,
+        variable: usize,
+    },
+
+    /// Group the solutions of the child node by the values of the key variables, and compute the
+    /// aggregates over each group, binding them to their variables. The other variables are left
+    /// unbound, and without keys all the solutions form a single group, even if there are none.
+    ///
+    /// All the solutions of the child node are evaluated before the first group is returned.
+    Group {
+        child: Box<Self>,
+        keys: Vec<usize>,
+        aggregates: Vec<(usize, Aggregate)>,
+    },
+
+    /// Sort the solutions of the child node according to the conditions, the first one taking
+    /// precedence over the next ones.
+    ///
+    /// All the solutions of the child node are evaluated before the first one is returned.
+    OrderBy {
+        child: Box<Self>,
+        conditions: Vec<OrderCondition>,
+    },
+
+    /// Remove the duplicated solutions of the child node, two solutions being duplicates if they
+    /// bind the same values to the specified variables. The first of the duplicates is kept.
+    Distinct {
+        child: Box<Self>,
+        variables: Vec<usize>,
+    },
+
     /// Skip the specified first elements from the child node.
     Skip { child: Box<Self>, first: usize },
 
@@ -93,17 +201,99 @@ impl QueryNode {
                 predicate.lookup_bound_variable(callback);
                 object.lookup_bound_variable(callback);
             }
-            QueryNode::Noop { bound_variables } => {
+            QueryNode::QuadPattern {
+                subject,
+                predicate,
+                object,
+                graph,
+            } => {
+                subject.lookup_bound_variable(callback);
+                predicate.lookup_bound_variable(callback);
+                object.lookup_bound_variable(callback);
+                graph.lookup_bound_variable(callback);
+            }
+            QueryNode::Path {
+                subject,
+                object,
+                graph,
+                ..
+            } => {
+                subject.lookup_bound_variable(callback);
+                object.lookup_bound_variable(callback);
+                if let Some(graph) = graph {
+                    graph.lookup_bound_variable(callback);
+                }
+            }
+            QueryNode::Noop { bound_variables }
+            | QueryNode::Values {
+                variables: bound_variables,
+                ..
+            } => {
                 bound_variables.iter().for_each(|v| callback(*v));
             }
+            QueryNode::SubQuery { bindings, .. } => {
+                bindings.iter().for_each(|(_, v)| callback(*v));
+            }
             QueryNode::CartesianProductJoin { left, right }
-            | QueryNode::ForLoopJoin { left, right } => {
+            | QueryNode::ForLoopJoin { left, right }
+            | QueryNode::Optional { left, right }
+            | QueryNode::Union { left, right } => {
                 left.lookup_bound_variables(callback);
                 right.lookup_bound_variables(callback);
             }
-            QueryNode::Skip { child, .. } | QueryNode::Limit { child, .. } => {
+            QueryNode::Filter { inner, .. } => {
+                inner.lookup_bound_variables(callback);
+            }
+            QueryNode::Minus { left, .. } | QueryNode::NotExists { left, .. } => {
+                left.lookup_bound_variables(callback);
+            }
+            QueryNode::Bind {
+                child, variable, ..
+            } => {
                 child.lookup_bound_variables(callback);
+                callback(*variable);
+            }
+            QueryNode::Group {
+                keys, aggregates, ..
+            } => {
+                keys.iter().for_each(|v| callback(*v));
+                aggregates.iter().for_each(|(v, _)| callback(*v));
             }
+            QueryNode::OrderBy { child, .. }
+            | QueryNode::Distinct { child, .. }
+            | QueryNode::Skip { child, .. }
+            | QueryNode::Limit { child, .. } => {
+                child.lookup_bound_variables(callback);
+            }
+        }
+    }
+}
+
+/// Represents a property path, with its predicates resolved against the state.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum PropertyPath {
+    /// A path of length one through the predicate, `None` if the predicate can't exist in the
+    /// state in which case it matches nothing.
+    Predicate(Option<Predicate>),
+    Inverse(Box<Self>),
+    Sequence(Vec<Self>),
+    Alternative(Vec<Self>),
+    ZeroOrMore(Box<Self>),
+    OneOrMore(Box<Self>),
+    ZeroOrOne(Box<Self>),
+}
+
+/// Represents a condition to sort solutions by, referencing the variable by its index.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum OrderCondition {
+    Asc(usize),
+    Desc(usize),
+}
+
+impl OrderCondition {
+    pub fn variable(&self) -> usize {
+        match self {
+            OrderCondition::Asc(v) | OrderCondition::Desc(v) => *v,
         }
     }
 }
@@ -173,6 +363,115 @@ mod tests {
                 },
                 BTreeSet::from([0usize, 1usize, 2usize, 3usize, 4usize, 5usize]),
             ),
+            (
+                QueryNode::Distinct {
+                    variables: vec![0usize],
+                    child: Box::new(QueryNode::OrderBy {
+                        conditions: vec![OrderCondition::Desc(1usize)],
+                        child: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0usize),
+                            predicate: PatternValue::Variable(1usize),
+                            object: PatternValue::Variable(2usize),
+                        }),
+                    }),
+                },
+                BTreeSet::from([0usize, 1usize, 2usize]),
+            ),
+            (
+                QueryNode::Optional {
+                    left: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0usize),
+                        predicate: PatternValue::Variable(1usize),
+                        object: PatternValue::Variable(2usize),
+                    }),
+                    right: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0usize),
+                        predicate: PatternValue::Variable(3usize),
+                        object: PatternValue::BlankVariable(4usize),
+                    }),
+                },
+                BTreeSet::from([0usize, 1usize, 2usize, 3usize, 4usize]),
+            ),
+            (
+                QueryNode::Union {
+                    left: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0usize),
+                        predicate: PatternValue::Variable(1usize),
+                        object: PatternValue::Variable(2usize),
+                    }),
+                    right: Box::new(QueryNode::Noop {
+                        bound_variables: vec![0usize, 3usize],
+                    }),
+                },
+                BTreeSet::from([0usize, 1usize, 2usize, 3usize]),
+            ),
+            (
+                QueryNode::Group {
+                    child: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0usize),
+                        predicate: PatternValue::Variable(1usize),
+                        object: PatternValue::Variable(2usize),
+                    }),
+                    keys: vec![0usize],
+                    aggregates: vec![(
+                        3usize,
+                        Aggregate::Count {
+                            expr: None,
+                            distinct: false,
+                        },
+                    )],
+                },
+                BTreeSet::from([0usize, 3usize]),
+            ),
+            (
+                QueryNode::Path {
+                    subject: PatternValue::Variable(0usize),
+                    path: PropertyPath::OneOrMore(Box::new(PropertyPath::Predicate(None))),
+                    object: PatternValue::BlankVariable(1usize),
+                    graph: None,
+                    max_depth: 10usize,
+                },
+                BTreeSet::from([0usize, 1usize]),
+            ),
+            (
+                QueryNode::Path {
+                    subject: PatternValue::Variable(0usize),
+                    path: PropertyPath::OneOrMore(Box::new(PropertyPath::Predicate(None))),
+                    object: PatternValue::Variable(1usize),
+                    graph: Some(PatternValue::Variable(2usize)),
+                    max_depth: 10usize,
+                },
+                BTreeSet::from([0usize, 1usize, 2usize]),
+            ),
+            (
+                QueryNode::Bind {
+                    child: Box::new(QueryNode::Values {
+                        variables: vec![0usize, 2usize],
+                        rows: vec![vec![None, None]],
+                    }),
+                    expr: Expression::Variable(0usize),
+                    variable: 1usize,
+                },
+                BTreeSet::from([0usize, 1usize, 2usize]),
+            ),
+            (
+                QueryNode::Minus {
+                    left: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0usize),
+                        predicate: PatternValue::Variable(1usize),
+                        object: PatternValue::Variable(2usize),
+                    }),
+                    right: Box::new(QueryNode::NotExists {
+                        left: Box::new(QueryNode::Noop {
+                            bound_variables: vec![0usize, 3usize],
+                        }),
+                        right: Box::new(QueryNode::Noop {
+                            bound_variables: vec![4usize],
+                        }),
+                    }),
+                },
+                BTreeSet::from([0usize, 1usize, 2usize]),
+            ),
         ];
 
         for case in cases {
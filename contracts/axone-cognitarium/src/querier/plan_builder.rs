@@ -1,18 +1,37 @@
+use crate::cursor;
 use crate::msg::{
-    Node, SimpleWhereCondition, TriplePattern, VarOrNamedNode, VarOrNode, VarOrNodeOrLiteral,
-    WhereClause, WhereCondition,
+    self, NamedNodeOrLiteral, Node, SelectItem, SelectQuery, SimpleWhereCondition, TriplePattern,
+    VarOrNamedNode, VarOrNamedNodeOrPath, VarOrNode, VarOrNodeOrLiteral, WhereClause,
+    WhereCondition,
 };
+use crate::querier::aggregate::Aggregate;
+use crate::querier::estimator::CardinalityEstimator;
+use crate::querier::expression::{Expression, RegexPattern};
 use crate::querier::mapper::{iri_as_node, literal_as_object};
-use crate::querier::plan::{PatternValue, PlanVariable, QueryNode, QueryPlan};
+use crate::querier::plan::{
+    OrderCondition, PatternValue, PlanVariable, PropertyPath, QueryNode, QueryPlan,
+};
+use crate::querier::variable::ResolvedVariable;
+use crate::rdf;
 use crate::state::{HasCachedNamespaces, Namespace, NamespaceResolver, Object, Predicate, Subject};
-use cosmwasm_std::{StdResult, Storage};
-use std::collections::HashMap;
+use cosmwasm_std::{StdError, StdResult, Storage};
+use std::collections::{BTreeSet, HashMap};
+use std::mem;
 
 pub struct PlanBuilder<'a> {
     storage: &'a dyn Storage,
     ns_resolver: NamespaceResolver,
     prefixes: &'a HashMap<String, String>,
     variables: Vec<PlanVariable>,
+    max_path_depth: usize,
+    strict_typing: bool,
+    graph: Option<PatternValue<Predicate>>,
+    estimator: Option<CardinalityEstimator<'a>>,
+    group_by: Vec<String>,
+    aggregates: Vec<(String, msg::Aggregate)>,
+    having: Option<msg::Expression>,
+    order_by: Vec<msg::OrderCondition>,
+    distinct: Option<Vec<String>>,
     limit: Option<usize>,
     skip: Option<usize>,
 }
@@ -28,33 +47,133 @@ impl<'a> PlanBuilder<'a> {
             ns_resolver: ns_cache.map_or_else(NamespaceResolver::new, Into::into),
             prefixes,
             variables: Vec::new(),
+            max_path_depth: usize::MAX,
+            strict_typing: false,
+            graph: None,
+            estimator: None,
+            group_by: Vec::new(),
+            aggregates: Vec::new(),
+            having: None,
+            order_by: Vec::new(),
+            distinct: None,
             skip: None,
             limit: None,
         }
     }
 
+    /// Bound the number of times the repeated property paths are followed, which is unbounded if
+    /// not set.
+    pub fn with_max_path_depth(mut self, depth: usize) -> Self {
+        self.max_path_depth = depth;
+        self
+    }
+
+    /// Canonicalize the lexical form of the typed literal constants, to match the ones of a store
+    /// with strict typing enabled.
+    pub fn with_strict_typing(mut self, strict_typing: bool) -> Self {
+        self.strict_typing = strict_typing;
+        self
+    }
+
+    /// Group the solutions by the values of the given variables, computing the aggregates over
+    /// each group and filtering them with the `having` expression.
+    ///
+    /// Without variables to group by, all the solutions form a single group as soon as there are
+    /// aggregates or a `having` expression.
+    pub fn with_grouping(
+        mut self,
+        group_by: Vec<String>,
+        aggregates: Vec<(String, msg::Aggregate)>,
+        having: Option<msg::Expression>,
+    ) -> Self {
+        self.group_by = group_by;
+        self.aggregates = aggregates;
+        self.having = having;
+        self
+    }
+
+    pub fn with_order_by(mut self, conditions: Vec<msg::OrderCondition>) -> Self {
+        self.order_by = conditions;
+        self
+    }
+
+    /// Remove the duplicated solutions, considering only the values of the given variables.
+    pub fn with_distinct(mut self, variables: Vec<String>) -> Self {
+        self.distinct = Some(variables);
+        self
+    }
+
     pub fn with_limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
         self
     }
 
-    #[allow(dead_code)]
     pub fn with_skip(mut self, skip: usize) -> Self {
         self.skip = Some(skip);
         self
     }
 
-    pub fn build_plan(&mut self, where_clause: &WhereClause) -> StdResult<QueryPlan> {
-        let bgp: Vec<QueryNode> = where_clause
+    /// Extract the aggregates of the selected items, ensuring the other selected variables are
+    /// grouped when grouping.
+    pub fn select_aggregates(query: &SelectQuery) -> StdResult<Vec<(String, msg::Aggregate)>> {
+        let aggregates: Vec<_> = query
+            .select
             .iter()
-            .map(|cond| {
-                let WhereCondition::Simple(SimpleWhereCondition::TriplePattern(pattern)) = cond;
-                self.build_triple_pattern(pattern)
+            .filter_map(|item| match item {
+                SelectItem::Aggregate {
+                    aggregate,
+                    variable,
+                } => Some((variable.clone(), aggregate.clone())),
+                SelectItem::Variable(_) => None,
             })
-            .collect::<StdResult<Vec<QueryNode>>>()?;
+            .collect();
+        if (!query.group_by.is_empty() || !aggregates.is_empty() || query.having.is_some())
+            && query
+                .select
+                .iter()
+                .any(|item| matches!(item, SelectItem::Variable(v) if !query.group_by.contains(v)))
+        {
+            Err(StdError::generic_err(
+                "Selected variable must be grouped when grouping",
+            ))?;
+        }
+        Ok(aggregates)
+    }
 
-        let mut node = Self::build_from_bgp(bgp);
+    pub fn build_plan(&mut self, where_clause: &WhereClause) -> StdResult<QueryPlan> {
+        let mut node = self.build_from_where_clause(where_clause)?;
 
+        if !self.group_by.is_empty() || !self.aggregates.is_empty() || self.having.is_some() {
+            node = self.build_group(node)?;
+        }
+        if !self.order_by.is_empty() {
+            let conditions = self
+                .order_by
+                .iter()
+                .map(|condition| match condition {
+                    msg::OrderCondition::Asc(name) => self
+                        .get_var_index(name, "Order variable not found in query")
+                        .map(OrderCondition::Asc),
+                    msg::OrderCondition::Desc(name) => self
+                        .get_var_index(name, "Order variable not found in query")
+                        .map(OrderCondition::Desc),
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            node = QueryNode::OrderBy {
+                child: Box::new(node),
+                conditions,
+            }
+        }
+        if let Some(variables) = &self.distinct {
+            let variables = variables
+                .iter()
+                .map(|name| self.get_var_index(name, "Selected variable not found in query"))
+                .collect::<StdResult<Vec<_>>>()?;
+            node = QueryNode::Distinct {
+                child: Box::new(node),
+                variables,
+            }
+        }
         if let Some(skip) = self.skip {
             node = QueryNode::Skip {
                 child: Box::new(node),
@@ -73,8 +192,207 @@ impl<'a> PlanBuilder<'a> {
         })
     }
 
-    fn build_from_bgp(bgp: Vec<QueryNode>) -> QueryNode {
-        bgp.into_iter()
+    fn build_group(&mut self, child: QueryNode) -> StdResult<QueryNode> {
+        let keys = self
+            .group_by
+            .iter()
+            .map(|name| self.get_var_index(name, "Group variable not found in query"))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut aggregates = Vec::with_capacity(self.aggregates.len());
+        for (name, aggregate) in self.aggregates.clone() {
+            let aggregate = self.build_aggregate(&aggregate)?;
+            if self
+                .variables
+                .iter()
+                .any(|var| matches!(var, PlanVariable::Basic(v) if v == &name))
+            {
+                Err(StdError::generic_err(
+                    "Aggregate variable already used in query",
+                ))?;
+            }
+
+            self.variables.push(PlanVariable::Basic(name));
+            aggregates.push((self.variables.len() - 1, aggregate));
+        }
+
+        let mut node = QueryNode::Group {
+            child: Box::new(child),
+            keys,
+            aggregates,
+        };
+        if let Some(having) = self.having.clone() {
+            node = QueryNode::Filter {
+                expr: self.build_expression(&having)?,
+                inner: Box::new(node),
+            };
+        }
+        Ok(node)
+    }
+
+    fn build_aggregate(&mut self, aggregate: &msg::Aggregate) -> StdResult<Aggregate> {
+        Ok(match aggregate {
+            msg::Aggregate::Count {
+                expression,
+                distinct,
+            } => Aggregate::Count {
+                expr: expression
+                    .as_ref()
+                    .map(|expr| self.build_expression(expr))
+                    .transpose()?,
+                distinct: *distinct,
+            },
+            msg::Aggregate::Sum {
+                expression,
+                distinct,
+            } => Aggregate::Sum {
+                expr: self.build_expression(expression)?,
+                distinct: *distinct,
+            },
+            msg::Aggregate::Avg {
+                expression,
+                distinct,
+            } => Aggregate::Avg {
+                expr: self.build_expression(expression)?,
+                distinct: *distinct,
+            },
+            msg::Aggregate::Min(expr) => Aggregate::Min(self.build_expression(expr)?),
+            msg::Aggregate::Max(expr) => Aggregate::Max(self.build_expression(expr)?),
+            msg::Aggregate::Sample(expr) => Aggregate::Sample(self.build_expression(expr)?),
+            msg::Aggregate::GroupConcat {
+                expression,
+                distinct,
+                separator,
+            } => Aggregate::GroupConcat {
+                expr: self.build_expression(expression)?,
+                distinct: *distinct,
+                separator: separator.clone().unwrap_or_else(|| " ".to_string()),
+            },
+        })
+    }
+
+    fn build_from_where_clause(&mut self, where_clause: &WhereClause) -> StdResult<QueryNode> {
+        let mut bgp: Vec<QueryNode> = Vec::with_capacity(where_clause.len());
+        let mut filters: Vec<Expression> = Vec::new();
+        let mut not_exists: Vec<QueryNode> = Vec::new();
+        for cond in where_clause {
+            match cond {
+                WhereCondition::Simple(SimpleWhereCondition::TriplePattern(pattern)) => {
+                    bgp.push(self.build_triple_pattern(pattern)?);
+                }
+                WhereCondition::Optional(clause) => {
+                    if bgp.is_empty() {
+                        Err(StdError::generic_err(
+                            "Optional condition must be preceded by at least one condition",
+                        ))?;
+                    }
+
+                    let left = self.build_from_bgp(mem::take(&mut bgp))?;
+                    let right = self.build_from_where_clause(clause)?;
+                    bgp.push(QueryNode::Optional {
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    });
+                }
+                WhereCondition::Union(clauses) => {
+                    if clauses.len() < 2 {
+                        Err(StdError::generic_err(
+                            "Union condition must contain at least two groups",
+                        ))?;
+                    }
+
+                    let mut branches = Vec::with_capacity(clauses.len());
+                    for clause in clauses {
+                        branches.push(self.build_from_where_clause(clause)?);
+                    }
+                    if let Some(union) =
+                        branches.into_iter().reduce(|left, right| QueryNode::Union {
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        })
+                    {
+                        bgp.push(union);
+                    }
+                }
+                WhereCondition::Graph { graph, r#where } => {
+                    bgp.push(self.build_graph(graph, r#where)?);
+                }
+                WhereCondition::Minus(clause) => {
+                    if bgp.is_empty() {
+                        Err(StdError::generic_err(
+                            "Minus condition must be preceded by at least one condition",
+                        ))?;
+                    }
+
+                    let left = self.build_from_bgp(mem::take(&mut bgp))?;
+                    let right = self.build_from_where_clause(clause)?;
+                    bgp.push(QueryNode::Minus {
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    });
+                }
+                WhereCondition::Filter(expr) => {
+                    filters.push(self.build_expression(expr)?);
+                }
+                WhereCondition::NotExists(clause) => {
+                    not_exists.push(self.build_from_where_clause(clause)?);
+                }
+                // Being evaluated independently, the values and the sub queries drive the
+                // evaluation of the conditions they're joined with.
+                WhereCondition::Values { variables, values } => {
+                    bgp.insert(0, self.build_values(variables, values)?);
+                }
+                WhereCondition::SubQuery(query) => {
+                    bgp.insert(0, self.build_sub_query(query)?);
+                }
+                WhereCondition::Bind {
+                    expression,
+                    variable,
+                } => {
+                    let child = if bgp.is_empty() {
+                        QueryNode::Values {
+                            variables: vec![],
+                            rows: vec![vec![]],
+                        }
+                    } else {
+                        self.build_from_bgp(mem::take(&mut bgp))?
+                    };
+
+                    let variable = self.resolve_basic_variable(variable.clone());
+                    if child.bound_variables().contains(&variable) {
+                        Err(StdError::generic_err(
+                            "Bind variable already used by the preceding conditions",
+                        ))?;
+                    }
+
+                    bgp.push(QueryNode::Bind {
+                        child: Box::new(child),
+                        expr: self.build_expression(expression)?,
+                        variable,
+                    });
+                }
+            }
+        }
+
+        // The negations are applied last, so they're checked against the filtered solutions only.
+        let node = filters
+            .into_iter()
+            .fold(self.build_from_bgp(bgp)?, |node, expr| QueryNode::Filter {
+                expr,
+                inner: Box::new(node),
+            });
+        Ok(not_exists
+            .into_iter()
+            .fold(node, |left, right| QueryNode::NotExists {
+                left: Box::new(left),
+                right: Box::new(right),
+            }))
+    }
+
+    fn build_from_bgp(&mut self, bgp: Vec<QueryNode>) -> StdResult<QueryNode> {
+        Ok(self
+            .reorder_bgp(bgp)?
+            .into_iter()
             .reduce(|left: QueryNode, right: QueryNode| -> QueryNode {
                 if left
                     .bound_variables()
@@ -94,12 +412,222 @@ impl<'a> PlanBuilder<'a> {
             })
             .unwrap_or(QueryNode::Noop {
                 bound_variables: vec![],
-            })
+            }))
+    }
+
+    /// Reorder each run of consecutive patterns of the basic graph pattern, so the ones with the
+    /// lowest estimated cost given the variables bound by the nodes before them are evaluated
+    /// first, the ones sharing a variable with them being preferred to avoid cartesian products.
+    /// The other nodes are kept in place, as well as the patterns of equal cost.
+    fn reorder_bgp(&mut self, bgp: Vec<QueryNode>) -> StdResult<Vec<QueryNode>> {
+        let mut ordered = Vec::with_capacity(bgp.len());
+        let mut bound = BTreeSet::new();
+        let mut run = Vec::new();
+        for node in bgp {
+            if matches!(
+                node,
+                QueryNode::TriplePattern { .. }
+                    | QueryNode::QuadPattern { .. }
+                    | QueryNode::Path { .. }
+            ) {
+                run.push(node);
+                continue;
+            }
+
+            self.reorder_patterns(mem::take(&mut run), &mut bound, &mut ordered)?;
+            bound.extend(node.bound_variables());
+            ordered.push(node);
+        }
+        self.reorder_patterns(run, &mut bound, &mut ordered)?;
+
+        Ok(ordered)
+    }
+
+    fn reorder_patterns(
+        &mut self,
+        mut patterns: Vec<QueryNode>,
+        bound: &mut BTreeSet<usize>,
+        ordered: &mut Vec<QueryNode>,
+    ) -> StdResult<()> {
+        if patterns.len() < 2 {
+            patterns.into_iter().for_each(|node| {
+                bound.extend(node.bound_variables());
+                ordered.push(node);
+            });
+            return Ok(());
+        }
+
+        let estimator = match &mut self.estimator {
+            Some(estimator) => estimator,
+            estimator => estimator.insert(CardinalityEstimator::new(self.storage)?),
+        };
+        while !patterns.is_empty() {
+            let mut best = None;
+            for (i, node) in patterns.iter().enumerate() {
+                let disjoint = !bound.is_empty() && node.bound_variables().is_disjoint(bound);
+                let candidate = (disjoint, estimator.estimate_cost(node, bound)?, i);
+                if best.as_ref().map_or(true, |best| &candidate < best) {
+                    best = Some(candidate);
+                }
+            }
+
+            let node = patterns.remove(best.map_or(0, |(_, _, i)| i));
+            bound.extend(node.bound_variables());
+            ordered.push(node);
+        }
+        Ok(())
+    }
+
+    /// Build the conditions matched against the given graph, the enclosing graph being restored
+    /// afterward.
+    fn build_graph(
+        &mut self,
+        graph: &VarOrNamedNode,
+        where_clause: &WhereClause,
+    ) -> StdResult<QueryNode> {
+        let graph_res = self.build_predicate_pattern(graph.clone());
+        let mut bound_variables: Vec<usize> = vec![];
+        let maybe_graph = Self::recover_ns_not_found_pattern_res(graph_res, &mut bound_variables)?;
+
+        let enclosing = self.graph.clone();
+        if maybe_graph.is_some() {
+            self.graph = maybe_graph.clone();
+        }
+        let res = self.build_from_where_clause(where_clause);
+        self.graph = enclosing;
+
+        let node = res?;
+        Ok(match maybe_graph {
+            Some(_) => node,
+            None => QueryNode::Noop {
+                bound_variables: node.bound_variables().into_iter().collect(),
+            },
+        })
+    }
+
+    /// Build the sub query with its own variables scope, only its selected variables being bound
+    /// to the ones of the enclosing query.
+    fn build_sub_query(&mut self, query: &SelectQuery) -> StdResult<QueryNode> {
+        let mut prefixes = self.prefixes.clone();
+        prefixes.extend(rdf::PrefixMap::from(query.prefixes.clone()).into_inner());
+
+        let mut builder = PlanBuilder::new(self.storage, &prefixes, Some(self.cached_namespaces()))
+            .with_max_path_depth(self.max_path_depth)
+            .with_strict_typing(self.strict_typing)
+            .with_grouping(
+                query.group_by.clone(),
+                Self::select_aggregates(query)?,
+                query.having.clone(),
+            )
+            .with_order_by(query.order_by.clone());
+        if query.distinct {
+            builder = builder.with_distinct(
+                query
+                    .select
+                    .iter()
+                    .map(|item| item.variable().to_string())
+                    .collect(),
+            );
+        }
+        if let Some(after) = &query.after {
+            builder = builder.with_skip(cursor::decode(after)?);
+        }
+        if let Some(limit) = query.limit {
+            builder = builder.with_limit(limit as usize);
+        }
+        let plan = builder.build_plan(&query.r#where)?;
+
+        let mut bindings = Vec::with_capacity(query.select.len());
+        for item in &query.select {
+            let inner = plan
+                .get_var_index(item.variable())
+                .ok_or_else(|| StdError::generic_err("Selected variable not found in query"))?;
+            bindings.push((
+                inner,
+                self.resolve_basic_variable(item.variable().to_string()),
+            ));
+        }
+
+        Ok(QueryNode::SubQuery {
+            plan: Box::new(plan),
+            bindings,
+        })
+    }
+
+    fn build_values(
+        &mut self,
+        variables: &[String],
+        values: &[Vec<Option<NamedNodeOrLiteral>>],
+    ) -> StdResult<QueryNode> {
+        let variables: Vec<usize> = variables
+            .iter()
+            .map(|v| self.resolve_basic_variable(v.clone()))
+            .collect();
+
+        let mut rows = Vec::with_capacity(values.len());
+        for row in values {
+            if row.len() != variables.len() {
+                Err(StdError::generic_err(
+                    "Values row must provide a value for each variable",
+                ))?;
+            }
+
+            let mut resolved = Vec::with_capacity(row.len());
+            for value in row {
+                resolved.push(value.as_ref().map(|v| self.build_value(v)).transpose()?);
+            }
+            rows.push(resolved);
+        }
+
+        Ok(QueryNode::Values { variables, rows })
+    }
+
+    /// Resolve the value against the state, it results in a computed value if it can't exist in
+    /// the state.
+    fn build_value(&mut self, value: &NamedNodeOrLiteral) -> StdResult<ResolvedVariable> {
+        let res = match value.clone() {
+            NamedNodeOrLiteral::NamedNode(iri) => {
+                iri_as_node(&mut self.ns_resolver, self.storage, self.prefixes, iri)
+                    .map(Object::Named)
+            }
+            NamedNodeOrLiteral::Literal(literal) => literal_as_object(
+                &mut self.ns_resolver,
+                self.storage,
+                self.prefixes,
+                literal,
+                self.strict_typing,
+            ),
+        };
+
+        match res {
+            Ok(object) => Ok(ResolvedVariable::Object(object)),
+            Err(err) if NamespaceResolver::is_ns_not_found_error(&err) => {
+                let value = match value.clone() {
+                    NamedNodeOrLiteral::NamedNode(iri) => {
+                        rdf::Value::try_from((Node::NamedNode(iri), self.prefixes))
+                    }
+                    NamedNodeOrLiteral::Literal(literal) => {
+                        rdf::Value::try_from((literal, self.prefixes))
+                    }
+                }?;
+                Ok(ResolvedVariable::Computed(value.into()))
+            }
+            Err(err) => Err(err),
+        }
     }
 
     fn build_triple_pattern(&mut self, pattern: &TriplePattern) -> StdResult<QueryNode> {
+        let predicate = match pattern.predicate.clone() {
+            VarOrNamedNodeOrPath::Variable(v) => VarOrNamedNode::Variable(v),
+            VarOrNamedNodeOrPath::NamedNode(iri)
+            | VarOrNamedNodeOrPath::Path(msg::PropertyPath::NamedNode(iri)) => {
+                VarOrNamedNode::NamedNode(iri)
+            }
+            VarOrNamedNodeOrPath::Path(path) => return self.build_path_pattern(pattern, &path),
+        };
+
         let subject_res = self.build_subject_pattern(pattern.subject.clone());
-        let predicate_res = self.build_predicate_pattern(pattern.predicate.clone());
+        let predicate_res = self.build_predicate_pattern(predicate);
         let object_res = self.build_object_pattern(pattern.object.clone());
 
         let mut bound_variables: Vec<usize> = vec![];
@@ -110,16 +638,109 @@ impl<'a> PlanBuilder<'a> {
         let maybe_object =
             Self::recover_ns_not_found_pattern_res(object_res, &mut bound_variables)?;
 
+        if let Some(graph) = &self.graph {
+            graph.lookup_bound_variable(&mut |v| bound_variables.push(v));
+        }
+
         Ok(match (maybe_subject, maybe_predicate, maybe_object) {
-            (Some(subject), Some(predicate), Some(object)) => QueryNode::TriplePattern {
+            (Some(subject), Some(predicate), Some(object)) => match self.graph.clone() {
+                Some(graph) => QueryNode::QuadPattern {
+                    subject,
+                    predicate,
+                    object,
+                    graph,
+                },
+                None => QueryNode::TriplePattern {
+                    subject,
+                    predicate,
+                    object,
+                },
+            },
+            _ => QueryNode::Noop { bound_variables },
+        })
+    }
+
+    fn build_path_pattern(
+        &mut self,
+        pattern: &TriplePattern,
+        path: &msg::PropertyPath,
+    ) -> StdResult<QueryNode> {
+        let subject_res = self.build_subject_pattern(pattern.subject.clone());
+        let path = self.build_property_path(path)?;
+        let object_res = self.build_object_pattern(pattern.object.clone());
+
+        let mut bound_variables: Vec<usize> = vec![];
+        let maybe_subject =
+            Self::recover_ns_not_found_pattern_res(subject_res, &mut bound_variables)?;
+        let maybe_object =
+            Self::recover_ns_not_found_pattern_res(object_res, &mut bound_variables)?;
+
+        if let Some(graph) = &self.graph {
+            graph.lookup_bound_variable(&mut |v| bound_variables.push(v));
+        }
+
+        Ok(match (maybe_subject, maybe_object) {
+            (Some(subject), Some(object)) => QueryNode::Path {
                 subject,
-                predicate,
+                path,
                 object,
+                graph: self.graph.clone(),
+                max_depth: self.max_path_depth,
             },
             _ => QueryNode::Noop { bound_variables },
         })
     }
 
+    fn build_property_path(&mut self, path: &msg::PropertyPath) -> StdResult<PropertyPath> {
+        Ok(match path {
+            msg::PropertyPath::NamedNode(iri) => {
+                match iri_as_node(
+                    &mut self.ns_resolver,
+                    self.storage,
+                    self.prefixes,
+                    iri.clone(),
+                ) {
+                    Ok(predicate) => PropertyPath::Predicate(Some(predicate)),
+                    Err(err) if NamespaceResolver::is_ns_not_found_error(&err) => {
+                        PropertyPath::Predicate(None)
+                    }
+                    Err(err) => Err(err)?,
+                }
+            }
+            msg::PropertyPath::Inverse(path) => {
+                PropertyPath::Inverse(Box::new(self.build_property_path(path)?))
+            }
+            msg::PropertyPath::Sequence(paths) | msg::PropertyPath::Alternative(paths)
+                if paths.is_empty() =>
+            {
+                Err(StdError::generic_err(
+                    "Property path must contain at least one path",
+                ))?
+            }
+            msg::PropertyPath::Sequence(paths) => PropertyPath::Sequence(
+                paths
+                    .iter()
+                    .map(|path| self.build_property_path(path))
+                    .collect::<StdResult<_>>()?,
+            ),
+            msg::PropertyPath::Alternative(paths) => PropertyPath::Alternative(
+                paths
+                    .iter()
+                    .map(|path| self.build_property_path(path))
+                    .collect::<StdResult<_>>()?,
+            ),
+            msg::PropertyPath::ZeroOrMore(path) => {
+                PropertyPath::ZeroOrMore(Box::new(self.build_property_path(path)?))
+            }
+            msg::PropertyPath::OneOrMore(path) => {
+                PropertyPath::OneOrMore(Box::new(self.build_property_path(path)?))
+            }
+            msg::PropertyPath::ZeroOrOne(path) => {
+                PropertyPath::ZeroOrOne(Box::new(self.build_property_path(path)?))
+            }
+        })
+    }
+
     fn recover_ns_not_found_pattern_res<T>(
         pattern_res: StdResult<PatternValue<T>>,
         bound_variables: &mut Vec<usize>,
@@ -185,10 +806,94 @@ impl<'a> PlanBuilder<'a> {
                 self.storage,
                 self.prefixes,
                 l,
+                self.strict_typing,
             )?),
         })
     }
 
+    fn build_expression(&mut self, expr: &msg::Expression) -> StdResult<Expression> {
+        Ok(match expr {
+            msg::Expression::NamedNode(iri) => Expression::Constant(
+                rdf::Value::try_from((Node::NamedNode(iri.clone()), self.prefixes))?.into(),
+            ),
+            msg::Expression::Literal(literal) => {
+                Expression::Constant(rdf::Value::try_from((literal.clone(), self.prefixes))?.into())
+            }
+            msg::Expression::Variable(v) => {
+                Expression::Variable(self.resolve_basic_variable(v.clone()))
+            }
+            msg::Expression::And(exprs) => Expression::And(self.build_expressions(exprs)?),
+            msg::Expression::Or(exprs) => Expression::Or(self.build_expressions(exprs)?),
+            msg::Expression::Not(expr) => Expression::Not(self.build_boxed_expression(expr)?),
+            msg::Expression::Equal(left, right) => Expression::Equal(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::NotEqual(left, right) => Expression::NotEqual(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::Greater(left, right) => Expression::Greater(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::GreaterOrEqual(left, right) => Expression::GreaterOrEqual(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::Less(left, right) => Expression::Less(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::LessOrEqual(left, right) => Expression::LessOrEqual(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::Bound(v) => Expression::Bound(self.resolve_basic_variable(v.clone())),
+            msg::Expression::Regex(expr, pattern, flags) => Expression::Regex(
+                self.build_boxed_expression(expr)?,
+                RegexPattern::try_new(pattern.clone(), flags.clone())?,
+            ),
+            msg::Expression::Contains(left, right) => Expression::Contains(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::StrStarts(left, right) => Expression::StrStarts(
+                self.build_boxed_expression(left)?,
+                self.build_boxed_expression(right)?,
+            ),
+            msg::Expression::Lang(expr) => Expression::Lang(self.build_boxed_expression(expr)?),
+            msg::Expression::Datatype(expr) => {
+                Expression::Datatype(self.build_boxed_expression(expr)?)
+            }
+            msg::Expression::IsIri(expr) => Expression::IsIri(self.build_boxed_expression(expr)?),
+            msg::Expression::IsBlank(expr) => {
+                Expression::IsBlank(self.build_boxed_expression(expr)?)
+            }
+            msg::Expression::IsLiteral(expr) => {
+                Expression::IsLiteral(self.build_boxed_expression(expr)?)
+            }
+        })
+    }
+
+    fn build_boxed_expression(&mut self, expr: &msg::Expression) -> StdResult<Box<Expression>> {
+        self.build_expression(expr).map(Box::new)
+    }
+
+    fn build_expressions(&mut self, exprs: &[msg::Expression]) -> StdResult<Vec<Expression>> {
+        exprs
+            .iter()
+            .map(|expr| self.build_expression(expr))
+            .collect()
+    }
+
+    fn get_var_index(&self, name: &str, not_found_msg: &str) -> StdResult<usize> {
+        self.variables
+            .iter()
+            .position(|var| matches!(var, PlanVariable::Basic(v) if v == name))
+            .ok_or_else(|| StdError::generic_err(not_found_msg))
+    }
+
     fn resolve_basic_variable(&mut self, v: String) -> usize {
         if let Some(index) = self.variables.iter().position(|var| match var {
             PlanVariable::Basic(name) => name == &v,
@@ -228,10 +933,12 @@ impl<'a> HasCachedNamespaces for PlanBuilder<'a> {
 mod test {
     use super::*;
     use crate::msg::{Literal, Node, Prefix, IRI};
+    use crate::querier::expression::Term;
     use crate::rdf::PrefixMap;
     use crate::state;
-    use crate::state::{namespaces, Namespace};
+    use crate::state::{namespaces, Namespace, Store, PREDICATE_STATS, STORE};
     use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{Addr, Uint128};
 
     #[test]
     fn proper_initialization() {
@@ -314,7 +1021,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Variable("s".to_string()),
-                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                     object: VarOrNodeOrLiteral::Variable("o".to_string()),
                 },
                 Ok(QueryNode::TriplePattern {
@@ -326,7 +1033,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Node(Node::BlankNode("1".to_string())),
-                    predicate: VarOrNamedNode::NamedNode(IRI::Full(
+                    predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
                         "http://axone.space/hasTitle".to_string(),
                     )),
                     object: VarOrNodeOrLiteral::Node(Node::BlankNode("2".to_string())),
@@ -345,7 +1052,7 @@ mod test {
                     subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
                         "http://axone.space/123456789".to_string(),
                     ))),
-                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                     object: VarOrNodeOrLiteral::Node(Node::NamedNode(IRI::Full(
                         "http://axone.space/1234567892".to_string(),
                     ))),
@@ -365,7 +1072,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Variable("p".to_string()),
-                    predicate: VarOrNamedNode::Variable("s".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("s".to_string()),
                     object: VarOrNodeOrLiteral::Literal(Literal::Simple("simple".to_string())),
                 },
                 Ok(QueryNode::TriplePattern {
@@ -379,7 +1086,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Variable("s".to_string()),
-                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                     object: VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString {
                         value: "tagged".to_string(),
                         language: "en".to_string(),
@@ -397,7 +1104,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Variable("s".to_string()),
-                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                     object: VarOrNodeOrLiteral::Literal(Literal::TypedValue {
                         value: "typed".to_string(),
                         datatype: IRI::Full("http://axone.space/type".to_string()),
@@ -420,7 +1127,7 @@ mod test {
                     subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
                         "notexisting#outch".to_string(),
                     ))),
-                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                     object: VarOrNodeOrLiteral::Variable("o".to_string()),
                 },
                 Ok(QueryNode::Noop {
@@ -430,7 +1137,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Variable("s".to_string()),
-                    predicate: VarOrNamedNode::NamedNode(IRI::Full(
+                    predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
                         "notexisting#outch".to_string(),
                     )),
                     object: VarOrNodeOrLiteral::Variable("o".to_string()),
@@ -442,7 +1149,7 @@ mod test {
             (
                 TriplePattern {
                     subject: VarOrNode::Variable("s".to_string()),
-                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                     object: VarOrNodeOrLiteral::Node(Node::NamedNode(IRI::Full(
                         "notexisting#outch".to_string(),
                     ))),
@@ -474,66 +1181,187 @@ mod test {
     }
 
     #[test]
-    fn build_plan() {
+    fn build_path_pattern() {
+        let iri = |value: &str| msg::PropertyPath::NamedNode(IRI::Full(value.to_string()));
+        let has_part = || {
+            PropertyPath::Predicate(Some(state::Node {
+                namespace: 0u128,
+                value: "hasPart".to_string(),
+            }))
+        };
         let cases = vec![
             (
-                None,
-                None,
-                vec![],
-                Ok(QueryPlan {
-                    entrypoint: QueryNode::Noop {
-                        bound_variables: vec![],
-                    },
-                    variables: vec![],
+                TriplePattern {
+                    subject: VarOrNode::Variable("s".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Path(iri("http://axone.space/hasPart")),
+                    object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                },
+                Ok(QueryNode::TriplePattern {
+                    subject: PatternValue::Variable(0usize),
+                    predicate: PatternValue::Constant(state::Node {
+                        namespace: 0u128,
+                        value: "hasPart".to_string(),
+                    }),
+                    object: PatternValue::Variable(1usize),
                 }),
             ),
             (
-                None,
-                None,
-                vec![TriplePattern {
-                    subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
-                        "notexisting#outch".to_string(),
+                TriplePattern {
+                    subject: VarOrNode::Variable("s".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Path(msg::PropertyPath::OneOrMore(Box::new(
+                        iri("http://axone.space/hasPart"),
                     ))),
-                    predicate: VarOrNamedNode::Variable("predicate".to_string()),
-                    object: VarOrNodeOrLiteral::Variable("object".to_string()),
-                }],
-                Ok(QueryPlan {
-                    entrypoint: QueryNode::Noop {
-                        bound_variables: vec![0usize, 1usize],
-                    },
-                    variables: vec![
-                        PlanVariable::Basic("predicate".to_string()),
-                        PlanVariable::Basic("object".to_string()),
-                    ],
+                    object: VarOrNodeOrLiteral::Node(Node::BlankNode("o".to_string())),
+                },
+                Ok(QueryNode::Path {
+                    subject: PatternValue::Variable(0usize),
+                    path: PropertyPath::OneOrMore(Box::new(has_part())),
+                    object: PatternValue::BlankVariable(1usize),
+                    graph: None,
+                    max_depth: 5usize,
                 }),
             ),
             (
-                None,
-                None,
-                vec![TriplePattern {
-                    subject: VarOrNode::Variable("subject".to_string()),
-                    predicate: VarOrNamedNode::Variable("predicate".to_string()),
-                    object: VarOrNodeOrLiteral::Variable("object".to_string()),
-                }],
-                Ok(QueryPlan {
-                    entrypoint: QueryNode::TriplePattern {
-                        subject: PatternValue::Variable(0usize),
-                        predicate: PatternValue::Variable(1usize),
-                        object: PatternValue::Variable(2usize),
-                    },
-                    variables: vec![
-                        PlanVariable::Basic("subject".to_string()),
-                        PlanVariable::Basic("predicate".to_string()),
-                        PlanVariable::Basic("object".to_string()),
-                    ],
+                TriplePattern {
+                    subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
+                        "http://axone.space/123456789".to_string(),
+                    ))),
+                    predicate: VarOrNamedNodeOrPath::Path(msg::PropertyPath::Sequence(vec![
+                        msg::PropertyPath::Inverse(Box::new(iri("http://axone.space/hasPart"))),
+                        msg::PropertyPath::Alternative(vec![
+                            msg::PropertyPath::ZeroOrMore(Box::new(iri("notexisting#outch"))),
+                            msg::PropertyPath::ZeroOrOne(Box::new(iri(
+                                "http://axone.space/hasPart",
+                            ))),
+                        ]),
+                    ])),
+                    object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                },
+                Ok(QueryNode::Path {
+                    subject: PatternValue::Constant(Subject::Named(state::Node {
+                        namespace: 0u128,
+                        value: "123456789".to_string(),
+                    })),
+                    path: PropertyPath::Sequence(vec![
+                        PropertyPath::Inverse(Box::new(has_part())),
+                        PropertyPath::Alternative(vec![
+                            PropertyPath::ZeroOrMore(Box::new(PropertyPath::Predicate(None))),
+                            PropertyPath::ZeroOrOne(Box::new(has_part())),
+                        ]),
+                    ]),
+                    object: PatternValue::Variable(0usize),
+                    graph: None,
+                    max_depth: 5usize,
                 }),
             ),
             (
-                Some(20usize),
-                None,
+                TriplePattern {
+                    subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
+                        "notexisting#outch".to_string(),
+                    ))),
+                    predicate: VarOrNamedNodeOrPath::Path(msg::PropertyPath::ZeroOrMore(Box::new(
+                        iri("http://axone.space/hasPart"),
+                    ))),
+                    object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                },
+                Ok(QueryNode::Noop {
+                    bound_variables: vec![0usize],
+                }),
+            ),
+            (
+                TriplePattern {
+                    subject: VarOrNode::Variable("s".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Path(msg::PropertyPath::Alternative(vec![])),
+                    object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                },
+                Err(StdError::generic_err(
+                    "Property path must contain at least one path",
+                )),
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+        namespaces()
+            .save(
+                deps.as_mut().storage,
+                "http://axone.space/".to_string(),
+                &Namespace {
+                    value: "http://axone.space/".to_string(),
+                    key: 0u128,
+                    counter: 1u128,
+                },
+            )
+            .unwrap();
+        for (pattern, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder =
+                PlanBuilder::new(&deps.storage, prefixes, None).with_max_path_depth(5usize);
+
+            assert_eq!(builder.build_triple_pattern(&pattern), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan() {
+        let cases = vec![
+            (
+                None,
+                None,
+                vec![],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Noop {
+                        bound_variables: vec![],
+                    },
+                    variables: vec![],
+                }),
+            ),
+            (
+                None,
+                None,
+                vec![TriplePattern {
+                    subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
+                        "notexisting#outch".to_string(),
+                    ))),
+                    predicate: VarOrNamedNodeOrPath::Variable("predicate".to_string()),
+                    object: VarOrNodeOrLiteral::Variable("object".to_string()),
+                }],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Noop {
+                        bound_variables: vec![0usize, 1usize],
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("predicate".to_string()),
+                        PlanVariable::Basic("object".to_string()),
+                    ],
+                }),
+            ),
+            (
+                None,
+                None,
+                vec![TriplePattern {
+                    subject: VarOrNode::Variable("subject".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("predicate".to_string()),
+                    object: VarOrNodeOrLiteral::Variable("object".to_string()),
+                }],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0usize),
+                        predicate: PatternValue::Variable(1usize),
+                        object: PatternValue::Variable(2usize),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("subject".to_string()),
+                        PlanVariable::Basic("predicate".to_string()),
+                        PlanVariable::Basic("object".to_string()),
+                    ],
+                }),
+            ),
+            (
+                Some(20usize),
+                None,
                 vec![TriplePattern {
                     subject: VarOrNode::Variable("subject".to_string()),
-                    predicate: VarOrNamedNode::Variable("predicate".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("predicate".to_string()),
                     object: VarOrNodeOrLiteral::Variable("object".to_string()),
                 }],
                 Ok(QueryPlan {
@@ -557,7 +1385,7 @@ mod test {
                 Some(20usize),
                 vec![TriplePattern {
                     subject: VarOrNode::Variable("subject".to_string()),
-                    predicate: VarOrNamedNode::Variable("predicate".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("predicate".to_string()),
                     object: VarOrNodeOrLiteral::Variable("object".to_string()),
                 }],
                 Ok(QueryPlan {
@@ -581,7 +1409,7 @@ mod test {
                 Some(50usize),
                 vec![TriplePattern {
                     subject: VarOrNode::Variable("subject".to_string()),
-                    predicate: VarOrNamedNode::Variable("predicate".to_string()),
+                    predicate: VarOrNamedNodeOrPath::Variable("predicate".to_string()),
                     object: VarOrNodeOrLiteral::Variable("object".to_string()),
                 }],
                 Ok(QueryPlan {
@@ -609,38 +1437,39 @@ mod test {
                 vec![
                     TriplePattern {
                         subject: VarOrNode::Variable("var1".to_string()),
-                        predicate: VarOrNamedNode::Variable("var2".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("var2".to_string()),
                         object: VarOrNodeOrLiteral::Variable("var3".to_string()),
                     },
                     TriplePattern {
                         subject: VarOrNode::Variable("var4".to_string()),
-                        predicate: VarOrNamedNode::Variable("var5".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("var5".to_string()),
                         object: VarOrNodeOrLiteral::Variable("var6".to_string()),
                     },
                     TriplePattern {
                         subject: VarOrNode::Variable("var1".to_string()),
-                        predicate: VarOrNamedNode::Variable("var5".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("var5".to_string()),
                         object: VarOrNodeOrLiteral::Node(Node::BlankNode("blank".to_string())),
                     },
                 ],
+                // The patterns sharing variables are joined first.
                 Ok(QueryPlan {
                     entrypoint: QueryNode::ForLoopJoin {
-                        left: Box::new(QueryNode::CartesianProductJoin {
+                        left: Box::new(QueryNode::ForLoopJoin {
                             left: Box::new(QueryNode::TriplePattern {
                                 subject: PatternValue::Variable(0usize),
                                 predicate: PatternValue::Variable(1usize),
                                 object: PatternValue::Variable(2usize),
                             }),
                             right: Box::new(QueryNode::TriplePattern {
-                                subject: PatternValue::Variable(3usize),
+                                subject: PatternValue::Variable(0usize),
                                 predicate: PatternValue::Variable(4usize),
-                                object: PatternValue::Variable(5usize),
+                                object: PatternValue::BlankVariable(6usize),
                             }),
                         }),
                         right: Box::new(QueryNode::TriplePattern {
-                            subject: PatternValue::Variable(0usize),
+                            subject: PatternValue::Variable(3usize),
                             predicate: PatternValue::Variable(4usize),
-                            object: PatternValue::BlankVariable(6usize),
+                            object: PatternValue::Variable(5usize),
                         }),
                     },
                     variables: vec![
@@ -660,12 +1489,12 @@ mod test {
                 vec![
                     TriplePattern {
                         subject: VarOrNode::Node(Node::BlankNode("1".to_string())),
-                        predicate: VarOrNamedNode::Variable("1".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("1".to_string()),
                         object: VarOrNodeOrLiteral::Node(Node::BlankNode("2".to_string())),
                     },
                     TriplePattern {
                         subject: VarOrNode::Node(Node::BlankNode("1".to_string())),
-                        predicate: VarOrNamedNode::Variable("1".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("1".to_string()),
                         object: VarOrNodeOrLiteral::Variable("2".to_string()),
                     },
                 ],
@@ -728,4 +1557,1045 @@ mod test {
             )
         }
     }
+
+    #[test]
+    fn build_plan_optional() {
+        let cases = vec![
+            (
+                vec![WhereCondition::Optional(vec![WhereCondition::Simple(
+                    SimpleWhereCondition::TriplePattern(TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                    }),
+                )])],
+                Err(StdError::generic_err(
+                    "Optional condition must be preceded by at least one condition",
+                )),
+            ),
+            (
+                vec![
+                    WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                            "http://axone.space/hasTitle".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Variable("title".to_string()),
+                    })),
+                    WhereCondition::Optional(vec![WhereCondition::Simple(
+                        SimpleWhereCondition::TriplePattern(TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                "http://axone.space/hasLicense".to_string(),
+                            )),
+                            object: VarOrNodeOrLiteral::Variable("license".to_string()),
+                        }),
+                    )]),
+                    WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                    })),
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::Optional {
+                            left: Box::new(QueryNode::TriplePattern {
+                                subject: PatternValue::Variable(0usize),
+                                predicate: PatternValue::Constant(state::Node {
+                                    namespace: 0u128,
+                                    value: "hasTitle".to_string(),
+                                }),
+                                object: PatternValue::Variable(1usize),
+                            }),
+                            right: Box::new(QueryNode::TriplePattern {
+                                subject: PatternValue::Variable(0usize),
+                                predicate: PatternValue::Constant(state::Node {
+                                    namespace: 0u128,
+                                    value: "hasLicense".to_string(),
+                                }),
+                                object: PatternValue::Variable(2usize),
+                            }),
+                        }),
+                        right: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0usize),
+                            predicate: PatternValue::Variable(3usize),
+                            object: PatternValue::Variable(4usize),
+                        }),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("title".to_string()),
+                        PlanVariable::Basic("license".to_string()),
+                        PlanVariable::Basic("p".to_string()),
+                        PlanVariable::Basic("o".to_string()),
+                    ],
+                }),
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+        namespaces()
+            .save(
+                deps.as_mut().storage,
+                "http://axone.space/".to_string(),
+                &Namespace {
+                    value: "http://axone.space/".to_string(),
+                    key: 0u128,
+                    counter: 1u128,
+                },
+            )
+            .unwrap();
+
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_values_bind() {
+        let pattern = |s: &str, p: &str, o: &str| {
+            WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::Variable(p.to_string()),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let spo = QueryNode::TriplePattern {
+            subject: PatternValue::Variable(0usize),
+            predicate: PatternValue::Variable(1usize),
+            object: PatternValue::Variable(2usize),
+        };
+        let cases = vec![
+            (
+                vec![
+                    pattern("s", "p", "o"),
+                    WhereCondition::Values {
+                        variables: vec!["s".to_string()],
+                        values: vec![
+                            vec![Some(NamedNodeOrLiteral::NamedNode(IRI::Full(
+                                "http://axone.space/a".to_string(),
+                            )))],
+                            vec![Some(NamedNodeOrLiteral::NamedNode(IRI::Full(
+                                "http://unknown.org/b".to_string(),
+                            )))],
+                            vec![None],
+                        ],
+                    },
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::Values {
+                            variables: vec![0usize],
+                            rows: vec![
+                                vec![Some(ResolvedVariable::Object(Object::Named(state::Node {
+                                    namespace: 0u128,
+                                    value: "a".to_string(),
+                                })))],
+                                vec![Some(ResolvedVariable::Computed(Term::NamedNode(
+                                    "http://unknown.org/b".to_string(),
+                                )))],
+                                vec![None],
+                            ],
+                        }),
+                        right: Box::new(spo.clone()),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("p".to_string()),
+                        PlanVariable::Basic("o".to_string()),
+                    ],
+                }),
+            ),
+            (
+                vec![WhereCondition::Values {
+                    variables: vec!["s".to_string(), "o".to_string()],
+                    values: vec![vec![None]],
+                }],
+                Err(StdError::generic_err(
+                    "Values row must provide a value for each variable",
+                )),
+            ),
+            (
+                vec![
+                    pattern("s", "p", "o"),
+                    WhereCondition::Bind {
+                        expression: msg::Expression::Variable("o".to_string()),
+                        variable: "x".to_string(),
+                    },
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Bind {
+                        child: Box::new(spo.clone()),
+                        expr: Expression::Variable(2usize),
+                        variable: 3usize,
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("p".to_string()),
+                        PlanVariable::Basic("o".to_string()),
+                        PlanVariable::Basic("x".to_string()),
+                    ],
+                }),
+            ),
+            (
+                vec![WhereCondition::Bind {
+                    expression: msg::Expression::Literal(Literal::Simple("a".to_string())),
+                    variable: "x".to_string(),
+                }],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Bind {
+                        child: Box::new(QueryNode::Values {
+                            variables: vec![],
+                            rows: vec![vec![]],
+                        }),
+                        expr: Expression::Constant(Term::String("a".to_string())),
+                        variable: 0usize,
+                    },
+                    variables: vec![PlanVariable::Basic("x".to_string())],
+                }),
+            ),
+            (
+                vec![
+                    pattern("s", "p", "o"),
+                    WhereCondition::Bind {
+                        expression: msg::Expression::Variable("s".to_string()),
+                        variable: "o".to_string(),
+                    },
+                ],
+                Err(StdError::generic_err(
+                    "Bind variable already used by the preceding conditions",
+                )),
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+        namespaces()
+            .save(
+                deps.as_mut().storage,
+                "http://axone.space/".to_string(),
+                &Namespace {
+                    value: "http://axone.space/".to_string(),
+                    key: 0u128,
+                    counter: 1u128,
+                },
+            )
+            .unwrap();
+
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_negation() {
+        let pattern = |s: &str, p: &str, o: &str| {
+            WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::Variable(p.to_string()),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let triple_pattern = |s: usize, p: usize, o: usize| QueryNode::TriplePattern {
+            subject: PatternValue::Variable(s),
+            predicate: PatternValue::Variable(p),
+            object: PatternValue::Variable(o),
+        };
+        let cases = vec![
+            (
+                vec![WhereCondition::Minus(vec![pattern("s", "p", "o")])],
+                Err(StdError::generic_err(
+                    "Minus condition must be preceded by at least one condition",
+                )),
+            ),
+            (
+                vec![
+                    pattern("s", "p", "o"),
+                    WhereCondition::Minus(vec![pattern("s", "p2", "o2")]),
+                    pattern("o", "p3", "o3"),
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::Minus {
+                            left: Box::new(triple_pattern(0, 1, 2)),
+                            right: Box::new(triple_pattern(0, 3, 4)),
+                        }),
+                        right: Box::new(triple_pattern(2, 5, 6)),
+                    },
+                    variables: ["s", "p", "o", "p2", "o2", "p3", "o3"]
+                        .iter()
+                        .map(|v| PlanVariable::Basic(v.to_string()))
+                        .collect(),
+                }),
+            ),
+            (
+                vec![
+                    WhereCondition::NotExists(vec![pattern("s", "p2", "o2")]),
+                    pattern("s", "p", "o"),
+                    WhereCondition::Filter(msg::Expression::Bound("o".to_string())),
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::NotExists {
+                        left: Box::new(QueryNode::Filter {
+                            expr: Expression::Bound(4usize),
+                            inner: Box::new(triple_pattern(0, 3, 4)),
+                        }),
+                        right: Box::new(triple_pattern(0, 1, 2)),
+                    },
+                    variables: ["s", "p2", "o2", "p", "o"]
+                        .iter()
+                        .map(|v| PlanVariable::Basic(v.to_string()))
+                        .collect(),
+                }),
+            ),
+        ];
+
+        let deps = mock_dependencies();
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_sub_query() {
+        let pattern = |s: &str, p: &str, o: &str| {
+            WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: VarOrNamedNodeOrPath::Variable(p.to_string()),
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let triple_pattern = |s: usize, p: usize, o: usize| QueryNode::TriplePattern {
+            subject: PatternValue::Variable(s),
+            predicate: PatternValue::Variable(p),
+            object: PatternValue::Variable(o),
+        };
+        let sub_query = |select: &str, limit: Option<u32>| {
+            WhereCondition::SubQuery(Box::new(SelectQuery {
+                prefixes: vec![],
+                select: vec![SelectItem::Variable(select.to_string())],
+                r#where: vec![pattern("s", "p", "o")],
+                group_by: vec![],
+                having: None,
+                distinct: false,
+                order_by: vec![],
+                limit,
+                after: None,
+            }))
+        };
+        let cases = vec![
+            (
+                vec![sub_query("x", None)],
+                Err(StdError::generic_err(
+                    "Selected variable not found in query",
+                )),
+            ),
+            (
+                vec![pattern("o", "p", "s"), sub_query("o", Some(1))],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::SubQuery {
+                            plan: Box::new(QueryPlan {
+                                entrypoint: QueryNode::Limit {
+                                    child: Box::new(triple_pattern(0, 1, 2)),
+                                    first: 1,
+                                },
+                                variables: ["s", "p", "o"]
+                                    .iter()
+                                    .map(|v| PlanVariable::Basic(v.to_string()))
+                                    .collect(),
+                            }),
+                            bindings: vec![(2, 0)],
+                        }),
+                        right: Box::new(triple_pattern(0, 1, 2)),
+                    },
+                    variables: ["o", "p", "s"]
+                        .iter()
+                        .map(|v| PlanVariable::Basic(v.to_string()))
+                        .collect(),
+                }),
+            ),
+        ];
+
+        let deps = mock_dependencies();
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_graph() {
+        let pattern = |p: VarOrNamedNodeOrPath| {
+            WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                subject: VarOrNode::Variable("s".to_string()),
+                predicate: p,
+                object: VarOrNodeOrLiteral::Variable("o".to_string()),
+            }))
+        };
+        let cases = vec![
+            (
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::Variable("g".to_string()),
+                    r#where: vec![pattern(VarOrNamedNodeOrPath::Variable("p".to_string()))],
+                }],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::QuadPattern {
+                        subject: PatternValue::Variable(1),
+                        predicate: PatternValue::Variable(2),
+                        object: PatternValue::Variable(3),
+                        graph: PatternValue::Variable(0),
+                    },
+                    variables: ["g", "s", "p", "o"]
+                        .iter()
+                        .map(|v| PlanVariable::Basic(v.to_string()))
+                        .collect(),
+                }),
+            ),
+            (
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::NamedNode(IRI::Full(
+                        "http://axone.space/unknown".to_string(),
+                    )),
+                    r#where: vec![pattern(VarOrNamedNodeOrPath::Variable("p".to_string()))],
+                }],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Noop {
+                        bound_variables: vec![0, 1, 2],
+                    },
+                    variables: ["s", "p", "o"]
+                        .iter()
+                        .map(|v| PlanVariable::Basic(v.to_string()))
+                        .collect(),
+                }),
+            ),
+            (
+                vec![WhereCondition::Graph {
+                    graph: VarOrNamedNode::Variable("g".to_string()),
+                    r#where: vec![pattern(VarOrNamedNodeOrPath::Path(
+                        msg::PropertyPath::ZeroOrMore(Box::new(msg::PropertyPath::NamedNode(
+                            IRI::Full("http://axone.space/knows".to_string()),
+                        ))),
+                    ))],
+                }],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Path {
+                        subject: PatternValue::Variable(1),
+                        path: PropertyPath::ZeroOrMore(Box::new(PropertyPath::Predicate(None))),
+                        object: PatternValue::Variable(2),
+                        graph: Some(PatternValue::Variable(0)),
+                        max_depth: usize::MAX,
+                    },
+                    variables: ["g", "s", "o"]
+                        .iter()
+                        .map(|v| PlanVariable::Basic(v.to_string()))
+                        .collect(),
+                }),
+            ),
+        ];
+
+        let deps = mock_dependencies();
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_reorder() {
+        let pattern = |s: &str, p: VarOrNamedNodeOrPath, o: &str| {
+            WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                subject: VarOrNode::Variable(s.to_string()),
+                predicate: p,
+                object: VarOrNodeOrLiteral::Variable(o.to_string()),
+            }))
+        };
+        let named =
+            |v: &str| VarOrNamedNodeOrPath::NamedNode(IRI::Full(format!("http://axone.space/{v}")));
+        let var = |v: &str| VarOrNamedNodeOrPath::Variable(v.to_string());
+        let node = |v: &str| state::Node {
+            namespace: 0u128,
+            value: v.to_string(),
+        };
+
+        let mut deps = mock_dependencies();
+        namespaces()
+            .save(
+                deps.as_mut().storage,
+                "http://axone.space/".to_string(),
+                &Namespace {
+                    value: "http://axone.space/".to_string(),
+                    key: 0u128,
+                    counter: 1u128,
+                },
+            )
+            .unwrap();
+        let mut store = Store::new(
+            Addr::unchecked("owner"),
+            msg::StoreLimitsInput::default().into(),
+            false,
+            false,
+        );
+        store.stat.triple_count = Uint128::new(1000);
+        STORE.save(deps.as_mut().storage, &store).unwrap();
+        PREDICATE_STATS
+            .save(deps.as_mut().storage, &node("common").key(), &500u128)
+            .unwrap();
+        PREDICATE_STATS
+            .save(deps.as_mut().storage, &node("rare").key(), &2u128)
+            .unwrap();
+
+        let cases = vec![
+            (
+                vec![
+                    pattern("s", var("p"), "o"),
+                    pattern("s", named("common"), "x"),
+                    pattern("s", named("rare"), "y"),
+                ],
+                QueryNode::ForLoopJoin {
+                    left: Box::new(QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0),
+                            predicate: PatternValue::Constant(node("rare")),
+                            object: PatternValue::Variable(4),
+                        }),
+                        right: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0),
+                            predicate: PatternValue::Constant(node("common")),
+                            object: PatternValue::Variable(3),
+                        }),
+                    }),
+                    right: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(0),
+                        predicate: PatternValue::Variable(1),
+                        object: PatternValue::Variable(2),
+                    }),
+                },
+            ),
+            (
+                vec![
+                    pattern("a", named("rare"), "b"),
+                    WhereCondition::Bind {
+                        expression: msg::Expression::Variable("b".to_string()),
+                        variable: "c".to_string(),
+                    },
+                    pattern("x", var("p"), "y"),
+                    pattern("c", named("common"), "x"),
+                ],
+                QueryNode::ForLoopJoin {
+                    left: Box::new(QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::Bind {
+                            child: Box::new(QueryNode::TriplePattern {
+                                subject: PatternValue::Variable(0),
+                                predicate: PatternValue::Constant(node("rare")),
+                                object: PatternValue::Variable(1),
+                            }),
+                            expr: Expression::Variable(1),
+                            variable: 2,
+                        }),
+                        right: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(2),
+                            predicate: PatternValue::Constant(node("common")),
+                            object: PatternValue::Variable(3),
+                        }),
+                    }),
+                    right: Box::new(QueryNode::TriplePattern {
+                        subject: PatternValue::Variable(3),
+                        predicate: PatternValue::Variable(4),
+                        object: PatternValue::Variable(5),
+                    }),
+                },
+            ),
+        ];
+
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(
+                builder.build_plan(&r#where).map(|plan| plan.entrypoint),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn build_plan_union() {
+        let cases = vec![
+            (
+                vec![WhereCondition::Union(vec![vec![WhereCondition::Simple(
+                    SimpleWhereCondition::TriplePattern(TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                            "http://axone.space/hasTitle".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                    }),
+                )]])],
+                Err(StdError::generic_err(
+                    "Union condition must contain at least two groups",
+                )),
+            ),
+            (
+                vec![
+                    WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+                        subject: VarOrNode::Variable("s".to_string()),
+                        predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                            "http://axone.space/hasPublisher".to_string(),
+                        )),
+                        object: VarOrNodeOrLiteral::Variable("p".to_string()),
+                    })),
+                    WhereCondition::Union(vec![
+                        vec![WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
+                            TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                    "http://axone.space/hasTitle".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                            },
+                        ))],
+                        vec![WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
+                            TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                    "http://axone.space/hasLabel".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("t".to_string()),
+                            },
+                        ))],
+                        vec![WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
+                            TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                    "http://axone.space/hasName".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("n".to_string()),
+                            },
+                        ))],
+                    ]),
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::ForLoopJoin {
+                        left: Box::new(QueryNode::TriplePattern {
+                            subject: PatternValue::Variable(0usize),
+                            predicate: PatternValue::Constant(state::Node {
+                                namespace: 0u128,
+                                value: "hasPublisher".to_string(),
+                            }),
+                            object: PatternValue::Variable(1usize),
+                        }),
+                        right: Box::new(QueryNode::Union {
+                            left: Box::new(QueryNode::Union {
+                                left: Box::new(QueryNode::TriplePattern {
+                                    subject: PatternValue::Variable(0usize),
+                                    predicate: PatternValue::Constant(state::Node {
+                                        namespace: 0u128,
+                                        value: "hasTitle".to_string(),
+                                    }),
+                                    object: PatternValue::Variable(2usize),
+                                }),
+                                right: Box::new(QueryNode::TriplePattern {
+                                    subject: PatternValue::Variable(0usize),
+                                    predicate: PatternValue::Constant(state::Node {
+                                        namespace: 0u128,
+                                        value: "hasLabel".to_string(),
+                                    }),
+                                    object: PatternValue::Variable(2usize),
+                                }),
+                            }),
+                            right: Box::new(QueryNode::TriplePattern {
+                                subject: PatternValue::Variable(0usize),
+                                predicate: PatternValue::Constant(state::Node {
+                                    namespace: 0u128,
+                                    value: "hasName".to_string(),
+                                }),
+                                object: PatternValue::Variable(3usize),
+                            }),
+                        }),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("p".to_string()),
+                        PlanVariable::Basic("t".to_string()),
+                        PlanVariable::Basic("n".to_string()),
+                    ],
+                }),
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+        namespaces()
+            .save(
+                deps.as_mut().storage,
+                "http://axone.space/".to_string(),
+                &Namespace {
+                    value: "http://axone.space/".to_string(),
+                    key: 0u128,
+                    counter: 1u128,
+                },
+            )
+            .unwrap();
+
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_filter() {
+        let pattern = WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+            subject: VarOrNode::Variable("s".to_string()),
+            predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                "http://axone.space/hasTitle".to_string(),
+            )),
+            object: VarOrNodeOrLiteral::Variable("title".to_string()),
+        }));
+        let pattern_node = QueryNode::TriplePattern {
+            subject: PatternValue::Variable(0usize),
+            predicate: PatternValue::Constant(state::Node {
+                namespace: 0u128,
+                value: "hasTitle".to_string(),
+            }),
+            object: PatternValue::Variable(1usize),
+        };
+
+        let cases = vec![
+            (
+                vec![
+                    WhereCondition::Filter(msg::Expression::Regex(
+                        Box::new(msg::Expression::Variable("title".to_string())),
+                        "(foo".to_string(),
+                        None,
+                    )),
+                    pattern.clone(),
+                ],
+                Err(StdError::generic_err(
+                    "Invalid regex pattern: found open group without closing ')'",
+                )),
+            ),
+            (
+                vec![
+                    WhereCondition::Filter(msg::Expression::Equal(
+                        Box::new(msg::Expression::Variable("s".to_string())),
+                        Box::new(msg::Expression::NamedNode(IRI::Prefixed(
+                            "unknown:foo".to_string(),
+                        ))),
+                    )),
+                    pattern.clone(),
+                ],
+                Err(StdError::generic_err("Prefix not found: unknown")),
+            ),
+            (
+                vec![
+                    pattern.clone(),
+                    WhereCondition::Filter(msg::Expression::Equal(
+                        Box::new(msg::Expression::Lang(Box::new(msg::Expression::Variable(
+                            "title".to_string(),
+                        )))),
+                        Box::new(msg::Expression::Literal(Literal::Simple("en".to_string()))),
+                    )),
+                    WhereCondition::Filter(msg::Expression::Not(Box::new(msg::Expression::Bound(
+                        "other".to_string(),
+                    )))),
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Filter {
+                        expr: Expression::Not(Box::new(Expression::Bound(2usize))),
+                        inner: Box::new(QueryNode::Filter {
+                            expr: Expression::Equal(
+                                Box::new(Expression::Lang(Box::new(Expression::Variable(1usize)))),
+                                Box::new(Expression::Constant(Term::String("en".to_string()))),
+                            ),
+                            inner: Box::new(pattern_node.clone()),
+                        }),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("title".to_string()),
+                        PlanVariable::Basic("other".to_string()),
+                    ],
+                }),
+            ),
+            (
+                vec![
+                    pattern.clone(),
+                    WhereCondition::Optional(vec![
+                        WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
+                            TriplePattern {
+                                subject: VarOrNode::Variable("s".to_string()),
+                                predicate: VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                    "http://axone.space/hasLicense".to_string(),
+                                )),
+                                object: VarOrNodeOrLiteral::Variable("license".to_string()),
+                            },
+                        )),
+                        WhereCondition::Filter(msg::Expression::IsIri(Box::new(
+                            msg::Expression::Variable("license".to_string()),
+                        ))),
+                    ]),
+                ],
+                Ok(QueryPlan {
+                    entrypoint: QueryNode::Optional {
+                        left: Box::new(pattern_node),
+                        right: Box::new(QueryNode::Filter {
+                            expr: Expression::IsIri(Box::new(Expression::Variable(2usize))),
+                            inner: Box::new(QueryNode::TriplePattern {
+                                subject: PatternValue::Variable(0usize),
+                                predicate: PatternValue::Constant(state::Node {
+                                    namespace: 0u128,
+                                    value: "hasLicense".to_string(),
+                                }),
+                                object: PatternValue::Variable(2usize),
+                            }),
+                        }),
+                    },
+                    variables: vec![
+                        PlanVariable::Basic("s".to_string()),
+                        PlanVariable::Basic("title".to_string()),
+                        PlanVariable::Basic("license".to_string()),
+                    ],
+                }),
+            ),
+        ];
+
+        let mut deps = mock_dependencies();
+        namespaces()
+            .save(
+                deps.as_mut().storage,
+                "http://axone.space/".to_string(),
+                &Namespace {
+                    value: "http://axone.space/".to_string(),
+                    key: 0u128,
+                    counter: 1u128,
+                },
+            )
+            .unwrap();
+
+        for (r#where, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None);
+
+            assert_eq!(builder.build_plan(&r#where), expected);
+        }
+    }
+
+    #[test]
+    fn build_plan_modifiers() {
+        let pattern = QueryNode::TriplePattern {
+            subject: PatternValue::Variable(0usize),
+            predicate: PatternValue::Variable(1usize),
+            object: PatternValue::Variable(2usize),
+        };
+        let cases = vec![
+            (
+                vec![
+                    msg::OrderCondition::Desc("o".to_string()),
+                    msg::OrderCondition::Asc("s".to_string()),
+                ],
+                None,
+                Ok(QueryNode::Limit {
+                    first: 5usize,
+                    child: Box::new(QueryNode::OrderBy {
+                        child: Box::new(pattern.clone()),
+                        conditions: vec![OrderCondition::Desc(2usize), OrderCondition::Asc(0usize)],
+                    }),
+                }),
+            ),
+            (
+                vec![],
+                Some(vec!["s".to_string(), "o".to_string()]),
+                Ok(QueryNode::Limit {
+                    first: 5usize,
+                    child: Box::new(QueryNode::Distinct {
+                        child: Box::new(pattern.clone()),
+                        variables: vec![0usize, 2usize],
+                    }),
+                }),
+            ),
+            (
+                vec![msg::OrderCondition::Asc("p".to_string())],
+                Some(vec!["s".to_string()]),
+                Ok(QueryNode::Limit {
+                    first: 5usize,
+                    child: Box::new(QueryNode::Distinct {
+                        child: Box::new(QueryNode::OrderBy {
+                            child: Box::new(pattern),
+                            conditions: vec![OrderCondition::Asc(1usize)],
+                        }),
+                        variables: vec![0usize],
+                    }),
+                }),
+            ),
+            (
+                vec![msg::OrderCondition::Asc("unknown".to_string())],
+                None,
+                Err(StdError::generic_err("Order variable not found in query")),
+            ),
+            (
+                vec![],
+                Some(vec!["unknown".to_string()]),
+                Err(StdError::generic_err(
+                    "Selected variable not found in query",
+                )),
+            ),
+        ];
+
+        let deps = mock_dependencies();
+        for (order_by, distinct, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None)
+                .with_order_by(order_by)
+                .with_limit(5usize);
+            if let Some(variables) = distinct {
+                builder = builder.with_distinct(variables);
+            }
+
+            assert_eq!(
+                builder
+                    .build_plan(&vec![WhereCondition::Simple(
+                        SimpleWhereCondition::TriplePattern(TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }),
+                    )])
+                    .map(|plan| plan.entrypoint),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn build_plan_grouping() {
+        let pattern = QueryNode::TriplePattern {
+            subject: PatternValue::Variable(0usize),
+            predicate: PatternValue::Variable(1usize),
+            object: PatternValue::Variable(2usize),
+        };
+        let count = |variable: &str| {
+            (
+                variable.to_string(),
+                msg::Aggregate::Count {
+                    expression: Some(msg::Expression::Variable("o".to_string())),
+                    distinct: true,
+                },
+            )
+        };
+        let cases = vec![
+            (
+                vec!["s".to_string()],
+                vec![count("n")],
+                None,
+                vec![msg::OrderCondition::Desc("n".to_string())],
+                Ok(QueryNode::OrderBy {
+                    child: Box::new(QueryNode::Group {
+                        child: Box::new(pattern.clone()),
+                        keys: vec![0usize],
+                        aggregates: vec![(
+                            3usize,
+                            Aggregate::Count {
+                                expr: Some(Expression::Variable(2usize)),
+                                distinct: true,
+                            },
+                        )],
+                    }),
+                    conditions: vec![OrderCondition::Desc(3usize)],
+                }),
+            ),
+            (
+                vec![],
+                vec![
+                    count("n"),
+                    (
+                        "m".to_string(),
+                        msg::Aggregate::Max(msg::Expression::Variable("p".to_string())),
+                    ),
+                ],
+                Some(msg::Expression::Bound("n".to_string())),
+                vec![],
+                Ok(QueryNode::Filter {
+                    expr: Expression::Bound(3usize),
+                    inner: Box::new(QueryNode::Group {
+                        child: Box::new(pattern.clone()),
+                        keys: vec![],
+                        aggregates: vec![
+                            (
+                                3usize,
+                                Aggregate::Count {
+                                    expr: Some(Expression::Variable(2usize)),
+                                    distinct: true,
+                                },
+                            ),
+                            (4usize, Aggregate::Max(Expression::Variable(1usize))),
+                        ],
+                    }),
+                }),
+            ),
+            (
+                vec!["p".to_string()],
+                vec![],
+                None,
+                vec![],
+                Ok(QueryNode::Group {
+                    child: Box::new(pattern),
+                    keys: vec![1usize],
+                    aggregates: vec![],
+                }),
+            ),
+            (
+                vec!["unknown".to_string()],
+                vec![],
+                None,
+                vec![],
+                Err(StdError::generic_err("Group variable not found in query")),
+            ),
+            (
+                vec![],
+                vec![count("o")],
+                None,
+                vec![],
+                Err(StdError::generic_err(
+                    "Aggregate variable already used in query",
+                )),
+            ),
+        ];
+
+        let deps = mock_dependencies();
+        for (group_by, aggregates, having, order_by, expected) in cases {
+            let prefixes = &PrefixMap::default().into_inner();
+            let mut builder = PlanBuilder::new(&deps.storage, prefixes, None)
+                .with_grouping(group_by, aggregates, having)
+                .with_order_by(order_by);
+
+            assert_eq!(
+                builder
+                    .build_plan(&vec![WhereCondition::Simple(
+                        SimpleWhereCondition::TriplePattern(TriplePattern {
+                            subject: VarOrNode::Variable("s".to_string()),
+                            predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            object: VarOrNodeOrLiteral::Variable("o".to_string()),
+                        }),
+                    )])
+                    .map(|plan| plan.entrypoint),
+                expected
+            );
+        }
+    }
 }
@@ -1,13 +1,19 @@
 use crate::msg::{Value, IRI};
+use crate::querier::expression::Term;
+use crate::rdf;
 use crate::state::{Literal, Object, Predicate, Subject};
 use axone_rdf::normalize::IdentifierIssuer;
 use cosmwasm_std::StdResult;
+use std::collections::BTreeSet;
 
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
 pub enum ResolvedVariable {
     Subject(Subject),
     Predicate(Predicate),
     Object(Object),
+    /// A value computed during the query evaluation, such as an aggregate, which may not exist in
+    /// the store.
+    Computed(Term),
 }
 
 impl ResolvedVariable {
@@ -20,6 +26,7 @@ impl ResolvedVariable {
                 Object::Blank(node) => Subject::Blank(*node),
                 Object::Literal(_) => None?,
             },
+            ResolvedVariable::Computed(_) => None?,
         })
     }
 
@@ -34,10 +41,10 @@ impl ResolvedVariable {
                 Object::Named(node) => node.clone(),
                 Object::Blank(_) | Object::Literal(_) => None?,
             },
+            ResolvedVariable::Computed(_) => None?,
         })
     }
 
-    #[allow(clippy::unnecessary_wraps)]
     pub fn as_object(&self) -> Option<Object> {
         Some(match self {
             ResolvedVariable::Subject(s) => match s {
@@ -46,9 +53,17 @@ impl ResolvedVariable {
             },
             ResolvedVariable::Predicate(p) => Object::Named(p.clone()),
             ResolvedVariable::Object(o) => o.clone(),
+            ResolvedVariable::Computed(_) => None?,
         })
     }
 
+    /// Returns the variable in a canonical form, so the same node compares equal whether it's been
+    /// resolved as a subject, a predicate or an object.
+    pub fn canonical(&self) -> Self {
+        self.as_object()
+            .map_or_else(|| self.clone(), ResolvedVariable::Object)
+    }
+
     pub fn as_value<F>(&self, ns_fn: &mut F, id_issuer: &mut IdentifierIssuer) -> StdResult<Value>
     where
         F: FnMut(u128) -> StdResult<String>,
@@ -92,11 +107,62 @@ impl ResolvedVariable {
                     },
                 },
             },
+            ResolvedVariable::Computed(term) => match term {
+                Term::NamedNode(iri) => Value::URI {
+                    value: IRI::Full(iri.clone()),
+                },
+                Term::BlankNode(id) => Value::BlankNode {
+                    value: id_issuer.get_str_or_issue(id.clone()).to_string(),
+                },
+                Term::String(value) => Value::Literal {
+                    value: value.clone(),
+                    lang: None,
+                    datatype: None,
+                },
+                Term::LangString(value, language) => Value::Literal {
+                    value: value.clone(),
+                    lang: Some(language.clone()),
+                    datatype: None,
+                },
+                _ => Value::Literal {
+                    value: term.lexical_form().unwrap_or_default(),
+                    lang: None,
+                    datatype: term.datatype().map(|d| IRI::Full(d.to_string())),
+                },
+            },
+        })
+    }
+
+    pub fn as_term<F>(&self, ns_fn: &mut F) -> StdResult<Term>
+    where
+        F: FnMut(u128) -> StdResult<String>,
+    {
+        Ok(match self {
+            ResolvedVariable::Subject(subject) => match subject {
+                Subject::Named(named) => Term::NamedNode(named.as_iri(ns_fn)?),
+                Subject::Blank(blank) => Term::BlankNode(blank.to_string()),
+            },
+            ResolvedVariable::Predicate(predicate) => Term::NamedNode(predicate.as_iri(ns_fn)?),
+            ResolvedVariable::Object(object) => match object {
+                Object::Named(named) => Term::NamedNode(named.as_iri(ns_fn)?),
+                Object::Blank(blank) => Term::BlankNode(blank.to_string()),
+                Object::Literal(literal) => match literal {
+                    Literal::Simple { value } => rdf::Value::LiteralSimple(value.clone()),
+                    Literal::I18NString { value, language } => {
+                        rdf::Value::LiteralLang(value.clone(), language.clone())
+                    }
+                    Literal::Typed { value, datatype } => {
+                        rdf::Value::LiteralDatatype(value.clone(), datatype.as_iri(ns_fn)?)
+                    }
+                }
+                .into(),
+            },
+            ResolvedVariable::Computed(term) => term.clone(),
         })
     }
 }
 
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
 pub struct ResolvedVariables {
     variables: Vec<Option<ResolvedVariable>>,
 }
@@ -141,6 +207,18 @@ impl ResolvedVariables {
         }
     }
 
+    /// Returns a copy with only the given variables set, the other ones being unbound.
+    pub fn retain(&self, indexes: &BTreeSet<usize>) -> Self {
+        Self {
+            variables: self
+                .variables
+                .iter()
+                .enumerate()
+                .map(|(index, var)| var.clone().filter(|_| indexes.contains(&index)))
+                .collect(),
+        }
+    }
+
     pub fn get(&self, index: usize) -> &Option<ResolvedVariable> {
         self.variables.get(index).unwrap_or(&None)
     }
@@ -299,6 +377,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn terms() {
+        let cases = vec![
+            (
+                ResolvedVariable::Subject(Subject::Named(Node {
+                    namespace: 0,
+                    value: "bar".to_string(),
+                })),
+                Ok(Term::NamedNode("foobar".to_string())),
+            ),
+            (
+                ResolvedVariable::Subject(Subject::Blank(0u128)),
+                Ok(Term::BlankNode("0".to_string())),
+            ),
+            (
+                ResolvedVariable::Predicate(Node {
+                    namespace: 1,
+                    value: "foo".to_string(),
+                }),
+                Ok(Term::NamedNode("barfoo".to_string())),
+            ),
+            (
+                ResolvedVariable::Object(Object::Blank(0u128)),
+                Ok(Term::BlankNode("0".to_string())),
+            ),
+            (
+                ResolvedVariable::Object(Object::Literal(Literal::I18NString {
+                    value: "foo".to_string(),
+                    language: "fr".to_string(),
+                })),
+                Ok(Term::LangString("foo".to_string(), "fr".to_string())),
+            ),
+            (
+                ResolvedVariable::Object(Object::Literal(Literal::Typed {
+                    value: "foo".to_string(),
+                    datatype: Node {
+                        namespace: 0,
+                        value: "bar".to_string(),
+                    },
+                })),
+                Ok(Term::Typed("foo".to_string(), "foobar".to_string())),
+            ),
+            (
+                ResolvedVariable::Object(Object::Literal(Literal::Typed {
+                    value: "foo".to_string(),
+                    datatype: Node {
+                        namespace: 2,
+                        value: "bar".to_string(),
+                    },
+                })),
+                Err(StdError::generic_err("namespace not found")),
+            ),
+        ];
+
+        for (var, expected) in cases {
+            assert_eq!(var.as_term(&mut ns), expected)
+        }
+    }
+
     #[test]
     fn merged_variables() {
         let mut vars1 = ResolvedVariables::with_capacity(3);
@@ -1,4 +1,4 @@
-use rio_api::model::{Literal, NamedNode, Triple};
+use rio_api::model::{GraphName, Literal, NamedNode, Quad, Triple};
 use std::fmt;
 
 #[derive(Eq, PartialEq, Debug, Clone, Hash)]
@@ -48,6 +48,8 @@ pub struct Atom {
     pub subject: Subject,
     pub property: Property,
     pub value: Value,
+    /// The name of the graph the atom belongs to, the default graph if not set.
+    pub graph: Option<String>,
 }
 
 impl fmt::Display for Atom {
@@ -84,6 +86,21 @@ impl<'a> From<&'a Atom> for Triple<'a> {
     }
 }
 
+impl<'a> From<&'a Atom> for Quad<'a> {
+    fn from(atom: &'a Atom) -> Self {
+        let triple: Triple<'a> = atom.into();
+        Quad {
+            subject: triple.subject,
+            predicate: triple.predicate,
+            object: triple.object,
+            graph_name: atom
+                .graph
+                .as_ref()
+                .map(|iri| GraphName::NamedNode(NamedNode { iri })),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +153,7 @@ mod tests {
                     subject: Subject::NamedNode("subject".into()),
                     property: Property("predicate".into()),
                     value: Value::LiteralLang("object".into(), "en".into()),
+                    graph: None,
                 }),
                 expected: "<subject> <predicate> 'object@en'".into(),
             },
@@ -1,5 +1,9 @@
 mod atom;
 mod mapper;
+mod vocab;
+mod xsd;
 
 pub use self::atom::*;
 pub use self::mapper::*;
+pub use self::vocab::*;
+pub use self::xsd::*;
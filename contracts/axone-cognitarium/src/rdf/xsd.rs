@@ -0,0 +1,1120 @@
+use crate::rdf::{XSD_BOOLEAN, XSD_DATE, XSD_DATE_TIME, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER};
+use cosmwasm_std::{StdError, StdResult};
+use std::cmp::Ordering;
+use std::{fmt, iter};
+
+/// The number of fractional digits kept at least when dividing numbers, the next ones being
+/// truncated.
+const DIVISION_PRECISION: usize = 18;
+
+/// The bounds of the `xsd:double` decimal exponents, as the one of `0.digits × 10^exponent`,
+/// beyond which parsed values saturate to infinity or zero, following the range of the IEEE 754
+/// double precision numbers.
+const MAX_DOUBLE_EXPONENT: i64 = 309;
+const MIN_DOUBLE_EXPONENT: i64 = -323;
+
+/// Represents the value of a literal typed with one of the supported XML Schema datatypes, parsed
+/// from its lexical form.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub enum XsdValue {
+    Boolean(bool),
+    Numeric(Numeric),
+    Date(Date),
+    DateTime(DateTime),
+}
+
+impl XsdValue {
+    /// Parse the lexical form of a literal according to its datatype, resulting in `None` if the
+    /// datatype is not supported.
+    pub fn parse(lexical: &str, datatype: &str) -> Option<StdResult<Self>> {
+        let lexical_trimmed = lexical.trim();
+        let value = match datatype {
+            XSD_BOOLEAN => parse_boolean(lexical_trimmed).map(XsdValue::Boolean),
+            XSD_INTEGER => Number::parse_integer(lexical_trimmed)
+                .map(|n| XsdValue::Numeric(Numeric::Integer(n))),
+            XSD_DECIMAL => Number::parse_decimal(lexical_trimmed)
+                .map(|n| XsdValue::Numeric(Numeric::Decimal(n))),
+            XSD_DOUBLE => {
+                Double::parse(lexical_trimmed).map(|d| XsdValue::Numeric(Numeric::Double(d)))
+            }
+            XSD_DATE => Date::parse(lexical_trimmed).map(XsdValue::Date),
+            XSD_DATE_TIME => DateTime::parse(lexical_trimmed).map(XsdValue::DateTime),
+            _ => return None,
+        };
+
+        Some(value.ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Invalid lexical form '{lexical}' for datatype <{datatype}>"
+            ))
+        }))
+    }
+
+    pub fn datatype(&self) -> &'static str {
+        match self {
+            XsdValue::Boolean(_) => XSD_BOOLEAN,
+            XsdValue::Numeric(n) => n.datatype(),
+            XsdValue::Date(_) => XSD_DATE,
+            XsdValue::DateTime(_) => XSD_DATE_TIME,
+        }
+    }
+}
+
+/// Formats the value in its canonical lexical form.
+impl fmt::Display for XsdValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XsdValue::Boolean(b) => write!(f, "{b}"),
+            XsdValue::Numeric(n) => write!(f, "{n}"),
+            XsdValue::Date(d) => write!(f, "{d}"),
+            XsdValue::DateTime(dt) => write!(f, "{dt}"),
+        }
+    }
+}
+
+fn parse_boolean(lexical: &str) -> Option<bool> {
+    match lexical {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Represents a numeric value, keeping track of its datatype, all of them being comparable
+/// between each others.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub enum Numeric {
+    Integer(Number),
+    Decimal(Number),
+    Double(Double),
+}
+
+impl Numeric {
+    pub fn datatype(&self) -> &'static str {
+        match self {
+            Numeric::Integer(_) => XSD_INTEGER,
+            Numeric::Decimal(_) => XSD_DECIMAL,
+            Numeric::Double(_) => XSD_DOUBLE,
+        }
+    }
+
+    /// Tells if the value is zero or `NaN`.
+    pub fn is_zero_or_nan(&self) -> bool {
+        match self {
+            Numeric::Integer(n) | Numeric::Decimal(n) | Numeric::Double(Double::Number(n)) => {
+                n.is_zero()
+            }
+            Numeric::Double(Double::NaN) => true,
+            Numeric::Double(_) => false,
+        }
+    }
+
+    /// Compare the values numerically regardless of their datatype, resulting in `None` if one of
+    /// them is `NaN`.
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        Some(self.as_extended()?.cmp(&other.as_extended()?))
+    }
+
+    /// Compare the values numerically, `NaN` coming first and equal values of different datatypes
+    /// being ordered by datatype.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.as_extended()
+            .cmp(&other.as_extended())
+            .then_with(|| self.datatype().cmp(other.datatype()))
+    }
+
+    /// Add the values, the result having the least common datatype of the operands following the
+    /// numeric type promotion rules, i.e. `xsd:integer` → `xsd:decimal` → `xsd:double`.
+    pub fn add(&self, other: &Self) -> StdResult<Self> {
+        Ok(match (self, other) {
+            (Numeric::Integer(l), Numeric::Integer(r)) => Numeric::Integer(l.add(r)?),
+            (
+                Numeric::Integer(l) | Numeric::Decimal(l),
+                Numeric::Integer(r) | Numeric::Decimal(r),
+            ) => Numeric::Decimal(l.add(r)?),
+            (l, r) => Numeric::Double(l.as_double().add(&r.as_double())?),
+        })
+    }
+
+    /// Divide the value by a non-zero count, as needed to compute averages, integers resulting in
+    /// decimals.
+    pub fn divide(&self, count: usize) -> Self {
+        match self {
+            Numeric::Integer(n) | Numeric::Decimal(n) => Numeric::Decimal(n.divide(count)),
+            Numeric::Double(Double::Number(n)) => Numeric::Double(Double::Number(n.divide(count))),
+            Numeric::Double(d) => Numeric::Double(d.clone()),
+        }
+    }
+
+    fn as_double(&self) -> Double {
+        match self {
+            Numeric::Integer(n) | Numeric::Decimal(n) => Double::Number(n.clone()),
+            Numeric::Double(d) => d.clone(),
+        }
+    }
+
+    fn as_extended(&self) -> Option<ExtendedNumber<'_>> {
+        match self {
+            Numeric::Integer(n) | Numeric::Decimal(n) | Numeric::Double(Double::Number(n)) => {
+                Some(ExtendedNumber::Finite(n))
+            }
+            Numeric::Double(Double::PositiveInfinity) => Some(ExtendedNumber::PositiveInfinity),
+            Numeric::Double(Double::NegativeInfinity) => Some(ExtendedNumber::NegativeInfinity),
+            Numeric::Double(Double::NaN) => None,
+        }
+    }
+}
+
+impl From<usize> for Numeric {
+    fn from(value: usize) -> Self {
+        Numeric::Integer(Number::new(false, &value.to_string(), "", 0).unwrap_or_else(Number::zero))
+    }
+}
+
+/// The totally ordered numbers, i.e. all the numeric values but `NaN`.
+#[derive(Eq, PartialEq, Ord, PartialOrd)]
+enum ExtendedNumber<'a> {
+    NegativeInfinity,
+    Finite(&'a Number),
+    PositiveInfinity,
+}
+
+impl fmt::Display for Numeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Numeric::Integer(n) => n.fmt_integer(f),
+            Numeric::Decimal(n) => n.fmt_decimal(f),
+            Numeric::Double(d) => write!(f, "{d}"),
+        }
+    }
+}
+
+/// An arbitrary precision decimal number whose value is `0.digits × 10^exponent`, normalized so
+/// its digits have neither leading nor trailing zeros, zero having no digits at all.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct Number {
+    negative: bool,
+    digits: String,
+    exponent: i64,
+}
+
+impl Number {
+    fn zero() -> Self {
+        Self {
+            negative: false,
+            digits: String::new(),
+            exponent: 0,
+        }
+    }
+
+    /// Build the number `int_part.frac_part × 10^exponent`, both parts being made of ASCII digits.
+    fn new(negative: bool, int_part: &str, frac_part: &str, exponent: i64) -> Option<Self> {
+        let all = format!("{int_part}{frac_part}");
+        let significant = all.trim_start_matches('0');
+        let digits = significant.trim_end_matches('0');
+        if digits.is_empty() {
+            return Some(Self::zero());
+        }
+
+        let leading_zeros = i64::try_from(all.len() - significant.len()).ok()?;
+        let exponent = i64::try_from(int_part.len())
+            .ok()?
+            .checked_sub(leading_zeros)?
+            .checked_add(exponent)?;
+
+        Some(Self {
+            negative,
+            digits: digits.to_string(),
+            exponent,
+        })
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    fn parse_integer(lexical: &str) -> Option<Self> {
+        let (negative, digits) = split_sign(lexical);
+        if !is_digits(digits) {
+            return None;
+        }
+        Self::new(negative, digits, "", 0)
+    }
+
+    fn parse_decimal(lexical: &str) -> Option<Self> {
+        let (negative, unsigned) = split_sign(lexical);
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part.chars().all(|c| c.is_ascii_digit())
+            || !frac_part.chars().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        Self::new(negative, int_part, frac_part, 0)
+    }
+
+    /// Split the number into its digits as an integer and the exponent of its last digit.
+    fn as_mantissa(&self) -> (&str, i64) {
+        let len = i64::try_from(self.digits.len()).unwrap_or(i64::MAX);
+        (&self.digits, self.exponent.saturating_sub(len))
+    }
+
+    fn add(&self, other: &Self) -> StdResult<Self> {
+        if self.is_zero() {
+            return Ok(other.clone());
+        }
+        if other.is_zero() {
+            return Ok(self.clone());
+        }
+
+        let overflow = || StdError::generic_err("Numeric overflow");
+        let ((l, l_exponent), (r, r_exponent)) = (self.as_mantissa(), other.as_mantissa());
+        let exponent = l_exponent.min(r_exponent);
+        let pad = |digits: &str, e: i64| -> StdResult<String> {
+            let zeros = e
+                .checked_sub(exponent)
+                .and_then(|n| usize::try_from(n).ok())
+                .ok_or_else(overflow)?;
+            Ok(digits.to_string() + &"0".repeat(zeros))
+        };
+        let (l, r) = (pad(l, l_exponent)?, pad(r, r_exponent)?);
+
+        let (negative, digits) = if self.negative == other.negative {
+            (self.negative, add_digits(&l, &r))
+        } else {
+            match l.len().cmp(&r.len()).then_with(|| l.cmp(&r)) {
+                Ordering::Equal => return Ok(Self::zero()),
+                Ordering::Greater => (self.negative, sub_digits(&l, &r)),
+                Ordering::Less => (other.negative, sub_digits(&r, &l)),
+            }
+        };
+        Self::new(negative, &digits, "", exponent).ok_or_else(overflow)
+    }
+
+    /// Divide the number by a non-zero count, keeping at least [DIVISION_PRECISION] fractional
+    /// digits.
+    fn divide(&self, count: usize) -> Self {
+        let (digits, exponent) = self.as_mantissa();
+        let count = count as u128;
+
+        let mut quotient = String::with_capacity(digits.len() + DIVISION_PRECISION);
+        let mut remainder = 0u128;
+        for digit in digits
+            .bytes()
+            .map(|b| b - b'0')
+            .chain(iter::repeat(0).take(DIVISION_PRECISION))
+        {
+            remainder = remainder * 10 + u128::from(digit);
+            quotient.push(char::from(b'0' + (remainder / count) as u8));
+            remainder %= count;
+        }
+
+        Self::new(
+            self.negative,
+            &quotient,
+            "",
+            exponent.saturating_sub(DIVISION_PRECISION as i64),
+        )
+        .unwrap_or_else(Self::zero)
+    }
+
+    fn signum(&self) -> i8 {
+        match (self.is_zero(), self.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        }
+    }
+
+    /// Writes the number in the canonical form of an `xsd:integer`, any fractional part being
+    /// truncated.
+    fn fmt_integer(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() || self.exponent <= 0 {
+            return write!(f, "0");
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let (int_part, _) = self.split_at_point();
+        write!(f, "{int_part}")
+    }
+
+    /// Writes the number in the canonical form of an `xsd:decimal`.
+    fn fmt_decimal(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative && !self.is_zero() {
+            write!(f, "-")?;
+        }
+        let (int_part, frac_part) = self.split_at_point();
+        write!(
+            f,
+            "{}.{}",
+            if int_part.is_empty() { "0" } else { &int_part },
+            if frac_part.is_empty() {
+                "0"
+            } else {
+                &frac_part
+            }
+        )
+    }
+
+    /// Writes the number in the canonical form of an `xsd:double`.
+    fn fmt_double(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0.0E0");
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let (first, rest) = self.digits.split_at(1);
+        write!(
+            f,
+            "{first}.{}E{}",
+            if rest.is_empty() { "0" } else { rest },
+            self.exponent - 1
+        )
+    }
+
+    /// Split the digits around the decimal point, padding them with zeros as needed.
+    fn split_at_point(&self) -> (String, String) {
+        let len = i64::try_from(self.digits.len()).unwrap_or(i64::MAX);
+        let zeros = |n: i64| "0".repeat(usize::try_from(n).unwrap_or_default());
+        if self.exponent <= 0 {
+            (String::new(), zeros(-self.exponent) + &self.digits)
+        } else if self.exponent >= len {
+            (
+                self.digits.clone() + &zeros(self.exponent - len),
+                String::new(),
+            )
+        } else {
+            let (int_part, frac_part) = self
+                .digits
+                .split_at(usize::try_from(self.exponent).unwrap_or_default());
+            (int_part.to_string(), frac_part.to_string())
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.signum().cmp(&other.signum()) {
+            Ordering::Equal if self.is_zero() => Ordering::Equal,
+            Ordering::Equal => {
+                let magnitude = self
+                    .exponent
+                    .cmp(&other.exponent)
+                    .then_with(|| self.digits.cmp(&other.digits));
+                if self.negative {
+                    magnitude.reverse()
+                } else {
+                    magnitude
+                }
+            }
+            ordering => ordering,
+        }
+    }
+}
+
+/// Represents an `xsd:double` value, finite ones being kept with their full decimal precision.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub enum Double {
+    Number(Number),
+    PositiveInfinity,
+    NegativeInfinity,
+    NaN,
+}
+
+impl Double {
+    fn parse(lexical: &str) -> Option<Self> {
+        match lexical {
+            "INF" | "+INF" => return Some(Double::PositiveInfinity),
+            "-INF" => return Some(Double::NegativeInfinity),
+            "NaN" => return Some(Double::NaN),
+            _ => {}
+        }
+
+        let (mantissa, exponent) = lexical
+            .split_once(['e', 'E'])
+            .map_or((lexical, None), |(m, e)| (m, Some(e)));
+        let exponent = match exponent {
+            Some(e) => {
+                let (negative, digits) = split_sign(e);
+                if !is_digits(digits) {
+                    return None;
+                }
+                let e = digits.parse::<i64>().ok()?;
+                if negative {
+                    -e
+                } else {
+                    e
+                }
+            }
+            None => 0,
+        };
+
+        let number = Number::parse_decimal(mantissa)?;
+        if number.is_zero() {
+            return Some(Double::Number(number));
+        }
+        Some(match number.exponent.saturating_add(exponent) {
+            e if e > MAX_DOUBLE_EXPONENT && number.negative => Double::NegativeInfinity,
+            e if e > MAX_DOUBLE_EXPONENT => Double::PositiveInfinity,
+            e if e < MIN_DOUBLE_EXPONENT => Double::Number(Number::zero()),
+            exponent => Double::Number(Number { exponent, ..number }),
+        })
+    }
+}
+
+impl Double {
+    fn add(&self, other: &Self) -> StdResult<Self> {
+        Ok(match (self, other) {
+            (Double::Number(l), Double::Number(r)) => Double::Number(l.add(r)?),
+            (Double::NaN, _)
+            | (_, Double::NaN)
+            | (Double::PositiveInfinity, Double::NegativeInfinity)
+            | (Double::NegativeInfinity, Double::PositiveInfinity) => Double::NaN,
+            (Double::PositiveInfinity, _) | (_, Double::PositiveInfinity) => {
+                Double::PositiveInfinity
+            }
+            (Double::NegativeInfinity, _) | (_, Double::NegativeInfinity) => {
+                Double::NegativeInfinity
+            }
+        })
+    }
+}
+
+impl fmt::Display for Double {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Double::Number(n) => n.fmt_double(f),
+            Double::PositiveInfinity => write!(f, "INF"),
+            Double::NegativeInfinity => write!(f, "-INF"),
+            Double::NaN => write!(f, "NaN"),
+        }
+    }
+}
+
+/// Represents an `xsd:date` value, with its optional timezone offset in minutes.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct Date {
+    year: i64,
+    month: u8,
+    day: u8,
+    timezone: Option<i16>,
+}
+
+impl Date {
+    fn parse(lexical: &str) -> Option<Self> {
+        let (year, month, day, rest) = parse_date_part(lexical)?;
+        Some(Self {
+            year,
+            month,
+            day,
+            timezone: parse_timezone(rest)?,
+        })
+    }
+
+    /// Compare the dates chronologically, a date without timezone being considered as UTC.
+    pub fn compare(&self, other: &Self) -> Ordering {
+        self.timestamp().cmp(&other.timestamp())
+    }
+
+    /// Compare the dates chronologically, equal dates being ordered by timezone.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+            .then_with(|| self.timezone.cmp(&other.timezone))
+    }
+
+    fn timestamp(&self) -> i128 {
+        i128::from(days_from_civil(self.year, self.month, self.day)) * 86400
+            - i128::from(self.timezone.unwrap_or_default()) * 60
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_date_part(f, self.year, self.month, self.day)?;
+        fmt_timezone(f, self.timezone)
+    }
+}
+
+/// Represents an `xsd:dateTime` value, with its optional timezone offset in minutes.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct DateTime {
+    year: i64,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    /// The fractional seconds digits, without trailing zeros.
+    fraction: String,
+    timezone: Option<i16>,
+}
+
+impl DateTime {
+    fn parse(lexical: &str) -> Option<Self> {
+        let (mut year, mut month, mut day, rest) = parse_date_part(lexical)?;
+        let rest = rest.strip_prefix('T')?;
+
+        let hour = parse_two_digits(rest.get(0..2)?)?;
+        let minute = parse_two_digits(rest.get(3..5)?)?;
+        let second = parse_two_digits(rest.get(6..8)?)?;
+        if rest.get(2..3)? != ":" || rest.get(5..6)? != ":" || minute > 59 || second > 59 {
+            return None;
+        }
+
+        let rest = &rest[8..];
+        let (fraction, rest) = match rest.strip_prefix('.') {
+            Some(frac) => {
+                let end = frac
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(frac.len());
+                if end == 0 {
+                    return None;
+                }
+                (frac[..end].trim_end_matches('0'), &frac[end..])
+            }
+            None => ("", rest),
+        };
+
+        let hour = match hour {
+            0..=23 => hour,
+            24 if minute == 0 && second == 0 && fraction.is_empty() => {
+                (year, month, day) = next_day(year, month, day)?;
+                0
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            fraction: fraction.to_string(),
+            timezone: parse_timezone(rest)?,
+        })
+    }
+
+    /// Compare the date times chronologically, a date time without timezone being considered as
+    /// UTC.
+    pub fn compare(&self, other: &Self) -> Ordering {
+        self.timestamp()
+            .cmp(&other.timestamp())
+            .then_with(|| self.fraction.cmp(&other.fraction))
+    }
+
+    /// Compare the date times chronologically, equal date times being ordered by timezone.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+            .then_with(|| self.timezone.cmp(&other.timezone))
+    }
+
+    fn timestamp(&self) -> i128 {
+        i128::from(days_from_civil(self.year, self.month, self.day)) * 86400
+            + i128::from(self.hour) * 3600
+            + i128::from(self.minute) * 60
+            + i128::from(self.second)
+            - i128::from(self.timezone.unwrap_or_default()) * 60
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_date_part(f, self.year, self.month, self.day)?;
+        write!(f, "T{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if !self.fraction.is_empty() {
+            write!(f, ".{}", self.fraction)?;
+        }
+        fmt_timezone(f, self.timezone)
+    }
+}
+
+/// The greatest year magnitude supported, keeping timestamps computation away from overflows.
+const MAX_YEAR: i64 = 999_999_999;
+
+/// Add two integers given as ASCII digits.
+fn add_digits(l: &str, r: &str) -> String {
+    let (l, r) = (l.as_bytes(), r.as_bytes());
+    let mut digits = Vec::with_capacity(l.len().max(r.len()) + 1);
+    let mut carry = 0u8;
+    for i in 0..l.len().max(r.len()) {
+        let digit = |n: &[u8]| n.len().checked_sub(i + 1).map_or(0, |j| n[j] - b'0');
+        let sum = digit(l) + digit(r) + carry;
+        digits.push(b'0' + sum % 10);
+        carry = sum / 10;
+    }
+    if carry > 0 {
+        digits.push(b'0' + carry);
+    }
+    digits.iter().rev().map(|b| char::from(*b)).collect()
+}
+
+/// Subtract two integers given as ASCII digits, the left one being the greatest.
+fn sub_digits(l: &str, r: &str) -> String {
+    let (l, r) = (l.as_bytes(), r.as_bytes());
+    let mut digits = Vec::with_capacity(l.len());
+    let mut borrow = 0u8;
+    for i in 0..l.len() {
+        let (l, r) = (
+            l[l.len() - 1 - i] - b'0',
+            r.len().checked_sub(i + 1).map_or(0, |j| r[j] - b'0') + borrow,
+        );
+        if l >= r {
+            digits.push(b'0' + l - r);
+            borrow = 0;
+        } else {
+            digits.push(b'0' + l + 10 - r);
+            borrow = 1;
+        }
+    }
+    digits.iter().rev().map(|b| char::from(*b)).collect()
+}
+
+fn split_sign(lexical: &str) -> (bool, &str) {
+    match lexical.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, lexical.strip_prefix('+').unwrap_or(lexical)),
+    }
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+fn parse_two_digits(s: &str) -> Option<u8> {
+    if s.len() != 2 || !is_digits(s) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse the `-?YYYY-MM-DD` part of a date or date time, returning the remaining input.
+fn parse_date_part(lexical: &str) -> Option<(i64, u8, u8, &str)> {
+    let (negative, unsigned) = lexical
+        .strip_prefix('-')
+        .map_or((false, lexical), |rest| (true, rest));
+    let year_len = unsigned.find('-')?;
+    let year_digits = &unsigned[..year_len];
+    if year_len < 4 || !is_digits(year_digits) || year_len > 4 && year_digits.starts_with('0') {
+        return None;
+    }
+    let year = year_digits.parse::<i64>().ok().filter(|y| *y <= MAX_YEAR)?;
+
+    let rest = &unsigned[year_len..];
+    let month = parse_two_digits(rest.get(1..3)?)?;
+    let day = parse_two_digits(rest.get(4..6)?)?;
+    if rest.get(3..4)? != "-" || !(1..=12).contains(&month) {
+        return None;
+    }
+
+    let year = if negative { -year } else { year };
+    if day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    Some((year, month, day, &rest[6..]))
+}
+
+/// Parse an optional timezone, either `Z` or `(+|-)hh:mm`, as an offset in minutes.
+fn parse_timezone(lexical: &str) -> Option<Option<i16>> {
+    if lexical.is_empty() {
+        return Some(None);
+    }
+    if lexical == "Z" {
+        return Some(Some(0));
+    }
+
+    let (negative, rest) = match lexical.get(0..1)? {
+        "+" => (false, &lexical[1..]),
+        "-" => (true, &lexical[1..]),
+        _ => return None,
+    };
+    if rest.len() != 5 || rest.get(2..3)? != ":" {
+        return None;
+    }
+    let hours = parse_two_digits(rest.get(0..2)?)?;
+    let minutes = parse_two_digits(rest.get(3..5)?)?;
+    if minutes > 59 || hours > 14 || hours == 14 && minutes > 0 {
+        return None;
+    }
+
+    let offset = i16::from(hours) * 60 + i16::from(minutes);
+    Some(Some(if negative { -offset } else { offset }))
+}
+
+fn fmt_date_part(f: &mut fmt::Formatter<'_>, year: i64, month: u8, day: u8) -> fmt::Result {
+    if year < 0 {
+        write!(f, "-")?;
+    }
+    write!(f, "{:04}-{:02}-{:02}", year.unsigned_abs(), month, day)
+}
+
+fn fmt_timezone(f: &mut fmt::Formatter<'_>, timezone: Option<i16>) -> fmt::Result {
+    match timezone {
+        None => Ok(()),
+        Some(0) => write!(f, "Z"),
+        Some(offset) => write!(
+            f,
+            "{}{:02}:{:02}",
+            if offset < 0 { '-' } else { '+' },
+            offset.unsigned_abs() / 60,
+            offset.unsigned_abs() % 60
+        ),
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn next_day(year: i64, month: u8, day: u8) -> Option<(i64, u8, u8)> {
+    if day < days_in_month(year, month) {
+        Some((year, month, day + 1))
+    } else if month < 12 {
+        Some((year, month + 1, 1))
+    } else {
+        Some((year.checked_add(1).filter(|y| *y <= MAX_YEAR)?, 1, 1))
+    }
+}
+
+/// Compute the number of days since the unix epoch of a proleptic gregorian calendar date.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let (month, day) = (i64::from(month), i64::from(day));
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_forms() {
+        let cases = vec![
+            ("true", XSD_BOOLEAN, Some("true")),
+            ("1", XSD_BOOLEAN, Some("true")),
+            (" 0 ", XSD_BOOLEAN, Some("false")),
+            ("yes", XSD_BOOLEAN, None),
+            ("10", XSD_INTEGER, Some("10")),
+            ("010", XSD_INTEGER, Some("10")),
+            ("+42", XSD_INTEGER, Some("42")),
+            ("-0", XSD_INTEGER, Some("0")),
+            ("-00120", XSD_INTEGER, Some("-120")),
+            ("1.0", XSD_INTEGER, None),
+            ("", XSD_INTEGER, None),
+            ("+", XSD_INTEGER, None),
+            ("1.50", XSD_DECIMAL, Some("1.5")),
+            ("001", XSD_DECIMAL, Some("1.0")),
+            (".5", XSD_DECIMAL, Some("0.5")),
+            ("-5.", XSD_DECIMAL, Some("-5.0")),
+            ("-0.000", XSD_DECIMAL, Some("0.0")),
+            ("0.0012", XSD_DECIMAL, Some("0.0012")),
+            ("1200", XSD_DECIMAL, Some("1200.0")),
+            (".", XSD_DECIMAL, None),
+            ("1e3", XSD_DECIMAL, None),
+            ("1500", XSD_DOUBLE, Some("1.5E3")),
+            ("1.5e3", XSD_DOUBLE, Some("1.5E3")),
+            ("0.015E+2", XSD_DOUBLE, Some("1.5E0")),
+            ("-12E-3", XSD_DOUBLE, Some("-1.2E-2")),
+            ("1", XSD_DOUBLE, Some("1.0E0")),
+            ("-0.0e10", XSD_DOUBLE, Some("0.0E0")),
+            ("+INF", XSD_DOUBLE, Some("INF")),
+            ("-INF", XSD_DOUBLE, Some("-INF")),
+            ("NaN", XSD_DOUBLE, Some("NaN")),
+            ("1.5E308", XSD_DOUBLE, Some("1.5E308")),
+            ("1E309", XSD_DOUBLE, Some("INF")),
+            ("-1E9223372036854775807", XSD_DOUBLE, Some("-INF")),
+            ("4.9E-324", XSD_DOUBLE, Some("4.9E-324")),
+            ("1E-9223372036854775807", XSD_DOUBLE, Some("0.0E0")),
+            ("1e", XSD_DOUBLE, None),
+            ("inf", XSD_DOUBLE, None),
+            ("1E99999999999999999999", XSD_DOUBLE, None),
+            ("2024-02-29", XSD_DATE, Some("2024-02-29")),
+            ("2024-02-29+00:00", XSD_DATE, Some("2024-02-29Z")),
+            ("-0044-03-15-05:30", XSD_DATE, Some("-0044-03-15-05:30")),
+            ("12024-01-01", XSD_DATE, Some("12024-01-01")),
+            ("2023-02-29", XSD_DATE, None),
+            ("2024-13-01", XSD_DATE, None),
+            ("024-01-01", XSD_DATE, None),
+            ("02024-01-01", XSD_DATE, None),
+            ("2024-01-01+15:00", XSD_DATE, None),
+            ("2024-1-01", XSD_DATE, None),
+            (
+                "2024-01-01T10:20:30",
+                XSD_DATE_TIME,
+                Some("2024-01-01T10:20:30"),
+            ),
+            (
+                "2024-01-01T10:20:30.500+00:00",
+                XSD_DATE_TIME,
+                Some("2024-01-01T10:20:30.5Z"),
+            ),
+            (
+                "2024-01-01T10:20:30.000+02:00",
+                XSD_DATE_TIME,
+                Some("2024-01-01T10:20:30+02:00"),
+            ),
+            (
+                "2023-12-31T24:00:00Z",
+                XSD_DATE_TIME,
+                Some("2024-01-01T00:00:00Z"),
+            ),
+            ("2023-12-31T24:00:01Z", XSD_DATE_TIME, None),
+            ("2023-12-31T10:60:00Z", XSD_DATE_TIME, None),
+            ("2023-12-31T10:00:00.Z", XSD_DATE_TIME, None),
+            ("2023-12-31", XSD_DATE_TIME, None),
+        ];
+
+        for (lexical, datatype, expected) in cases {
+            let result = XsdValue::parse(lexical, datatype).unwrap();
+            match expected {
+                Some(canonical) => {
+                    let value = result.unwrap();
+                    assert_eq!(value.to_string(), canonical, "{lexical}");
+                    assert_eq!(value.datatype(), datatype);
+                }
+                None => assert_eq!(
+                    result.unwrap_err(),
+                    StdError::generic_err(format!(
+                        "Invalid lexical form '{lexical}' for datatype <{datatype}>"
+                    ))
+                ),
+            }
+        }
+
+        assert_eq!(
+            XsdValue::parse("foo", "http://www.w3.org/2001/XMLSchema#string"),
+            None
+        );
+    }
+
+    #[test]
+    fn compare() {
+        let numeric = |lexical: &str, datatype: &str| match XsdValue::parse(lexical, datatype) {
+            Some(Ok(XsdValue::Numeric(n))) => n,
+            _ => panic!("not a numeric: {lexical}"),
+        };
+        let cases = vec![
+            (
+                ("10", XSD_INTEGER),
+                ("010", XSD_INTEGER),
+                Some(Ordering::Equal),
+            ),
+            (
+                ("9", XSD_INTEGER),
+                ("10", XSD_INTEGER),
+                Some(Ordering::Less),
+            ),
+            (
+                ("-9", XSD_INTEGER),
+                ("-10", XSD_INTEGER),
+                Some(Ordering::Greater),
+            ),
+            (
+                ("1", XSD_INTEGER),
+                ("1.0", XSD_DECIMAL),
+                Some(Ordering::Equal),
+            ),
+            (
+                ("0.2", XSD_DECIMAL),
+                ("0.19", XSD_DECIMAL),
+                Some(Ordering::Greater),
+            ),
+            (
+                ("0", XSD_INTEGER),
+                ("-0.0", XSD_DECIMAL),
+                Some(Ordering::Equal),
+            ),
+            (
+                ("-1", XSD_INTEGER),
+                ("0", XSD_INTEGER),
+                Some(Ordering::Less),
+            ),
+            (
+                ("1500", XSD_INTEGER),
+                ("1.5E3", XSD_DOUBLE),
+                Some(Ordering::Equal),
+            ),
+            (
+                ("1E300", XSD_DOUBLE),
+                ("INF", XSD_DOUBLE),
+                Some(Ordering::Less),
+            ),
+            (
+                ("-INF", XSD_DOUBLE),
+                ("-1E300", XSD_DOUBLE),
+                Some(Ordering::Less),
+            ),
+            (("NaN", XSD_DOUBLE), ("NaN", XSD_DOUBLE), None),
+            (("1", XSD_INTEGER), ("NaN", XSD_DOUBLE), None),
+        ];
+
+        for ((l, l_type), (r, r_type), expected) in cases {
+            assert_eq!(
+                numeric(l, l_type).compare(&numeric(r, r_type)),
+                expected,
+                "{l} <=> {r}"
+            );
+        }
+
+        let date_time = |lexical: &str| match XsdValue::parse(lexical, XSD_DATE_TIME) {
+            Some(Ok(XsdValue::DateTime(dt))) => dt,
+            _ => panic!("not a date time: {lexical}"),
+        };
+        let cases = vec![
+            (
+                "2024-01-01T10:00:00Z",
+                "2024-01-01T12:00:00+02:00",
+                Ordering::Equal,
+            ),
+            (
+                "2024-01-01T10:00:00",
+                "2024-01-01T10:00:00Z",
+                Ordering::Equal,
+            ),
+            (
+                "2024-01-01T10:00:00.05Z",
+                "2024-01-01T10:00:00.5Z",
+                Ordering::Less,
+            ),
+            (
+                "2023-12-31T24:00:00Z",
+                "2024-01-01T00:00:00Z",
+                Ordering::Equal,
+            ),
+            (
+                "-0001-01-01T00:00:00Z",
+                "0001-01-01T00:00:00Z",
+                Ordering::Less,
+            ),
+            (
+                "2024-03-01T00:00:00Z",
+                "2024-02-29T23:59:59Z",
+                Ordering::Greater,
+            ),
+        ];
+        for (l, r, expected) in cases {
+            assert_eq!(date_time(l).compare(&date_time(r)), expected, "{l} <=> {r}");
+        }
+
+        let date = |lexical: &str| match XsdValue::parse(lexical, XSD_DATE) {
+            Some(Ok(XsdValue::Date(d))) => d,
+            _ => panic!("not a date: {lexical}"),
+        };
+        assert_eq!(
+            date("2024-01-02").compare(&date("2024-01-01")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            date("2024-01-01-05:00").compare(&date("2024-01-01Z")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let numeric = |lexical: &str, datatype: &str| match XsdValue::parse(lexical, datatype) {
+            Some(Ok(XsdValue::Numeric(n))) => n,
+            _ => panic!("not a numeric: {lexical}"),
+        };
+        let spread_sum = format!("1.{}1E308", "0".repeat(630));
+        let cases = vec![
+            (("1", XSD_INTEGER), ("2", XSD_INTEGER), "3", XSD_INTEGER),
+            (("99", XSD_INTEGER), ("1", XSD_INTEGER), "100", XSD_INTEGER),
+            (("-5", XSD_INTEGER), ("3", XSD_INTEGER), "-2", XSD_INTEGER),
+            (("5", XSD_INTEGER), ("-5", XSD_INTEGER), "0", XSD_INTEGER),
+            (
+                ("1000", XSD_INTEGER),
+                ("-1", XSD_INTEGER),
+                "999",
+                XSD_INTEGER,
+            ),
+            (
+                ("1", XSD_INTEGER),
+                ("0.25", XSD_DECIMAL),
+                "1.25",
+                XSD_DECIMAL,
+            ),
+            (
+                ("-0.5", XSD_DECIMAL),
+                ("0.25", XSD_DECIMAL),
+                "-0.25",
+                XSD_DECIMAL,
+            ),
+            (("0", XSD_INTEGER), ("1.5", XSD_DECIMAL), "1.5", XSD_DECIMAL),
+            (
+                ("1.5E3", XSD_DOUBLE),
+                ("1", XSD_INTEGER),
+                "1.501E3",
+                XSD_DOUBLE,
+            ),
+            (
+                ("1E-2", XSD_DOUBLE),
+                ("1E2", XSD_DOUBLE),
+                "1.0001E2",
+                XSD_DOUBLE,
+            ),
+            (
+                ("1E308", XSD_DOUBLE),
+                ("1E-323", XSD_DOUBLE),
+                spread_sum.as_str(),
+                XSD_DOUBLE,
+            ),
+            (("INF", XSD_DOUBLE), ("1", XSD_INTEGER), "INF", XSD_DOUBLE),
+            (("INF", XSD_DOUBLE), ("-INF", XSD_DOUBLE), "NaN", XSD_DOUBLE),
+            (("NaN", XSD_DOUBLE), ("1", XSD_DOUBLE), "NaN", XSD_DOUBLE),
+        ];
+
+        for ((l, l_type), (r, r_type), expected, expected_type) in cases {
+            let sum = numeric(l, l_type).add(&numeric(r, r_type)).unwrap();
+            assert_eq!(
+                (sum.to_string().as_str(), sum.datatype()),
+                (expected, expected_type),
+                "{l} + {r}"
+            );
+        }
+
+        let cases = vec![
+            (("6", XSD_INTEGER), 3, "2.0", XSD_DECIMAL),
+            (("7", XSD_INTEGER), 2, "3.5", XSD_DECIMAL),
+            (("1", XSD_INTEGER), 3, "0.333333333333333333", XSD_DECIMAL),
+            (("-2", XSD_DECIMAL), 3, "-0.666666666666666666", XSD_DECIMAL),
+            (("0", XSD_INTEGER), 4, "0.0", XSD_DECIMAL),
+            (("1200", XSD_INTEGER), 5, "240.0", XSD_DECIMAL),
+            (("3E0", XSD_DOUBLE), 2, "1.5E0", XSD_DOUBLE),
+            (("-INF", XSD_DOUBLE), 2, "-INF", XSD_DOUBLE),
+        ];
+
+        for ((value, datatype), count, expected, expected_type) in cases {
+            let quotient = numeric(value, datatype).divide(count);
+            assert_eq!(
+                (quotient.to_string().as_str(), quotient.datatype()),
+                (expected, expected_type),
+                "{value} / {count}"
+            );
+        }
+
+        assert_eq!(Numeric::from(42usize).to_string(), "42");
+    }
+}
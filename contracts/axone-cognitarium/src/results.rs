@@ -0,0 +1,279 @@
+use crate::msg::{Head, Results, ResultsFormat, Value, IRI};
+use cosmwasm_std::{to_json_vec, StdResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Serialize the results of a select query in the provided format, following the
+/// [SPARQL 1.1 Query Results JSON Format](https://www.w3.org/TR/sparql11-results-json/) and the
+/// [SPARQL 1.1 Query Results CSV and TSV Formats](https://www.w3.org/TR/sparql11-results-csv-tsv/)
+/// specifications.
+pub fn write(format: &ResultsFormat, head: &Head, results: &Results) -> StdResult<Vec<u8>> {
+    match format {
+        ResultsFormat::Json => write_json(head, results),
+        ResultsFormat::Csv => Ok(write_delimited(
+            head, results, ",", "\r\n", csv_header, csv_term,
+        )),
+        ResultsFormat::Tsv => Ok(write_delimited(
+            head, results, "\t", "\n", tsv_header, tsv_term,
+        )),
+    }
+}
+
+#[derive(Serialize)]
+struct JsonResults<'a> {
+    head: &'a Head,
+    results: JsonBindings<'a>,
+}
+
+#[derive(Serialize)]
+struct JsonBindings<'a> {
+    bindings: Vec<BTreeMap<&'a str, JsonTerm<'a>>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum JsonTerm<'a> {
+    #[serde(rename = "uri")]
+    Uri { value: &'a str },
+    #[serde(rename = "literal")]
+    Literal {
+        value: &'a str,
+        #[serde(rename = "xml:lang", skip_serializing_if = "Option::is_none")]
+        lang: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        datatype: Option<&'a str>,
+    },
+    #[serde(rename = "bnode")]
+    BNode { value: &'a str },
+}
+
+impl<'a> From<&'a Value> for JsonTerm<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::URI { value } => JsonTerm::Uri { value: iri(value) },
+            Value::Literal {
+                value,
+                lang,
+                datatype,
+            } => JsonTerm::Literal {
+                value,
+                lang: lang.as_deref(),
+                datatype: datatype.as_ref().map(iri),
+            },
+            Value::BlankNode { value } => JsonTerm::BNode { value },
+        }
+    }
+}
+
+fn write_json(head: &Head, results: &Results) -> StdResult<Vec<u8>> {
+    to_json_vec(&JsonResults {
+        head,
+        results: JsonBindings {
+            bindings: results
+                .bindings
+                .iter()
+                .map(|binding| {
+                    binding
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.into()))
+                        .collect()
+                })
+                .collect(),
+        },
+    })
+}
+
+/// Write the results as lines of delimited fields, the header line listing the variables and an
+/// unbound variable resulting in an empty field.
+fn write_delimited(
+    head: &Head,
+    results: &Results,
+    delimiter: &str,
+    eol: &str,
+    header: fn(&str) -> String,
+    term: fn(&Value) -> String,
+) -> Vec<u8> {
+    let mut out = String::new();
+    out.push_str(
+        &head
+            .vars
+            .iter()
+            .map(|var| header(var))
+            .collect::<Vec<_>>()
+            .join(delimiter),
+    );
+    out.push_str(eol);
+
+    for binding in &results.bindings {
+        out.push_str(
+            &head
+                .vars
+                .iter()
+                .map(|var| binding.get(var).map(term).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(delimiter),
+        );
+        out.push_str(eol);
+    }
+
+    out.into_bytes()
+}
+
+fn csv_header(var: &str) -> String {
+    csv_field(var)
+}
+
+/// Write the term as a CSV field, i.e. without any information about its kind, datatype or
+/// language.
+fn csv_term(value: &Value) -> String {
+    csv_field(&match value {
+        Value::URI { value } => iri(value).to_string(),
+        Value::Literal { value, .. } => value.clone(),
+        Value::BlankNode { value } => format!("_:{value}"),
+    })
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn tsv_header(var: &str) -> String {
+    format!("?{var}")
+}
+
+/// Write the term as a TSV field, i.e. using its [Turtle](https://www.w3.org/TR/turtle/)
+/// representation.
+fn tsv_term(value: &Value) -> String {
+    match value {
+        Value::URI { value } => format!("<{}>", iri(value)),
+        Value::Literal {
+            value,
+            lang: Some(lang),
+            ..
+        } => format!("\"{}\"@{lang}", escape_literal(value)),
+        Value::Literal {
+            value,
+            datatype: Some(datatype),
+            ..
+        } => format!("\"{}\"^^<{}>", escape_literal(value), iri(datatype)),
+        Value::Literal { value, .. } => format!("\"{}\"", escape_literal(value)),
+        Value::BlankNode { value } => format!("_:{value}"),
+    }
+}
+
+fn escape_literal(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The results only contain full IRIs, the prefixed ones are written as is.
+fn iri(iri: &IRI) -> &str {
+    match iri {
+        IRI::Prefixed(value) | IRI::Full(value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proper_write() {
+        let head = Head {
+            vars: vec!["x".to_string(), "y".to_string()],
+        };
+        let results = Results {
+            bindings: vec![
+                BTreeMap::from([
+                    (
+                        "x".to_string(),
+                        Value::URI {
+                            value: IRI::Full("http://example.org/a".to_string()),
+                        },
+                    ),
+                    (
+                        "y".to_string(),
+                        Value::Literal {
+                            value: "say \"hi\",\tthere".to_string(),
+                            lang: Some("en".to_string()),
+                            datatype: None,
+                        },
+                    ),
+                ]),
+                BTreeMap::from([
+                    (
+                        "x".to_string(),
+                        Value::BlankNode {
+                            value: "b0".to_string(),
+                        },
+                    ),
+                    (
+                        "y".to_string(),
+                        Value::Literal {
+                            value: "1".to_string(),
+                            lang: None,
+                            datatype: Some(IRI::Full(
+                                "http://www.w3.org/2001/XMLSchema#integer".to_string(),
+                            )),
+                        },
+                    ),
+                ]),
+                BTreeMap::from([(
+                    "y".to_string(),
+                    Value::Literal {
+                        value: "plain".to_string(),
+                        lang: None,
+                        datatype: None,
+                    },
+                )]),
+            ],
+        };
+
+        let cases = vec![
+            (
+                ResultsFormat::Json,
+                "{\"head\":{\"vars\":[\"x\",\"y\"]},\"results\":{\"bindings\":[\
+                {\"x\":{\"type\":\"uri\",\"value\":\"http://example.org/a\"},\
+                \"y\":{\"type\":\"literal\",\"value\":\"say \\\"hi\\\",\\tthere\",\"xml:lang\":\"en\"}},\
+                {\"x\":{\"type\":\"bnode\",\"value\":\"b0\"},\
+                \"y\":{\"type\":\"literal\",\"value\":\"1\",\"datatype\":\"http://www.w3.org/2001/XMLSchema#integer\"}},\
+                {\"y\":{\"type\":\"literal\",\"value\":\"plain\"}}]}}",
+            ),
+            (
+                ResultsFormat::Csv,
+                "x,y\r\n\
+                http://example.org/a,\"say \"\"hi\"\",\tthere\"\r\n\
+                _:b0,1\r\n\
+                ,plain\r\n",
+            ),
+            (
+                ResultsFormat::Tsv,
+                "?x\t?y\n\
+                <http://example.org/a>\t\"say \\\"hi\\\",\\tthere\"@en\n\
+                _:b0\t\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>\n\
+                \t\"plain\"\n",
+            ),
+        ];
+
+        for (format, expected) in cases {
+            assert_eq!(
+                String::from_utf8(write(&format, &head, &results).unwrap()).unwrap(),
+                expected,
+            );
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use cosmwasm_std::{StdError, StdResult};
+use std::fmt;
+
+/// Represents a position in the query text, lines and columns starting at 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Forge a parsing error located at this position.
+    pub fn error(&self, msg: impl fmt::Display) -> StdError {
+        StdError::parse_err(
+            "SPARQL query",
+            format!("{msg} at line {}, column {}", self.line, self.column),
+        )
+    }
+}
+
+/// Represents a lexical token of a SPARQL query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    IriRef(String),
+    /// A prefixed name, or a prefix alone when the local part is empty, e.g. `ex:`.
+    PrefixedName(String),
+    Var(String),
+    BlankNode(String),
+    String(String),
+    LangTag(String),
+    Integer(String),
+    Decimal(String),
+    Double(String),
+    /// A keyword, a function name, or one of the `a`, `true` and `false` terms.
+    Name(String),
+    Punct(&'static str),
+    Eof,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::IriRef(iri) => write!(f, "IRI <{iri}>"),
+            Token::PrefixedName(name) => write!(f, "prefixed name '{name}'"),
+            Token::Var(name) => write!(f, "variable ?{name}"),
+            Token::BlankNode(label) => write!(f, "blank node _:{label}"),
+            Token::String(_) => write!(f, "string"),
+            Token::LangTag(tag) => write!(f, "language tag @{tag}"),
+            Token::Integer(value) | Token::Decimal(value) | Token::Double(value) => {
+                write!(f, "number {value}")
+            }
+            Token::Name(name) => write!(f, "'{name}'"),
+            Token::Punct(punct) => write!(f, "'{punct}'"),
+            Token::Eof => write!(f, "end of query"),
+        }
+    }
+}
+
+/// The punctuations, the longest ones first so they take precedence.
+const PUNCTUATIONS: [&str; 25] = [
+    "^^", "||", "&&", "!=", "<=", ">=", "{", "}", "(", ")", "[", "]", ".", ";", ",", "*", "+", "?",
+    "^", "/", "|", "!", "=", "<", ">",
+];
+
+/// Split the query into tokens along with their position, the last one being [Token::Eof].
+pub fn tokenize(input: &str) -> StdResult<Vec<(Token, Position)>> {
+    let mut lexer = Lexer {
+        chars: input.chars().collect(),
+        offset: 0,
+        position: Position { line: 1, column: 1 },
+    };
+
+    let mut tokens = Vec::new();
+    loop {
+        lexer.skip_whitespaces();
+        let position = lexer.position;
+        match lexer.peek(0) {
+            None => {
+                tokens.push((Token::Eof, position));
+                return Ok(tokens);
+            }
+            Some(c) => tokens.push((lexer.next_token(c, position)?, position)),
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    offset: usize,
+    position: Position,
+}
+
+impl Lexer {
+    fn peek(&self, n: usize) -> Option<char> {
+        self.chars.get(self.offset + n).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.offset += 1;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn bump_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut value = String::new();
+        while let Some(c) = self.peek(0).filter(|c| predicate(*c)) {
+            value.push(c);
+            self.bump();
+        }
+        value
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(c) = self.peek(0) {
+            match c {
+                '#' => {
+                    self.bump_while(|c| c != '\n');
+                }
+                c if c.is_whitespace() => {
+                    self.bump();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn next_token(&mut self, c: char, position: Position) -> StdResult<Token> {
+        match c {
+            '<' => {
+                if let Some(iri) = self.iri_ref() {
+                    return Ok(Token::IriRef(iri));
+                }
+                self.punct(position)
+            }
+            '?' | '$' if self.peek(1).is_some_and(is_name_char) => {
+                self.bump();
+                Ok(Token::Var(self.bump_while(is_name_char)))
+            }
+            '_' if self.peek(1) == Some(':') => {
+                self.bump();
+                self.bump();
+                let label = self.bump_while(is_name_char);
+                if label.is_empty() {
+                    Err(position.error("empty blank node label"))?;
+                }
+                Ok(Token::BlankNode(label))
+            }
+            '"' | '\'' => self.string(c, position).map(Token::String),
+            '@' => {
+                self.bump();
+                let tag = self.bump_while(|c| c.is_ascii_alphanumeric() || c == '-');
+                if tag.is_empty() {
+                    Err(position.error("empty language tag"))?;
+                }
+                Ok(Token::LangTag(tag))
+            }
+            c if c.is_ascii_digit()
+                || (c == '.' && self.peek(1).is_some_and(|c| c.is_ascii_digit()))
+                || ((c == '+' || c == '-')
+                    && self.peek(1).is_some_and(|c| c.is_ascii_digit() || c == '.')) =>
+            {
+                Ok(self.number())
+            }
+            c if c.is_alphabetic() || c == ':' => Ok(self.name()),
+            _ => self.punct(position),
+        }
+    }
+
+    /// Read an IRI reference if the next characters form one, leaving the lexer untouched
+    /// otherwise so `<` can be read as an operator.
+    fn iri_ref(&mut self) -> Option<String> {
+        let mut len = 1;
+        loop {
+            match self.peek(len)? {
+                '>' => break,
+                c if c <= ' ' || "<\"{}|^`\\".contains(c) => return None,
+                _ => len += 1,
+            }
+        }
+
+        self.bump();
+        let iri = self.bump_while(|c| c != '>');
+        self.bump();
+        Some(iri)
+    }
+
+    fn string(&mut self, quote: char, position: Position) -> StdResult<String> {
+        let long = self.peek(1) == Some(quote) && self.peek(2) == Some(quote);
+        for _ in 0..if long { 3 } else { 1 } {
+            self.bump();
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                None => Err(position.error("unterminated string"))?,
+                Some(c) if c == quote => {
+                    if !long {
+                        return Ok(value);
+                    }
+                    if self.peek(0) == Some(quote) && self.peek(1) == Some(quote) {
+                        self.bump();
+                        self.bump();
+                        return Ok(value);
+                    }
+                    value.push(c);
+                }
+                Some('\n' | '\r') if !long => Err(position.error("unterminated string"))?,
+                Some('\\') => value.push(self.escape()?),
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> StdResult<char> {
+        let position = self.position;
+        Ok(match self.bump() {
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some(c @ ('"' | '\'' | '\\')) => c,
+            Some(c @ ('u' | 'U')) => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let mut hex = String::with_capacity(len);
+                for _ in 0..len {
+                    hex.extend(self.bump());
+                }
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| position.error("invalid unicode escape sequence"))?
+            }
+            _ => Err(position.error("invalid escape sequence"))?,
+        })
+    }
+
+    fn number(&mut self) -> Token {
+        let mut value = String::new();
+        if let Some(sign @ ('+' | '-')) = self.peek(0) {
+            value.push(sign);
+            self.bump();
+        }
+        value.push_str(&self.bump_while(|c| c.is_ascii_digit()));
+
+        let mut decimal = false;
+        if self.peek(0) == Some('.') && self.peek(1).is_some_and(|c| c.is_ascii_digit()) {
+            decimal = true;
+            value.push('.');
+            self.bump();
+            value.push_str(&self.bump_while(|c| c.is_ascii_digit()));
+        }
+
+        let exponent = match (self.peek(0), self.peek(1), self.peek(2)) {
+            (Some('e' | 'E'), Some(c), _) if c.is_ascii_digit() => 1,
+            (Some('e' | 'E'), Some('+' | '-'), Some(c)) if c.is_ascii_digit() => 2,
+            _ => 0,
+        };
+        if exponent == 0 {
+            return if decimal {
+                Token::Decimal(value)
+            } else {
+                Token::Integer(value)
+            };
+        }
+        for _ in 0..exponent {
+            value.extend(self.bump());
+        }
+        value.push_str(&self.bump_while(|c| c.is_ascii_digit()));
+        Token::Double(value)
+    }
+
+    /// Read a keyword or a prefixed name, which can't end with a dot so the one ending a triple
+    /// isn't consumed.
+    fn name(&mut self) -> Token {
+        let mut name = self.bump_while(|c| is_name_char(c) || c == '.');
+        let prefixed = self.peek(0) == Some(':');
+        if prefixed {
+            name.push(':');
+            self.bump();
+            name.push_str(&self.bump_while(|c| is_name_char(c) || ".:%".contains(c)));
+        }
+
+        let trimmed = name.trim_end_matches('.').len();
+        let trailing_dots = name.len() - trimmed;
+        name.truncate(trimmed);
+        self.offset -= trailing_dots;
+        self.position.column -= trailing_dots;
+
+        if prefixed {
+            Token::PrefixedName(name)
+        } else {
+            Token::Name(name)
+        }
+    }
+
+    fn punct(&mut self, position: Position) -> StdResult<Token> {
+        for punct in PUNCTUATIONS {
+            if punct
+                .chars()
+                .enumerate()
+                .all(|(i, c)| self.peek(i) == Some(c))
+            {
+                for _ in 0..punct.len() {
+                    self.bump();
+                }
+                return Ok(Token::Punct(punct));
+            }
+        }
+
+        Err(position.error(format!(
+            "unexpected character '{}'",
+            self.peek(0).unwrap_or_default()
+        )))
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proper_tokenize() {
+        let cases = vec![
+            (
+                "SELECT ?s WHERE { ?s a ex:Foo. }",
+                vec![
+                    Token::Name("SELECT".to_string()),
+                    Token::Var("s".to_string()),
+                    Token::Name("WHERE".to_string()),
+                    Token::Punct("{"),
+                    Token::Var("s".to_string()),
+                    Token::Name("a".to_string()),
+                    Token::PrefixedName("ex:Foo".to_string()),
+                    Token::Punct("."),
+                    Token::Punct("}"),
+                    Token::Eof,
+                ],
+            ),
+            (
+                "PREFIX : <http://ex.org/> # comment\n:a <= 'it\\'s'@en-GB \"\"\"x\"y\"\"\"^^xsd:string",
+                vec![
+                    Token::Name("PREFIX".to_string()),
+                    Token::PrefixedName(":".to_string()),
+                    Token::IriRef("http://ex.org/".to_string()),
+                    Token::PrefixedName(":a".to_string()),
+                    Token::Punct("<="),
+                    Token::String("it's".to_string()),
+                    Token::LangTag("en-GB".to_string()),
+                    Token::String("x\"y".to_string()),
+                    Token::Punct("^^"),
+                    Token::PrefixedName("xsd:string".to_string()),
+                    Token::Eof,
+                ],
+            ),
+            (
+                "1 -2.5 .5e3 ?x<?y _:b0 ^ex:p+/ex:q? $v",
+                vec![
+                    Token::Integer("1".to_string()),
+                    Token::Decimal("-2.5".to_string()),
+                    Token::Double(".5e3".to_string()),
+                    Token::Var("x".to_string()),
+                    Token::Punct("<"),
+                    Token::Var("y".to_string()),
+                    Token::BlankNode("b0".to_string()),
+                    Token::Punct("^"),
+                    Token::PrefixedName("ex:p".to_string()),
+                    Token::Punct("+"),
+                    Token::Punct("/"),
+                    Token::PrefixedName("ex:q".to_string()),
+                    Token::Punct("?"),
+                    Token::Var("v".to_string()),
+                    Token::Eof,
+                ],
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                tokenize(input).map(|tokens| tokens.into_iter().map(|(t, _)| t).collect()),
+                Ok(expected),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn tokenize_positions() {
+        let tokens = tokenize("SELECT\n  ?s\t{ }").unwrap();
+        assert_eq!(
+            tokens.into_iter().map(|(_, p)| p).collect::<Vec<_>>(),
+            vec![
+                Position { line: 1, column: 1 },
+                Position { line: 2, column: 3 },
+                Position { line: 2, column: 6 },
+                Position { line: 2, column: 8 },
+                Position { line: 2, column: 9 },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_tokenize() {
+        let cases = vec![
+            (
+                "SELECT ?s\n  \"abc",
+                "Error parsing into type SPARQL query: unterminated string at line 2, column 3",
+            ),
+            (
+                "?s ~",
+                "Error parsing into type SPARQL query: unexpected character '~' at line 1, column 4",
+            ),
+            (
+                "'\\q'",
+                "Error parsing into type SPARQL query: invalid escape sequence at line 1, column 3",
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                tokenize(input).map_err(|err| err.to_string()),
+                Err(expected.to_string())
+            );
+        }
+    }
+}
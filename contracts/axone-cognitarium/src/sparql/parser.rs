@@ -0,0 +1,1438 @@
+use crate::cursor;
+use crate::msg::{
+    Aggregate, AskQuery, ConstructQuery, DescribeQuery, Expression, Literal, NamedNodeOrLiteral,
+    Node, OrderCondition, Prefix, PropertyPath, SelectItem, SelectQuery, SimpleWhereCondition,
+    TripleConstructTemplate, TriplePattern, VarOrNamedNode, VarOrNamedNodeOrPath, VarOrNode,
+    VarOrNodeOrLiteral, WhereClause, WhereCondition, IRI,
+};
+use crate::rdf::{RDF_TYPE, XSD_BOOLEAN, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER};
+use crate::sparql::lexer::{tokenize, Position, Token};
+use cosmwasm_std::StdResult;
+
+/// Represents a parsed SPARQL query, in the form of the corresponding query message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    Select(SelectQuery),
+    Construct(ConstructQuery),
+    Describe(DescribeQuery),
+    Ask(AskQuery),
+}
+
+/// Parse a SPARQL query text into its query message.
+///
+/// Only the subset of the SPARQL 1.1 grammar the query engine supports is accepted, the errors
+/// reporting the line and column they occur at.
+pub fn parse(query: &str) -> StdResult<Query> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        index: 0,
+        prefixes: Vec::new(),
+    };
+    parser.parse_query()
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize,
+    prefixes: Vec<Prefix>,
+}
+
+type Triple = (VarOrNode, VarOrNamedNodeOrPath, VarOrNodeOrLiteral);
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.tokens
+            .get(self.index)
+            .or(self.tokens.last())
+            .map_or(&Token::Eof, |(token, _)| token)
+    }
+
+    fn position(&self) -> Position {
+        self.tokens
+            .get(self.index)
+            .or(self.tokens.last())
+            .map_or(Position { line: 1, column: 1 }, |(_, position)| *position)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.index < self.tokens.len() {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn unexpected<T>(&self) -> StdResult<T> {
+        Err(self.position().error(format!("unexpected {}", self.peek())))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Name(name) if name.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = self.is_keyword(keyword);
+        if is_keyword {
+            self.next();
+        }
+        is_keyword
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> StdResult<()> {
+        if !self.eat_keyword(keyword) {
+            Err(self
+                .position()
+                .error(format!("expected '{keyword}', found {}", self.peek())))?;
+        }
+        Ok(())
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let is_punct = matches!(self.peek(), Token::Punct(p) if *p == punct);
+        if is_punct {
+            self.next();
+        }
+        is_punct
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> StdResult<()> {
+        if !self.eat_punct(punct) {
+            Err(self
+                .position()
+                .error(format!("expected '{punct}', found {}", self.peek())))?;
+        }
+        Ok(())
+    }
+
+    fn parse_query(&mut self) -> StdResult<Query> {
+        self.parse_prologue()?;
+
+        let query = if self.eat_keyword("SELECT") {
+            Query::Select(self.parse_select()?)
+        } else if self.eat_keyword("CONSTRUCT") {
+            Query::Construct(self.parse_construct()?)
+        } else if self.eat_keyword("DESCRIBE") {
+            Query::Describe(self.parse_describe()?)
+        } else if self.eat_keyword("ASK") {
+            self.eat_keyword("WHERE");
+            Query::Ask(AskQuery {
+                prefixes: self.prefixes.clone(),
+                r#where: self.parse_group()?,
+            })
+        } else {
+            return Err(self.position().error(format!(
+                "expected 'SELECT', 'CONSTRUCT', 'DESCRIBE' or 'ASK', found {}",
+                self.peek()
+            )));
+        };
+
+        if *self.peek() != Token::Eof {
+            self.unexpected()?;
+        }
+        Ok(query)
+    }
+
+    fn parse_prologue(&mut self) -> StdResult<()> {
+        while self.eat_keyword("PREFIX") {
+            let position = self.position();
+            let prefix = match self.next() {
+                Token::PrefixedName(name) if name.ends_with(':') => {
+                    name.trim_end_matches(':').to_string()
+                }
+                token => Err(position.error(format!("expected prefix, found {token}")))?,
+            };
+            let position = self.position();
+            let namespace = match self.next() {
+                Token::IriRef(iri) => iri,
+                token => Err(position.error(format!("expected IRI, found {token}")))?,
+            };
+            self.prefixes.retain(|p| p.prefix != prefix);
+            self.prefixes.push(Prefix { prefix, namespace });
+        }
+        Ok(())
+    }
+
+    fn parse_select(&mut self) -> StdResult<SelectQuery> {
+        let distinct = self.eat_keyword("DISTINCT");
+
+        let mut select = Vec::new();
+        let all = self.eat_punct("*");
+        if !all {
+            loop {
+                match self.peek().clone() {
+                    Token::Var(name) => {
+                        self.next();
+                        select.push(SelectItem::Variable(name));
+                    }
+                    Token::Punct("(") => {
+                        self.next();
+                        let aggregate = self.parse_aggregate()?;
+                        self.expect_keyword("AS")?;
+                        let variable = self.parse_var()?;
+                        self.expect_punct(")")?;
+                        select.push(SelectItem::Aggregate {
+                            aggregate,
+                            variable,
+                        });
+                    }
+                    _ if select.is_empty() => self.unexpected()?,
+                    _ => break,
+                }
+            }
+        }
+
+        self.eat_keyword("WHERE");
+        let r#where = self.parse_group()?;
+        if all {
+            select = Self::where_variables(&r#where)
+                .into_iter()
+                .map(SelectItem::Variable)
+                .collect();
+        }
+
+        let mut group_by = Vec::new();
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by.push(self.parse_var()?);
+            while let Token::Var(name) = self.peek().clone() {
+                self.next();
+                group_by.push(name);
+            }
+        }
+
+        let mut having = None;
+        if self.eat_keyword("HAVING") {
+            let mut constraints = vec![self.parse_constraint()?];
+            while matches!(self.peek(), Token::Punct("(") | Token::Name(_))
+                && !["ORDER", "LIMIT", "OFFSET"]
+                    .iter()
+                    .any(|keyword| self.is_keyword(keyword))
+            {
+                constraints.push(self.parse_constraint()?);
+            }
+            having = Some(match constraints.len() {
+                1 => constraints.remove(0),
+                _ => Expression::And(constraints),
+            });
+        }
+
+        let mut order_by = Vec::new();
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            order_by.push(self.parse_order_condition()?);
+            while matches!(self.peek(), Token::Var(_))
+                || self.is_keyword("ASC")
+                || self.is_keyword("DESC")
+            {
+                order_by.push(self.parse_order_condition()?);
+            }
+        }
+
+        let mut limit = None;
+        let mut after = None;
+        loop {
+            if limit.is_none() && self.eat_keyword("LIMIT") {
+                limit = Some(self.parse_integer()?);
+            } else if after.is_none() && self.eat_keyword("OFFSET") {
+                after = Some(cursor::encode(self.parse_integer()? as usize));
+            } else {
+                break;
+            }
+        }
+
+        Ok(SelectQuery {
+            prefixes: self.prefixes.clone(),
+            select,
+            r#where,
+            group_by,
+            having,
+            distinct,
+            order_by,
+            limit,
+            after,
+        })
+    }
+
+    fn parse_construct(&mut self) -> StdResult<ConstructQuery> {
+        let (construct, r#where) = if self.eat_keyword("WHERE") {
+            (vec![], self.parse_group()?)
+        } else {
+            self.expect_punct("{")?;
+            let mut construct = Vec::new();
+            while !self.eat_punct("}") {
+                let position = self.position();
+                for (subject, predicate, object) in self.parse_triples()? {
+                    let predicate = match predicate {
+                        VarOrNamedNodeOrPath::Variable(v) => VarOrNamedNode::Variable(v),
+                        VarOrNamedNodeOrPath::NamedNode(iri) => VarOrNamedNode::NamedNode(iri),
+                        VarOrNamedNodeOrPath::Path(_) => {
+                            Err(position.error("property path can't be used in a template"))?
+                        }
+                    };
+                    construct.push(TripleConstructTemplate {
+                        subject,
+                        predicate,
+                        object,
+                        graph: None,
+                    });
+                }
+                if !self.eat_punct(".") && !matches!(self.peek(), Token::Punct("}")) {
+                    self.unexpected()?;
+                }
+            }
+            self.eat_keyword("WHERE");
+            (construct, self.parse_group()?)
+        };
+
+        Ok(ConstructQuery {
+            prefixes: self.prefixes.clone(),
+            construct,
+            r#where,
+            after: self.parse_offset()?,
+        })
+    }
+
+    fn parse_describe(&mut self) -> StdResult<DescribeQuery> {
+        let resource = match self.peek().clone() {
+            Token::Var(name) => {
+                self.next();
+                VarOrNamedNode::Variable(name)
+            }
+            _ => VarOrNamedNode::NamedNode(self.parse_iri()?),
+        };
+
+        let r#where = if self.eat_keyword("WHERE") || matches!(self.peek(), Token::Punct("{")) {
+            self.parse_group()?
+        } else {
+            vec![]
+        };
+
+        Ok(DescribeQuery {
+            prefixes: self.prefixes.clone(),
+            resource,
+            r#where,
+            after: self.parse_offset()?,
+        })
+    }
+
+    fn parse_offset(&mut self) -> StdResult<Option<String>> {
+        Ok(if self.eat_keyword("OFFSET") {
+            Some(cursor::encode(self.parse_integer()? as usize))
+        } else {
+            None
+        })
+    }
+
+    fn parse_group(&mut self) -> StdResult<WhereClause> {
+        self.expect_punct("{")?;
+        if self.eat_keyword("SELECT") {
+            // The prefixes of the enclosing query being inherited, the sub query declares none.
+            let query = SelectQuery {
+                prefixes: vec![],
+                ..self.parse_select()?
+            };
+            self.expect_punct("}")?;
+            return Ok(vec![WhereCondition::SubQuery(Box::new(query))]);
+        }
+
+        let mut clause = Vec::new();
+        loop {
+            match self.peek().clone() {
+                Token::Punct("}") => {
+                    self.next();
+                    return Ok(clause);
+                }
+                Token::Punct(".") => {
+                    self.next();
+                }
+                Token::Punct("{") => {
+                    let mut groups = vec![self.parse_group()?];
+                    while self.eat_keyword("UNION") {
+                        groups.push(self.parse_group()?);
+                    }
+                    if groups.len() > 1 {
+                        clause.push(WhereCondition::Union(groups));
+                    } else {
+                        clause.extend(groups.remove(0));
+                    }
+                }
+                Token::Name(_) if self.eat_keyword("OPTIONAL") => {
+                    clause.push(WhereCondition::Optional(self.parse_group()?));
+                }
+                Token::Name(_) if self.eat_keyword("FILTER") => {
+                    if self.eat_keyword("NOT") {
+                        self.expect_keyword("EXISTS")?;
+                        clause.push(WhereCondition::NotExists(self.parse_group()?));
+                    } else {
+                        clause.push(WhereCondition::Filter(self.parse_constraint()?));
+                    }
+                }
+                Token::Name(_) if self.eat_keyword("GRAPH") => {
+                    let graph = match self.peek().clone() {
+                        Token::Var(name) => {
+                            self.next();
+                            VarOrNamedNode::Variable(name)
+                        }
+                        _ => VarOrNamedNode::NamedNode(self.parse_iri()?),
+                    };
+                    clause.push(WhereCondition::Graph {
+                        graph,
+                        r#where: self.parse_group()?,
+                    });
+                }
+                Token::Name(_) if self.eat_keyword("MINUS") => {
+                    clause.push(WhereCondition::Minus(self.parse_group()?));
+                }
+                Token::Name(_) if self.eat_keyword("VALUES") => {
+                    clause.push(self.parse_values()?);
+                }
+                Token::Name(_) if self.eat_keyword("BIND") => {
+                    self.expect_punct("(")?;
+                    let expression = self.parse_expression()?;
+                    self.expect_keyword("AS")?;
+                    let variable = self.parse_var()?;
+                    self.expect_punct(")")?;
+                    clause.push(WhereCondition::Bind {
+                        expression,
+                        variable,
+                    });
+                }
+                _ => {
+                    for (subject, predicate, object) in self.parse_triples()? {
+                        clause.push(WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
+                            TriplePattern {
+                                subject,
+                                predicate,
+                                object,
+                            },
+                        )));
+                    }
+                    if !self.eat_punct(".")
+                        && !matches!(self.peek(), Token::Punct("{" | "}") | Token::Name(_))
+                    {
+                        self.unexpected()?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parse an inline table of values, either for a single variable `?v { value... }` or for
+    /// several ones `(?v...) { (value...)... }`, `UNDEF` leaving the variable unbound.
+    fn parse_values(&mut self) -> StdResult<WhereCondition> {
+        let single = !self.eat_punct("(");
+        let mut variables = vec![self.parse_var()?];
+        if !single {
+            while let Token::Var(name) = self.peek().clone() {
+                self.next();
+                variables.push(name);
+            }
+            self.expect_punct(")")?;
+        }
+
+        self.expect_punct("{")?;
+        let mut values = Vec::new();
+        while !self.eat_punct("}") {
+            if single {
+                values.push(vec![self.parse_value()?]);
+                continue;
+            }
+
+            let position = self.position();
+            self.expect_punct("(")?;
+            let mut row = Vec::with_capacity(variables.len());
+            while !self.eat_punct(")") {
+                row.push(self.parse_value()?);
+            }
+            if row.len() != variables.len() {
+                Err(position.error("values row size doesn't match the variables"))?;
+            }
+            values.push(row);
+        }
+
+        Ok(WhereCondition::Values { variables, values })
+    }
+
+    fn parse_value(&mut self) -> StdResult<Option<NamedNodeOrLiteral>> {
+        if self.eat_keyword("UNDEF") {
+            return Ok(None);
+        }
+        Ok(match self.peek() {
+            Token::IriRef(_) | Token::PrefixedName(_) => {
+                Some(NamedNodeOrLiteral::NamedNode(self.parse_iri()?))
+            }
+            _ => Some(NamedNodeOrLiteral::Literal(self.parse_literal()?)),
+        })
+    }
+
+    /// Parse the triples sharing a same subject, the predicates being separated by `;` and the
+    /// objects of a same predicate by `,`.
+    fn parse_triples(&mut self) -> StdResult<Vec<Triple>> {
+        let subject = match self.peek().clone() {
+            Token::Var(name) => {
+                self.next();
+                VarOrNode::Variable(name)
+            }
+            Token::BlankNode(label) => {
+                self.next();
+                VarOrNode::Node(Node::BlankNode(label))
+            }
+            Token::IriRef(_) | Token::PrefixedName(_) => {
+                VarOrNode::Node(Node::NamedNode(self.parse_iri()?))
+            }
+            _ => self.unexpected()?,
+        };
+
+        let mut triples = Vec::new();
+        loop {
+            let predicate = match self.peek().clone() {
+                Token::Var(name) => {
+                    self.next();
+                    VarOrNamedNodeOrPath::Variable(name)
+                }
+                _ => match self.parse_path()? {
+                    PropertyPath::NamedNode(iri) => VarOrNamedNodeOrPath::NamedNode(iri),
+                    path => VarOrNamedNodeOrPath::Path(path),
+                },
+            };
+
+            loop {
+                let object = self.parse_object()?;
+                triples.push((subject.clone(), predicate.clone(), object));
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+
+            if !self.eat_punct(";") {
+                return Ok(triples);
+            }
+            while self.eat_punct(";") {}
+            if matches!(self.peek(), Token::Punct("." | "}")) {
+                return Ok(triples);
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> StdResult<VarOrNodeOrLiteral> {
+        Ok(match self.peek().clone() {
+            Token::Var(name) => {
+                self.next();
+                VarOrNodeOrLiteral::Variable(name)
+            }
+            Token::BlankNode(label) => {
+                self.next();
+                VarOrNodeOrLiteral::Node(Node::BlankNode(label))
+            }
+            Token::IriRef(_) | Token::PrefixedName(_) => {
+                VarOrNodeOrLiteral::Node(Node::NamedNode(self.parse_iri()?))
+            }
+            _ => VarOrNodeOrLiteral::Literal(self.parse_literal()?),
+        })
+    }
+
+    fn parse_path(&mut self) -> StdResult<PropertyPath> {
+        let mut alternatives = vec![self.parse_path_sequence()?];
+        while self.eat_punct("|") {
+            alternatives.push(self.parse_path_sequence()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => PropertyPath::Alternative(alternatives),
+        })
+    }
+
+    fn parse_path_sequence(&mut self) -> StdResult<PropertyPath> {
+        let mut sequence = vec![self.parse_path_element()?];
+        while self.eat_punct("/") {
+            sequence.push(self.parse_path_element()?);
+        }
+        Ok(match sequence.len() {
+            1 => sequence.remove(0),
+            _ => PropertyPath::Sequence(sequence),
+        })
+    }
+
+    fn parse_path_element(&mut self) -> StdResult<PropertyPath> {
+        let inverse = self.eat_punct("^");
+
+        let mut path = match self.peek() {
+            Token::Punct("(") => {
+                self.next();
+                let path = self.parse_path()?;
+                self.expect_punct(")")?;
+                path
+            }
+            Token::Name(name) if name == "a" => {
+                self.next();
+                PropertyPath::NamedNode(IRI::Full(RDF_TYPE.to_string()))
+            }
+            _ => PropertyPath::NamedNode(self.parse_iri()?),
+        };
+
+        if self.eat_punct("*") {
+            path = PropertyPath::ZeroOrMore(Box::new(path));
+        } else if self.eat_punct("+") {
+            path = PropertyPath::OneOrMore(Box::new(path));
+        } else if self.eat_punct("?") {
+            path = PropertyPath::ZeroOrOne(Box::new(path));
+        }
+
+        Ok(if inverse {
+            PropertyPath::Inverse(Box::new(path))
+        } else {
+            path
+        })
+    }
+
+    fn parse_aggregate(&mut self) -> StdResult<Aggregate> {
+        let position = self.position();
+        let name = match self.next() {
+            Token::Name(name) => name.to_ascii_uppercase(),
+            token => Err(position.error(format!("expected aggregate, found {token}")))?,
+        };
+        self.expect_punct("(")?;
+        let distinct = self.eat_keyword("DISTINCT");
+
+        let aggregate = match name.as_str() {
+            "COUNT" if self.eat_punct("*") => Aggregate::Count {
+                expression: None,
+                distinct,
+            },
+            "COUNT" => Aggregate::Count {
+                expression: Some(self.parse_expression()?),
+                distinct,
+            },
+            "SUM" => Aggregate::Sum {
+                expression: self.parse_expression()?,
+                distinct,
+            },
+            "AVG" => Aggregate::Avg {
+                expression: self.parse_expression()?,
+                distinct,
+            },
+            "MIN" => Aggregate::Min(self.parse_expression()?),
+            "MAX" => Aggregate::Max(self.parse_expression()?),
+            "SAMPLE" => Aggregate::Sample(self.parse_expression()?),
+            "GROUP_CONCAT" => {
+                let expression = self.parse_expression()?;
+                let mut separator = None;
+                if self.eat_punct(";") {
+                    self.expect_keyword("SEPARATOR")?;
+                    self.expect_punct("=")?;
+                    separator = Some(self.parse_string()?);
+                }
+                Aggregate::GroupConcat {
+                    expression,
+                    distinct,
+                    separator,
+                }
+            }
+            _ => Err(position.error(format!("unsupported aggregate '{name}'")))?,
+        };
+
+        self.expect_punct(")")?;
+        Ok(aggregate)
+    }
+
+    fn parse_order_condition(&mut self) -> StdResult<OrderCondition> {
+        if self.eat_keyword("ASC") {
+            self.expect_punct("(")?;
+            let variable = self.parse_var()?;
+            self.expect_punct(")")?;
+            return Ok(OrderCondition::Asc(variable));
+        }
+        if self.eat_keyword("DESC") {
+            self.expect_punct("(")?;
+            let variable = self.parse_var()?;
+            self.expect_punct(")")?;
+            return Ok(OrderCondition::Desc(variable));
+        }
+        self.parse_var().map(OrderCondition::Asc)
+    }
+
+    /// Parse a constraint, i.e. a bracketed expression or a function call.
+    fn parse_constraint(&mut self) -> StdResult<Expression> {
+        match self.peek() {
+            Token::Punct("(") => {
+                self.next();
+                let expr = self.parse_expression()?;
+                self.expect_punct(")")?;
+                Ok(expr)
+            }
+            Token::Name(_) => self.parse_function(),
+            _ => self.unexpected(),
+        }
+    }
+
+    fn parse_expression(&mut self) -> StdResult<Expression> {
+        let mut operands = vec![self.parse_and_expression()?];
+        while self.eat_punct("||") {
+            operands.push(self.parse_and_expression()?);
+        }
+        Ok(match operands.len() {
+            1 => operands.remove(0),
+            _ => Expression::Or(operands),
+        })
+    }
+
+    fn parse_and_expression(&mut self) -> StdResult<Expression> {
+        let mut operands = vec![self.parse_relational_expression()?];
+        while self.eat_punct("&&") {
+            operands.push(self.parse_relational_expression()?);
+        }
+        Ok(match operands.len() {
+            1 => operands.remove(0),
+            _ => Expression::And(operands),
+        })
+    }
+
+    fn parse_relational_expression(&mut self) -> StdResult<Expression> {
+        let left = self.parse_unary_expression()?;
+
+        let operator: fn(Box<Expression>, Box<Expression>) -> Expression = match self.peek() {
+            Token::Punct("=") => Expression::Equal,
+            Token::Punct("!=") => Expression::NotEqual,
+            Token::Punct(">") => Expression::Greater,
+            Token::Punct(">=") => Expression::GreaterOrEqual,
+            Token::Punct("<") => Expression::Less,
+            Token::Punct("<=") => Expression::LessOrEqual,
+            _ => return Ok(left),
+        };
+        self.next();
+
+        Ok(operator(
+            Box::new(left),
+            Box::new(self.parse_unary_expression()?),
+        ))
+    }
+
+    fn parse_unary_expression(&mut self) -> StdResult<Expression> {
+        if self.eat_punct("!") {
+            return Ok(Expression::Not(Box::new(self.parse_unary_expression()?)));
+        }
+
+        match self.peek().clone() {
+            Token::Punct("(") => {
+                self.next();
+                let expr = self.parse_expression()?;
+                self.expect_punct(")")?;
+                Ok(expr)
+            }
+            Token::Var(name) => {
+                self.next();
+                Ok(Expression::Variable(name))
+            }
+            Token::IriRef(_) | Token::PrefixedName(_) => {
+                self.parse_iri().map(Expression::NamedNode)
+            }
+            Token::Name(name) if !["true", "false"].contains(&name.as_str()) => {
+                self.parse_function()
+            }
+            _ => self.parse_literal().map(Expression::Literal),
+        }
+    }
+
+    fn parse_function(&mut self) -> StdResult<Expression> {
+        let position = self.position();
+        let name = match self.next() {
+            Token::Name(name) => name.to_ascii_uppercase(),
+            token => Err(position.error(format!("expected function, found {token}")))?,
+        };
+        self.expect_punct("(")?;
+
+        let unary: fn(Box<Expression>) -> Expression = match name.as_str() {
+            "BOUND" => {
+                let variable = self.parse_var()?;
+                self.expect_punct(")")?;
+                return Ok(Expression::Bound(variable));
+            }
+            "REGEX" => {
+                let text = self.parse_expression()?;
+                self.expect_punct(",")?;
+                let pattern = self.parse_string()?;
+                let flags = if self.eat_punct(",") {
+                    Some(self.parse_string()?)
+                } else {
+                    None
+                };
+                self.expect_punct(")")?;
+                return Ok(Expression::Regex(Box::new(text), pattern, flags));
+            }
+            "CONTAINS" | "STRSTARTS" => {
+                let left = Box::new(self.parse_expression()?);
+                self.expect_punct(",")?;
+                let right = Box::new(self.parse_expression()?);
+                self.expect_punct(")")?;
+                return Ok(if name == "CONTAINS" {
+                    Expression::Contains(left, right)
+                } else {
+                    Expression::StrStarts(left, right)
+                });
+            }
+            "LANG" => Expression::Lang,
+            "DATATYPE" => Expression::Datatype,
+            "ISIRI" | "ISURI" => Expression::IsIri,
+            "ISBLANK" => Expression::IsBlank,
+            "ISLITERAL" => Expression::IsLiteral,
+            _ => Err(position.error(format!("unsupported function '{name}'")))?,
+        };
+
+        let expr = self.parse_expression()?;
+        self.expect_punct(")")?;
+        Ok(unary(Box::new(expr)))
+    }
+
+    fn parse_literal(&mut self) -> StdResult<Literal> {
+        let position = self.position();
+        let typed = |value: String, datatype: &str| Literal::TypedValue {
+            value,
+            datatype: IRI::Full(datatype.to_string()),
+        };
+
+        Ok(match self.next() {
+            Token::String(value) => match self.peek().clone() {
+                Token::LangTag(language) => {
+                    self.next();
+                    Literal::LanguageTaggedString { value, language }
+                }
+                Token::Punct("^^") => {
+                    self.next();
+                    Literal::TypedValue {
+                        value,
+                        datatype: self.parse_iri()?,
+                    }
+                }
+                _ => Literal::Simple(value),
+            },
+            Token::Integer(value) => typed(value, XSD_INTEGER),
+            Token::Decimal(value) => typed(value, XSD_DECIMAL),
+            Token::Double(value) => typed(value, XSD_DOUBLE),
+            Token::Name(value) if value == "true" || value == "false" => typed(value, XSD_BOOLEAN),
+            token => Err(position.error(format!("unexpected {token}")))?,
+        })
+    }
+
+    fn parse_string(&mut self) -> StdResult<String> {
+        let position = self.position();
+        match self.next() {
+            Token::String(value) => Ok(value),
+            token => Err(position.error(format!("expected string, found {token}"))),
+        }
+    }
+
+    fn parse_integer(&mut self) -> StdResult<u32> {
+        let position = self.position();
+        match self.next() {
+            Token::Integer(value) => value
+                .parse()
+                .map_err(|_| position.error(format!("invalid integer {value}"))),
+            token => Err(position.error(format!("expected integer, found {token}"))),
+        }
+    }
+
+    fn parse_var(&mut self) -> StdResult<String> {
+        let position = self.position();
+        match self.next() {
+            Token::Var(name) => Ok(name),
+            token => Err(position.error(format!("expected variable, found {token}"))),
+        }
+    }
+
+    fn parse_iri(&mut self) -> StdResult<IRI> {
+        let position = self.position();
+        match self.next() {
+            Token::IriRef(iri) => Ok(IRI::Full(iri)),
+            Token::PrefixedName(name) => {
+                let prefix = name.split(':').next().unwrap_or_default();
+                if !self.prefixes.iter().any(|p| p.prefix == prefix) {
+                    Err(position.error(format!("undeclared prefix '{prefix}'")))?;
+                }
+                Ok(IRI::Prefixed(name))
+            }
+            token => Err(position.error(format!("expected IRI, found {token}"))),
+        }
+    }
+
+    /// Collect the variables of the triple patterns of the clause, in their order of appearance.
+    fn where_variables(clause: &WhereClause) -> Vec<String> {
+        let mut variables = Vec::new();
+        let mut push = |name: &String| {
+            if !variables.contains(name) {
+                variables.push(name.clone());
+            }
+        };
+
+        let mut stack: Vec<&WhereCondition> = clause.iter().rev().collect();
+        while let Some(condition) = stack.pop() {
+            match condition {
+                WhereCondition::Simple(SimpleWhereCondition::TriplePattern(pattern)) => {
+                    if let VarOrNode::Variable(name) = &pattern.subject {
+                        push(name);
+                    }
+                    if let VarOrNamedNodeOrPath::Variable(name) = &pattern.predicate {
+                        push(name);
+                    }
+                    if let VarOrNodeOrLiteral::Variable(name) = &pattern.object {
+                        push(name);
+                    }
+                }
+                WhereCondition::Optional(clause) => stack.extend(clause.iter().rev()),
+                WhereCondition::Graph { graph, r#where } => {
+                    if let VarOrNamedNode::Variable(name) = graph {
+                        push(name);
+                    }
+                    stack.extend(r#where.iter().rev());
+                }
+                WhereCondition::Union(clauses) => {
+                    stack.extend(clauses.iter().rev().flat_map(|clause| clause.iter().rev()));
+                }
+                WhereCondition::Values { variables, .. } => variables.iter().for_each(&mut push),
+                WhereCondition::Bind { variable, .. } => push(variable),
+                WhereCondition::SubQuery(query) => query
+                    .select
+                    .iter()
+                    .for_each(|item| push(&item.variable().to_string())),
+                WhereCondition::Filter(_)
+                | WhereCondition::Minus(_)
+                | WhereCondition::NotExists(_) => {}
+            }
+        }
+        variables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefix(prefix: &str, namespace: &str) -> Prefix {
+        Prefix {
+            prefix: prefix.to_string(),
+            namespace: namespace.to_string(),
+        }
+    }
+
+    fn pattern(
+        subject: VarOrNode,
+        predicate: VarOrNamedNodeOrPath,
+        object: VarOrNodeOrLiteral,
+    ) -> WhereCondition {
+        WhereCondition::Simple(SimpleWhereCondition::TriplePattern(TriplePattern {
+            subject,
+            predicate,
+            object,
+        }))
+    }
+
+    fn var(name: &str) -> VarOrNode {
+        VarOrNode::Variable(name.to_string())
+    }
+
+    fn var_object(name: &str) -> VarOrNodeOrLiteral {
+        VarOrNodeOrLiteral::Variable(name.to_string())
+    }
+
+    fn prefixed(name: &str) -> IRI {
+        IRI::Prefixed(name.to_string())
+    }
+
+    #[test]
+    fn parse_select() {
+        let cases = vec![
+            (
+                "PREFIX ex: <http://example.com/>\nSELECT ?s ?o WHERE { ?s ex:p ?o } LIMIT 10",
+                SelectQuery {
+                    prefixes: vec![prefix("ex", "http://example.com/")],
+                    select: vec![
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Variable("o".to_string()),
+                    ],
+                    r#where: vec![pattern(
+                        var("s"),
+                        VarOrNamedNodeOrPath::NamedNode(prefixed("ex:p")),
+                        var_object("o"),
+                    )],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: Some(10),
+                    after: None,
+                },
+            ),
+            (
+                r#"select distinct * {
+                    ?s a <http://example.com/T> ; <http://example.com/p> "v"@en, 1.5 , true .
+                    _:b <http://example.com/p> "x"^^<http://example.com/dt>
+                    OPTIONAL { ?s ?p ?o }
+                    { ?s <http://example.com/q> ?q } UNION { ?r <http://example.com/q> -3 }
+                    FILTER (?o != "x" && (BOUND(?q) || !isIRI(?s)))
+                    FILTER regex(?o, "^a", "i")
+                } OFFSET 30"#,
+                SelectQuery {
+                    prefixes: vec![],
+                    select: vec![
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Variable("p".to_string()),
+                        SelectItem::Variable("o".to_string()),
+                        SelectItem::Variable("q".to_string()),
+                        SelectItem::Variable("r".to_string()),
+                    ],
+                    r#where: vec![
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::NamedNode(IRI::Full(RDF_TYPE.to_string())),
+                            VarOrNodeOrLiteral::Node(Node::NamedNode(IRI::Full(
+                                "http://example.com/T".to_string(),
+                            ))),
+                        ),
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                "http://example.com/p".to_string(),
+                            )),
+                            VarOrNodeOrLiteral::Literal(Literal::LanguageTaggedString {
+                                value: "v".to_string(),
+                                language: "en".to_string(),
+                            }),
+                        ),
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                "http://example.com/p".to_string(),
+                            )),
+                            VarOrNodeOrLiteral::Literal(Literal::TypedValue {
+                                value: "1.5".to_string(),
+                                datatype: IRI::Full(XSD_DECIMAL.to_string()),
+                            }),
+                        ),
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                "http://example.com/p".to_string(),
+                            )),
+                            VarOrNodeOrLiteral::Literal(Literal::TypedValue {
+                                value: "true".to_string(),
+                                datatype: IRI::Full(XSD_BOOLEAN.to_string()),
+                            }),
+                        ),
+                        pattern(
+                            VarOrNode::Node(Node::BlankNode("b".to_string())),
+                            VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                "http://example.com/p".to_string(),
+                            )),
+                            VarOrNodeOrLiteral::Literal(Literal::TypedValue {
+                                value: "x".to_string(),
+                                datatype: IRI::Full("http://example.com/dt".to_string()),
+                            }),
+                        ),
+                        WhereCondition::Optional(vec![pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            var_object("o"),
+                        )]),
+                        WhereCondition::Union(vec![
+                            vec![pattern(
+                                var("s"),
+                                VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                    "http://example.com/q".to_string(),
+                                )),
+                                var_object("q"),
+                            )],
+                            vec![pattern(
+                                var("r"),
+                                VarOrNamedNodeOrPath::NamedNode(IRI::Full(
+                                    "http://example.com/q".to_string(),
+                                )),
+                                VarOrNodeOrLiteral::Literal(Literal::TypedValue {
+                                    value: "-3".to_string(),
+                                    datatype: IRI::Full(XSD_INTEGER.to_string()),
+                                }),
+                            )],
+                        ]),
+                        WhereCondition::Filter(Expression::And(vec![
+                            Expression::NotEqual(
+                                Box::new(Expression::Variable("o".to_string())),
+                                Box::new(Expression::Literal(Literal::Simple("x".to_string()))),
+                            ),
+                            Expression::Or(vec![
+                                Expression::Bound("q".to_string()),
+                                Expression::Not(Box::new(Expression::IsIri(Box::new(
+                                    Expression::Variable("s".to_string()),
+                                )))),
+                            ]),
+                        ])),
+                        WhereCondition::Filter(Expression::Regex(
+                            Box::new(Expression::Variable("o".to_string())),
+                            "^a".to_string(),
+                            Some("i".to_string()),
+                        )),
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: true,
+                    order_by: vec![],
+                    limit: None,
+                    after: Some(cursor::encode(30)),
+                },
+            ),
+            (
+                "PREFIX ex: <http://example.com/>
+                SELECT ?s (COUNT(DISTINCT ?o) AS ?n) (GROUP_CONCAT(?o; SEPARATOR=\", \") AS ?all)
+                WHERE { ?s ex:p/^ex:q|ex:r* ?o }
+                GROUP BY ?s HAVING (?n > 1) ORDER BY DESC(?n) ?s",
+                SelectQuery {
+                    prefixes: vec![prefix("ex", "http://example.com/")],
+                    select: vec![
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Aggregate {
+                            aggregate: Aggregate::Count {
+                                expression: Some(Expression::Variable("o".to_string())),
+                                distinct: true,
+                            },
+                            variable: "n".to_string(),
+                        },
+                        SelectItem::Aggregate {
+                            aggregate: Aggregate::GroupConcat {
+                                expression: Expression::Variable("o".to_string()),
+                                distinct: false,
+                                separator: Some(", ".to_string()),
+                            },
+                            variable: "all".to_string(),
+                        },
+                    ],
+                    r#where: vec![pattern(
+                        var("s"),
+                        VarOrNamedNodeOrPath::Path(PropertyPath::Alternative(vec![
+                            PropertyPath::Sequence(vec![
+                                PropertyPath::NamedNode(prefixed("ex:p")),
+                                PropertyPath::Inverse(Box::new(PropertyPath::NamedNode(prefixed(
+                                    "ex:q",
+                                )))),
+                            ]),
+                            PropertyPath::ZeroOrMore(Box::new(PropertyPath::NamedNode(prefixed(
+                                "ex:r",
+                            )))),
+                        ])),
+                        var_object("o"),
+                    )],
+                    group_by: vec!["s".to_string()],
+                    having: Some(Expression::Greater(
+                        Box::new(Expression::Variable("n".to_string())),
+                        Box::new(Expression::Literal(Literal::TypedValue {
+                            value: "1".to_string(),
+                            datatype: IRI::Full(XSD_INTEGER.to_string()),
+                        })),
+                    )),
+                    distinct: false,
+                    order_by: vec![
+                        OrderCondition::Desc("n".to_string()),
+                        OrderCondition::Asc("s".to_string()),
+                    ],
+                    limit: None,
+                    after: None,
+                },
+            ),
+            (
+                "SELECT * WHERE {
+                    VALUES (?s ?o) { (<http://example.com/a> UNDEF) (<http://example.com/b> \"b\") }
+                    ?s ?p ?o BIND(LANG(?o) AS ?l)
+                }",
+                SelectQuery {
+                    prefixes: vec![],
+                    select: vec![
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Variable("o".to_string()),
+                        SelectItem::Variable("p".to_string()),
+                        SelectItem::Variable("l".to_string()),
+                    ],
+                    r#where: vec![
+                        WhereCondition::Values {
+                            variables: vec!["s".to_string(), "o".to_string()],
+                            values: vec![
+                                vec![
+                                    Some(NamedNodeOrLiteral::NamedNode(IRI::Full(
+                                        "http://example.com/a".to_string(),
+                                    ))),
+                                    None,
+                                ],
+                                vec![
+                                    Some(NamedNodeOrLiteral::NamedNode(IRI::Full(
+                                        "http://example.com/b".to_string(),
+                                    ))),
+                                    Some(NamedNodeOrLiteral::Literal(Literal::Simple(
+                                        "b".to_string(),
+                                    ))),
+                                ],
+                            ],
+                        },
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            var_object("o"),
+                        ),
+                        WhereCondition::Bind {
+                            expression: Expression::Lang(Box::new(Expression::Variable(
+                                "o".to_string(),
+                            ))),
+                            variable: "l".to_string(),
+                        },
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                },
+            ),
+            (
+                "SELECT ?s { ?s ?p ?o MINUS { ?s ?p \"x\" } FILTER NOT EXISTS { ?o ?p ?s } }",
+                SelectQuery {
+                    prefixes: vec![],
+                    select: vec![SelectItem::Variable("s".to_string())],
+                    r#where: vec![
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            var_object("o"),
+                        ),
+                        WhereCondition::Minus(vec![pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            VarOrNodeOrLiteral::Literal(Literal::Simple("x".to_string())),
+                        )]),
+                        WhereCondition::NotExists(vec![pattern(
+                            var("o"),
+                            VarOrNamedNodeOrPath::Variable("p".to_string()),
+                            var_object("s"),
+                        )]),
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                },
+            ),
+            (
+                "PREFIX ex: <http://example.com/>\nSELECT * { ?s ex:p ?o { SELECT ?o { ?o ex:q ?x } ORDER BY DESC(?x) LIMIT 1 } }",
+                SelectQuery {
+                    prefixes: vec![prefix("ex", "http://example.com/")],
+                    select: vec![
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Variable("o".to_string()),
+                    ],
+                    r#where: vec![
+                        pattern(
+                            var("s"),
+                            VarOrNamedNodeOrPath::NamedNode(prefixed("ex:p")),
+                            var_object("o"),
+                        ),
+                        WhereCondition::SubQuery(Box::new(SelectQuery {
+                            prefixes: vec![],
+                            select: vec![SelectItem::Variable("o".to_string())],
+                            r#where: vec![pattern(
+                                var("o"),
+                                VarOrNamedNodeOrPath::NamedNode(prefixed("ex:q")),
+                                var_object("x"),
+                            )],
+                            group_by: vec![],
+                            having: None,
+                            distinct: false,
+                            order_by: vec![OrderCondition::Desc("x".to_string())],
+                            limit: Some(1),
+                            after: None,
+                        })),
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                },
+            ),
+            (
+                "PREFIX ex: <http://example.com/>\nSELECT * { GRAPH ?g { ?s ex:p ?o } GRAPH ex:g { ?s ex:q ?x } }",
+                SelectQuery {
+                    prefixes: vec![prefix("ex", "http://example.com/")],
+                    select: vec![
+                        SelectItem::Variable("g".to_string()),
+                        SelectItem::Variable("s".to_string()),
+                        SelectItem::Variable("o".to_string()),
+                        SelectItem::Variable("x".to_string()),
+                    ],
+                    r#where: vec![
+                        WhereCondition::Graph {
+                            graph: VarOrNamedNode::Variable("g".to_string()),
+                            r#where: vec![pattern(
+                                var("s"),
+                                VarOrNamedNodeOrPath::NamedNode(prefixed("ex:p")),
+                                var_object("o"),
+                            )],
+                        },
+                        WhereCondition::Graph {
+                            graph: VarOrNamedNode::NamedNode(prefixed("ex:g")),
+                            r#where: vec![pattern(
+                                var("s"),
+                                VarOrNamedNodeOrPath::NamedNode(prefixed("ex:q")),
+                                var_object("x"),
+                            )],
+                        },
+                    ],
+                    group_by: vec![],
+                    having: None,
+                    distinct: false,
+                    order_by: vec![],
+                    limit: None,
+                    after: None,
+                },
+            ),
+        ];
+
+        for (query, expected) in cases {
+            assert_eq!(parse(query), Ok(Query::Select(expected)), "{query}");
+        }
+    }
+
+    #[test]
+    fn parse_construct_describe_ask() {
+        let cases = vec![
+            (
+                "PREFIX ex: <http://example.com/> CONSTRUCT { ?s ex:p ?o . } WHERE { ?s ex:q+ ?o }",
+                Query::Construct(ConstructQuery {
+                    prefixes: vec![prefix("ex", "http://example.com/")],
+                    construct: vec![TripleConstructTemplate {
+                        subject: var("s"),
+                        predicate: VarOrNamedNode::NamedNode(prefixed("ex:p")),
+                        object: var_object("o"),
+                        graph: None,
+                    }],
+                    r#where: vec![pattern(
+                        var("s"),
+                        VarOrNamedNodeOrPath::Path(PropertyPath::OneOrMore(Box::new(
+                            PropertyPath::NamedNode(prefixed("ex:q")),
+                        ))),
+                        var_object("o"),
+                    )],
+                    after: None,
+                }),
+            ),
+            (
+                "CONSTRUCT WHERE { ?s ?p ?o }",
+                Query::Construct(ConstructQuery {
+                    prefixes: vec![],
+                    construct: vec![],
+                    r#where: vec![pattern(
+                        var("s"),
+                        VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        var_object("o"),
+                    )],
+                    after: None,
+                }),
+            ),
+            (
+                "DESCRIBE <http://example.com/s>",
+                Query::Describe(DescribeQuery {
+                    prefixes: vec![],
+                    resource: VarOrNamedNode::NamedNode(IRI::Full(
+                        "http://example.com/s".to_string(),
+                    )),
+                    r#where: vec![],
+                    after: None,
+                }),
+            ),
+            (
+                "DESCRIBE ?s { ?s ?p \"x\" } OFFSET 2",
+                Query::Describe(DescribeQuery {
+                    prefixes: vec![],
+                    resource: VarOrNamedNode::Variable("s".to_string()),
+                    r#where: vec![pattern(
+                        var("s"),
+                        VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        VarOrNodeOrLiteral::Literal(Literal::Simple("x".to_string())),
+                    )],
+                    after: Some(cursor::encode(2)),
+                }),
+            ),
+            (
+                "ASK { <http://example.com/s> ?p ?o }",
+                Query::Ask(AskQuery {
+                    prefixes: vec![],
+                    r#where: vec![pattern(
+                        VarOrNode::Node(Node::NamedNode(IRI::Full(
+                            "http://example.com/s".to_string(),
+                        ))),
+                        VarOrNamedNodeOrPath::Variable("p".to_string()),
+                        var_object("o"),
+                    )],
+                }),
+            ),
+        ];
+
+        for (query, expected) in cases {
+            assert_eq!(parse(query), Ok(expected), "{query}");
+        }
+    }
+
+    #[test]
+    fn invalid_parse() {
+        let cases = vec![
+            (
+                "INSERT DATA { <http://example.com/s> <http://example.com/p> \"o\" }",
+                "expected 'SELECT', 'CONSTRUCT', 'DESCRIBE' or 'ASK', found 'INSERT' at line 1, column 1",
+            ),
+            (
+                "SELECT ?s WHERE {\n  ?s ?p ?o\n  ?s ?p ?o }",
+                "unexpected variable ?s at line 3, column 3",
+            ),
+            (
+                "SELECT ?s WHERE { ?s ex:p ?o }",
+                "undeclared prefix 'ex' at line 1, column 22",
+            ),
+            (
+                "SELECT ?s WHERE { ?s ?p ?o } LIMIT ?s",
+                "expected integer, found variable ?s at line 1, column 36",
+            ),
+            (
+                "SELECT ?s WHERE { ?s ?p ?o FILTER(STRLEN(?o) > 2) }",
+                "unsupported function 'STRLEN' at line 1, column 35",
+            ),
+            (
+                "SELECT (STRLEN(?o) AS ?l) WHERE { ?s ?p ?o }",
+                "unsupported aggregate 'STRLEN' at line 1, column 9",
+            ),
+            (
+                "CONSTRUCT { ?s <http://example.com/p>+ ?o } WHERE { ?s ?p ?o }",
+                "property path can't be used in a template at line 1, column 13",
+            ),
+            (
+                "SELECT ?s WHERE { ?s ?p ?o } LIMIT 1 LIMIT 2",
+                "unexpected 'LIMIT' at line 1, column 38",
+            ),
+            (
+                "DESCRIBE ?s { ?s ?p ?o } LIMIT 1",
+                "unexpected 'LIMIT' at line 1, column 26",
+            ),
+            (
+                "SELECT ?s WHERE { VALUES (?s ?o) { (<http://example.com/a>) } }",
+                "values row size doesn't match the variables at line 1, column 36",
+            ),
+            (
+                "SELECT ?s WHERE { ?s ?p ?o ",
+                "unexpected end of query at line 1, column 28",
+            ),
+        ];
+
+        for (query, expected) in cases {
+            assert_eq!(
+                parse(query).map_err(|err| err.to_string()),
+                Err(format!("Error parsing into type SPARQL query: {expected}")),
+                "{query}"
+            );
+        }
+    }
+}
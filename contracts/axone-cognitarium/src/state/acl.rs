@@ -0,0 +1,119 @@
+use crate::msg;
+use cosmwasm_std::Addr;
+use cw_storage_plus::Map;
+use serde::{Deserialize, Serialize};
+
+/// The roles granted to the addresses, the owner of the store being implicitly granted all of them
+/// without any restriction.
+pub const GRANTS: Map<'_, &Addr, Vec<Grant>> = Map::new("grants");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Grant {
+    pub role: Role,
+    /// The scope the role is restricted to, the whole store if not set.
+    pub scope: Option<AccessScope>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Writer,
+    Deleter,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AccessScope {
+    Graph(String),
+    Namespace(String),
+}
+
+impl AccessScope {
+    /// Tells whether a triple is in the scope, given the IRI of its subject if named and the IRI
+    /// of its graph if any.
+    pub fn contains(&self, subject: Option<&str>, graph: Option<&str>) -> bool {
+        match self {
+            AccessScope::Graph(iri) => graph == Some(iri.as_str()),
+            AccessScope::Namespace(ns) => subject.is_some_and(|s| s.starts_with(ns.as_str())),
+        }
+    }
+}
+
+impl From<msg::Role> for Role {
+    fn from(value: msg::Role) -> Self {
+        match value {
+            msg::Role::Writer => Role::Writer,
+            msg::Role::Deleter => Role::Deleter,
+        }
+    }
+}
+
+impl From<Role> for msg::Role {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Writer => msg::Role::Writer,
+            Role::Deleter => msg::Role::Deleter,
+        }
+    }
+}
+
+impl From<msg::AccessScope> for AccessScope {
+    fn from(value: msg::AccessScope) -> Self {
+        match value {
+            msg::AccessScope::Graph(iri) => AccessScope::Graph(iri),
+            msg::AccessScope::Namespace(ns) => AccessScope::Namespace(ns),
+        }
+    }
+}
+
+impl From<AccessScope> for msg::AccessScope {
+    fn from(value: AccessScope) -> Self {
+        match value {
+            AccessScope::Graph(iri) => msg::AccessScope::Graph(iri),
+            AccessScope::Namespace(ns) => msg::AccessScope::Namespace(ns),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_contains() {
+        let cases = vec![
+            (
+                AccessScope::Graph("https://example.org/g".to_string()),
+                Some("https://example.org/s"),
+                Some("https://example.org/g"),
+                true,
+            ),
+            (
+                AccessScope::Graph("https://example.org/g".to_string()),
+                Some("https://example.org/s"),
+                None,
+                false,
+            ),
+            (
+                AccessScope::Namespace("https://example.org/".to_string()),
+                Some("https://example.org/s"),
+                None,
+                true,
+            ),
+            (
+                AccessScope::Namespace("https://example.org/".to_string()),
+                Some("https://other.org/s"),
+                Some("https://example.org/g"),
+                false,
+            ),
+            (
+                AccessScope::Namespace("https://example.org/".to_string()),
+                None,
+                None,
+                false,
+            ),
+        ];
+
+        for (scope, subject, graph, expected) in cases {
+            assert_eq!(scope.contains(subject, graph), expected);
+        }
+    }
+}
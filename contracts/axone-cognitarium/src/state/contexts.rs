@@ -0,0 +1,5 @@
+use cw_storage_plus::Map;
+
+/// The JSON-LD context documents registered by their IRI, being the only remote contexts the
+/// inserted JSON-LD data can reference.
+pub const JSON_LD_CONTEXTS: Map<'_, &str, String> = Map::new("json_ld_context");
@@ -1,9 +1,17 @@
+mod acl;
 mod blank_nodes;
+mod contexts;
 mod namespaces;
+mod provenance;
+mod statistics;
 mod store;
 mod triples;
 
+pub use acl::*;
 pub use blank_nodes::*;
+pub use contexts::*;
 pub use namespaces::*;
+pub use provenance::*;
+pub use statistics::*;
 pub use store::*;
 pub use triples::*;
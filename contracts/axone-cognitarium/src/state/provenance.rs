@@ -0,0 +1,51 @@
+use crate::msg;
+use crate::state::TriplePK;
+use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Item, Map};
+use serde::{Deserialize, Serialize};
+
+/// A counter serving as batch unique identifier generator.
+pub const BATCH_ID_COUNTER: Item<'_, u64> = Item::new("batch_id");
+
+/// The batches of triples inserted in the store, by identifier.
+pub const BATCHES: Map<'_, u64, Batch> = Map::new("batches");
+
+/// Links the triples, by primary key, to the batch they've been inserted with. The triples inserted
+/// before the provenance tracking aren't linked to any batch.
+pub const TRIPLE_BATCHES: Map<'_, TriplePK<'_>, u64> = Map::new("triple_batches");
+
+/// Represents the submission of triples to the store by a single message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Batch {
+    pub sender: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+impl Batch {
+    pub fn new(sender: Addr, block: &BlockInfo) -> Self {
+        Self {
+            sender,
+            height: block.height,
+            time: block.time,
+        }
+    }
+
+    /// Saves the batch under a new identifier, which is returned.
+    pub fn save(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        let id = BATCH_ID_COUNTER.may_load(storage)?.unwrap_or_default() + 1;
+        BATCH_ID_COUNTER.save(storage, &id)?;
+        BATCHES.save(storage, id, self)?;
+
+        Ok(id)
+    }
+
+    pub fn into_response(self, id: u64) -> msg::TripleProvenanceResponse {
+        msg::TripleProvenanceResponse {
+            batch_id: id,
+            sender: self.sender.to_string(),
+            block_height: self.height,
+            block_time: self.time,
+        }
+    }
+}
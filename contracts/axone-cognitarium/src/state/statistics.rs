@@ -0,0 +1,87 @@
+use crate::state::{Object, Subject, Triple};
+use cosmwasm_std::{StdResult, Storage};
+use cw_storage_plus::Map;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The number of triples per predicate, referenced by its key.
+pub const PREDICATE_STATS: Map<'_, &[u8], u128> = Map::new("predicate_stat");
+
+/// The number of triples per namespace of their named subject and object.
+pub const NAMESPACE_STATS: Map<'_, u128, NamespaceStat> = Map::new("namespace_stat");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamespaceStat {
+    /// The number of triples whose subject is a named node of the namespace.
+    pub subject_count: u128,
+
+    /// The number of triples whose object is a named node of the namespace.
+    pub object_count: u128,
+}
+
+/// [StatisticsBatchService] accumulates the changes of the cardinality statistics implied by the
+/// insertion and deletion of triples, in order to write them once to the state.
+#[derive(Default)]
+pub struct StatisticsBatchService {
+    predicates: BTreeMap<Vec<u8>, i128>,
+    subjects: BTreeMap<u128, i128>,
+    objects: BTreeMap<u128, i128>,
+}
+
+impl StatisticsBatchService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the triple as inserted if `diff` is positive, or as deleted if negative. This is
+    /// applied to the in-memory batch only, [Self::flush] must be called to write the changes to
+    /// the state.
+    pub fn count(&mut self, triple: &Triple, diff: i128) {
+        *self.predicates.entry(triple.predicate.key()).or_default() += diff;
+        if let Subject::Named(n) = &triple.subject {
+            *self.subjects.entry(n.namespace).or_default() += diff;
+        }
+        if let Object::Named(n) = &triple.object {
+            *self.objects.entry(n.namespace).or_default() += diff;
+        }
+    }
+
+    /// Write the accumulated changes to the state, the statistics dropping to zero being removed.
+    pub fn flush(&mut self, storage: &mut dyn Storage) -> StdResult<()> {
+        for (key, diff) in std::mem::take(&mut self.predicates) {
+            let count = apply(PREDICATE_STATS.may_load(storage, &key)?.unwrap_or(0), diff);
+            if count == 0 {
+                PREDICATE_STATS.remove(storage, &key);
+            } else {
+                PREDICATE_STATS.save(storage, &key, &count)?;
+            }
+        }
+
+        let mut namespaces: BTreeMap<u128, (i128, i128)> = BTreeMap::new();
+        for (ns, diff) in std::mem::take(&mut self.subjects) {
+            namespaces.entry(ns).or_default().0 = diff;
+        }
+        for (ns, diff) in std::mem::take(&mut self.objects) {
+            namespaces.entry(ns).or_default().1 = diff;
+        }
+        for (ns, (subject_diff, object_diff)) in namespaces {
+            let mut stat = NAMESPACE_STATS.may_load(storage, ns)?.unwrap_or_default();
+            stat.subject_count = apply(stat.subject_count, subject_diff);
+            stat.object_count = apply(stat.object_count, object_diff);
+            if stat == NamespaceStat::default() {
+                NAMESPACE_STATS.remove(storage, ns);
+            } else {
+                NAMESPACE_STATS.save(storage, ns, &stat)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn apply(count: u128, diff: i128) -> u128 {
+    if diff < 0 {
+        count.saturating_sub(diff.unsigned_abs())
+    } else {
+        count.saturating_add(diff.unsigned_abs())
+    }
+}
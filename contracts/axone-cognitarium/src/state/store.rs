@@ -11,24 +11,34 @@ pub struct Store {
     pub owner: Addr,
     pub limits: StoreLimits,
     pub stat: StoreStat,
+    #[serde(default)]
+    pub strict_typing: bool,
+    #[serde(default)]
+    pub versioning: bool,
 }
 
 impl Store {
-    pub fn new(owner: Addr, limits: StoreLimits) -> Store {
+    pub fn new(owner: Addr, limits: StoreLimits, strict_typing: bool, versioning: bool) -> Store {
         Store {
             owner,
             limits,
             stat: StoreStat::default(),
+            strict_typing,
+            versioning,
         }
     }
 }
 
+/// The grants being stored apart from the [Store], they're left empty.
 impl From<Store> for StoreResponse {
     fn from(value: Store) -> Self {
         Self {
             owner: value.owner.into(),
             limits: value.limits.into(),
             stat: value.stat.into(),
+            strict_typing: value.strict_typing,
+            versioning: value.versioning,
+            grants: vec![],
         }
     }
 }
@@ -42,6 +52,8 @@ pub struct StoreLimits {
     pub max_query_variable_count: u32,
     pub max_insert_data_byte_size: Uint128,
     pub max_insert_data_triple_count: Uint128,
+    #[serde(default = "msg::StoreLimitsInput::default_max_query_path_depth")]
+    pub max_query_path_depth: u32,
 }
 
 impl From<msg::StoreLimitsInput> for StoreLimits {
@@ -54,6 +66,22 @@ impl From<msg::StoreLimitsInput> for StoreLimits {
             max_query_variable_count: value.max_query_variable_count,
             max_insert_data_byte_size: value.max_insert_data_byte_size,
             max_insert_data_triple_count: value.max_insert_data_triple_count,
+            max_query_path_depth: value.max_query_path_depth,
+        }
+    }
+}
+
+impl From<msg::StoreLimits> for StoreLimits {
+    fn from(value: msg::StoreLimits) -> Self {
+        StoreLimits {
+            max_triple_count: value.max_triple_count,
+            max_byte_size: value.max_byte_size,
+            max_triple_byte_size: value.max_triple_byte_size,
+            max_query_limit: value.max_query_limit,
+            max_query_variable_count: value.max_query_variable_count,
+            max_insert_data_byte_size: value.max_insert_data_byte_size,
+            max_insert_data_triple_count: value.max_insert_data_triple_count,
+            max_query_path_depth: value.max_query_path_depth,
         }
     }
 }
@@ -68,6 +96,7 @@ impl From<StoreLimits> for msg::StoreLimits {
             max_query_variable_count: value.max_query_variable_count,
             max_insert_data_byte_size: value.max_insert_data_byte_size,
             max_insert_data_triple_count: value.max_insert_data_triple_count,
+            max_query_path_depth: value.max_query_path_depth,
         }
     }
 }
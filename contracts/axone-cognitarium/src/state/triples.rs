@@ -1,22 +1,38 @@
 use blake3::Hash;
 use cosmwasm_std::StdResult;
-use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Map, MultiIndex};
 use serde::{Deserialize, Serialize};
 
 /// Represents a triple primary key as a tuple of:
 /// - Object hash
 /// - Predicate in a binary format
-/// - Subject in a binary format
+/// - Subject in a binary format, followed by the graph name for a triple of a named graph (see
+///   [Subject::key_in_graph])
 pub type TriplePK<'a> = (&'a [u8], Vec<u8>, Vec<u8>);
 
 pub struct TripleIndexes<'a> {
     pub subject_and_predicate: MultiIndex<'a, (Vec<u8>, Vec<u8>), Triple, TriplePK<'a>>,
+    /// Indexes the triples by graph name and subject, the triples of the default graph being
+    /// indexed under an empty graph name.
+    pub graph_and_subject: MultiIndex<'a, (Vec<u8>, Vec<u8>), Triple, TriplePK<'a>>,
+    /// Indexes the triples by predicate and subject, allowing to range over the triples of a
+    /// predicate without scanning the whole store.
+    pub predicate_and_subject: MultiIndex<'a, (Vec<u8>, Vec<u8>), Triple, TriplePK<'a>>,
 }
 
 impl IndexList<Triple> for TripleIndexes<'_> {
     fn get_indexes(&self) -> Box<dyn Iterator<Item = &'_ dyn Index<Triple>> + '_> {
         let subject_and_predicate: &dyn Index<Triple> = &self.subject_and_predicate;
-        Box::new(vec![subject_and_predicate].into_iter())
+        let graph_and_subject: &dyn Index<Triple> = &self.graph_and_subject;
+        let predicate_and_subject: &dyn Index<Triple> = &self.predicate_and_subject;
+        Box::new(
+            vec![
+                subject_and_predicate,
+                graph_and_subject,
+                predicate_and_subject,
+            ]
+            .into_iter(),
+        )
     }
 }
 
@@ -29,20 +45,77 @@ pub fn triples<'a>() -> IndexedMap<'a, TriplePK<'a>, Triple, TripleIndexes<'a>>
                 "TRIPLE",
                 "TRIPLE__SUBJECT_PREDICATE",
             ),
+            graph_and_subject: MultiIndex::new(
+                |_pk, triple| {
+                    (
+                        triple.graph.as_ref().map(Node::key).unwrap_or_default(),
+                        triple.subject.key(),
+                    )
+                },
+                "TRIPLE",
+                "TRIPLE__GRAPH_SUBJECT",
+            ),
+            predicate_and_subject: MultiIndex::new(
+                |_pk, triple| (triple.predicate.key(), triple.subject.key()),
+                "TRIPLE",
+                "TRIPLE__PREDICATE_SUBJECT",
+            ),
         },
     )
 }
 
+/// Indexes the triples by the heights their periods of validity have been closed at, allowing to
+/// prune the history without scanning the whole store.
+pub const TRIPLE_EXPIRATIONS: Map<'_, (u64, TriplePK<'_>), ()> = Map::new("triple_expirations");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Triple {
     pub subject: Subject,
     pub predicate: Predicate,
     pub object: Object,
+    /// The name of the graph the triple belongs to, the default graph if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph: Option<Node>,
+    /// The periods the triple has been part of the store over, in chronological order, only the
+    /// last one being possibly still open. Left empty when the store isn't versioned, the triple
+    /// then being valid until deleted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validity: Vec<Validity>,
+}
+
+/// Represents a period, in block heights, a triple has been part of the store over.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Validity {
+    /// The height the triple has been inserted at.
+    pub valid_from: u64,
+    /// The height the triple has been deleted at, excluded from the period.
+    pub valid_to: Option<u64>,
+}
+
+impl Validity {
+    pub fn contains(&self, height: u64) -> bool {
+        self.valid_from <= height && self.valid_to.map_or(true, |to| height < to)
+    }
 }
 
 impl Triple {
+    /// Tells whether the triple is part of the store at the given height, or currently if not
+    /// provided.
+    pub fn is_valid_at(&self, height: Option<u64>) -> bool {
+        match (self.validity.last(), height) {
+            (None, _) => true,
+            (Some(last), None) => last.valid_to.is_none(),
+            (Some(_), Some(height)) => self.validity.iter().any(|v| v.contains(height)),
+        }
+    }
+
+    /// Provides the subject part of the triple primary key.
+    pub fn subject_key(&self) -> Vec<u8> {
+        self.subject.key_in_graph(self.graph.as_ref())
+    }
+
     pub fn namespaces(&self) -> Vec<u128> {
-        let mut namespaces = Vec::with_capacity(3);
+        let mut namespaces = Vec::with_capacity(4);
         if let Subject::Named(n) = &self.subject {
             namespaces.push(n.namespace);
         }
@@ -55,11 +128,15 @@ impl Triple {
             _ => {}
         }
 
+        if let Some(graph) = &self.graph {
+            namespaces.push(graph.namespace);
+        }
+
         namespaces
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Subject {
     Named(Node),
     Blank(BlankNode),
@@ -86,11 +163,23 @@ impl Subject {
             }
         }
     }
+
+    /// Provides the key of the subject followed by the given graph name if any, separated by a
+    /// `0xFF` byte which can't be part of neither the UTF-8 encoded value of a named node nor the
+    /// fixed size key of a blank node, keeping the key of the default graph unchanged.
+    pub fn key_in_graph(&self, graph: Option<&Node>) -> Vec<u8> {
+        let mut key = self.key();
+        if let Some(graph) = graph {
+            key.push(0xFF);
+            key.extend(graph.key());
+        }
+        key
+    }
 }
 
 pub type Predicate = Node;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Object {
     Named(Node),
     Blank(BlankNode),
@@ -134,7 +223,7 @@ impl Object {
 pub const BLANK_NODE_SIZE: usize = 16usize;
 pub type BlankNode = u128;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Node {
     pub namespace: u128,
     pub value: String,
@@ -158,7 +247,7 @@ impl Node {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Literal {
     Simple { value: String },
     I18NString { value: String, language: String },
@@ -169,6 +258,43 @@ pub enum Literal {
 mod test {
     use super::*;
 
+    #[test]
+    fn triple_is_valid_at() {
+        let triple = |validity: Vec<(u64, Option<u64>)>| Triple {
+            subject: Subject::Blank(0u128),
+            predicate: Node {
+                namespace: 0,
+                value: "p".to_string(),
+            },
+            object: Object::Blank(1u128),
+            graph: None,
+            validity: validity
+                .into_iter()
+                .map(|(valid_from, valid_to)| Validity {
+                    valid_from,
+                    valid_to,
+                })
+                .collect(),
+        };
+        let cases = vec![
+            (triple(vec![]), None, true),
+            (triple(vec![]), Some(5), true),
+            (triple(vec![(10, None)]), None, true),
+            (triple(vec![(10, None)]), Some(9), false),
+            (triple(vec![(10, None)]), Some(10), true),
+            (triple(vec![(10, Some(20))]), None, false),
+            (triple(vec![(10, Some(20))]), Some(19), true),
+            (triple(vec![(10, Some(20))]), Some(20), false),
+            (triple(vec![(10, Some(20)), (30, None)]), Some(25), false),
+            (triple(vec![(10, Some(20)), (30, None)]), Some(15), true),
+            (triple(vec![(10, Some(20)), (30, None)]), None, true),
+        ];
+
+        for (triple, height, expected) in cases {
+            assert_eq!(triple.is_valid_at(height), expected);
+        }
+    }
+
     #[test]
     fn object_hash() {
         let cases = vec![
@@ -281,4 +407,38 @@ mod test {
             assert_ne!(case.0.as_hash(), case.1.as_hash())
         }
     }
+
+    #[test]
+    fn subject_key_in_graph() {
+        let node = |value: &str| Node {
+            namespace: 0,
+            value: value.to_string(),
+        };
+        let cases = vec![
+            (
+                (Subject::Named(node("a")), None),
+                (Subject::Named(node("a")), Some(node("g"))),
+            ),
+            (
+                (Subject::Named(node("a")), Some(node("g1"))),
+                (Subject::Named(node("a")), Some(node("g2"))),
+            ),
+            (
+                (Subject::Named(node("a")), Some(node("bg"))),
+                (Subject::Named(node("ab")), Some(node("g"))),
+            ),
+            (
+                (Subject::Blank(0u128), Some(node("g"))),
+                (Subject::Blank(0u128), None),
+            ),
+        ];
+
+        for ((s1, g1), (s2, g2)) in cases {
+            assert_ne!(s1.key_in_graph(g1.as_ref()), s2.key_in_graph(g2.as_ref()));
+        }
+        assert_eq!(
+            Subject::Named(node("a")).key_in_graph(None),
+            Subject::Named(node("a")).key()
+        );
+    }
 }
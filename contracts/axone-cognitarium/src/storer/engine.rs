@@ -1,13 +1,19 @@
 use crate::error::StoreError;
+use crate::querier::ResolvedQuad;
+use crate::rdf;
+use crate::rdf::XsdValue;
 use crate::state::{
-    triples, Literal, NamespaceBatchService, Node, Object, Store, Subject, Triple,
-    BLANK_NODE_IDENTIFIER_COUNTER, BLANK_NODE_SIZE, STORE,
+    triples, AccessScope, Batch, Literal, NamespaceBatchService, NamespaceResolver, Node, Object,
+    StatisticsBatchService, Store, Subject, Triple, TriplePK, Validity,
+    BLANK_NODE_IDENTIFIER_COUNTER, BLANK_NODE_SIZE, STORE, TRIPLE_BATCHES, TRIPLE_EXPIRATIONS,
 };
 use crate::ContractError;
 use axone_rdf::normalize::IdentifierIssuer;
 use axone_rdf::serde::TripleReader;
 use axone_rdf::uri::explode_iri;
-use cosmwasm_std::{StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::PrefixBound;
+use either::{Left, Right};
 use rio_api::model;
 use rio_api::model::Term;
 use std::io::BufRead;
@@ -17,6 +23,12 @@ pub struct StoreEngine<'a> {
     storage: &'a mut dyn Storage,
     store: Store,
     ns_batch_svc: NamespaceBatchService,
+    stats_batch_svc: StatisticsBatchService,
+    insert_scopes: Option<Vec<AccessScope>>,
+    delete_scopes: Option<Vec<AccessScope>>,
+    batch: Option<Batch>,
+    batch_id: Option<u64>,
+    height: u64,
     blank_node_id_issuer: IdentifierIssuer,
     initial_triple_count: Uint128,
     initial_byte_size: Uint128,
@@ -31,26 +43,110 @@ impl<'a> StoreEngine<'a> {
             storage,
             store: store.clone(),
             ns_batch_svc,
+            stats_batch_svc: StatisticsBatchService::new(),
+            insert_scopes: None,
+            delete_scopes: None,
+            batch: None,
+            batch_id: None,
+            height: 0,
             blank_node_id_issuer: IdentifierIssuer::new("", blank_node_id_counter),
             initial_triple_count: store.stat.triple_count,
             initial_byte_size: store.stat.byte_size,
         })
     }
 
+    /// Restrict the insertion to the triples in at least one of the scopes if any, an error being
+    /// returned for any triple out of them.
+    pub fn with_insert_scopes(mut self, scopes: Option<Vec<AccessScope>>) -> Self {
+        self.insert_scopes = scopes;
+        self
+    }
+
+    /// Restrict the deletion to the triples in at least one of the scopes if any, an error being
+    /// returned for any existing triple out of them.
+    pub fn with_delete_scopes(mut self, scopes: Option<Vec<AccessScope>>) -> Self {
+        self.delete_scopes = scopes;
+        self
+    }
+
+    /// Links the inserted triples to the batch if any, the already existing ones keeping their
+    /// original provenance. The batch is only saved once a triple is actually inserted.
+    pub fn with_batch(mut self, batch: Option<Batch>) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    /// Sets the block height the triples are inserted and deleted at, recorded in their periods of
+    /// validity if the store is versioned.
+    pub fn at_height(mut self, height: u64) -> Self {
+        self.height = height;
+        self
+    }
+
     pub fn store_all<R: BufRead>(
         &mut self,
         reader: &mut TripleReader<R>,
     ) -> Result<Uint128, ContractError> {
-        reader.read_all(|t| self.store_triple(t))?;
+        reader.read_all_quads(|q| self.store_triple(q))?;
         self.finish()
     }
 
-    fn store_triple(&mut self, t: model::Triple<'_>) -> Result<(), ContractError> {
+    fn store_triple(&mut self, t: model::Quad<'_>) -> Result<(), ContractError> {
+        let triple = Self::rio_to_triple(
+            t,
+            &mut |ns_str| {
+                self.ns_batch_svc
+                    .resolve_or_allocate(self.storage, ns_str)
+                    .map(|ns| ns.key)
+            },
+            &mut self.blank_node_id_issuer,
+            self.store.strict_typing,
+        )?;
+        self.insert_triple(triple)
+    }
+
+    /// Delete the triples, then insert the quads resolved for each solution of a WHERE clause, the
+    /// blank nodes of the templates being issued fresh for each solution. The limits of the store
+    /// apply to the net result of the deletion and the insertion.
+    ///
+    /// Returns the number of triples deleted and inserted.
+    pub fn modify_all(
+        &mut self,
+        delete: &[Triple],
+        insert: Vec<Vec<ResolvedQuad>>,
+    ) -> Result<(Uint128, Uint128), ContractError> {
+        for triple in delete {
+            self.delete_triple(triple)?;
+        }
+        let remaining_count = self.store.stat.triple_count;
+
+        for (i, quads) in insert.into_iter().enumerate() {
+            for quad in quads {
+                let triple = self.resolved_to_triple(quad, i)?;
+                self.insert_triple(triple)?;
+            }
+        }
+
+        let counts = (
+            self.initial_triple_count - remaining_count,
+            self.store.stat.triple_count - remaining_count,
+        );
+        self.finish()?;
+        Ok(counts)
+    }
+
+    fn insert_triple(&mut self, mut triple: Triple) -> Result<(), ContractError> {
+        self.verify_scopes(&triple, false)?;
+
         self.store.stat.triple_count += Uint128::one();
         if self.store.stat.triple_count > self.store.limits.max_triple_count {
             Err(StoreError::TripleCount(self.store.limits.max_triple_count))?;
         }
-        if self.store.stat.triple_count - self.initial_triple_count
+        if self
+            .store
+            .stat
+            .triple_count
+            .saturating_sub(self.initial_triple_count)
             > self.store.limits.max_insert_data_triple_count
         {
             Err(StoreError::InsertDataTripleCount(
@@ -58,15 +154,6 @@ impl<'a> StoreEngine<'a> {
             ))?;
         }
 
-        let triple = Self::rio_to_triple(
-            t,
-            &mut |ns_str| {
-                self.ns_batch_svc
-                    .resolve_or_allocate(self.storage, ns_str)
-                    .map(|ns| ns.key)
-            },
-            &mut self.blank_node_id_issuer,
-        )?;
         let t_size = Uint128::from(self.triple_size(&triple).map_err(ContractError::Std)? as u128);
         if t_size > self.store.limits.max_triple_byte_size {
             Err(StoreError::TripleByteSize(
@@ -79,7 +166,11 @@ impl<'a> StoreEngine<'a> {
         if self.store.stat.byte_size > self.store.limits.max_byte_size {
             Err(StoreError::ByteSize(self.store.limits.max_byte_size))?;
         }
-        if self.store.stat.byte_size - self.initial_byte_size
+        if self
+            .store
+            .stat
+            .byte_size
+            .saturating_sub(self.initial_byte_size)
             > self.store.limits.max_insert_data_byte_size
         {
             Err(StoreError::InsertDataByteSize(
@@ -88,27 +179,60 @@ impl<'a> StoreEngine<'a> {
         }
 
         let mut new_ns_refs = Vec::new();
+        let mut inserted = false;
+        let object_hash = triple.object.as_hash();
+        let pk = (
+            object_hash.as_bytes().as_slice(),
+            triple.predicate.key(),
+            triple.subject_key(),
+        );
+        let height = self.height;
+        if self.store.versioning {
+            triple.validity = vec![Validity {
+                valid_from: height,
+                valid_to: None,
+            }];
+        }
         triples()
             .update(
                 self.storage,
-                (
-                    triple.object.as_hash().as_bytes(),
-                    triple.predicate.key(),
-                    triple.subject.key(),
-                ),
-                |maybe_triple| {
-                    if let Some(t) = maybe_triple {
+                pk.clone(),
+                |maybe_triple| match maybe_triple {
+                    // A deleted triple of a versioned store, its history being kept.
+                    Some(mut t) if !t.is_valid_at(None) => {
+                        match t.validity.last_mut() {
+                            Some(last) if last.valid_to == Some(height) => last.valid_to = None,
+                            _ => t.validity.push(Validity {
+                                valid_from: height,
+                                valid_to: None,
+                            }),
+                        }
+                        self.stats_batch_svc.count(&t, 1);
+                        inserted = true;
+                        Ok(t)
+                    }
+                    Some(t) => {
                         self.store.stat.triple_count -= Uint128::one();
                         self.store.stat.byte_size -= t_size;
                         Ok(t)
-                    } else {
+                    }
+                    None => {
                         new_ns_refs.append(&mut triple.namespaces());
+                        self.stats_batch_svc.count(&triple, 1);
+                        inserted = true;
                         Ok(triple)
                     }
                 },
             )
             .map_err(ContractError::Std)?;
 
+        if let (true, Some(batch)) = (inserted, &self.batch) {
+            let batch_id = match self.batch_id {
+                Some(id) => id,
+                None => *self.batch_id.insert(batch.save(self.storage)?),
+            };
+            TRIPLE_BATCHES.save(self.storage, pk, &batch_id)?;
+        }
         for ns_key in new_ns_refs {
             self.ns_batch_svc.count_ref(self.storage, ns_key)?;
         }
@@ -129,35 +253,126 @@ impl<'a> StoreEngine<'a> {
                 (
                     triple.object.as_hash().as_bytes(),
                     triple.predicate.key(),
-                    triple.subject.key(),
+                    triple.subject_key(),
                 ),
             )
             .map_err(ContractError::Std)?;
 
-        if old.is_some() {
-            triples().replace(
-                self.storage,
-                (
-                    triple.object.as_hash().as_bytes(),
-                    triple.predicate.key(),
-                    triple.subject.key(),
-                ),
-                None,
-                old.as_ref(),
-            )?;
+        if let Some(mut old) = old.filter(|t| t.is_valid_at(None)) {
+            self.verify_scopes(triple, true)?;
+            let object_hash = triple.object.as_hash();
+            let pk = (
+                object_hash.as_bytes().as_slice(),
+                triple.predicate.key(),
+                triple.subject_key(),
+            );
             self.store.stat.triple_count -= Uint128::one();
             let triple_size = self.triple_size(triple).map_err(ContractError::Std)?;
             self.store.stat.byte_size -= Uint128::from(triple_size as u128);
+            self.stats_batch_svc.count(triple, -1);
 
-            for ns_key in triple.namespaces() {
-                self.ns_batch_svc
-                    .free_ref(self.storage, ns_key)
-                    .map_err(ContractError::Std)?;
+            // The triple of a versioned store is kept, closing its period of validity instead.
+            if let Some(last) = old.validity.last_mut() {
+                last.valid_to = Some(self.height);
+                triples().save(self.storage, pk.clone(), &old)?;
+                TRIPLE_EXPIRATIONS.save(self.storage, (self.height, pk), &())?;
+                return Ok(());
+            }
+
+            triples().replace(self.storage, pk.clone(), None, Some(&old))?;
+            self.remove_triple_refs(triple, pk)?;
+        }
+        Ok(())
+    }
+
+    /// Forgets the periods of validity closed at a height lower than or equal to the cutoff,
+    /// removing the triples left without any, processing at most `limit` of them from the oldest.
+    ///
+    /// Returns the number of triples removed and whether periods are left to process.
+    pub fn prune_history(
+        &mut self,
+        cutoff: u64,
+        limit: usize,
+    ) -> Result<(Uint128, bool), ContractError> {
+        let mut expirations = TRIPLE_EXPIRATIONS
+            .prefix_range(
+                self.storage,
+                None,
+                Some(PrefixBound::inclusive(cutoff)),
+                Order::Ascending,
+            )
+            .take(limit.saturating_add(1))
+            .collect::<StdResult<Vec<_>>>()?;
+        let has_more = expirations.len() > limit;
+        expirations.truncate(limit);
+
+        let mut count = Uint128::zero();
+        for ((height, (object_hash, predicate, subject)), _) in expirations {
+            let pk = (object_hash.as_slice(), predicate, subject);
+            TRIPLE_EXPIRATIONS.remove(self.storage, (height, pk.clone()));
+
+            let Some(mut triple) = triples().may_load(self.storage, pk.clone())? else {
+                continue;
+            };
+            triple
+                .validity
+                .retain(|v| v.valid_to.map_or(true, |to| to > cutoff));
+            if !triple.validity.is_empty() {
+                triples().save(self.storage, pk, &triple)?;
+                continue;
             }
+
+            triples().remove(self.storage, pk.clone())?;
+            self.remove_triple_refs(&triple, pk)?;
+            count += Uint128::one();
+        }
+
+        self.finish()?;
+        Ok((count, has_more))
+    }
+
+    /// Removes what refers to a triple physically removed from the store.
+    fn remove_triple_refs(&mut self, triple: &Triple, pk: TriplePK<'_>) -> StdResult<()> {
+        TRIPLE_BATCHES.remove(self.storage, pk);
+        for ns_key in triple.namespaces() {
+            self.ns_batch_svc.free_ref(self.storage, ns_key)?;
         }
         Ok(())
     }
 
+    /// Verifies the triple is in at least one of the insertion or deletion scopes if restricted.
+    fn verify_scopes(&mut self, triple: &Triple, deletion: bool) -> Result<(), ContractError> {
+        let scopes = if deletion {
+            &self.delete_scopes
+        } else {
+            &self.insert_scopes
+        };
+        let Some(scopes) = scopes else {
+            return Ok(());
+        };
+
+        let ns_fn = &mut |ns_key| {
+            self.ns_batch_svc
+                .resolve_from_key(self.storage, ns_key)
+                .and_then(NamespaceResolver::none_as_error_middleware)
+                .map(|ns| ns.value)
+        };
+        let subject = match &triple.subject {
+            Subject::Named(n) => Some(n.as_iri(ns_fn)?),
+            Subject::Blank(_) => None,
+        };
+        let graph = triple.graph.as_ref().map(|g| g.as_iri(ns_fn)).transpose()?;
+
+        if scopes
+            .iter()
+            .any(|scope| scope.contains(subject.as_deref(), graph.as_deref()))
+        {
+            Ok(())
+        } else {
+            Err(ContractError::Unauthorized)
+        }
+    }
+
     /// Flushes the store to the storage.
     /// Returns the number of triples added or removed (absolute value).
     fn finish(&mut self) -> Result<Uint128, ContractError> {
@@ -168,6 +383,8 @@ impl<'a> StoreEngine<'a> {
             self.store.stat.namespace_count -= Uint128::new(ns_diff.neg() as u128);
         }
 
+        self.stats_batch_svc.flush(self.storage)?;
+
         BLANK_NODE_IDENTIFIER_COUNTER.save(self.storage, &self.blank_node_id_issuer.counter)?;
 
         STORE.save(self.storage, &self.store)?;
@@ -185,20 +402,101 @@ impl<'a> StoreEngine<'a> {
     }
 
     fn rio_to_triple<F>(
-        triple: model::Triple<'_>,
+        quad: model::Quad<'_>,
         ns_fn: &mut F,
         id_issuer: &mut IdentifierIssuer,
+        strict_typing: bool,
     ) -> StdResult<Triple>
     where
         F: FnMut(String) -> StdResult<u128>,
     {
         Ok(Triple {
-            subject: Self::rio_to_subject(triple.subject, ns_fn, id_issuer)?,
-            predicate: Self::rio_to_node(triple.predicate, ns_fn)?,
-            object: Self::rio_to_object(triple.object, ns_fn, id_issuer)?,
+            subject: Self::rio_to_subject(quad.subject, ns_fn, id_issuer)?,
+            predicate: Self::rio_to_node(quad.predicate, ns_fn)?,
+            object: Self::rio_to_object(quad.object, ns_fn, id_issuer, strict_typing)?,
+            graph: quad
+                .graph_name
+                .map(|graph| Self::rio_to_graph(graph, ns_fn))
+                .transpose()?,
+            validity: vec![],
+        })
+    }
+
+    /// Map the quad resolved for the given solution to a triple, allocating the namespaces of its
+    /// constants and issuing its blank nodes.
+    fn resolved_to_triple(&mut self, quad: ResolvedQuad, solution: usize) -> StdResult<Triple> {
+        let ns_fn = &mut |ns_str| {
+            self.ns_batch_svc
+                .resolve_or_allocate(self.storage, ns_str)
+                .map(|ns| ns.key)
+        };
+
+        Ok(Triple {
+            subject: match quad.subject {
+                Left(rdf::Subject::NamedNode(iri)) => {
+                    Subject::Named(Self::rio_to_node(model::NamedNode { iri: &iri }, ns_fn)?)
+                }
+                Left(rdf::Subject::BlankNode(id)) => Subject::Blank(
+                    self.blank_node_id_issuer
+                        .get_n_or_issue(format!("{solution}/{id}")),
+                ),
+                Right(subject) => subject,
+            },
+            predicate: match quad.predicate {
+                Left(rdf::Property(iri)) => {
+                    Self::rio_to_node(model::NamedNode { iri: &iri }, ns_fn)?
+                }
+                Right(predicate) => predicate,
+            },
+            object: match quad.object {
+                Left(rdf::Value::BlankNode(id)) => Object::Blank(
+                    self.blank_node_id_issuer
+                        .get_n_or_issue(format!("{solution}/{id}")),
+                ),
+                Left(value) => Self::rio_to_object(
+                    match &value {
+                        rdf::Value::NamedNode(iri) | rdf::Value::BlankNode(iri) => {
+                            model::NamedNode { iri }.into()
+                        }
+                        rdf::Value::LiteralSimple(value) => model::Literal::Simple { value }.into(),
+                        rdf::Value::LiteralLang(value, language) => {
+                            model::Literal::LanguageTaggedString { value, language }.into()
+                        }
+                        rdf::Value::LiteralDatatype(value, datatype) => model::Literal::Typed {
+                            value,
+                            datatype: model::NamedNode { iri: datatype },
+                        }
+                        .into(),
+                    },
+                    ns_fn,
+                    &mut self.blank_node_id_issuer,
+                    self.store.strict_typing,
+                )?,
+                Right(object) => object,
+            },
+            graph: match quad.graph {
+                Some(Left(rdf::Property(iri))) => {
+                    Some(Self::rio_to_node(model::NamedNode { iri: &iri }, ns_fn)?)
+                }
+                Some(Right(graph)) => Some(graph),
+                None => None,
+            },
+            validity: vec![],
         })
     }
 
+    fn rio_to_graph<F>(graph: model::GraphName<'_>, ns_fn: &mut F) -> StdResult<Node>
+    where
+        F: FnMut(String) -> StdResult<u128>,
+    {
+        match graph {
+            model::GraphName::NamedNode(node) => Self::rio_to_node(node, ns_fn),
+            model::GraphName::BlankNode(_) => {
+                Err(StdError::generic_err("Blank node graph name unsupported"))
+            }
+        }
+    }
+
     fn rio_to_subject<F>(
         subject: model::Subject<'_>,
         ns_fn: &mut F,
@@ -231,6 +529,7 @@ impl<'a> StoreEngine<'a> {
         object: Term<'_>,
         ns_fn: &mut F,
         id_issuer: &mut IdentifierIssuer,
+        strict_typing: bool,
     ) -> StdResult<Object>
     where
         F: FnMut(String) -> StdResult<u128>,
@@ -240,12 +539,20 @@ impl<'a> StoreEngine<'a> {
                 Ok(Object::Blank(id_issuer.get_n_or_issue(node.id.to_string())))
             }
             Term::NamedNode(node) => Self::rio_to_node(node, ns_fn).map(Object::Named),
-            Term::Literal(literal) => Self::rio_to_literal(literal, ns_fn).map(Object::Literal),
+            Term::Literal(literal) => {
+                Self::rio_to_literal(literal, ns_fn, strict_typing).map(Object::Literal)
+            }
             Term::Triple(_) => Err(StdError::generic_err("RDF star syntax unsupported")),
         }
     }
 
-    fn rio_to_literal<F>(literal: model::Literal<'_>, ns_fn: &mut F) -> StdResult<Literal>
+    /// Convert the literal, the ones typed with a supported XML Schema datatype being validated and
+    /// canonicalized when strict typing is enabled, and kept as is otherwise.
+    fn rio_to_literal<F>(
+        literal: model::Literal<'_>,
+        ns_fn: &mut F,
+        strict_typing: bool,
+    ) -> StdResult<Literal>
     where
         F: FnMut(String) -> StdResult<u128>,
     {
@@ -258,8 +565,13 @@ impl<'a> StoreEngine<'a> {
                 language: language.to_string(),
             }),
             model::Literal::Typed { value, datatype } => {
+                let value = match XsdValue::parse(value, datatype.iri) {
+                    Some(Ok(xsd)) if strict_typing => xsd.to_string(),
+                    Some(Err(e)) if strict_typing => Err(e)?,
+                    _ => value.to_string(),
+                };
                 Self::rio_to_node(datatype, ns_fn).map(|node| Literal::Typed {
-                    value: value.to_string(),
+                    value,
                     datatype: node,
                 })
             }
@@ -269,7 +581,11 @@ impl<'a> StoreEngine<'a> {
     fn triple_size(&mut self, triple: &Triple) -> StdResult<usize> {
         Ok(self.subject_size(&triple.subject)?
             + self.node_size(&triple.predicate)?
-            + self.object_size(&triple.object)?)
+            + self.object_size(&triple.object)?
+            + match &triple.graph {
+                Some(graph) => self.node_size(graph)?,
+                None => 0,
+            })
     }
 
     fn subject_size(&mut self, subject: &Subject) -> StdResult<usize> {
@@ -59,6 +59,8 @@ pub fn instantiate(
             label: format!("{}_triplestore", msg.name),
             msg: to_json_binary(&axone_cognitarium::msg::InstantiateMsg {
                 limits: msg.triplestore_config.limits.into(),
+                strict_typing: false,
+                versioning: false,
             })?,
             funds: vec![],
             salt,
@@ -143,9 +145,8 @@ mod tests {
     };
     use crate::testutil::testutil::read_test_data;
     use axone_cognitarium::msg::{
-        DataFormat, Head, Node, Results, SelectItem, SelectQuery, SelectResponse,
-        SimpleWhereCondition, TriplePattern, Value, VarOrNamedNode, VarOrNode, VarOrNodeOrLiteral,
-        WhereCondition, IRI,
+        AskQuery, AskResponse, DataFormat, Node, SimpleWhereCondition, TriplePattern,
+        VarOrNamedNodeOrPath, VarOrNode, VarOrNodeOrLiteral, WhereCondition, IRI,
     };
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{
@@ -153,7 +154,6 @@ mod tests {
         SystemError, SystemResult, Uint128, Uint64, WasmQuery,
     };
     use cw_utils::PaymentError::NonPayable;
-    use std::collections::BTreeMap;
 
     #[test]
     fn proper_instantiate() {
@@ -201,6 +201,8 @@ mod tests {
                 label: "my-dataverse_triplestore".to_string(),
                 msg: to_json_binary(&axone_cognitarium::msg::InstantiateMsg {
                     limits: store_limits.into(),
+                    strict_typing: false,
+                    versioning: false,
                 })
                 .unwrap(),
                 funds: vec![],
@@ -297,29 +299,25 @@ mod tests {
                 let query_msg: StdResult<axone_cognitarium::msg::QueryMsg> = from_json(msg);
                 assert_eq!(
                     query_msg,
-                    Ok(axone_cognitarium::msg::QueryMsg::Select {
-                        query: SelectQuery {
+                    Ok(axone_cognitarium::msg::QueryMsg::Ask {
+                        query: AskQuery {
                             prefixes: vec![],
-                            limit: Some(1u32),
-                            select: vec![SelectItem::Variable("p".to_string())],
                             r#where: vec![WhereCondition::Simple(
                                 SimpleWhereCondition::TriplePattern(TriplePattern {
                                     subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
                                         "http://example.edu/credentials/3732".to_string(),
                                     ))),
-                                    predicate: VarOrNamedNode::Variable("p".to_string()),
+                                    predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                                     object: VarOrNodeOrLiteral::Variable("o".to_string()),
                                 })
                             )],
-                        }
+                        },
+                        as_of_height: None,
                     })
                 );
 
-                let select_resp = SelectResponse {
-                    results: Results { bindings: vec![] },
-                    head: Head { vars: vec![] },
-                };
-                SystemResult::Ok(ContractResult::Ok(to_json_binary(&select_resp).unwrap()))
+                let ask_resp = AskResponse { result: false };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&ask_resp).unwrap()))
             }
             _ => SystemResult::Err(SystemError::Unknown {}),
         });
@@ -472,18 +470,8 @@ _:b0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://example.org/exam
         let mut deps = mock_dependencies();
         deps.querier.update_wasm(|query| match query {
             WasmQuery::Smart { .. } => {
-                let select_resp = SelectResponse {
-                    results: Results {
-                        bindings: vec![BTreeMap::from([(
-                            "p".to_string(),
-                            Value::BlankNode {
-                                value: "".to_string(),
-                            },
-                        )])],
-                    },
-                    head: Head { vars: vec![] },
-                };
-                SystemResult::Ok(ContractResult::Ok(to_json_binary(&select_resp).unwrap()))
+                let ask_resp = AskResponse { result: true };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&ask_resp).unwrap()))
             }
             _ => SystemResult::Err(SystemError::Unknown {}),
         });
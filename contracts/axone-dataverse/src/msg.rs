@@ -119,6 +119,10 @@ pub struct TripleStoreLimitsInput {
     /// The maximum number of triples an insert data query can contain (after parsing).
     /// Default to [Uint128::MAX] if not set, which can be considered as no limit.
     pub max_insert_data_triple_count: Option<Uint128>,
+    /// The maximum depth of the traversal of a property path, i.e. the maximum number of times
+    /// a repeated path is followed when querying.
+    /// Default to 10 if not set.
+    pub max_query_path_depth: Option<u32>,
 }
 
 impl From<TripleStoreLimitsInput> for axone_cognitarium::msg::StoreLimitsInput {
@@ -145,6 +149,9 @@ impl From<TripleStoreLimitsInput> for axone_cognitarium::msg::StoreLimitsInput {
         if let Some(max_insert_data_triple_count) = value.max_insert_data_triple_count {
             limits.max_insert_data_triple_count = max_insert_data_triple_count;
         }
+        if let Some(max_query_path_depth) = value.max_query_path_depth {
+            limits.max_query_path_depth = max_query_path_depth;
+        }
 
         limits
     }
@@ -2,7 +2,7 @@ use crate::registrar::credential::DataverseCredential;
 use crate::state::DATAVERSE;
 use crate::ContractError;
 use axone_cognitarium::msg::{
-    DataFormat, Node, SelectItem, SelectQuery, SimpleWhereCondition, TriplePattern, VarOrNamedNode,
+    AskQuery, DataFormat, Node, SimpleWhereCondition, TriplePattern, VarOrNamedNodeOrPath,
     VarOrNode, VarOrNodeOrLiteral, WhereCondition, IRI,
 };
 use axone_cognitarium_client::CognitariumClient;
@@ -30,25 +30,23 @@ impl ClaimRegistrar {
         deps: &DepsMut<'_>,
         credential: &DataverseCredential<'_>,
     ) -> Result<WasmMsg, ContractError> {
-        let resp = self.triplestore.select(
+        let resp = self.triplestore.ask(
             deps.querier,
-            SelectQuery {
+            AskQuery {
                 prefixes: vec![],
-                limit: Some(1u32),
-                select: vec![SelectItem::Variable("p".to_string())],
                 r#where: vec![WhereCondition::Simple(SimpleWhereCondition::TriplePattern(
                     TriplePattern {
                         subject: VarOrNode::Node(Node::NamedNode(IRI::Full(
                             credential.id.to_string(),
                         ))),
-                        predicate: VarOrNamedNode::Variable("p".to_string()),
+                        predicate: VarOrNamedNodeOrPath::Variable("p".to_string()),
                         object: VarOrNodeOrLiteral::Variable("o".to_string()),
                     },
                 ))],
             },
         )?;
 
-        if !resp.results.bindings.is_empty() {
+        if resp.result {
             Err(ContractError::CredentialAlreadyExists(
                 credential.id.to_string(),
             ))?;
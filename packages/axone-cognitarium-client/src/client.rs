@@ -1,4 +1,6 @@
-use axone_cognitarium::msg::{DataFormat, ExecuteMsg, QueryMsg, SelectQuery, SelectResponse};
+use axone_cognitarium::msg::{
+    AskQuery, AskResponse, DataFormat, ExecuteMsg, QueryMsg, SelectQuery, SelectResponse,
+};
 use cosmwasm_std::{
     to_json_binary, Addr, Binary, Coin, CustomQuery, QuerierWrapper, QueryRequest, StdResult,
     WasmMsg, WasmQuery,
@@ -20,7 +22,28 @@ impl CognitariumClient {
         querier: QuerierWrapper<'_, C>,
         query: SelectQuery,
     ) -> StdResult<SelectResponse> {
-        self.query_wasm(querier, &QueryMsg::Select { query })
+        self.query_wasm(
+            querier,
+            &QueryMsg::Select {
+                query,
+                format: None,
+                as_of_height: None,
+            },
+        )
+    }
+
+    pub fn ask<C: CustomQuery>(
+        &self,
+        querier: QuerierWrapper<'_, C>,
+        query: AskQuery,
+    ) -> StdResult<AskResponse> {
+        self.query_wasm(
+            querier,
+            &QueryMsg::Ask {
+                query,
+                as_of_height: None,
+            },
+        )
     }
 
     pub fn insert_data(&self, format: Option<DataFormat>, data: Binary) -> StdResult<WasmMsg> {
@@ -0,0 +1,283 @@
+use crate::jsonld::json::JsonValue;
+use crate::owned_model::{Id, OwnedLiteral, OwnedQuad, OwnedTerm};
+use rio_api::model::Quad;
+use std::io;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// The statements of a subject.
+type Statements<'a> = (&'a Id, Vec<&'a OwnedQuad>);
+
+/// A formatter of quads as a compacted [JSON-LD](https://www.w3.org/TR/json-ld11/) document,
+/// whose context is made of the provided prefixes.
+///
+/// The statements are grouped by graph and subject in their order of appearance, thus the document
+/// is only written once all the quads have been formatted.
+pub struct JsonLdFormatter<W: io::Write> {
+    write: W,
+    prefixes: Vec<(String, String)>,
+    quads: Vec<OwnedQuad>,
+}
+
+impl<W: io::Write> JsonLdFormatter<W> {
+    pub fn new(write: W) -> Self {
+        Self {
+            write,
+            prefixes: vec![],
+            quads: vec![],
+        }
+    }
+
+    /// Use the given prefixes, as `(prefix, namespace)` pairs, to compact the IRIs.
+    pub fn with_prefixes(mut self, prefixes: Vec<(String, String)>) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    pub fn format(&mut self, quad: &Quad<'_>) -> io::Result<()> {
+        self.quads.push((*quad).try_into().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "RDF Star notation not supported",
+            )
+        })?);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut out = String::new();
+        self.document().write(&mut out);
+        self.write.write_all(out.as_bytes())?;
+        Ok(self.write)
+    }
+
+    fn document(&self) -> JsonValue {
+        let mut graphs: Vec<(Option<&Id>, Vec<Statements<'_>>)> = vec![];
+        for quad in &self.quads {
+            let graph_name = quad.graph_name.as_ref();
+            if !graphs.iter().any(|(name, _)| *name == graph_name) {
+                graphs.push((graph_name, vec![]));
+            }
+            let Some((_, graph)) = graphs.iter_mut().find(|(name, _)| *name == graph_name) else {
+                continue;
+            };
+            match graph
+                .iter()
+                .position(|(subject, _)| **subject == quad.subject)
+            {
+                Some(i) => graph[i].1.push(quad),
+                None => graph.push((&quad.subject, vec![quad])),
+            }
+        }
+
+        let mut nodes = vec![];
+        for (name, graph) in graphs {
+            let graph_nodes = graph
+                .into_iter()
+                .map(|(subject, quads)| self.node(subject, quads))
+                .collect();
+            match name {
+                None => nodes.extend(graph_nodes),
+                Some(name) => nodes.push(JsonValue::Object(vec![
+                    ("@id".to_string(), JsonValue::String(self.id(name))),
+                    ("@graph".to_string(), JsonValue::Array(graph_nodes)),
+                ])),
+            }
+        }
+
+        let mut members = vec![];
+        if !self.prefixes.is_empty() {
+            members.push((
+                "@context".to_string(),
+                JsonValue::Object(
+                    self.prefixes
+                        .iter()
+                        .map(|(prefix, ns)| (prefix.clone(), JsonValue::String(ns.clone())))
+                        .collect(),
+                ),
+            ));
+        }
+        members.push(("@graph".to_string(), JsonValue::Array(nodes)));
+        JsonValue::Object(members)
+    }
+
+    fn node(&self, subject: &Id, quads: Vec<&OwnedQuad>) -> JsonValue {
+        let mut types = vec![];
+        let mut properties: Vec<(String, Vec<JsonValue>)> = vec![];
+        for quad in quads {
+            match &quad.object {
+                OwnedTerm::Named(iri) if quad.predicate == RDF_TYPE => {
+                    types.push(JsonValue::String(self.compact(iri)));
+                }
+                object => {
+                    let key = self.compact(&quad.predicate);
+                    let value = self.value(object);
+                    match properties.iter_mut().find(|(k, _)| *k == key) {
+                        Some((_, values)) => values.push(value),
+                        None => properties.push((key, vec![value])),
+                    }
+                }
+            }
+        }
+
+        let mut members = vec![("@id".to_string(), JsonValue::String(self.id(subject)))];
+        if !types.is_empty() {
+            members.push(("@type".to_string(), single_or_array(types)));
+        }
+        members.extend(
+            properties
+                .into_iter()
+                .map(|(key, values)| (key, single_or_array(values))),
+        );
+        JsonValue::Object(members)
+    }
+
+    fn value(&self, term: &OwnedTerm) -> JsonValue {
+        match term {
+            OwnedTerm::Named(iri) => JsonValue::Object(vec![(
+                "@id".to_string(),
+                JsonValue::String(self.compact(iri)),
+            )]),
+            OwnedTerm::Blank(id) => JsonValue::Object(vec![(
+                "@id".to_string(),
+                JsonValue::String(format!("_:{id}")),
+            )]),
+            OwnedTerm::Literal(OwnedLiteral::Simple(value)) => JsonValue::String(value.clone()),
+            OwnedTerm::Literal(OwnedLiteral::Typed { value, datatype })
+                if datatype == XSD_STRING =>
+            {
+                JsonValue::String(value.clone())
+            }
+            OwnedTerm::Literal(OwnedLiteral::LanguageTaggedString { value, language }) => {
+                JsonValue::Object(vec![
+                    ("@value".to_string(), JsonValue::String(value.clone())),
+                    ("@language".to_string(), JsonValue::String(language.clone())),
+                ])
+            }
+            OwnedTerm::Literal(OwnedLiteral::Typed { value, datatype }) => JsonValue::Object(vec![
+                ("@value".to_string(), JsonValue::String(value.clone())),
+                (
+                    "@type".to_string(),
+                    JsonValue::String(self.compact(datatype)),
+                ),
+            ]),
+        }
+    }
+
+    fn id(&self, id: &Id) -> String {
+        match id {
+            Id::Named(iri) => self.compact(iri),
+            Id::Blank(id) => format!("_:{id}"),
+        }
+    }
+
+    /// Compact the IRI with the prefix of the longest matching namespace, if any, only the
+    /// namespaces ending with a delimiter being usable as prefix as stated by the
+    /// [JSON-LD 1.1](https://www.w3.org/TR/json-ld11/#compact-iris) specification.
+    fn compact(&self, iri: &str) -> String {
+        self.prefixes
+            .iter()
+            .filter(|(_, ns)| ns.ends_with([':', '/', '?', '#', '[', ']', '@']))
+            .filter_map(|(prefix, ns)| {
+                iri.strip_prefix(ns.as_str())
+                    .filter(|local| !local.is_empty() && !local.starts_with("//"))
+                    .map(|local| (prefix, ns, local))
+            })
+            .max_by_key(|(_, ns, _)| ns.len())
+            .map_or_else(
+                || iri.to_string(),
+                |(prefix, _, local)| format!("{prefix}:{local}"),
+            )
+    }
+}
+
+fn single_or_array(mut values: Vec<JsonValue>) -> JsonValue {
+    if values.len() == 1 {
+        values.remove(0)
+    } else {
+        JsonValue::Array(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonld::JsonLdParser;
+    use rio_api::model::{BlankNode, GraphName, Literal, NamedNode, Subject, Term};
+
+    #[test]
+    fn proper_format() {
+        let ex = |iri| NamedNode { iri };
+        let quads = vec![
+            Quad {
+                subject: Subject::NamedNode(ex("http://example.org/a")),
+                predicate: ex(RDF_TYPE),
+                object: Term::NamedNode(ex("http://example.org/Person")),
+                graph_name: None,
+            },
+            Quad {
+                subject: Subject::NamedNode(ex("http://example.org/a")),
+                predicate: ex("http://example.org/name"),
+                object: Term::Literal(Literal::Simple { value: "A" }),
+                graph_name: None,
+            },
+            Quad {
+                subject: Subject::NamedNode(ex("http://example.org/a")),
+                predicate: ex("http://example.org/name"),
+                object: Term::Literal(Literal::LanguageTaggedString {
+                    value: "\"a\"",
+                    language: "fr",
+                }),
+                graph_name: None,
+            },
+            Quad {
+                subject: Subject::BlankNode(BlankNode { id: "b0" }),
+                predicate: ex("http://other.org/age"),
+                object: Term::Literal(Literal::Typed {
+                    value: "42",
+                    datatype: ex("http://www.w3.org/2001/XMLSchema#integer"),
+                }),
+                graph_name: Some(GraphName::NamedNode(ex("http://example.org/g"))),
+            },
+            Quad {
+                subject: Subject::NamedNode(ex("http://example.org/a")),
+                predicate: ex("http://example.org/knows"),
+                object: Term::BlankNode(BlankNode { id: "b0" }),
+                graph_name: None,
+            },
+        ];
+
+        let mut formatter = JsonLdFormatter::new(vec![]).with_prefixes(vec![
+            ("ex".to_string(), "http://example.org/".to_string()),
+            (
+                "xsd".to_string(),
+                "http://www.w3.org/2001/XMLSchema#".to_string(),
+            ),
+            ("no".to_string(), "http://other.org/age".to_string()),
+        ]);
+        for quad in &quads {
+            formatter.format(quad).unwrap();
+        }
+        let out = String::from_utf8(formatter.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            out,
+            r#"{"@context":{"ex":"http://example.org/","xsd":"http://www.w3.org/2001/XMLSchema#","no":"http://other.org/age"},"@graph":[{"@id":"ex:a","@type":"ex:Person","ex:name":["A",{"@value":"\"a\"","@language":"fr"}],"ex:knows":{"@id":"_:b0"}},{"@id":"ex:g","@graph":[{"@id":"_:b0","http://other.org/age":{"@value":"42","@type":"xsd:integer"}}]}]}"#
+        );
+
+        let mut parsed = vec![];
+        JsonLdParser::new(out.as_bytes())
+            .parse_all(&mut |quad| -> Result<(), crate::jsonld::JsonLdError> {
+                parsed.push(OwnedQuad::try_from(quad).unwrap());
+                Ok(())
+            })
+            .unwrap();
+        let mut expected: Vec<OwnedQuad> = quads
+            .into_iter()
+            .map(|quad| OwnedQuad::try_from(quad).unwrap())
+            .collect();
+        expected.swap(3, 4);
+        assert_eq!(parsed, expected);
+    }
+}
@@ -0,0 +1,359 @@
+use crate::jsonld::JsonLdError;
+
+/// The maximum nesting depth of arrays and objects accepted by the parser.
+const MAX_DEPTH: usize = 64;
+
+/// A JSON value, the numbers being kept in their lexical form as floating point operations are
+/// not available on chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// The members of an object, in their order of appearance.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub fn parse(src: &str) -> Result<Self, JsonLdError> {
+        let mut parser = Parser {
+            src: src.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespaces();
+        if parser.pos < parser.src.len() {
+            Err(parser.error("unexpected trailing characters"))?;
+        }
+        Ok(value)
+    }
+
+    /// Get the value of the object member with the given key, if any.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn write(&self, out: &mut String) {
+        match self {
+            JsonValue::Null => out.push_str("null"),
+            JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            JsonValue::Number(n) => out.push_str(n),
+            JsonValue::String(s) => write_string(s, out),
+            JsonValue::Array(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    value.write(out);
+                }
+                out.push(']');
+            }
+            JsonValue::Object(members) => {
+                out.push('{');
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_string(key, out);
+                    out.push(':');
+                    value.write(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, msg: &str) -> JsonLdError {
+        JsonLdError::Json(self.pos, msg.to_string())
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespaces();
+        self.src.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, token: &[u8]) -> Result<(), JsonLdError> {
+        if self.src[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error("unexpected character"))
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<JsonValue, JsonLdError> {
+        if depth > MAX_DEPTH {
+            Err(self.error("maximum nesting depth exceeded"))?;
+        }
+
+        match self.peek() {
+            Some(b'n') => self.expect(b"null").map(|_| JsonValue::Null),
+            Some(b't') => self.expect(b"true").map(|_| JsonValue::Bool(true)),
+            Some(b'f') => self.expect(b"false").map(|_| JsonValue::Bool(false)),
+            Some(b'"') => self.parse_string().map(JsonValue::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut values = vec![];
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(values));
+                }
+                loop {
+                    values.push(self.parse_value(depth + 1)?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(JsonValue::Array(values));
+                        }
+                        _ => Err(self.error("expected ',' or ']'"))?,
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut members = vec![];
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(members));
+                }
+                loop {
+                    if self.peek() != Some(b'"') {
+                        Err(self.error("expected object key"))?;
+                    }
+                    let key = self.parse_string()?;
+                    if self.peek() != Some(b':') {
+                        Err(self.error("expected ':'"))?;
+                    }
+                    self.pos += 1;
+                    members.push((key, self.parse_value(depth + 1)?));
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(JsonValue::Object(members));
+                        }
+                        _ => Err(self.error("expected ',' or '}'"))?,
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, JsonLdError> {
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            let from = parser.pos;
+            while let Some(b'0'..=b'9') = parser.src.get(parser.pos) {
+                parser.pos += 1;
+            }
+            parser.pos > from
+        };
+
+        if self.src.get(self.pos) == Some(&b'-') {
+            self.pos += 1;
+        }
+        // Leading zeros are not allowed.
+        if self.src.get(self.pos) == Some(&b'0') {
+            self.pos += 1;
+        } else if !digits(self) {
+            Err(self.error("invalid number"))?;
+        }
+        if self.src.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            if !digits(self) {
+                Err(self.error("invalid number"))?;
+            }
+        }
+        if let Some(b'e' | b'E') = self.src.get(self.pos) {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.src.get(self.pos) {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                Err(self.error("invalid number"))?;
+            }
+        }
+
+        Ok(JsonValue::Number(
+            String::from_utf8_lossy(&self.src[start..self.pos]).to_string(),
+        ))
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonLdError> {
+        self.pos += 1;
+        let mut bytes = vec![];
+        loop {
+            let b = *self
+                .src
+                .get(self.pos)
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = *self
+                        .src
+                        .get(self.pos)
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.parse_unicode_escape()?,
+                        _ => Err(self.error("invalid escape sequence"))?,
+                    };
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                0..=0x1f => Err(self.error("control character in string"))?,
+                _ => bytes.push(b),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 string"))
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonLdError> {
+        let high = self.parse_hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            self.expect(b"\\u")?;
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                Err(self.error("invalid surrogate pair"))?;
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonLdError> {
+        let hex = self
+            .src
+            .get(self.pos..self.pos + 4)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(hex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proper_parse_write() {
+        let cases = vec![
+            ("null", JsonValue::Null),
+            (
+                " [true, false, -1.5e+3, 0] ",
+                JsonValue::Array(vec![
+                    JsonValue::Bool(true),
+                    JsonValue::Bool(false),
+                    JsonValue::Number("-1.5e+3".to_string()),
+                    JsonValue::Number("0".to_string()),
+                ]),
+            ),
+            (
+                r#"{"b": "a\"\\\né😀", "a": {}}"#,
+                JsonValue::Object(vec![
+                    ("b".to_string(), JsonValue::String("a\"\\\né😀".to_string())),
+                    ("a".to_string(), JsonValue::Object(vec![])),
+                ]),
+            ),
+        ];
+
+        for (src, expected) in cases {
+            let value = JsonValue::parse(src).unwrap();
+            assert_eq!(value, expected);
+
+            let mut out = String::new();
+            value.write(&mut out);
+            assert_eq!(JsonValue::parse(&out).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn invalid_parse() {
+        let nested = "[".repeat(100);
+        let cases = vec![
+            (
+                "",
+                JsonLdError::Json(0, "unexpected end of input".to_string()),
+            ),
+            (
+                "[1,]",
+                JsonLdError::Json(3, "unexpected character".to_string()),
+            ),
+            (
+                "{\"a\" 1}",
+                JsonLdError::Json(5, "expected ':'".to_string()),
+            ),
+            (
+                "\"a",
+                JsonLdError::Json(2, "unterminated string".to_string()),
+            ),
+            (
+                "01",
+                JsonLdError::Json(1, "unexpected trailing characters".to_string()),
+            ),
+            ("1.", JsonLdError::Json(2, "invalid number".to_string())),
+            (
+                nested.as_str(),
+                JsonLdError::Json(65, "maximum nesting depth exceeded".to_string()),
+            ),
+        ];
+
+        for (src, expected) in cases {
+            assert_eq!(JsonValue::parse(src), Err(expected), "{src}");
+        }
+    }
+}
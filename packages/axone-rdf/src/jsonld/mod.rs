@@ -0,0 +1,47 @@
+//! A [JSON-LD 1.1](https://www.w3.org/TR/json-ld11/) support limited to what can be done on chain,
+//! i.e. without fetching remote contexts nor using floating point numbers.
+
+mod formatter;
+mod json;
+mod parser;
+
+pub use formatter::JsonLdFormatter;
+pub use parser::JsonLdParser;
+
+use crate::jsonld::json::JsonValue;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum JsonLdError {
+    #[error("Invalid JSON at position {0}: {1}")]
+    Json(usize, String),
+
+    #[error("Couldn't read JSON-LD: {0}")]
+    Io(String),
+
+    #[error("Unknown context: {0}")]
+    UnknownContext(String),
+
+    #[error("Invalid context: {0}")]
+    InvalidContext(String),
+
+    #[error("Invalid IRI: {0}")]
+    InvalidIri(String),
+
+    #[error("Invalid JSON-LD: {0}")]
+    Invalid(String),
+
+    #[error("Unsupported JSON-LD feature: {0}")]
+    Unsupported(String),
+}
+
+/// Check that the given document is a valid context document, i.e. a JSON object holding a
+/// `@context` entry, as expected for a context to be registered.
+pub fn validate_context(document: &str) -> Result<(), JsonLdError> {
+    match JsonValue::parse(document)?.get("@context") {
+        Some(JsonValue::Object(_) | JsonValue::Array(_)) => Ok(()),
+        _ => Err(JsonLdError::InvalidContext(
+            "expected an object with a @context entry".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,971 @@
+use crate::jsonld::json::JsonValue;
+use crate::jsonld::JsonLdError;
+use crate::owned_model::{Id, OwnedLiteral, OwnedQuad, OwnedTerm};
+use rio_api::model::Quad;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+/// The maximum number of nested context references followed when processing a context.
+const MAX_CONTEXT_DEPTH: usize = 8;
+
+/// A parser of expanded or compacted [JSON-LD](https://www.w3.org/TR/json-ld11/) documents
+/// producing the quads of their [RDF representation](https://www.w3.org/TR/json-ld11/#relationship-to-rdf).
+///
+/// Remote contexts can't be fetched, the only contexts which can be referenced by IRI are the
+/// ones provided at construction. The numbers are kept in their lexical form, the non integer
+/// ones being typed as `xsd:double` without being canonicalized.
+pub struct JsonLdParser<R: BufRead> {
+    src: R,
+    contexts: HashMap<String, String>,
+}
+
+impl<R: BufRead> JsonLdParser<R> {
+    pub fn new(src: R) -> Self {
+        Self {
+            src,
+            contexts: HashMap::new(),
+        }
+    }
+
+    /// Register the context documents, by their IRI, which can be referenced by the parsed
+    /// documents.
+    pub fn with_contexts(mut self, contexts: HashMap<String, String>) -> Self {
+        self.contexts = contexts;
+        self
+    }
+
+    pub fn parse_all<E, F>(&mut self, on_quad: &mut F) -> Result<(), E>
+    where
+        F: FnMut(Quad<'_>) -> Result<(), E>,
+        E: From<JsonLdError>,
+    {
+        let mut document = String::new();
+        self.src
+            .read_to_string(&mut document)
+            .map_err(|e| JsonLdError::Io(e.to_string()))?;
+
+        let quads = ToRdf::new(&self.contexts).convert(&JsonValue::parse(&document)?)?;
+        for quad in &quads {
+            on_quad(quad.into())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+struct Context {
+    base: Option<String>,
+    vocab: Option<String>,
+    language: Option<String>,
+    terms: HashMap<String, TermDefinition>,
+}
+
+#[derive(Clone, Default)]
+struct TermDefinition {
+    /// The IRI, or keyword, the term maps to, `None` if explicitly mapped to null.
+    id: Option<String>,
+    type_mapping: Option<String>,
+    /// The language of the strings, overriding the default one if set.
+    language: Option<Option<String>>,
+    container: Option<Container>,
+}
+
+/// The containers affecting the produced statements, the `@set` and `@index` ones having no
+/// effect are ignored.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Container {
+    List,
+    Language,
+}
+
+struct ToRdf<'a> {
+    contexts: &'a HashMap<String, String>,
+    quads: Vec<OwnedQuad>,
+    blank_nodes: HashMap<String, String>,
+    blank_node_count: u64,
+}
+
+impl<'a> ToRdf<'a> {
+    fn new(contexts: &'a HashMap<String, String>) -> Self {
+        Self {
+            contexts,
+            quads: vec![],
+            blank_nodes: HashMap::new(),
+            blank_node_count: 0,
+        }
+    }
+
+    fn convert(mut self, document: &JsonValue) -> Result<Vec<OwnedQuad>, JsonLdError> {
+        let ctx = Context::default();
+        for value in as_slice(document) {
+            let members = as_object(value)?;
+
+            // A top level object with only a context and a graph only holds the default graph.
+            let keys_count = members
+                .iter()
+                .filter(|(key, _)| key == "@context" || key == "@graph")
+                .count();
+            match get(members, "@graph") {
+                Some(graph) if keys_count == members.len() => {
+                    let ctx = self.with_local_context(&ctx, members)?;
+                    for node in as_slice(graph) {
+                        self.node(&ctx, as_object(node)?, None)?;
+                    }
+                }
+                _ => {
+                    self.node(&ctx, members, None)?;
+                }
+            }
+        }
+        Ok(self.quads)
+    }
+
+    fn node(
+        &mut self,
+        ctx: &Context,
+        members: &[(String, JsonValue)],
+        graph: Option<&Id>,
+    ) -> Result<Id, JsonLdError> {
+        let ctx = self.with_local_context(ctx, members)?;
+
+        let subject = match members
+            .iter()
+            .find(|(key, _)| keyword(&ctx, key) == "@id")
+            .map(|(_, value)| value)
+        {
+            Some(JsonValue::String(id)) => self.iri_id(&ctx, id, false)?,
+            Some(_) => Err(JsonLdError::Invalid("@id must be a string".to_string()))?,
+            None => self.blank_node(None),
+        };
+
+        for (key, value) in members {
+            match keyword(&ctx, key) {
+                "@context" | "@id" | "@index" => {}
+                "@type" => {
+                    for t in as_slice(value) {
+                        let object = match t {
+                            JsonValue::String(t) => self.iri_id(&ctx, t, true)?.into(),
+                            _ => Err(JsonLdError::Invalid("@type must be a string".to_string()))?,
+                        };
+                        self.push(&subject, RDF_TYPE, object, graph);
+                    }
+                }
+                "@graph" => {
+                    for node in as_slice(value) {
+                        self.node(&ctx, as_object(node)?, Some(&subject))?;
+                    }
+                }
+                k if k.starts_with('@') => {
+                    Err(JsonLdError::Unsupported(format!("{k} in node object")))?;
+                }
+                _ => {
+                    // Properties not mapped to an IRI are dropped, as well as the blank node ones
+                    // which would require generalized RDF.
+                    let predicate = match ctx.expand_iri(key, true, false)? {
+                        Some(iri) if !iri.starts_with("_:") && iri.contains(':') => iri,
+                        _ => continue,
+                    };
+                    let def = ctx.terms.get(key);
+                    for object in self.values(&ctx, value, def, graph)? {
+                        self.push(&subject, &predicate, object, graph);
+                    }
+                }
+            }
+        }
+
+        Ok(subject)
+    }
+
+    fn values(
+        &mut self,
+        ctx: &Context,
+        value: &JsonValue,
+        def: Option<&TermDefinition>,
+        graph: Option<&Id>,
+    ) -> Result<Vec<OwnedTerm>, JsonLdError> {
+        let container = def.and_then(|def| def.container);
+        match value {
+            JsonValue::Null => Ok(vec![]),
+            JsonValue::Array(items) if container == Some(Container::List) => {
+                Ok(vec![self.list(ctx, items, def, graph)?])
+            }
+            JsonValue::Array(items) => {
+                let mut terms = vec![];
+                for item in items {
+                    terms.extend(self.values(ctx, item, def, graph)?);
+                }
+                Ok(terms)
+            }
+            JsonValue::Object(members)
+                if container == Some(Container::Language) && get(members, "@value").is_none() =>
+            {
+                let mut terms = vec![];
+                for (language, strings) in members {
+                    for string in as_slice(strings) {
+                        let value = match string {
+                            JsonValue::String(value) => value.clone(),
+                            _ => Err(JsonLdError::Invalid(
+                                "language map values must be strings".to_string(),
+                            ))?,
+                        };
+                        terms.push(OwnedTerm::Literal(match language.as_str() {
+                            "@none" => OwnedLiteral::Simple(value),
+                            _ => OwnedLiteral::LanguageTaggedString {
+                                value,
+                                language: language.to_lowercase(),
+                            },
+                        }));
+                    }
+                }
+                Ok(terms)
+            }
+            JsonValue::Object(members) => self.object_values(ctx, members, def, graph),
+            JsonValue::String(value) => Ok(vec![match def
+                .and_then(|def| def.type_mapping.as_deref())
+            {
+                Some("@id") => self.iri_id(ctx, value, false)?.into(),
+                Some("@vocab") => self.iri_id(ctx, value, true)?.into(),
+                Some(datatype) => OwnedTerm::Literal(OwnedLiteral::Typed {
+                    value: value.clone(),
+                    datatype: datatype.to_string(),
+                }),
+                None => OwnedTerm::Literal(
+                    match def
+                        .and_then(|def| def.language.clone())
+                        .unwrap_or_else(|| ctx.language.clone())
+                    {
+                        Some(language) => OwnedLiteral::LanguageTaggedString {
+                            value: value.clone(),
+                            language: language.to_lowercase(),
+                        },
+                        None => OwnedLiteral::Simple(value.clone()),
+                    },
+                ),
+            }]),
+            JsonValue::Number(_) | JsonValue::Bool(_) => Ok(vec![OwnedTerm::Literal(
+                native_literal(value, def.and_then(|def| def.type_mapping.as_deref()))?,
+            )]),
+        }
+    }
+
+    fn object_values(
+        &mut self,
+        ctx: &Context,
+        members: &[(String, JsonValue)],
+        def: Option<&TermDefinition>,
+        graph: Option<&Id>,
+    ) -> Result<Vec<OwnedTerm>, JsonLdError> {
+        let find = |kw: &str| {
+            members
+                .iter()
+                .find(|(key, _)| keyword(ctx, key) == kw)
+                .map(|(_, value)| value)
+        };
+
+        if let Some(value) = find("@value") {
+            let datatype = match find("@type") {
+                Some(JsonValue::String(t)) => match self.iri_id(ctx, t, true)? {
+                    Id::Named(iri) => Some(iri),
+                    Id::Blank(_) => Err(JsonLdError::Invalid(
+                        "@type of a value can't be a blank node".to_string(),
+                    ))?,
+                },
+                Some(_) => Err(JsonLdError::Invalid("@type must be a string".to_string()))?,
+                None => None,
+            };
+            let language = match find("@language") {
+                Some(JsonValue::String(language)) => Some(language.to_lowercase()),
+                Some(_) => Err(JsonLdError::Invalid(
+                    "@language must be a string".to_string(),
+                ))?,
+                None => None,
+            };
+
+            return Ok(match (value, datatype, language) {
+                (JsonValue::Null, ..) => vec![],
+                (JsonValue::String(value), Some(datatype), None) => {
+                    vec![OwnedTerm::Literal(OwnedLiteral::Typed {
+                        value: value.clone(),
+                        datatype,
+                    })]
+                }
+                (JsonValue::String(value), None, Some(language)) => {
+                    vec![OwnedTerm::Literal(OwnedLiteral::LanguageTaggedString {
+                        value: value.clone(),
+                        language,
+                    })]
+                }
+                (JsonValue::String(value), None, None) => {
+                    vec![OwnedTerm::Literal(OwnedLiteral::Simple(value.clone()))]
+                }
+                (JsonValue::Number(_) | JsonValue::Bool(_), datatype, None) => {
+                    vec![OwnedTerm::Literal(native_literal(
+                        value,
+                        datatype.as_deref(),
+                    )?)]
+                }
+                (JsonValue::Array(_) | JsonValue::Object(_), ..) => {
+                    Err(JsonLdError::Unsupported("JSON literals".to_string()))?
+                }
+                _ => Err(JsonLdError::Invalid(
+                    "a value can't have both a @type and a @language".to_string(),
+                ))?,
+            });
+        }
+
+        if let Some(items) = find("@list") {
+            return Ok(vec![self.list(ctx, as_slice(items), def, graph)?]);
+        }
+        if let Some(items) = find("@set") {
+            return self.values(ctx, items, def, graph);
+        }
+
+        Ok(vec![self.node(ctx, members, graph)?.into()])
+    }
+
+    /// Produce the statements of an [RDF collection](https://www.w3.org/TR/rdf11-mt/#rdf-collections)
+    /// holding the given items, returning its head.
+    fn list(
+        &mut self,
+        ctx: &Context,
+        items: &[JsonValue],
+        def: Option<&TermDefinition>,
+        graph: Option<&Id>,
+    ) -> Result<OwnedTerm, JsonLdError> {
+        let mut terms = vec![];
+        for item in items {
+            if let JsonValue::Array(_) = item {
+                Err(JsonLdError::Unsupported("lists of lists".to_string()))?;
+            }
+            terms.extend(self.values(ctx, item, def, graph)?);
+        }
+
+        let nodes: Vec<Id> = terms.iter().map(|_| self.blank_node(None)).collect();
+        for (i, term) in terms.into_iter().enumerate() {
+            let rest = nodes
+                .get(i + 1)
+                .cloned()
+                .map_or_else(|| OwnedTerm::Named(RDF_NIL.to_string()), OwnedTerm::from);
+            self.push(&nodes[i], RDF_FIRST, term, graph);
+            self.push(&nodes[i], RDF_REST, rest, graph);
+        }
+
+        Ok(nodes
+            .into_iter()
+            .next()
+            .map_or_else(|| OwnedTerm::Named(RDF_NIL.to_string()), OwnedTerm::from))
+    }
+
+    fn with_local_context<'c>(
+        &self,
+        ctx: &'c Context,
+        members: &[(String, JsonValue)],
+    ) -> Result<Cow<'c, Context>, JsonLdError> {
+        Ok(match get(members, "@context") {
+            Some(local) => Cow::Owned(self.process_context(ctx, local, 0)?),
+            None => Cow::Borrowed(ctx),
+        })
+    }
+
+    fn process_context(
+        &self,
+        active: &Context,
+        local: &JsonValue,
+        depth: usize,
+    ) -> Result<Context, JsonLdError> {
+        if depth > MAX_CONTEXT_DEPTH {
+            Err(JsonLdError::InvalidContext(
+                "too many nested context references".to_string(),
+            ))?;
+        }
+
+        match local {
+            JsonValue::Null => Ok(Context::default()),
+            JsonValue::Array(locals) => {
+                let mut result = active.clone();
+                for local in locals {
+                    result = self.process_context(&result, local, depth)?;
+                }
+                Ok(result)
+            }
+            JsonValue::String(iri) => {
+                let document = self
+                    .contexts
+                    .get(iri)
+                    .ok_or_else(|| JsonLdError::UnknownContext(iri.clone()))?;
+                match JsonValue::parse(document)?.get("@context") {
+                    Some(local) => self.process_context(active, local, depth + 1),
+                    None => Err(JsonLdError::InvalidContext(format!(
+                        "no @context entry in {iri}"
+                    ))),
+                }
+            }
+            JsonValue::Object(members) => {
+                let mut result = active.clone();
+                for (key, value) in members {
+                    match (key.as_str(), value) {
+                        ("@base", JsonValue::Null) => result.base = None,
+                        ("@base", JsonValue::String(base)) => result.base = Some(base.clone()),
+                        ("@vocab", JsonValue::Null) => result.vocab = None,
+                        ("@vocab", JsonValue::String(vocab)) => {
+                            result.vocab =
+                                Some(result.expand_iri(vocab, true, true)?.unwrap_or_default());
+                        }
+                        ("@language", JsonValue::Null) => result.language = None,
+                        ("@language", JsonValue::String(language)) => {
+                            result.language = Some(language.to_lowercase());
+                        }
+                        ("@version" | "@protected" | "@propagate" | "@direction", _) => {}
+                        ("@base" | "@vocab" | "@language", _) => {
+                            Err(JsonLdError::InvalidContext(format!("invalid {key} value")))?;
+                        }
+                        (k, _) if k.starts_with('@') => {
+                            Err(JsonLdError::Unsupported(format!("{k} in context")))?;
+                        }
+                        _ => {}
+                    }
+                }
+
+                let mut defined = HashMap::new();
+                for (term, _) in members.iter().filter(|(key, _)| !key.starts_with('@')) {
+                    create_term(&mut result, members, term, &mut defined)?;
+                }
+                Ok(result)
+            }
+            _ => Err(JsonLdError::InvalidContext(
+                "a context must be an object, an array, an IRI or null".to_string(),
+            )),
+        }
+    }
+
+    /// Expand the IRI of a node, being either relative to the document or to the vocabulary.
+    fn iri_id(&mut self, ctx: &Context, value: &str, vocab: bool) -> Result<Id, JsonLdError> {
+        match ctx.expand_iri(value, vocab, true)? {
+            Some(iri) if iri.starts_with('@') => Err(JsonLdError::InvalidIri(value.to_string())),
+            Some(iri) => Ok(match iri.strip_prefix("_:") {
+                Some(label) => self.blank_node(Some(label)),
+                None => Id::Named(iri),
+            }),
+            None => Err(JsonLdError::InvalidIri(value.to_string())),
+        }
+    }
+
+    /// Get the blank node identified in the document by the given label, or a new one if no label.
+    fn blank_node(&mut self, label: Option<&str>) -> Id {
+        if let Some(id) = label.and_then(|label| self.blank_nodes.get(label)) {
+            return Id::Blank(id.clone());
+        }
+
+        let id = format!("b{}", self.blank_node_count);
+        self.blank_node_count += 1;
+        if let Some(label) = label {
+            self.blank_nodes.insert(label.to_string(), id.clone());
+        }
+        Id::Blank(id)
+    }
+
+    fn push(&mut self, subject: &Id, predicate: &str, object: OwnedTerm, graph: Option<&Id>) {
+        self.quads.push(OwnedQuad {
+            subject: subject.clone(),
+            predicate: predicate.to_string(),
+            object,
+            graph_name: graph.cloned(),
+        });
+    }
+}
+
+impl Context {
+    /// Expand the given value as an IRI, a blank node identifier or a keyword, `None` meaning it
+    /// can't be expanded.
+    fn expand_iri(
+        &self,
+        value: &str,
+        vocab: bool,
+        document_relative: bool,
+    ) -> Result<Option<String>, JsonLdError> {
+        if value.starts_with('@') {
+            return Ok(Some(value.to_string()));
+        }
+        if vocab {
+            if let Some(def) = self.terms.get(value) {
+                return Ok(def.id.clone());
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Ok(Some(value.to_string()));
+            }
+            return Ok(Some(match self.terms.get(prefix) {
+                Some(TermDefinition { id: Some(ns), .. }) => format!("{ns}{suffix}"),
+                _ => value.to_string(),
+            }));
+        }
+        if vocab {
+            if let Some(ns) = &self.vocab {
+                return Ok(Some(format!("{ns}{value}")));
+            }
+        }
+        if document_relative {
+            return match &self.base {
+                Some(base) => Ok(Some(resolve(base, value))),
+                None => Err(JsonLdError::InvalidIri(format!(
+                    "relative IRI {value} without base"
+                ))),
+            };
+        }
+        Ok(None)
+    }
+}
+
+/// Create the definition of the term from the local context, the terms it depends on being
+/// defined first.
+fn create_term(
+    ctx: &mut Context,
+    local: &[(String, JsonValue)],
+    term: &str,
+    defined: &mut HashMap<String, bool>,
+) -> Result<(), JsonLdError> {
+    // A term being defined is not defined again, a cyclic definition resulting in a dependency
+    // resolved against the active context.
+    if defined.contains_key(term) {
+        return Ok(());
+    }
+    let value = match get(local, term) {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    defined.insert(term.to_string(), false);
+
+    let def = match value {
+        JsonValue::Null => TermDefinition::default(),
+        JsonValue::String(id) => TermDefinition {
+            id: Some(expand_term_iri(ctx, local, id, defined)?),
+            ..TermDefinition::default()
+        },
+        JsonValue::Object(members) => {
+            for key in ["@reverse", "@context", "@nest"] {
+                if get(members, key).is_some() {
+                    Err(JsonLdError::Unsupported(format!(
+                        "{key} in term definition"
+                    )))?;
+                }
+            }
+
+            let id = match get(members, "@id") {
+                Some(JsonValue::String(id)) => Some(expand_term_iri(ctx, local, id, defined)?),
+                Some(JsonValue::Null) => None,
+                Some(_) => Err(JsonLdError::InvalidContext(format!(
+                    "invalid @id of term {term}"
+                )))?,
+                None if term.contains(':') => Some(expand_term_iri(ctx, local, term, defined)?),
+                None => Some(
+                    ctx.vocab
+                        .as_ref()
+                        .map(|ns| format!("{ns}{term}"))
+                        .ok_or_else(|| {
+                            JsonLdError::InvalidContext(format!("no IRI mapping for term {term}"))
+                        })?,
+                ),
+            };
+            let type_mapping = match get(members, "@type") {
+                Some(JsonValue::String(t)) if t == "@id" || t == "@vocab" => Some(t.clone()),
+                Some(JsonValue::String(t)) if t.starts_with('@') => {
+                    Err(JsonLdError::Unsupported(format!("{t} type mapping")))?
+                }
+                Some(JsonValue::String(t)) => Some(expand_term_iri(ctx, local, t, defined)?),
+                Some(_) => Err(JsonLdError::InvalidContext(format!(
+                    "invalid @type of term {term}"
+                )))?,
+                None => None,
+            };
+            let language = match get(members, "@language") {
+                Some(JsonValue::String(language)) => Some(Some(language.to_lowercase())),
+                Some(JsonValue::Null) => Some(None),
+                Some(_) => Err(JsonLdError::InvalidContext(format!(
+                    "invalid @language of term {term}"
+                )))?,
+                None => None,
+            };
+            let mut container = None;
+            for c in get(members, "@container").map(as_slice).unwrap_or_default() {
+                match c {
+                    JsonValue::String(c) if c == "@list" => container = Some(Container::List),
+                    JsonValue::String(c) if c == "@language" => {
+                        container = Some(Container::Language);
+                    }
+                    JsonValue::String(c) if c == "@set" || c == "@index" => {}
+                    JsonValue::String(c) => {
+                        Err(JsonLdError::Unsupported(format!("{c} container")))?;
+                    }
+                    _ => Err(JsonLdError::InvalidContext(format!(
+                        "invalid @container of term {term}"
+                    )))?,
+                }
+            }
+
+            TermDefinition {
+                id,
+                type_mapping,
+                language,
+                container,
+            }
+        }
+        _ => Err(JsonLdError::InvalidContext(format!(
+            "invalid definition of term {term}"
+        )))?,
+    };
+
+    ctx.terms.insert(term.to_string(), def);
+    defined.insert(term.to_string(), true);
+    Ok(())
+}
+
+fn expand_term_iri(
+    ctx: &mut Context,
+    local: &[(String, JsonValue)],
+    value: &str,
+    defined: &mut HashMap<String, bool>,
+) -> Result<String, JsonLdError> {
+    if value.starts_with('@') {
+        return Ok(value.to_string());
+    }
+
+    let dependency = value.split_once(':').map_or(value, |(prefix, _)| prefix);
+    create_term(ctx, local, dependency, defined)?;
+
+    match ctx.expand_iri(value, true, false)? {
+        Some(iri) if iri.contains(':') || iri.starts_with('@') => Ok(iri),
+        _ => Err(JsonLdError::InvalidContext(format!(
+            "invalid IRI mapping {value}"
+        ))),
+    }
+}
+
+/// Get the literal of a native JSON value, i.e. a number or a boolean.
+///
+/// As defined by the JSON-LD RDF conversion, the numbers with a fractional part, the ones greater
+/// than or equal to 10^21 in absolute value and the ones typed as `xsd:double` are given the
+/// canonical lexical form of a double, e.g. `1.5E0`, the other ones the one of an integer.
+fn native_literal(value: &JsonValue, datatype: Option<&str>) -> Result<OwnedLiteral, JsonLdError> {
+    let datatype = datatype.filter(|datatype| !datatype.starts_with('@'));
+    Ok(match value {
+        JsonValue::Bool(b) => OwnedLiteral::Typed {
+            value: b.to_string(),
+            datatype: datatype.unwrap_or(XSD_BOOLEAN).to_string(),
+        },
+        JsonValue::Number(n) => {
+            let number = n
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| JsonLdError::Unsupported(format!("number {n} out of range")))?;
+
+            if datatype == Some(XSD_DOUBLE) || number.fract() != 0.0 || number.abs() >= 1e21 {
+                OwnedLiteral::Typed {
+                    value: canonical_double(number),
+                    datatype: datatype.unwrap_or(XSD_DOUBLE).to_string(),
+                }
+            } else {
+                OwnedLiteral::Typed {
+                    value: if n.contains(['.', 'e', 'E']) {
+                        format!("{number:.0}")
+                    } else {
+                        n.clone()
+                    },
+                    datatype: datatype.unwrap_or(XSD_INTEGER).to_string(),
+                }
+            }
+        }
+        _ => Err(JsonLdError::Invalid("expected a native value".to_string()))?,
+    })
+}
+
+/// Get the canonical lexical form of a double, i.e. its shortest representation in scientific
+/// notation, the mantissa holding at least one fractional digit.
+fn canonical_double(number: f64) -> String {
+    let repr = format!("{number:E}");
+    match repr.split_once('E') {
+        Some((mantissa, exponent)) if !mantissa.contains('.') => {
+            format!("{mantissa}.0E{exponent}")
+        }
+        _ => repr,
+    }
+}
+
+/// Resolve the IRI reference, which has no scheme, against the base IRI as defined by RFC 3986
+/// §5.2.2, the dot segments of the resulting path being removed.
+fn resolve(base: &str, value: &str) -> String {
+    let base = base.split_once('#').map_or(base, |(base, _)| base);
+    let (scheme, base) = base.split_once(':').unwrap_or(("", base));
+    if value.starts_with("//") {
+        let (authority, path, suffix) = split_reference(value, true);
+        return format!("{scheme}:{authority}{}{suffix}", remove_dot_segments(path));
+    }
+
+    let (authority, base_path, base_query) = split_reference(base, base.starts_with("//"));
+    let prefix = format!("{scheme}:{authority}");
+    let (_, path, suffix) = split_reference(value, false);
+    if path.is_empty() {
+        return match suffix.chars().next() {
+            Some('?') => format!("{prefix}{base_path}{suffix}"),
+            _ => format!("{prefix}{base_path}{base_query}{suffix}"),
+        };
+    }
+
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else if !authority.is_empty() && base_path.is_empty() {
+        format!("/{path}")
+    } else {
+        let directory = base_path.rfind('/').map_or("", |i| &base_path[..=i]);
+        format!("{directory}{path}")
+    };
+    format!("{prefix}{}{suffix}", remove_dot_segments(&path))
+}
+
+/// Split the reference into its authority, prefixed with `//` if any, its path, and the remaining
+/// query and fragment.
+fn split_reference(value: &str, with_authority: bool) -> (&str, &str, &str) {
+    let authority_end = if with_authority {
+        value[2..]
+            .find(['/', '?', '#'])
+            .map_or(value.len(), |i| i + 2)
+    } else {
+        0
+    };
+    let (authority, rest) = value.split_at(authority_end);
+    let (path, suffix) = rest.split_at(rest.find(['?', '#']).unwrap_or(rest.len()));
+    (authority, path, suffix)
+}
+
+/// Remove the `.` and `..` segments of the path, as defined by RFC 3986 §5.2.4.
+fn remove_dot_segments(path: &str) -> String {
+    let mut output = Vec::new();
+    let mut segments = path.split('/').peekable();
+    while let Some(segment) = segments.next() {
+        let last = segments.peek().is_none();
+        match segment {
+            "." => {}
+            ".." => {
+                if !matches!(output.as_slice(), [] | [""]) {
+                    output.pop();
+                }
+            }
+            segment => {
+                output.push(segment);
+                continue;
+            }
+        }
+        if last {
+            output.push("");
+        }
+    }
+    output.join("/")
+}
+
+/// Resolve the keyword the key is an alias of, if any.
+fn keyword<'k>(ctx: &'k Context, key: &'k str) -> &'k str {
+    match ctx.terms.get(key) {
+        Some(TermDefinition { id: Some(id), .. }) if id.starts_with('@') => id,
+        _ => key,
+    }
+}
+
+fn get<'v>(members: &'v [(String, JsonValue)], key: &str) -> Option<&'v JsonValue> {
+    members.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn as_slice(value: &JsonValue) -> &[JsonValue] {
+    match value {
+        JsonValue::Array(values) => values,
+        value => std::slice::from_ref(value),
+    }
+}
+
+fn as_object(value: &JsonValue) -> Result<&[(String, JsonValue)], JsonLdError> {
+    match value {
+        JsonValue::Object(members) => Ok(members),
+        _ => Err(JsonLdError::Invalid("expected a node object".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rio_api::formatter::QuadsFormatter;
+    use rio_turtle::NQuadsFormatter;
+
+    fn parse(document: &str, contexts: HashMap<String, String>) -> Result<String, JsonLdError> {
+        let mut formatter = NQuadsFormatter::new(vec![]);
+        JsonLdParser::new(document.as_bytes())
+            .with_contexts(contexts)
+            .parse_all(&mut |quad| {
+                formatter
+                    .format(&quad)
+                    .map_err(|e| JsonLdError::Io(e.to_string()))
+            })?;
+        Ok(String::from_utf8_lossy(&formatter.finish().unwrap()).to_string())
+    }
+
+    #[test]
+    fn proper_parse() {
+        let cases = vec![
+            (
+                r#"{
+                    "@context": {
+                        "ex": "http://example.org/",
+                        "name": "ex:name",
+                        "knows": {"@id": "ex:knows", "@type": "@id"},
+                        "age": {"@id": "ex:age", "@type": "http://www.w3.org/2001/XMLSchema#integer"},
+                        "id": "@id"
+                    },
+                    "id": "ex:alice",
+                    "@type": "ex:Person",
+                    "name": ["Alice", {"@value": "Alicia", "@language": "ES"}],
+                    "age": "42",
+                    "knows": "ex:bob",
+                    "ex:score": 1.5,
+                    "ex:rank": 1.0,
+                    "ex:ratio": 0.25,
+                    "ex:count": 1e21,
+                    "ex:active": true,
+                    "unknown": "dropped"
+                }"#,
+                "<http://example.org/alice> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/Person> .\n\
+                <http://example.org/alice> <http://example.org/name> \"Alice\" .\n\
+                <http://example.org/alice> <http://example.org/name> \"Alicia\"@es .\n\
+                <http://example.org/alice> <http://example.org/age> \"42\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+                <http://example.org/alice> <http://example.org/knows> <http://example.org/bob> .\n\
+                <http://example.org/alice> <http://example.org/score> \"1.5E0\"^^<http://www.w3.org/2001/XMLSchema#double> .\n\
+                <http://example.org/alice> <http://example.org/rank> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+                <http://example.org/alice> <http://example.org/ratio> \"2.5E-1\"^^<http://www.w3.org/2001/XMLSchema#double> .\n\
+                <http://example.org/alice> <http://example.org/count> \"1.0E21\"^^<http://www.w3.org/2001/XMLSchema#double> .\n\
+                <http://example.org/alice> <http://example.org/active> \"true\"^^<http://www.w3.org/2001/XMLSchema#boolean> .\n",
+            ),
+            (
+                r#"[{
+                    "@id": "http://example.org/alice",
+                    "http://example.org/knows": [{"@id": "_:bob"}, {"http://example.org/name": [{"@value": "Carol"}]}],
+                    "http://example.org/items": [{"@list": [{"@value": 1}, {"@value": "2"}]}]
+                }, {
+                    "@id": "_:bob",
+                    "http://example.org/name": {"@value": "Bob", "@type": "http://www.w3.org/2001/XMLSchema#string"}
+                }]"#,
+                "_:b1 <http://example.org/name> \"Carol\" .\n\
+                <http://example.org/alice> <http://example.org/knows> _:b0 .\n\
+                <http://example.org/alice> <http://example.org/knows> _:b1 .\n\
+                _:b2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+                _:b2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:b3 .\n\
+                _:b3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"2\" .\n\
+                _:b3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .\n\
+                <http://example.org/alice> <http://example.org/items> _:b2 .\n\
+                _:b0 <http://example.org/name> \"Bob\"^^<http://www.w3.org/2001/XMLSchema#string> .\n",
+            ),
+            (
+                r#"{
+                    "@context": ["https://example.org/context", {"@language": "en"}],
+                    "@graph": [{
+                        "@id": "ex:g",
+                        "@graph": {"@id": "ex:a", "label": "A", "tags": {"fr": "étiquette"}}
+                    }, {
+                        "@id": "doc",
+                        "label": "Doc"
+                    }]
+                }"#,
+                "<http://example.org/a> <http://example.org/label> \"A\"@en <http://example.org/g> .\n\
+                <http://example.org/a> <http://example.org/tags> \"étiquette\"@fr <http://example.org/g> .\n\
+                <http://example.org/base/doc> <http://example.org/label> \"Doc\"@en .\n",
+            ),
+            (
+                r##"{
+                    "@context": {"@base": "http://example.org/a/b/c?x#y", "p": "http://example.org/p"},
+                    "@graph": [
+                        {"@id": "../d", "p": "1"},
+                        {"@id": "./e/.", "p": "2"},
+                        {"@id": "../../../f", "p": "3"},
+                        {"@id": "/g/./h/../i", "p": "4"},
+                        {"@id": "?q", "p": "5"},
+                        {"@id": "#j", "p": "6"},
+                        {"@id": "//example.com/k/..", "p": "7"}
+                    ]
+                }"##,
+                "<http://example.org/a/d> <http://example.org/p> \"1\" .\n\
+                <http://example.org/a/b/e/> <http://example.org/p> \"2\" .\n\
+                <http://example.org/f> <http://example.org/p> \"3\" .\n\
+                <http://example.org/g/i> <http://example.org/p> \"4\" .\n\
+                <http://example.org/a/b/c?q> <http://example.org/p> \"5\" .\n\
+                <http://example.org/a/b/c?x#j> <http://example.org/p> \"6\" .\n\
+                <http://example.com/> <http://example.org/p> \"7\" .\n",
+            ),
+        ];
+
+        let contexts = HashMap::from([(
+            "https://example.org/context".to_string(),
+            r#"{"@context": {
+                "@base": "http://example.org/base/",
+                "@vocab": "http://example.org/",
+                "ex": "http://example.org/",
+                "tags": {"@container": "@language"}
+            }}"#
+            .to_string(),
+        )]);
+        for (document, expected) in cases {
+            assert_eq!(parse(document, contexts.clone()), Ok(expected.to_string()));
+        }
+    }
+
+    #[test]
+    fn invalid_parse() {
+        let cases = vec![
+            (
+                r#"{"@context": "https://example.org/unknown", "@id": "http://example.org/a"}"#,
+                JsonLdError::UnknownContext("https://example.org/unknown".to_string()),
+            ),
+            (
+                r#"{"@id": "a", "http://example.org/p": "v"}"#,
+                JsonLdError::InvalidIri("relative IRI a without base".to_string()),
+            ),
+            (
+                r#"{"@id": "../a", "http://example.org/p": "v"}"#,
+                JsonLdError::InvalidIri("relative IRI ../a without base".to_string()),
+            ),
+            (
+                r#"{"@context": {"p": {"@reverse": "http://example.org/p"}}}"#,
+                JsonLdError::Unsupported("@reverse in term definition".to_string()),
+            ),
+            (
+                r#"{"@context": {"@import": "http://example.org/context"}}"#,
+                JsonLdError::Unsupported("@import in context".to_string()),
+            ),
+            (
+                r#"{"http://example.org/p": {"@value": {"a": 1}}}"#,
+                JsonLdError::Unsupported("JSON literals".to_string()),
+            ),
+            (
+                r#"{"@id": "http://example.org/a", "http://example.org/p": 1e400}"#,
+                JsonLdError::Unsupported("number 1e400 out of range".to_string()),
+            ),
+            (
+                r#""http://example.org/a""#,
+                JsonLdError::Invalid("expected a node object".to_string()),
+            ),
+            (
+                r#"{"@id": "http://example.org/a""#,
+                JsonLdError::Json(30, "expected ',' or '}'".to_string()),
+            ),
+        ];
+
+        for (document, expected) in cases {
+            assert_eq!(parse(document, HashMap::new()), Err(expected));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod dataset;
+pub mod jsonld;
 pub mod normalize;
 pub mod owned_model;
 pub mod serde;
@@ -3,11 +3,12 @@ use rio_api::model::{BlankNode, GraphName, Literal, NamedNode, Quad, Subject, Te
 #[derive(Debug)]
 pub struct RDFStarUnsupported;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct OwnedQuad {
-    subject: OwnedSubject,
-    predicate: String,
-    object: OwnedTerm,
-    graph_name: Option<OwnedGraphName>,
+    pub subject: OwnedSubject,
+    pub predicate: String,
+    pub object: OwnedTerm,
+    pub graph_name: Option<OwnedGraphName>,
 }
 
 impl TryFrom<Quad<'_>> for OwnedQuad {
@@ -36,6 +37,7 @@ impl<'a> From<&'a OwnedQuad> for Quad<'a> {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Id {
     Named(String),
     Blank(String),
@@ -84,6 +86,7 @@ impl<'a> From<&'a OwnedGraphName> for GraphName<'a> {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum OwnedTerm {
     Named(String),
     Blank(String),
@@ -103,6 +106,15 @@ impl TryFrom<Term<'_>> for OwnedTerm {
     }
 }
 
+impl From<Id> for OwnedTerm {
+    fn from(value: Id) -> Self {
+        match value {
+            Id::Named(iri) => OwnedTerm::Named(iri),
+            Id::Blank(id) => OwnedTerm::Blank(id),
+        }
+    }
+}
+
 impl<'a> From<&'a OwnedTerm> for Term<'a> {
     fn from(value: &'a OwnedTerm) -> Self {
         match value {
@@ -113,6 +125,7 @@ impl<'a> From<&'a OwnedTerm> for Term<'a> {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum OwnedLiteral {
     Simple(String),
     LanguageTaggedString { value: String, language: String },
@@ -1,12 +1,14 @@
+use crate::jsonld::{JsonLdError, JsonLdFormatter, JsonLdParser};
 use crate::owned_model::OwnedQuad;
 use rio_api::formatter::TriplesFormatter;
 use rio_api::model::{Quad, Triple};
 use rio_api::parser::{QuadsParser, TriplesParser};
 use rio_turtle::{
-    NQuadsFormatter, NQuadsParser, NTriplesFormatter, NTriplesParser, TurtleError, TurtleFormatter,
-    TurtleParser,
+    NQuadsFormatter, NQuadsParser, NTriplesFormatter, NTriplesParser, TriGFormatter, TriGParser,
+    TurtleError, TurtleFormatter, TurtleParser,
 };
 use rio_xml::{RdfXmlError, RdfXmlFormatter, RdfXmlParser};
+use std::collections::HashMap;
 use std::io::{self, BufRead};
 use thiserror::Error;
 
@@ -27,6 +29,19 @@ pub enum NQuadsReadError {
     Parse(#[from] TurtleError),
 }
 
+pub struct TriGReader<R: BufRead> {
+    parser: TriGParser<R>,
+}
+
+#[derive(Error, Debug)]
+pub enum TriGReadError {
+    #[error("RDF Star notation not supported")]
+    RDFStarUnsupported,
+
+    #[error("Couldn't parse rdf: {0}")]
+    Parse(#[from] TurtleError),
+}
+
 pub struct TripleWriter<W: io::Write> {
     writer: TriplesWriterKind<W>,
 }
@@ -37,6 +52,8 @@ pub enum TriplesParserKind<R: BufRead> {
     Turtle(TurtleParser<R>),
     RdfXml(RdfXmlParser<R>),
     NQuads(NQuadsParser<R>),
+    TriG(TriGParser<R>),
+    JsonLd(JsonLdParser<R>),
 }
 
 pub enum TriplesWriterKind<W: io::Write> {
@@ -44,6 +61,8 @@ pub enum TriplesWriterKind<W: io::Write> {
     Turtle(TurtleFormatter<W>),
     RdfXml(io::Result<RdfXmlFormatter<W>>),
     NQuads(NQuadsFormatter<W>),
+    TriG(TriGFormatter<W>),
+    JsonLd(JsonLdFormatter<W>),
 }
 
 pub enum DataFormat {
@@ -55,6 +74,10 @@ pub enum DataFormat {
     NTriples,
     /// Represents a [N-Quads](https://www.w3.org/TR/n-quads/) format.
     NQuads,
+    /// Represents a [TriG](https://www.w3.org/TR/trig/) format.
+    TriG,
+    /// Represents a [JSON-LD](https://www.w3.org/TR/json-ld11/) format.
+    JsonLd,
 }
 
 impl<R: BufRead> TripleReader<R> {
@@ -65,28 +88,70 @@ impl<R: BufRead> TripleReader<R> {
                 DataFormat::Turtle => TriplesParserKind::Turtle(TurtleParser::new(src, None)),
                 DataFormat::NTriples => TriplesParserKind::NTriples(NTriplesParser::new(src)),
                 DataFormat::NQuads => TriplesParserKind::NQuads(NQuadsParser::new(src)),
+                DataFormat::TriG => TriplesParserKind::TriG(TriGParser::new(src, None)),
+                DataFormat::JsonLd => TriplesParserKind::JsonLd(JsonLdParser::new(src)),
             },
         }
     }
 
+    /// Register the JSON-LD context documents, by their IRI, which can be referenced by the read
+    /// data, the other formats not using them.
+    pub fn with_contexts(mut self, contexts: HashMap<String, String>) -> Self {
+        self.parser = match self.parser {
+            TriplesParserKind::JsonLd(parser) => {
+                TriplesParserKind::JsonLd(parser.with_contexts(contexts))
+            }
+            parser => parser,
+        };
+        self
+    }
+
     pub fn read_all<E, UF>(&mut self, mut use_fn: UF) -> Result<(), E>
     where
         UF: FnMut(Triple<'_>) -> Result<(), E>,
-        E: From<TurtleError> + From<RdfXmlError>,
+        E: From<TurtleError> + From<RdfXmlError> + From<JsonLdError>,
     {
+        let mut use_quad_fn = |quad: Quad<'_>| -> Result<(), E> {
+            use_fn(Triple {
+                subject: quad.subject,
+                predicate: quad.predicate,
+                object: quad.object,
+            })
+        };
+
         match &mut self.parser {
             TriplesParserKind::NTriples(parser) => parser.parse_all(&mut use_fn),
             TriplesParserKind::Turtle(parser) => parser.parse_all(&mut use_fn),
             TriplesParserKind::RdfXml(parser) => parser.parse_all(&mut use_fn),
-            TriplesParserKind::NQuads(parser) => {
-                parser.parse_all(&mut |quad: Quad<'_>| -> Result<(), E> {
-                    use_fn(Triple {
-                        subject: quad.subject,
-                        predicate: quad.predicate,
-                        object: quad.object,
-                    })
-                })
-            }
+            TriplesParserKind::NQuads(parser) => parser.parse_all(&mut use_quad_fn),
+            TriplesParserKind::TriG(parser) => parser.parse_all(&mut use_quad_fn),
+            TriplesParserKind::JsonLd(parser) => parser.parse_all(&mut use_quad_fn),
+        }
+    }
+
+    /// Read all the statements as quads, the ones of the formats not supporting named graphs
+    /// belonging to the default graph.
+    pub fn read_all_quads<E, UF>(&mut self, mut use_fn: UF) -> Result<(), E>
+    where
+        UF: FnMut(Quad<'_>) -> Result<(), E>,
+        E: From<TurtleError> + From<RdfXmlError> + From<JsonLdError>,
+    {
+        let mut use_triple_fn = |triple: Triple<'_>| -> Result<(), E> {
+            use_fn(Quad {
+                subject: triple.subject,
+                predicate: triple.predicate,
+                object: triple.object,
+                graph_name: None,
+            })
+        };
+
+        match &mut self.parser {
+            TriplesParserKind::NTriples(parser) => parser.parse_all(&mut use_triple_fn),
+            TriplesParserKind::Turtle(parser) => parser.parse_all(&mut use_triple_fn),
+            TriplesParserKind::RdfXml(parser) => parser.parse_all(&mut use_triple_fn),
+            TriplesParserKind::NQuads(parser) => parser.parse_all(&mut use_fn),
+            TriplesParserKind::TriG(parser) => parser.parse_all(&mut use_fn),
+            TriplesParserKind::JsonLd(parser) => parser.parse_all(&mut use_fn),
         }
     }
 }
@@ -114,6 +179,29 @@ impl<R: BufRead> NQuadsReader<R> {
     }
 }
 
+impl<R: BufRead> TriGReader<R> {
+    pub fn new(src: R) -> Self {
+        TriGReader {
+            parser: TriGParser::new(src, None),
+        }
+    }
+
+    pub fn read_all(&mut self) -> Result<Vec<OwnedQuad>, TriGReadError> {
+        let mut quads = vec![];
+
+        self.parser
+            .parse_all(&mut |quad| -> Result<(), TriGReadError> {
+                quads.push(
+                    quad.try_into()
+                        .map_err(|_| TriGReadError::RDFStarUnsupported)?,
+                );
+                Ok(())
+            })?;
+
+        Ok(quads)
+    }
+}
+
 impl<W: io::Write> TripleWriter<W> {
     pub fn new(format: &DataFormat, dst: W) -> Self {
         TripleWriter {
@@ -122,10 +210,24 @@ impl<W: io::Write> TripleWriter<W> {
                 DataFormat::Turtle => TriplesWriterKind::Turtle(TurtleFormatter::new(dst)),
                 DataFormat::NTriples => TriplesWriterKind::NTriples(NTriplesFormatter::new(dst)),
                 DataFormat::NQuads => TriplesWriterKind::NQuads(NQuadsFormatter::new(dst)),
+                DataFormat::TriG => TriplesWriterKind::TriG(TriGFormatter::new(dst)),
+                DataFormat::JsonLd => TriplesWriterKind::JsonLd(JsonLdFormatter::new(dst)),
             },
         }
     }
 
+    /// Use the given prefixes, as `(prefix, namespace)` pairs, as the JSON-LD context compacting
+    /// the written IRIs, the other formats not using them.
+    pub fn with_prefixes(mut self, prefixes: Vec<(String, String)>) -> Self {
+        self.writer = match self.writer {
+            TriplesWriterKind::JsonLd(formatter) => {
+                TriplesWriterKind::JsonLd(formatter.with_prefixes(prefixes))
+            }
+            writer => writer,
+        };
+        self
+    }
+
     pub fn write(&mut self, triple: &Triple<'_>) -> io::Result<()> {
         match &mut self.writer {
             TriplesWriterKind::Turtle(formatter) => formatter.format(triple),
@@ -142,6 +244,24 @@ impl<W: io::Write> TripleWriter<W> {
 
                 formatter.format(quad)
             }
+            TriplesWriterKind::TriG(formatter) => {
+                use rio_api::formatter::QuadsFormatter;
+
+                let quad = &Quad {
+                    subject: triple.subject,
+                    predicate: triple.predicate,
+                    object: triple.object,
+                    graph_name: None,
+                };
+
+                formatter.format(quad)
+            }
+            TriplesWriterKind::JsonLd(formatter) => formatter.format(&Quad {
+                subject: triple.subject,
+                predicate: triple.predicate,
+                object: triple.object,
+                graph_name: None,
+            }),
             TriplesWriterKind::RdfXml(format_result) => match format_result {
                 Ok(formatter) => formatter.format(triple),
                 Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
@@ -149,6 +269,28 @@ impl<W: io::Write> TripleWriter<W> {
         }
     }
 
+    /// Write the quad, its graph name being output only by the formats supporting named graphs.
+    pub fn write_quad(&mut self, quad: &Quad<'_>) -> io::Result<()> {
+        match &mut self.writer {
+            TriplesWriterKind::NQuads(formatter) => {
+                use rio_api::formatter::QuadsFormatter;
+
+                formatter.format(quad)
+            }
+            TriplesWriterKind::TriG(formatter) => {
+                use rio_api::formatter::QuadsFormatter;
+
+                formatter.format(quad)
+            }
+            TriplesWriterKind::JsonLd(formatter) => formatter.format(quad),
+            _ => self.write(&Triple {
+                subject: quad.subject,
+                predicate: quad.predicate,
+                object: quad.object,
+            }),
+        }
+    }
+
     pub fn write_all(&mut self, triples: Vec<&Triple<'_>>) -> io::Result<()> {
         for triple in triples {
             self.write(triple)?;
@@ -161,6 +303,8 @@ impl<W: io::Write> TripleWriter<W> {
             TriplesWriterKind::Turtle(formatter) => formatter.finish(),
             TriplesWriterKind::NTriples(formatter) => formatter.finish(),
             TriplesWriterKind::NQuads(formatter) => formatter.finish(),
+            TriplesWriterKind::TriG(formatter) => formatter.finish(),
+            TriplesWriterKind::JsonLd(formatter) => formatter.finish(),
             TriplesWriterKind::RdfXml(format_result) => match format_result {
                 Ok(formatter) => formatter.finish(),
                 Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
@@ -168,3 +312,49 @@ impl<W: io::Write> TripleWriter<W> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::owned_model::{Id, OwnedLiteral, OwnedTerm};
+
+    #[test]
+    fn proper_trig_read_write() {
+        let data = "@prefix ex: <http://example.org/> .
+ex:a ex:p \"1\" .
+ex:g { ex:a ex:p ex:b . }
+";
+        let quads = TriGReader::new(data.as_bytes()).read_all().unwrap();
+        assert_eq!(
+            quads,
+            vec![
+                OwnedQuad {
+                    subject: Id::Named("http://example.org/a".to_string()),
+                    predicate: "http://example.org/p".to_string(),
+                    object: OwnedTerm::Literal(OwnedLiteral::Simple("1".to_string())),
+                    graph_name: None,
+                },
+                OwnedQuad {
+                    subject: Id::Named("http://example.org/a".to_string()),
+                    predicate: "http://example.org/p".to_string(),
+                    object: OwnedTerm::Named("http://example.org/b".to_string()),
+                    graph_name: Some(Id::Named("http://example.org/g".to_string())),
+                },
+            ]
+        );
+
+        let mut writer = TripleWriter::new(&DataFormat::TriG, vec![]);
+        for quad in &quads {
+            writer.write_quad(&quad.into()).unwrap();
+        }
+        let out = writer.finish().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "<http://example.org/a> <http://example.org/p> \"1\" .
+<http://example.org/g> {
+\t<http://example.org/a> <http://example.org/p> <http://example.org/b> .
+}
+"
+        );
+    }
+}